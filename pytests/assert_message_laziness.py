@@ -0,0 +1,29 @@
+calls = []
+
+def message(text, calls=calls):
+    calls.append(text)
+    return text
+
+def condition(value, calls=calls):
+    calls.append("condition")
+    return value
+
+assert condition(True), message("top level")
+assert_eq(["condition"], calls)
+
+def check(n):
+    assert n > 0, message("in function")
+    return n
+
+assert_eq(5, check(5))
+assert_eq(["condition"], calls)
+
+for i in [1, 2, 3]:
+    assert i < 10, message("in loop")
+    if i == 2:
+        assert i == 2, message("in branch")
+assert_eq(["condition"], calls)
+
+while len(calls) < 3:
+    assert condition(len(calls) > 0), message("in while")
+assert_eq(["condition", "condition", "condition"], calls)
@@ -0,0 +1,12 @@
+x = 10
+assert x == 10
+assert x > 5, "x should be greater than 5"
+assert [1, 2]
+
+def positive(n):
+    assert n > 0, "n must be positive"
+    return n
+
+assert_eq(positive(3), 3)
+if x > 0:
+    assert x
@@ -0,0 +1,30 @@
+import ast
+
+tree = ast.parse("1 + 2")
+assert_eq(tree.__class__.__name__, "Module")
+assert_eq(len(tree.body), 1)
+expr = tree.body[0].value
+assert_eq(expr.__class__.__name__, "BinaryOperation")
+assert_eq(expr._fields, ("left", "operator", "right"))
+assert_eq(expr.operator, "+")
+assert_eq(expr.left.__class__.__name__, "IntegerValue")
+assert_eq(expr.left.value, 1)
+assert_eq(expr.right.value, 2)
+assert_eq(repr(expr), "BinaryOperation(left=IntegerValue(value=1), operator='+', right=IntegerValue(value=2))")
+
+tree = ast.parse("def f(a, b=1):\n    return a\nx = f(2)")
+function = tree.body[0]
+assert_eq(function.function_name, "f")
+assert_eq([p.name for p in function.parameters], ["a", "b"])
+assert_eq(function.parameters[0].default, None)
+assert_eq(function.parameters[1].default.value, 1)
+assert_eq(function.body[0].value.name, "a")
+assert_eq(tree.body[1].path, ["x"])
+assert_eq(tree.body[1].expression.function.name, "f")
+
+try:
+    ast.parse("x = (")
+    error = None
+except SyntaxError:
+    error = "SyntaxError"
+assert_eq(error, "SyntaxError")
@@ -0,0 +1,60 @@
+x = 10
+x += 5
+assert_eq(x, 15)
+x -= 3
+assert_eq(x, 12)
+x *= 2
+assert_eq(x, 24)
+x %= 5
+assert_eq(x, 4)
+
+s = "ab"
+s += "cd"
+assert_eq(s, "abcd")
+
+def local_counter():
+    total = 0
+    for i in [1, 2, 3]:
+        total += i
+    return total
+
+assert_eq(local_counter(), 6)
+
+class Box:
+    def __init__(self):
+        self.inner = Inner()
+
+class Inner:
+    def __init__(self):
+        self.value = 1
+
+b = Box()
+b.inner.value += 41
+assert_eq(b.inner.value, 42)
+
+items = [1, 2, 3]
+items[1] = 20
+assert_eq(items, [1, 20, 3])
+items[2] += 30
+assert_eq(items, [1, 20, 33])
+
+counts = dict()
+for word in ["a", "b", "a", "c", "a"]:
+    counts[word] = counts.get(word, 0)
+    counts[word] += 1
+assert_eq(counts["a"], 3)
+assert_eq(counts["b"], 1)
+assert_eq(counts["c"], 1)
+
+calls = []
+def container():
+    calls.append("container")
+    return counts
+
+def key():
+    calls.append("key")
+    return "b"
+
+container()[key()] += 10
+assert_eq(counts["b"], 11)
+assert_eq(calls, ["container", "key"])
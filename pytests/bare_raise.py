@@ -0,0 +1,57 @@
+class Oops:
+    code = 1
+
+def reraise_with_log(log):
+    try:
+        raise Oops()
+    except Oops:
+        log.append("inner")
+        raise
+
+log = []
+original = Oops()
+try:
+    try:
+        raise original
+    except Oops:
+        log.append("inner")
+        raise
+except Oops as e:
+    log.append("outer")
+    assert_eq(id(original), id(e))
+assert_eq(["inner", "outer"], log)
+
+log = []
+try:
+    reraise_with_log(log)
+except Oops as e:
+    log.append("outer")
+    assert_eq(1, e.code)
+assert_eq(["inner", "outer"], log)
+
+log = []
+try:
+    try:
+        x = "a" + 1
+    except TypeError:
+        try:
+            raise Oops()
+        except Oops:
+            log.append("nested")
+        raise
+except TypeError as e:
+    log.append(str(e))
+assert_eq(["nested", "can only concatenate str (not \"int\") to str"], log)
+
+def reraise():
+    raise
+
+log = []
+try:
+    try:
+        raise Oops()
+    except Oops:
+        reraise()
+except Oops:
+    log.append("from a function")
+assert_eq(["from a function"], log)
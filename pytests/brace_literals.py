@@ -0,0 +1,32 @@
+empty = {}
+assert_eq("{}", repr(empty))
+empty["k"] = 1
+assert_eq(1, empty["k"])
+
+pair = {1: 2}
+assert_eq(2, pair[1])
+assert_eq({1: 2, "a": "b"}, {1: 2, "a": "b",})
+
+numbers = {1, 2, 2, 3}
+assert_eq(3, len(numbers))
+assert_eq(True, 2 in numbers)
+assert_eq(False, 4 in numbers)
+assert_eq({3, 2, 1}, numbers)
+assert_eq("{1, 2, 3}", repr(numbers))
+assert_eq("{'x'}", str({"x",}))
+assert_eq("set()", repr(set()))
+
+numbers.add(4)
+numbers.add(1)
+assert_eq(4, len(numbers))
+numbers.discard(10)
+numbers.remove(4)
+assert_eq({1, 2, 3}, numbers)
+assert_eq(True, numbers == frozenset([1, 2, 3]))
+assert_eq({1, 2, 3, 4}, numbers.union({4}))
+assert_eq({2}, numbers.intersection({2, 5}))
+
+nested = {1: {2, 3}, (1, 2): {4: 5}}
+assert_eq({3, 2}, nested[1])
+assert_eq(5, nested[(1, 2)][4])
+assert_eq(2, len({frozenset([1]), frozenset([2]), frozenset([1])}))
@@ -0,0 +1,11 @@
+assert_eq(len([1, 2]), 2)
+assert_eq(__builtins__.len([1, 2]), 2)
+
+def len_inside_function():
+    return len([1, 2, 3])
+assert_eq(len_inside_function(), 3)
+
+assert_eq(list(range(3)), [0, 1, 2])
+assert_eq(list(__builtins__.range(3)), [0, 1, 2])
+assert_eq(globals().get("range"), None)
+assert_eq(globals().get("len"), None)
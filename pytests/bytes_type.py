@@ -0,0 +1,36 @@
+data = b"abc"
+assert_eq(97, data[0])
+assert_eq(99, data[-1])
+assert_eq(3, len(data))
+assert_eq("abc", data.decode())
+assert_eq("abc", b'abc'.decode("utf-8"))
+assert_eq("abc", b"abc".decode("ascii"))
+
+assert_eq(True, b"abc" == b"abc")
+assert_eq(False, b"abc" == b"abd")
+assert_eq(False, b"abc" == "abc")
+assert_eq(b"abcdef", b"abc" + b"def")
+
+escaped = b"\x00\xff\n\t\\\101"
+assert_eq(6, len(escaped))
+assert_eq(0, escaped[0])
+assert_eq(255, escaped[1])
+assert_eq(10, escaped[2])
+assert_eq(65, escaped[5])
+
+assert_eq("b'abc'", repr(b"abc"))
+assert_eq("b'\\x00\\xff'", repr(b"\x00\xff"))
+assert_eq("é", b"\xc3\xa9".decode())
+assert_eq("é", b"\xe9".decode("latin-1"))
+
+total = 0
+for byte in b"\x01\x02\x03":
+    total = total + byte
+assert_eq(6, total)
+
+empty = "not empty"
+if b"":
+    empty = "wrong"
+else:
+    empty = "empty"
+assert_eq("empty", empty)
@@ -0,0 +1,43 @@
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def sum(self):
+        return self.x + self.y
+
+p = Point(1, 2)
+assert_eq(1, p.x)
+assert_eq(2, p.y)
+assert_eq(3, p.sum())
+
+q = Point(y=20, x=10)
+assert_eq(10, q.x)
+assert_eq(20, q.y)
+
+r = Point(5, y=6)
+assert_eq(11, r.sum())
+
+class Config:
+    def __init__(self, name, debug=False, level=1):
+        self.name = name
+        self.debug = debug
+        self.level = level
+
+c = Config("app")
+assert_eq("app", c.name)
+assert_eq(False, c.debug)
+assert_eq(1, c.level)
+c2 = Config("svc", level=3)
+assert_eq(False, c2.debug)
+assert_eq(3, c2.level)
+
+class Pair:
+    def __init__(self, items):
+        self.first = items[0]
+        self.count = len(items)
+
+pair = Pair([1, 2, 3])
+assert_eq(1, pair.first)
+assert_eq(3, pair.count)
+assert_eq(False, id(pair) == id(None))
@@ -0,0 +1,46 @@
+class Counter:
+    created = 0
+
+    def __init__(self, start):
+        self.value = start
+
+    @classmethod
+    def from_pair(cls, a, b):
+        return cls(a + b)
+
+    @classmethod
+    def kind(cls):
+        return cls
+
+    @staticmethod
+    def add(a, b):
+        return a + b
+
+    @staticmethod
+    def answer():
+        return 42
+
+    def double(self):
+        return self.value * 2
+
+c = Counter.from_pair(1, 2)
+assert_eq(3, c.value)
+assert_eq(6, c.double())
+assert_eq(True, id(Counter.kind()) == id(Counter))
+assert_eq(True, id(c.kind()) == id(Counter))
+assert_eq(8, c.from_pair(3, 5).value)
+
+assert_eq(5, Counter.add(2, 3))
+assert_eq(5, c.add(2, 3))
+assert_eq(42, Counter.answer())
+assert_eq(42, c.answer())
+
+assert_eq(0, Counter.created)
+assert_eq(0, c.created)
+assert_eq(6, Counter.double(c))
+
+def triple(x):
+    return x * 3
+
+wrapped = staticmethod(triple)
+assert_eq(9, wrapped.__func__(3))
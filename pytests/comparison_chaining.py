@@ -0,0 +1,33 @@
+assert_eq(True, 1 <= 1 < 2)
+assert_eq(False, 1 < 1 <= 2)
+assert_eq(True, 1 < 2 < 3 < 4)
+assert_eq(False, 1 < 2 > 3 < 4)
+assert_eq(True, 3 > 2 >= 2 != 5)
+assert_eq(True, 1 == 1 == 1)
+assert_eq(False, 1 == 1 == 2)
+assert_eq(True, 1 < 2 in [2, 3])
+
+assert_eq(True, (1 < 2) < 3)
+assert_eq(True, 1 < 1 < 2 == False or True)
+assert_eq(False, not 1 < 2 < 3)
+assert_eq(True, 0 < 1 + 1 < 3)
+
+calls = []
+def middle(value, calls=calls):
+    calls.append(value)
+    return value
+
+assert_eq(True, 0 < middle(1) < 2)
+assert_eq([1], calls)
+
+assert_eq(False, 2 < middle(1) < middle(5))
+assert_eq([1, 1], calls)
+
+x = 5
+if 0 <= x < 10:
+    inside = True
+else:
+    inside = False
+assert_eq(True, inside)
+assert_eq([x for x in [0, 5, 10, 15] if 0 < x <= 10], [5, 10])
+assert_eq(True, 1 < middle(2) < abs(-3))
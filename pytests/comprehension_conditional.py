@@ -0,0 +1,33 @@
+xs = [3, -1, 0, -7, 2]
+assert_eq([3, 1, 0, 7, 2], [x if x > 0 else -x for x in xs])
+assert_eq([3, 0, 2], [x for x in xs if x >= 0])
+assert_eq([6, 4], [x * 2 for x in xs if x > 0])
+assert_eq([3], [x for x in xs if x > 0 if x != 2])
+assert_eq([], [x for x in [] if x])
+assert_eq([0, 1, 4], [n * n for n in range(3)])
+assert_eq(["neg", "zero", "pos"], ["pos" if n > 0 else "zero" if n == 0 else "neg" for n in (-5, 0, 5)])
+assert_eq([[1], [2]], [[v] for v in [1, 2]])
+assert_eq([[0, 1], [0, 1]], [[a for a in range(2)] for b in range(2)])
+
+def absolutes(values):
+    return [v if v >= 0 else -v for v in values]
+
+assert_eq([1, 2, 3], absolutes([-1, 2, -3]))
+
+def scaled(values, factor):
+    return [v * factor for v in values if v]
+
+assert_eq([10, 30], scaled([1, 0, 3], 10))
+
+value = 5
+assert_eq("big", "big" if value > 3 else "small")
+assert_eq("small", "big" if value > 10 else "small")
+assert_eq(8, 1 + 2 if False else 4 + 4)
+assert_eq(3, (1 + 2) if True else 0)
+assert_eq(1, 1 if value else 2)
+assert_eq(2, 1 if [] else 2)
+
+total = 0
+for n in [x for x in range(5) if x > 1]:
+    total = total + n
+assert_eq(9, total)
@@ -0,0 +1,21 @@
+i = 99
+squares = [i * i for i in range(3)]
+assert_eq([0, 1, 4], squares)
+assert_eq(99, i)
+assert_eq(None, globals().get(".i"))
+
+assert_eq([], [j for j in []])
+assert_eq(None, globals().get("j"))
+
+def in_function(n):
+    x = "kept"
+    values = [x + n for x in [1, 2, 3] if x > 1]
+    return [x, values]
+assert_eq(["kept", [12, 13]], in_function(10))
+
+nested = [[i * 10 + k for k in range(2)] + [i] for i in range(3)]
+assert_eq([[0, 1, 0], [10, 11, 1], [20, 21, 2]], nested)
+
+same_name = [[i for i in range(i)] for i in range(3)]
+assert_eq([[], [0], [0, 1]], same_name)
+assert_eq(99, i)
@@ -0,0 +1,26 @@
+odds = []
+for x in [1, 2, 3, 4, 5]:
+    if x % 2 == 0:
+        continue
+    odds.append(x)
+assert_eq([1, 3, 5], odds)
+
+i = 0
+total = 0
+while i < 10:
+    i = i + 1
+    if i > 3:
+        continue
+    total = total + i
+assert_eq(10, i)
+assert_eq(6, total)
+
+pairs = []
+for a in [1, 2, 3]:
+    for b in [1, 2, 3]:
+        if a == b:
+            continue
+        if b > 2:
+            break
+        pairs.append(a * 10 + b)
+assert_eq([12, 21, 31, 32], pairs)
@@ -0,0 +1,62 @@
+import copy
+
+nested = [[1, 2], {"a": [3, 4]}, (5, [6])]
+deep = copy.deepcopy(nested)
+assert_eq(nested, deep)
+deep[0].append(99)
+deep[1]["a"].append(100)
+deep[2][1].append(101)
+assert_eq([1, 2], nested[0])
+assert_eq([3, 4], nested[1]["a"])
+assert_eq([6], nested[2][1])
+assert_eq([1, 2, 99], deep[0])
+
+shallow = copy.copy(nested)
+assert_eq(False, id(shallow) == id(nested))
+assert_eq(True, id(shallow[0]) == id(nested[0]))
+shallow.append(7)
+assert_eq(3, len(nested))
+
+d = {"x": [1]}
+d2 = copy.copy(d)
+d2["y"] = 2
+assert_eq(False, "y" in d)
+assert_eq(True, id(d2["x"]) == id(d["x"]))
+
+t = (1, 2, 3)
+assert_eq(True, id(copy.copy(t)) == id(t))
+assert_eq(True, id(copy.deepcopy(t)) == id(t))
+t2 = (1, [2])
+assert_eq(False, id(copy.deepcopy(t2)) == id(t2))
+
+inner = [1, 2]
+shared = [inner, inner]
+shared_copy = copy.deepcopy(shared)
+assert_eq(True, id(shared_copy[0]) == id(shared_copy[1]))
+assert_eq(False, id(shared_copy[0]) == id(inner))
+
+a = [1]
+a.append(a)
+b = copy.deepcopy(a)
+assert_eq(True, id(b[1]) == id(b))
+assert_eq(False, id(b) == id(a))
+
+class Point:
+    def __init__(self, x, items):
+        self.x = x
+        self.items = items
+
+p = Point(1, [1, 2])
+p2 = copy.copy(p)
+p3 = copy.deepcopy(p)
+p2.x = 5
+assert_eq(1, p.x)
+assert_eq(True, id(p2.items) == id(p.items))
+p3.items.append(3)
+assert_eq([1, 2], p.items)
+assert_eq([1, 2, 3], p3.items)
+
+s = {1, 2}
+s2 = copy.copy(s)
+s2.add(3)
+assert_eq({1, 2}, s)
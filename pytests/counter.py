@@ -0,0 +1,32 @@
+import collections
+
+c = collections.Counter("mississippi")
+assert_eq(4, c["s"])
+assert_eq(4, c["i"])
+assert_eq(2, c["p"])
+assert_eq(1, c["m"])
+assert_eq(0, c["z"])
+assert_eq(False, "z" in c)
+assert_eq(4, len(c))
+assert_eq(11, c.total())
+
+assert_eq([("i", 4), ("s", 4)], c.most_common(2))
+assert_eq([("i", 4), ("s", 4), ("p", 2), ("m", 1)], c.most_common())
+assert_eq([], c.most_common(0))
+assert_eq("Counter({'i': 4, 's': 4, 'p': 2, 'm': 1})", repr(c))
+
+words = collections.Counter(["a", "b", "a", "c", "a", "b"])
+assert_eq([("a", 3)], words.most_common(1))
+words.update(["c", "c", "c"])
+assert_eq([("c", 4), ("a", 3), ("b", 2)], words.most_common())
+words.subtract(["a", "a"])
+assert_eq(1, words["a"])
+words["d"] = 10
+assert_eq(("d", 10), words.most_common(1)[0])
+
+from_mapping = collections.Counter({"x": 2, "y": 5})
+assert_eq([("y", 5), ("x", 2)], from_mapping.most_common())
+assert_eq("Counter()", repr(collections.Counter()))
+
+pairs = collections.Counter([(1, 2), (1, 2), (3, 4)])
+assert_eq(2, pairs[(1, 2)])
@@ -0,0 +1,44 @@
+a = [1]
+a.append(a)
+b = [1]
+b.append(b)
+assert_eq(True, a == a)
+assert_eq(True, a == b)
+assert_eq(False, a != b)
+
+c = [2]
+c.append(c)
+assert_eq(False, a == c)
+
+longer = [1]
+longer.append(longer)
+longer.append(3)
+assert_eq(False, a == longer)
+
+x = []
+y = [x]
+x.append(y)
+p = []
+q = [p]
+p.append(q)
+assert_eq(True, x == p)
+assert_eq(True, y == q)
+
+d1 = {"k": 1}
+d1["self"] = d1
+d2 = {"k": 1}
+d2["self"] = d2
+assert_eq(True, d1 == d2)
+d3 = {"k": 2}
+d3["self"] = d3
+assert_eq(False, d1 == d3)
+
+t1 = ([],)
+t1[0].append(t1)
+t2 = ([],)
+t2[0].append(t2)
+assert_eq(True, t1 == t2)
+
+assert_eq(True, [1, [2, 3]] == [1, [2, 3]])
+assert_eq(False, [1, [2, 3]] == [1, [2, 4]])
+assert_eq(True, a == b)
@@ -0,0 +1,33 @@
+a = []
+a.append(a)
+assert_eq("[[...]]", repr(a))
+assert_eq("[[...]]", str(a))
+
+b = [1, 2]
+b.append(b)
+b.append(3)
+assert_eq("[1, 2, [...], 3]", repr(b))
+
+outer = [1]
+inner = [outer]
+outer.append(inner)
+assert_eq("[1, [[...]]]", repr(outer))
+assert_eq("[[1, [...]]]", repr(inner))
+
+d = {}
+d["self"] = d
+assert_eq("{'self': {...}}", repr(d))
+
+mixed = {"items": []}
+mixed["items"].append(mixed)
+assert_eq("{'items': [{...}]}", repr(mixed))
+
+shared = [0]
+twice = [shared, shared]
+assert_eq("[[0], [0]]", repr(twice))
+
+assert_eq("[[...]]", repr(a))
+
+holder = ([],)
+holder[0].append(holder)
+assert_eq("([(...)],)", repr(holder))
@@ -0,0 +1,75 @@
+def double(f):
+    def wrapper(x, f=f):
+        return f(x) * 2
+    return wrapper
+
+@double
+def add_one(x):
+    return x + 1
+
+assert_eq(4, add_one(1))
+
+def add_ten(f):
+    def wrapper(x, f=f):
+        return f(x) + 10
+    return wrapper
+
+@add_ten
+@double
+def identity(x):
+    return x
+
+assert_eq(12, identity(1))
+
+@double
+@add_ten
+def identity2(x):
+    return x
+
+assert_eq(22, identity2(1))
+
+calls = []
+
+def register(f):
+    calls.append(f(0))
+    return f
+
+@register
+def zero(x):
+    return x
+
+assert_eq([0], calls)
+assert_eq(5, zero(5))
+
+def multiply_by(n):
+    def decorator(f, n=n):
+        def wrapper(x, f=f, n=n):
+            return f(x) * n
+        return wrapper
+    return decorator
+
+@multiply_by(3)
+def plus_two(x):
+    return x + 2
+
+assert_eq(9, plus_two(1))
+
+def tag(cls):
+    cls.tagged = True
+    return cls
+
+@tag
+class Thing:
+    def value(self):
+        return 42
+
+assert_eq(True, Thing.tagged)
+assert_eq(42, Thing().value())
+
+def outer():
+    @double
+    def inner(x):
+        return x + 5
+    return inner(0)
+
+assert_eq(10, outer())
@@ -0,0 +1,15 @@
+class Node:
+    def __init__(self):
+        self.child = None
+        self.value = 0
+
+root = Node()
+root.child = Node()
+root.child.child = Node()
+root.child.child.child = Node()
+root.child.child.child.value = 42
+root.child.child.value = 7
+
+assert_eq(42, root.child.child.child.value)
+assert_eq(7, root.child.child.value)
+assert_eq(0, root.child.value)
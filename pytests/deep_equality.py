@@ -0,0 +1,20 @@
+assert_eq(True, [[1], [2]] == [[1], [2]])
+assert_eq(False, [[1], [2]] == [[1], [3]])
+assert_eq(True, [[1], [2]] != [[1], [3]])
+assert_eq(False, [[1, 2]] == [[1]])
+
+assert_eq(True, {"a": [1]} == {"a": [1]})
+assert_eq(False, {"a": [1]} == {"a": [2]})
+assert_eq(True, {"a": [1]} != {"b": [1]})
+assert_eq(True, {"a": 1, "b": 2} == {"b": 2, "a": 1})
+
+assert_eq(True, ((1, [2]), 3) == ((1, [2]), 3))
+assert_eq(True, ((1, [2]), 3) != ((1, [2, 0]), 3))
+assert_eq(True, [{"k": (1, [2, {"x": 1.0}])}] == [{"k": (1, [2, {"x": 1}])}])
+assert_eq(False, [{"k": (1, [2, {"x": 1.5}])}] == [{"k": (1, [2, {"x": 1}])}])
+
+assert_eq(False, [1] == (1,))
+assert_eq(False, (1,) == None)
+assert_eq(True, {} != None)
+assert_eq(True, "a" != 1)
+assert_eq(False, b"a" == None)
@@ -0,0 +1,32 @@
+d = {}
+d["zebra"] = 1
+d["apple"] = 2
+d["mango"] = 3
+d[10] = 4
+d[-5] = 5
+
+assert_eq(["zebra", "apple", "mango", 10, -5], list(d.keys()))
+assert_eq([1, 2, 3, 4, 5], list(d.values()))
+assert_eq([("zebra", 1), ("apple", 2), ("mango", 3), (10, 4), (-5, 5)], list(d.items()))
+assert_eq("{'zebra': 1, 'apple': 2, 'mango': 3, 10: 4, -5: 5}", repr(d))
+
+iterated = []
+for key in d:
+    iterated.append(key)
+assert_eq(["zebra", "apple", "mango", 10, -5], iterated)
+
+d["apple"] = 20
+assert_eq(["zebra", "apple", "mango", 10, -5], list(d.keys()))
+assert_eq([1, 20, 3, 4, 5], list(d.values()))
+
+literal = {"c": 1, "a": 2, "b": 3}
+assert_eq(["c", "a", "b"], list(literal))
+assert_eq("{'c': 1, 'a': 2, 'b': 3}", str(literal))
+
+duplicated = {"x": 1, "y": 2, "x": 3}
+assert_eq([("x", 3), ("y", 2)], list(duplicated.items()))
+
+copied = dict(literal)
+copied["d"] = 4
+assert_eq(["c", "a", "b", "d"], list(copied.keys()))
+assert_eq([], list({}.items()))
@@ -0,0 +1,25 @@
+d = {"a": "b", 1: 2}
+assert_eq(True, "a" in d)
+assert_eq(False, "b" in d)
+assert_eq(True, "b" not in d)
+assert_eq(False, "a" not in d)
+assert_eq(True, 1 in d)
+assert_eq(False, 2 in d)
+assert_eq(True, 2 not in d)
+assert_eq(True, 1.0 in d)
+
+assert_eq(True, d.__contains__("a"))
+assert_eq(False, d.__contains__("b"))
+
+values = {"x": "y", "y": None}
+assert_eq(True, "y" in values)
+assert_eq(False, None in values)
+
+empty = {}
+assert_eq(False, "a" in empty)
+assert_eq(True, "a" not in empty)
+
+d["b"] = "a"
+assert_eq(True, "b" in d)
+assert_eq(True, "b" in d and "a" in d)
+assert_eq([k for k in ["a", "b", "c", 2] if k not in d], ["c", 2])
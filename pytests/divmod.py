@@ -0,0 +1,51 @@
+assert_eq(True, divmod(7, 3) == (2, 1))
+assert_eq((-3, 2), divmod(-7, 3))
+assert_eq((-3, -2), divmod(7, -3))
+assert_eq((2, -1), divmod(-7, -3))
+assert_eq((2, 0), divmod(6, 3))
+assert_eq((-4.0, 0.5), divmod(-7.5, 2))
+
+result = divmod(-17, 5)
+assert_eq(-17, result[0] * 5 + result[1])
+
+assert_eq(2, -7 % 3)
+assert_eq(-2, 7 % -3)
+assert_eq(0.5, -7.5 % 2)
+
+assert_eq(-4, -7 // 2)
+assert_eq(-4.0, -7.5 // 2)
+
+caught = None
+try:
+    x = 1 // 0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("integer division or modulo by zero", caught)
+
+caught = None
+try:
+    x = 1.0 // 0.0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("float floor division by zero", caught)
+
+caught = None
+try:
+    x = (1).__floordiv__(0)
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("integer division or modulo by zero", caught)
+
+caught = None
+try:
+    x = 1 / 0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("division by zero", caught)
+
+caught = None
+try:
+    x = 1.0 / 0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("float division by zero", caught)
@@ -0,0 +1,15 @@
+import json
+import gc
+
+ran = []
+if __name__ == "__main__":
+    ran.append("main")
+if json.__name__ == "__main__":
+    ran.append("json")
+assert_eq(ran, ["main"])
+assert_eq(json.__name__, "json")
+assert_eq(gc.__name__, "gc")
+
+def name_from_function():
+    return __name__
+assert_eq(name_from_function(), "__main__")
@@ -0,0 +1,42 @@
+calls = []
+
+def check(name, result):
+    calls.append(name)
+    return result
+
+def classify(n):
+    if check("negative", n < 0):
+        return "negative"
+    elif check("zero", n == 0):
+        return "zero"
+    elif check("small", n < 10):
+        return "small"
+    else:
+        return "big"
+
+assert_eq(classify(-1), "negative")
+assert_eq(calls, ["negative"])
+
+calls = []
+assert_eq(classify(0), "zero")
+assert_eq(calls, ["negative", "zero"])
+
+calls = []
+assert_eq(classify(5), "small")
+assert_eq(calls, ["negative", "zero", "small"])
+
+calls = []
+assert_eq(classify(50), "big")
+assert_eq(calls, ["negative", "zero", "small"])
+
+def no_else(n):
+    result = "none"
+    if n == 1:
+        result = "one"
+    elif n == 2:
+        result = "two"
+    return result
+
+assert_eq(no_else(1), "one")
+assert_eq(no_else(2), "two")
+assert_eq(no_else(3), "none")
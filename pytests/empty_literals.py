@@ -0,0 +1,29 @@
+empty_list = []
+empty_dict = {}
+empty_tuple = ()
+
+assert_eq(len(empty_list), 0)
+assert_eq(len(empty_dict), 0)
+assert_eq(len(empty_tuple), 0)
+
+assert_eq(repr(empty_list), "[]")
+assert_eq(repr(empty_dict), "{}")
+assert_eq(repr(empty_tuple), "()")
+
+assert_eq(empty_dict, dict())
+assert_eq(empty_tuple, tuple())
+assert_eq(tuple([1, 2]), tuple([1, 2]))
+assert_eq(repr(tuple([1])), "(1,)")
+assert_eq(repr(tuple([1, "a"])), "(1, 'a')")
+assert_eq(len(tuple([1, 2, 3])), 3)
+assert_eq(tuple([5, 6])[1], 6)
+
+empty_dict["key"] = "value"
+assert_eq(len(empty_dict), 1)
+assert_eq({} == {}, True)
+assert_eq(() == (), True)
+
+total = 0
+for item in tuple([1, 2, 3]):
+    total += item
+assert_eq(total, 6)
@@ -0,0 +1,38 @@
+try:
+    raise ValueError("bad")
+except ValueError as e:
+    assert_eq("bad", str(e))
+    assert_eq(("bad",), e.args)
+    assert_eq("ValueError('bad')", repr(e))
+
+e = TypeError()
+assert_eq("", str(e))
+assert_eq((), e.args)
+assert_eq("TypeError()", repr(e))
+
+e = IndexError("index", 3)
+assert_eq("('index', 3)", str(e))
+assert_eq(("index", 3), e.args)
+assert_eq("IndexError('index', 3)", repr(e))
+
+assert_eq("42", str(ValueError(42)))
+
+try:
+    x = [1, 2][5]
+except IndexError as e:
+    assert_eq(("list index out of range",), e.args)
+
+try:
+    assert 1 == 2, 42
+except AssertionError as e:
+    assert_eq((42,), e.args)
+
+try:
+    x = "a" + 1
+except Exception as e:
+    assert_eq("can only concatenate str (not \"int\") to str", str(e))
+
+try:
+    x = pow(2, 3, 0)
+except ValueError as e:
+    assert_eq("pow() 3rd argument cannot be 0", str(e))
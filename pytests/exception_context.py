@@ -0,0 +1,55 @@
+class Oops:
+    code = 1
+
+original = Oops()
+try:
+    try:
+        raise original
+    except Oops:
+        x = "a" + 1
+except TypeError as e:
+    assert_eq(id(original), id(e.__context__))
+
+def fail():
+    x = "a" + 1
+
+try:
+    try:
+        raise original
+    except Oops:
+        fail()
+except TypeError as e:
+    assert_eq(id(original), id(e.__context__))
+
+second = Oops()
+try:
+    try:
+        raise second
+    except Oops:
+        raise
+except Oops as e:
+    assert_eq(False, "__context__" in vars(e))
+
+try:
+    try:
+        x = "a" + 1
+    except TypeError as first:
+        try:
+            raise second
+        except Oops:
+            raise
+except Oops as e:
+    assert_eq(id(first), id(e.__context__))
+
+try:
+    try:
+        raise ValueError("a")
+    except ValueError as a:
+        try:
+            raise TypeError("b")
+        except TypeError:
+            raise a
+except ValueError as e:
+    assert_eq(id(a), id(e))
+    assert_eq("b", str(e.__context__))
+    assert_eq(None, e.__context__.__context__)
@@ -0,0 +1,75 @@
+exec("x = 5")
+assert_eq(x, 5)
+
+code = compile("y = x * 2")
+exec(code)
+assert_eq(y, 10)
+exec(code)
+assert_eq(y, 10)
+
+def exec_in_function(a):
+    b = a * 2
+    exec("z = a + b + x")
+    exec("assert_eq(a + b, 3)")
+    return a
+assert_eq(exec_in_function(1), 1)
+
+error = None
+try:
+    z
+except NameError as e:
+    error = str(e)
+assert_eq(error, "name 'z' is not defined")
+
+def exec_sees_its_own_names():
+    exec("w = 2\nassert_eq(w * 2, 4)\ndel w")
+exec_sees_its_own_names()
+
+try:
+    compile("x = (")
+    error = None
+except SyntaxError as e:
+    error = "SyntaxError"
+assert_eq(error, "SyntaxError")
+
+try:
+    exec("w = [1][5]")
+    error = None
+except IndexError:
+    error = "IndexError"
+assert_eq(error, "IndexError")
+
+exec("def double(n):\n    return n * 2")
+assert_eq(double(21), 42)
+
+assert_eq(eval("1 + 2"), 3)
+assert_eq(eval("x * 2 + y"), 20)
+assert_eq(eval("double(x)"), 10)
+assert_eq(eval("[x, y]"), [5, 10])
+
+try:
+    eval("x = 1")
+    error = None
+except SyntaxError as e:
+    error = "SyntaxError"
+assert_eq(error, "SyntaxError")
+assert_eq(x, 5)
+
+try:
+    eval("[1][5]")
+    error = None
+except IndexError:
+    error = "IndexError"
+assert_eq(error, "IndexError")
+
+g = 100
+def eval_locals(a):
+    b = a * 2
+    return eval("a + b + g")
+assert_eq(eval_locals(1), 103)
+
+def eval_shadowed_global():
+    x = "local"
+    return eval("x")
+assert_eq(eval_shadowed_global(), "local")
+assert_eq(eval("x"), 5)
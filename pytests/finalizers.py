@@ -0,0 +1,63 @@
+import gc
+
+log = []
+
+class Resource:
+    def __init__(self, name):
+        self.name = name
+
+    def __del__(self):
+        log.append(self.name)
+
+def use_resource():
+    r = Resource("local")
+    return r.name
+
+assert_eq("local", use_resource())
+gc.collect()
+assert_eq(["local"], log)
+
+kept = Resource("kept")
+gc.collect()
+assert_eq(["local"], log)
+
+kept = None
+gc.collect()
+assert_eq(["local", "kept"], log)
+
+holder = [Resource("in list")]
+gc.collect()
+assert_eq(2, len(log))
+holder = []
+gc.collect()
+assert_eq(["local", "kept", "in list"], log)
+
+survivors = []
+
+class Phoenix:
+    def __del__(self):
+        log.append("phoenix")
+        survivors.append(self)
+
+p = Phoenix()
+p = None
+gc.collect()
+assert_eq(1, len(survivors))
+survivors = []
+gc.collect()
+phoenix_runs = 0
+for entry in log:
+    if entry == "phoenix":
+        phoenix_runs += 1
+assert_eq(1, phoenix_runs)
+
+class Broken:
+    def __del__(self):
+        log.append("broken")
+        x = "a" + 1
+
+b = Broken()
+b = None
+gc.collect()
+assert_eq("broken", log[len(log) - 1])
+assert_eq(0, gc.collect())
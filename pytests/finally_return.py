@@ -0,0 +1,64 @@
+log = []
+
+def overrides_return():
+    try:
+        return 1
+    finally:
+        return 2
+
+assert_eq(2, overrides_return())
+
+def runs_before_returning():
+    try:
+        log.append("body")
+        return "from body"
+    finally:
+        log.append("finally")
+
+assert_eq("from body", runs_before_returning())
+assert_eq(["body", "finally"], log)
+
+def swallows_exception():
+    try:
+        raise ValueError("lost")
+    finally:
+        return "from finally"
+
+assert_eq("from finally", swallows_exception())
+
+def returns_from_handler():
+    try:
+        raise ValueError("bad")
+    except ValueError:
+        return "handler"
+    finally:
+        log.append("handler finally")
+
+log = []
+assert_eq("handler", returns_from_handler())
+assert_eq(["handler finally"], log)
+
+def nested():
+    try:
+        try:
+            return "inner"
+        finally:
+            log.append("inner finally")
+    finally:
+        log.append("outer finally")
+
+log = []
+assert_eq("inner", nested())
+assert_eq(["inner finally", "outer finally"], log)
+
+def in_loop():
+    for i in [1, 2, 3]:
+        try:
+            if i == 2:
+                return i
+        finally:
+            log.append(i)
+
+log = []
+assert_eq(2, in_loop())
+assert_eq([1, 2], log)
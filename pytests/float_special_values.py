@@ -0,0 +1,21 @@
+inf = float("inf")
+nan = float("nan")
+
+assert_eq(True, inf > 1e308)
+assert_eq(True, float("-inf") < -1e308)
+assert_eq(True, float("  Infinity ") == inf)
+assert_eq("inf", str(inf))
+assert_eq("-inf", str(-inf))
+assert_eq("nan", str(nan))
+
+assert_eq(True, nan != nan)
+assert_eq(False, nan == nan)
+assert_eq(False, nan < 1.0)
+assert_eq(False, nan >= 1.0)
+
+assert_eq(1.5, float("1.5"))
+assert_eq(-2.0, float(" -2 "))
+assert_eq(3.0, float(3))
+assert_eq(1.0, float(True))
+assert_eq(0.0, float())
+assert_eq(True, 2.5 >= 2.5)
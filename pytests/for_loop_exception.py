@@ -0,0 +1,33 @@
+def gen():
+    yield 1
+    raise ValueError("boom")
+
+seen = []
+caught = None
+try:
+    for x in gen():
+        seen.append(x)
+except ValueError as e:
+    caught = str(e)
+assert_eq(seen, [1])
+assert_eq(caught, "boom")
+
+def loop_in_function():
+    total = 0
+    for x in gen():
+        total = total + x
+    return total
+
+caught = None
+try:
+    loop_in_function()
+except ValueError as e:
+    caught = str(e)
+assert_eq(caught, "boom")
+
+caught = None
+try:
+    squares = [x * x for x in gen()]
+except ValueError as e:
+    caught = str(e)
+assert_eq(caught, "boom")
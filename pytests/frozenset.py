@@ -0,0 +1,50 @@
+primes = frozenset([2, 3, 5, 7, 5, 3])
+assert_eq(4, len(primes))
+assert_eq(True, 5 in primes)
+assert_eq(False, 4 in primes)
+assert_eq(True, 4 not in primes)
+assert_eq(True, 2.0 in primes)
+
+assert_eq(frozenset([7, 5, 3, 2]), primes)
+assert_eq(hash(frozenset([7, 5, 3, 2])), hash(primes))
+assert_eq(hash(1), hash(1.0))
+assert_eq(hash((1, "a")), hash((1, "a")))
+
+owners = {}
+owners[frozenset(["alice", "bob"])] = "shared"
+owners[frozenset(["carol"])] = "private"
+assert_eq("shared", owners[frozenset(["bob", "alice"])])
+assert_eq(True, frozenset(["carol"]) in owners)
+assert_eq(2, len(owners))
+owners[frozenset(["bob", "alice", "bob"])] = "team"
+assert_eq(2, len(owners))
+assert_eq("team", owners[frozenset(["alice", "bob"])])
+
+nested = frozenset([frozenset([1, 2]), frozenset([2, 1]), frozenset()])
+assert_eq(2, len(nested))
+assert_eq(True, frozenset([2, 1]) in nested)
+
+odds = frozenset([1, 3, 5, 7])
+assert_eq(frozenset([3, 5, 7]), primes.intersection(odds))
+assert_eq(frozenset([1, 2, 3, 5, 7]), primes.union(odds))
+assert_eq(frozenset([2]), primes - odds)
+assert_eq(frozenset([1, 2]), primes ^ odds)
+assert_eq(frozenset([1, 2]), primes.symmetric_difference([1, 3, 5, 7]))
+assert_eq(True, frozenset([3, 5]) <= primes)
+assert_eq(True, frozenset([3, 5]) < primes)
+assert_eq(False, primes < primes)
+assert_eq(True, primes.issuperset([2, 3]))
+assert_eq(True, primes.issubset(range(10)))
+assert_eq(True, primes.isdisjoint([4, 6]))
+
+assert_eq("frozenset()", repr(frozenset()))
+assert_eq("frozenset({1, 2})", str(frozenset([1, 2])))
+
+total = 0
+for p in primes:
+    total = total + p
+assert_eq(17, total)
+
+assert_eq(True, 2 in [1, 2])
+assert_eq(True, "b" in ("a", "b"))
+assert_eq(False, 3 in (1, 2))
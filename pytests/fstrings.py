@@ -0,0 +1,36 @@
+assert_eq("3.14", f"{3.14159:.2f}")
+assert_eq("ff", f"{255:x}")
+
+name = "world"
+count = 3
+assert_eq("hello world", f"hello {name}")
+assert_eq("3 items", f"{count} items")
+assert_eq("6", f"{count * 2}")
+assert_eq("", f"")
+assert_eq("{braces}", f"{{braces}}")
+assert_eq("'world'", f"{name!r}")
+assert_eq("world", f"{name!s}")
+assert_eq("[1, 2]", f"{[1, 2]}")
+assert_eq("True", f"{count != 2}")
+assert_eq("None", f"{None}")
+
+assert_eq("  42", f"{42:4}")
+assert_eq("0042", f"{42:04d}")
+assert_eq("+42", f"{42:+}")
+assert_eq("1,000,000", f"{1000000:,}")
+assert_eq("0b101", f"{5:#b}")
+assert_eq("1.50e+03", f"{1500.0:.2e}")
+assert_eq("25.0%", f"{0.25:.1%}")
+assert_eq("ab  |", f"{'ab':4}|")
+assert_eq("  ab", f"{'ab':>4}")
+assert_eq("**ab**", f"{'ab':*^6}")
+assert_eq("True", f"{True}")
+
+assert_eq("3.14", format(3.14159, ".2f"))
+assert_eq("ff", format(255, "x"))
+assert_eq("7", format(7))
+
+x = 1
+assert_eq("a\n1", f"a\n{x}")
+assert_eq("\t1\x41é\101", f"\t{x}\x41é\101")
+assert_eq("it's 1", f'it\'s {x}')
@@ -0,0 +1,22 @@
+import functools
+
+def add(a, b):
+    return a + b
+
+def to_digits(total, digit):
+    return total * 10 + digit
+
+assert_eq(10, functools.reduce(add, [1, 2, 3, 4]))
+assert_eq(15, functools.reduce(add, [1, 2, 3, 4], 5))
+assert_eq(1234, functools.reduce(to_digits, (1, 2, 3, 4)))
+assert_eq(42, functools.reduce(add, [42]))
+assert_eq(7, functools.reduce(add, [], 7))
+assert_eq("abc", functools.reduce(add, ["a", "b", "c"]))
+assert_eq(6, functools.reduce(add, range(4)))
+
+error = None
+try:
+    functools.reduce(add, 5)
+except TypeError as e:
+    error = str(e)
+assert_eq("'int' object is not iterable", error)
@@ -0,0 +1,45 @@
+def accumulate():
+    total = 0
+    while True:
+        value = yield total
+        if value == None:
+            return total
+        total = total + value
+
+acc = accumulate()
+assert_eq(0, acc.send(None))
+assert_eq(5, acc.send(5))
+assert_eq(7, acc.send(2))
+assert_eq(17, acc.send(10))
+
+def echo(received):
+    first = yield "ready"
+    received.append(first)
+    second = yield first * 2
+    received.append(second)
+
+received = []
+gen = echo(received)
+assert_eq("ready", gen.__next__())
+assert_eq(6, gen.send(3))
+assert_eq([3], received)
+assert_eq([], list(gen))
+assert_eq([3, None], received)
+
+def no_send():
+    x = yield 1
+    yield x
+
+gen = no_send()
+assert_eq(1, gen.__next__())
+assert_eq(None, gen.__next__())
+
+def doubled_input():
+    value = yield
+    while True:
+        value = yield value * 2
+
+doubler = doubled_input()
+assert_eq(None, doubler.send(None))
+assert_eq(8, doubler.send(4))
+assert_eq(20, doubler.send(10))
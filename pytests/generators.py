@@ -0,0 +1,45 @@
+def countdown(n):
+    while n > 0:
+        yield n
+        n = n - 1
+
+assert_eq([3, 2, 1], list(countdown(3)))
+
+collected = []
+for value in countdown(2):
+    collected.append(value * 10)
+assert_eq([20, 10], collected)
+
+def with_return():
+    yield 1
+    yield 2
+    return "done"
+
+gen = with_return()
+assert_eq(1, gen.__next__())
+assert_eq(2, gen.__next__())
+assert_eq([], list(gen))
+
+def bare():
+    yield
+
+assert_eq([None], list(bare()))
+
+def squares(items):
+    for item in items:
+        yield item * item
+
+assert_eq([1, 4, 9], [x for x in squares([1, 2, 3])])
+
+def lazy(log):
+    log.append("started")
+    yield 1
+    log.append("resumed")
+
+log = []
+started = lazy(log)
+assert_eq([], log)
+assert_eq(1, started.__next__())
+assert_eq(["started"], log)
+assert_eq([], list(started))
+assert_eq(["started", "resumed"], log)
@@ -0,0 +1,24 @@
+counter = 10
+
+def read_counter():
+    return counter
+
+def add(a, b):
+    return a + b
+
+g = globals()
+assert_eq(g["counter"], 10)
+assert_eq(g["add"](1, 2), 3)
+assert_eq(g.get("undefined_name"), None)
+assert_eq(locals()["counter"], 10)
+
+assert_eq(read_counter(), 10)
+counter = 20
+assert_eq(read_counter(), 20)
+assert_eq(globals()["counter"], 20)
+
+def shadow():
+    counter = 1
+    return [counter, globals()["counter"]]
+
+assert_eq(shadow(), [1, 20])
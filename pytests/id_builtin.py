@@ -0,0 +1,14 @@
+x = [1, 2]
+assert_eq(id(x) == id(x), True)
+
+y = [1, 2]
+assert_eq(id(x) == id(y), False)
+
+a = 100
+b = 50 + 50
+assert_eq(id(a) == id(b), True)
+
+def same(value):
+    return value
+
+assert_eq(id(same(x)) == id(x), True)
@@ -0,0 +1,54 @@
+class Box:
+    label = "default"
+
+    def __init__(self, value):
+        self.value = value
+
+    def remember(self, item):
+        self.saved = item
+
+    def recall(self):
+        return self.saved
+
+    def relabel(self, label):
+        self.label = label
+
+b = Box(1)
+b.remember([1, 2])
+assert_eq([1, 2], b.recall())
+assert_eq([1, 2], b.saved)
+
+b.extra = "outside"
+assert_eq("outside", b.extra)
+b.value = b.value + 10
+assert_eq(11, b.value)
+
+other = Box(2)
+other.remember("other")
+assert_eq("other", other.recall())
+assert_eq([1, 2], b.recall())
+
+assert_eq("default", b.label)
+b.relabel("mine")
+assert_eq("mine", b.label)
+assert_eq("default", other.label)
+assert_eq("default", Box.label)
+
+class Counter:
+    count = 0
+
+    def bump(self):
+        self.count += 1
+        return self.count
+
+c = Counter()
+c.bump()
+assert_eq(2, c.bump())
+assert_eq(0, Counter.count)
+assert_eq(0, Counter().count)
+
+def shout(text):
+    return text + "!"
+
+b.callback = shout
+assert_eq("hi!", b.callback("hi"))
@@ -0,0 +1,35 @@
+import itertools
+
+assert_eq([1, 2, 3, 4, 5], list(itertools.chain([1, 2], (3,), [], [4, 5])))
+assert_eq([], list(itertools.chain()))
+assert_eq(["x", "x", "x"], list(itertools.repeat("x", 3)))
+assert_eq([], list(itertools.repeat("x", 0)))
+assert_eq([], list(itertools.repeat("x", -2)))
+
+total = 0
+for value in itertools.chain(range(3), [10]):
+    total = total + value
+assert_eq(13, total)
+
+counted = []
+for value in itertools.count(5, 2):
+    if value > 11:
+        break
+    counted.append(value)
+assert_eq([5, 7, 9, 11], counted)
+
+numbers = itertools.count()
+assert_eq(0, numbers.__next__())
+assert_eq(1, numbers.__next__())
+assert_eq(2, numbers.__next__())
+
+forever = itertools.repeat(7)
+assert_eq(7, forever.__next__())
+assert_eq(7, forever.__next__())
+
+error = None
+try:
+    list(itertools.chain([1], 5))
+except TypeError as e:
+    error = str(e)
+assert_eq("'int' object is not iterable", error)
@@ -0,0 +1,27 @@
+import json
+
+data = {"name": "horse", "tags": ["a", "b"], "nested": {"x": [1, 2.5, None], "ok": True, "no": False}, "empty": {}, "list": []}
+text = json.dumps(data)
+assert_eq('{"name": "horse", "tags": ["a", "b"], "nested": {"x": [1, 2.5, null], "ok": true, "no": false}, "empty": {}, "list": []}', text)
+assert_eq(data, json.loads(text))
+assert_eq(text, json.dumps(json.loads(text)))
+
+assert_eq('"a\\"b\\\\c\\nd\\te"', json.dumps("a\"b\\c\nd\te"))
+assert_eq("a\"b\\c\nd\te", json.loads(json.dumps("a\"b\\c\nd\te")))
+assert_eq('"caf\\u00e9"', json.dumps("café"))
+assert_eq("café", json.loads('"caf\\u00e9"'))
+assert_eq("\U0001F600", json.loads('"\\ud83d\\ude00"'))
+assert_eq('"\\ud83d\\ude00"', json.dumps("\U0001F600"))
+assert_eq('"\\u0001"', json.dumps("\x01"))
+
+assert_eq("null", json.dumps(None))
+assert_eq("true", json.dumps(True))
+assert_eq("-7", json.dumps(-7))
+assert_eq("[1, 2]", json.dumps((1, 2)))
+assert_eq('{"1": 2}', json.dumps({1: 2}))
+
+assert_eq([1, -2, 3.5, 100.0, 0.01], json.loads("[1, -2, 3.5, 1e2, 1E-2]"))
+assert_eq({"a": [{"b": None}]}, json.loads(' { "a" : [ { "b" : null } ] } '))
+assert_eq({"a": 2}, json.loads('{"a": 1, "a": 2}'))
+assert_eq("/", json.loads('"\\/"'))
+assert_eq(True, json.loads("true"))
@@ -0,0 +1,23 @@
+def describe(name, greeting="hello", punctuation="!"):
+    return greeting + " " + name + punctuation
+
+assert_eq(describe("bob"), "hello bob!")
+assert_eq(describe("bob", "hi"), "hi bob!")
+assert_eq(describe("bob", punctuation="?"), "hello bob?")
+assert_eq(describe(punctuation=".", name="alice"), "hello alice.")
+assert_eq(describe("alice", greeting="hey", punctuation="..."), "hey alice...")
+
+class Counter:
+    def __init__(self, start=0, step=1):
+        self.value = start
+        self.step = step
+
+    def advance(self, times=1):
+        self.value = self.value + self.step * times
+        return self.value
+
+c = Counter(step=5)
+assert_eq(c.advance(), 5)
+assert_eq(c.advance(times=2), 15)
+
+print("a", "b", sep=", ")
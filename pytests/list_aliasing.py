@@ -0,0 +1,37 @@
+a = [1, 2]
+b = a
+b.append(3)
+assert_eq([1, 2, 3], a)
+a.append(4)
+assert_eq([1, 2, 3, 4], b)
+
+copy = a + []
+copy.append(5)
+assert_eq([1, 2, 3, 4], a)
+assert_eq([1, 2, 3, 4, 5], copy)
+a.append(6)
+assert_eq([1, 2, 3, 4, 5], copy)
+
+b[0] = 10
+assert_eq(10, a[0])
+assert_eq(1, copy[0])
+
+joined = a + [7]
+joined[1] = 20
+assert_eq(2, a[1])
+assert_eq(20, joined[1])
+
+inner = [0]
+outer = [inner] + []
+outer[0].append(1)
+assert_eq([0, 1], inner)
+
+def append_to(items, value):
+    items.append(value)
+
+append_to(b, 99)
+assert_eq(99, a[len(a) - 1])
+
+holder = {"items": a}
+holder["items"].append(100)
+assert_eq(100, a[len(a) - 1])
@@ -0,0 +1,50 @@
+a = [1]
+b = a
+a += [2]
+assert_eq([1, 2], b)
+assert_eq([1, 2], a)
+
+a += (3, 4)
+assert_eq([1, 2, 3, 4], b)
+a += "xy"
+assert_eq([1, 2, 3, 4, "x", "y"], b)
+
+c = [0]
+c += c
+assert_eq([0, 0], c)
+
+d = a + [5]
+assert_eq(7, len(d))
+assert_eq(6, len(b))
+
+holder = {"items": [1]}
+alias = holder["items"]
+holder["items"] += [2]
+assert_eq([1, 2], alias)
+
+class Box:
+    def __init__(self):
+        self.items = []
+
+box = Box()
+kept = box.items
+box.items += [9]
+assert_eq([9], kept)
+
+n = 1
+m = n
+n += 1
+assert_eq(1, m)
+assert_eq(2, n)
+s = "ab"
+t = s
+s += "c"
+assert_eq("ab", t)
+assert_eq("abc", s)
+
+def extend_argument(items):
+    items += [3]
+
+mine = [1, 2]
+extend_argument(mine)
+assert_eq([1, 2, 3], mine)
@@ -0,0 +1,22 @@
+def f(a, b=2):
+    c = a + b
+    return locals()
+
+d = f(1)
+assert_eq(len(d), 3)
+assert_eq(d["a"], 1)
+assert_eq(d["b"], 2)
+assert_eq(d["c"], 3)
+assert_eq(d.get("missing"), None)
+assert_eq(d.get("missing", 10), 10)
+assert_eq(list(d.keys()), ["a", "b", "c"])
+assert_eq(str(d), "{'a': 1, 'b': 2, 'c': 3}")
+
+def g():
+    return locals()
+
+assert_eq(len(g()), 0)
+
+assert_eq([1, 2] == [1, 2], True)
+assert_eq([1, 2] == [2, 1], False)
+assert_eq([1, 2] == [1, 2, 3], False)
@@ -0,0 +1,24 @@
+def double(x):
+    return x * 2
+
+def is_even(x):
+    return x % 2 == 0
+
+xs = [1, 2, 3, 4, 5, 6]
+assert_eq([4, 8, 12], list(map(double, filter(is_even, xs))))
+assert_eq([2, 4, 6, 8, 10, 12], list(filter(is_even, map(double, xs))))
+assert_eq([0, 4, 8], list(map(double, map(double, range(3)))))
+
+pipeline = map(double, filter(is_even, xs))
+assert_eq(id(pipeline), id(pipeline.__iter__()))
+
+total = 0
+for value in map(double, filter(is_even, xs)):
+    total = total + value
+assert_eq(24, total)
+
+iterator = xs.__iter__()
+assert_eq(id(iterator), id(iterator.__iter__()))
+assert_eq([2, 4, 6, 8, 10, 12], list(map(double, iterator)))
+
+assert_eq([], list(map(double, filter(is_even, [1, 3, 5]))))
@@ -0,0 +1,9 @@
+assert_eq("ABC", "abc".upper())
+assert_eq("abc", "ABC".lower())
+assert_eq(3, [1, 2, 3].__len__())
+assert_eq(None, [1, 2, 3].append(4))
+nested = [[1, 2, 3]]
+nested[0].append(4)
+assert_eq([1, 2, 3, 4], nested[0])
+assert_eq([[1, 2, 3, 4]], nested)
+assert_eq(5, len([1, 2] + [3, 4, 5]))
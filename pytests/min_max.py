@@ -0,0 +1,20 @@
+assert_eq(0, max([], default=0))
+assert_eq(None, min([], default=None))
+assert_eq("b", min(["aa", "b"], key=len))
+assert_eq("aa", max(["aa", "b"], key=len))
+assert_eq(5, max([3, 5, 1]))
+assert_eq(1, min([3, 5, 1]))
+assert_eq(9, max(4, 9, 2))
+assert_eq(2, min(4, 9, 2))
+assert_eq(2.5, max(1, 2.5, 2))
+assert_eq(7, max((7,), default=0))
+assert_eq(3, max(range(4)))
+
+def negate(x):
+    return 0 - x
+
+assert_eq(1, max([3, 5, 1], key=negate))
+assert_eq(2, min(-3, 2, key=abs))
+assert_eq(4, max(4, -4, key=abs))
+assert_eq(-4, max(-4, 4, key=abs))
+assert_eq("ab", min(["ab", "cd", "efg"], key=len))
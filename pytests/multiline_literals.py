@@ -0,0 +1,10 @@
+numbers = [1,
+    2,
+        3]
+assert_eq(3, len(numbers))
+total = numbers[0] + numbers[1] + numbers[2]
+assert_eq(6, total)
+assert_eq(3, len([
+    1, 2,
+    3
+]))
@@ -0,0 +1,29 @@
+t = (1, 2)
+assert_eq(2, t[-1])
+assert_eq(1, t[-2])
+
+error = None
+try:
+    t[-3]
+except IndexError as e:
+    error = str(e)
+assert_eq("tuple index out of range", error)
+
+error = None
+try:
+    t["0"]
+except TypeError as e:
+    error = str(e)
+assert_eq("tuple indices must be integers or slices, not str", error)
+
+l = [1, 2, 3]
+assert_eq(3, l[-1])
+l[-1] = 4
+assert_eq([1, 2, 4], l)
+
+error = None
+try:
+    l[-4]
+except IndexError as e:
+    error = str(e)
+assert_eq("list index out of range", error)
@@ -0,0 +1,23 @@
+def classify(numbers):
+    small = 0
+    big = 0
+    for n in numbers:
+        if n > 2:
+            if n > 4:
+                big = big + 1
+            else:
+                small = small + 1
+        i = 0
+        while i < n:
+            if i == 1:
+                break
+            i = i + 1
+    return big * 10 + small
+
+assert_eq(22, classify([1, 3, 4, 5, 6]))
+
+total = 0
+for x in [1, 2, 3]:
+    if x > 1:
+        total = total + x
+assert_eq(5, total)
@@ -0,0 +1,48 @@
+log = []
+try:
+    try:
+        raise ValueError("inner")
+    except TypeError:
+        log.append("inner handler")
+    except IndexError:
+        log.append("inner handler")
+    log.append("after inner")
+except ValueError as e:
+    log.append("outer " + str(e))
+assert_eq(["outer inner"], log)
+
+def raises():
+    try:
+        x = [1][3]
+    except ValueError:
+        log.append("function handler")
+    finally:
+        log.append("function finally")
+
+log = []
+for i in [1, 2]:
+    try:
+        try:
+            raises()
+        except TypeError:
+            log.append("middle handler")
+        finally:
+            log.append("middle finally")
+    except IndexError:
+        log.append("outer handler")
+assert_eq(["function finally", "middle finally", "outer handler", "function finally", "middle finally", "outer handler"], log)
+
+log = []
+try:
+    try:
+        raise TypeError("first")
+    except TypeError:
+        try:
+            raise IndexError("second")
+        except ValueError:
+            log.append("wrong handler")
+except IndexError as e:
+    log.append(str(e))
+except TypeError:
+    log.append("wrong handler")
+assert_eq(["second"], log)
@@ -0,0 +1,30 @@
+assert_eq(hash(1), hash(1.0))
+assert_eq(hash(0), hash(0.0))
+assert_eq(hash(0), hash(-0.0))
+assert_eq(hash(-7), hash(-7.0))
+assert_eq(hash(True), hash(1.0))
+assert_eq(hash(1180591620717411303424), hash(1180591620717411303424.0))
+assert_eq(-2, hash(-1.0))
+
+d = {1: "a"}
+assert_eq("a", d[1.0])
+d[1.0] = "b"
+assert_eq(1, len(d))
+assert_eq("b", d[1])
+
+mixed = {}
+mixed[2] = "int"
+mixed[2.0] = "float"
+mixed[2.5] = "half"
+assert_eq(2, len(mixed))
+assert_eq("float", mixed[2])
+assert_eq("half", mixed[2.5])
+assert_eq([2, 2.5], list(mixed.keys()))
+
+s = {1, 1.0, 2.0, 2, 3}
+assert_eq(3, len(s))
+assert_eq(True, 3.0 in s)
+assert_eq(False, 3.5 in s)
+
+assert_eq(True, 1.0 in {1: None})
+assert_eq(1, len({0: "a", 0.0: "b", False: "c"}))
@@ -0,0 +1,39 @@
+class Item:
+    def __init__(self, name):
+        self.name = name
+    def upper_name(self):
+        return self.name.upper()
+
+class Box:
+    def __init__(self):
+        self.items = [Item("a"), Item("b")]
+    def get_list(self):
+        return self.items
+    def get_self(self):
+        return self
+
+obj = Box()
+assert_eq(obj.get_list()[0].name, "a")
+assert_eq(obj.get_list()[1].upper_name(), "B")
+assert_eq(obj.items[1].name, "b")
+assert_eq(obj.get_self().get_list()[0].upper_name().lower(), "a")
+assert_eq([[1, 2], [3]][0][1], 2)
+grid = [[1, 2], [3, 4]]
+assert_eq(grid[1][0], 3)
+assert_eq(obj.get_self().items[0].name + "!", "a!")
+
+def add_one(x):
+    return x + 1
+
+def add_ten(x):
+    return x + 10
+
+def get_adder():
+    return add_one
+
+def adders():
+    return [add_one, add_ten]
+
+assert_eq(get_adder()(3), 4)
+assert_eq(adders()[1](5), 15)
+assert_eq(adders()[0](adders()[1](0)), 11)
@@ -0,0 +1,14 @@
+assert_eq(24, pow(2, 10, 1000))
+assert_eq(1024, pow(2, 10))
+assert_eq(1, pow(7, 0))
+assert_eq(-8, pow(-2, 3))
+assert_eq(0.25, pow(2, -2))
+assert_eq(6.25, pow(2.5, 2))
+assert_eq(3.0, pow(9, 0.5))
+assert_eq(9, pow(3, 200, 13))
+assert_eq(2, pow(-2, 3, 5))
+assert_eq(-2, pow(2, 3, -5))
+assert_eq(0, pow(5, 0, 1))
+assert_eq(5, pow(3, -1, 7))
+assert_eq(1024, pow(2, 10, None))
+assert_eq(445, pow(4, 13, 497))
@@ -0,0 +1,23 @@
+assert_eq(0, 1 // 2)
+assert_eq(-4, -7 // 2)
+assert_eq(-4, 7 // -2)
+assert_eq(3.0, 7.5 // 2)
+assert_eq(3.0, 7 // 2.0)
+
+assert_eq(8, 2 ** 3)
+assert_eq(512, 2 ** 3 ** 2)
+assert_eq(64, (2 ** 3) ** 2)
+assert_eq(-4, -2 ** 2)
+assert_eq(4, (-2) ** 2)
+assert_eq(0.5, 2 ** -1)
+assert_eq(-18, 2 * -3 ** 2)
+assert_eq(6.25, 2.5 ** 2)
+
+assert_eq(-6, ~5)
+assert_eq(4, ~-5)
+assert_eq(6, -~5)
+
+x = 10
+x //= 3
+x **= 2
+assert_eq(9, x)
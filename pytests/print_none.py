@@ -1,2 +1,7 @@
 x = None
-print(x)
\ No newline at end of file
+print(x)
+print([None])
+assert_eq("None", repr(None))
+assert_eq("[None]", str([None]))
+assert_eq("(None, 1)", str((None, 1)))
+assert_eq("{None: None}", str({None: None}))
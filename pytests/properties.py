@@ -0,0 +1,62 @@
+class Rectangle:
+    def __init__(self, width, height):
+        self.width = width
+        self.height = height
+
+    @property
+    def area(self):
+        return self.width * self.height
+
+r = Rectangle(2, 3)
+assert_eq(6, r.area)
+r.width = 10
+assert_eq(30, r.area)
+
+class Temperature:
+    def __init__(self, celsius):
+        self.celsius = celsius
+
+    @property
+    def fahrenheit(self):
+        return self.celsius * 9 / 5 + 32
+
+    @fahrenheit.setter
+    def fahrenheit(self, value):
+        self.celsius = (value - 32) * 5 / 9
+
+t = Temperature(100)
+assert_eq(212.0, t.fahrenheit)
+t.fahrenheit = 32
+assert_eq(0.0, t.celsius)
+assert_eq(32.0, t.fahrenheit)
+
+class Account:
+    def __init__(self):
+        self._balance = 0
+        self.balance = 50
+
+    @property
+    def balance(self):
+        return self._balance
+
+    @balance.setter
+    def balance(self, value):
+        if value < 0:
+            self._balance = 0
+        else:
+            self._balance = value
+
+a = Account()
+assert_eq(50, a.balance)
+a.balance = -5
+assert_eq(0, a.balance)
+a.balance += 7
+assert_eq(7, a.balance)
+
+def get_name(self):
+    return "name"
+
+class Named:
+    name = property(get_name)
+
+assert_eq("name", Named().name)
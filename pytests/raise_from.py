@@ -0,0 +1,34 @@
+class Oops:
+    code = 1
+
+class Wrapped:
+    code = 2
+
+original = Oops()
+try:
+    try:
+        raise original
+    except Oops as e:
+        raise Wrapped() from e
+except Wrapped as e:
+    assert_eq(2, e.code)
+    assert_eq(id(original), id(e.__cause__))
+    assert_eq(id(original), id(e.__context__))
+    assert_eq(True, e.__suppress_context__)
+
+try:
+    raise Wrapped() from None
+except Wrapped as e:
+    assert_eq(None, e.__cause__)
+    assert_eq(True, e.__suppress_context__)
+
+def convert(value):
+    try:
+        return "a" + value
+    except TypeError as error:
+        raise Wrapped() from error
+
+try:
+    convert(1)
+except Wrapped as e:
+    assert_eq("can only concatenate str (not \"int\") to str", str(e.__cause__))
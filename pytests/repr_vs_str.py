@@ -0,0 +1,9 @@
+assert_eq(str("abc"), "abc")
+assert_eq(repr("abc"), "'abc'")
+assert_eq(repr("it's"), "\"it's\"")
+assert_eq(repr("say \"hi\" it's"), "'say \"hi\" it\\'s'")
+assert_eq(repr("back\\slash"), "'back\\\\slash'")
+
+assert_eq(str(["a", 1]), "['a', 1]")
+assert_eq(repr(["a", 1]), "['a', 1]")
+assert_eq(repr([]), "[]")
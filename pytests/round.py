@@ -0,0 +1,22 @@
+assert_eq(2, round(2.5))
+assert_eq(4, round(3.5))
+assert_eq(0, round(0.5))
+assert_eq(-2, round(-2.5))
+assert_eq(-4, round(-3.7))
+assert_eq(3.14, round(3.14159, 2))
+assert_eq(2.67, round(2.675, 2))
+assert_eq(0.12, round(0.125, 2))
+assert_eq(1200.0, round(1234.5, -2))
+assert_eq(2, round(2.5, None))
+assert_eq("2", str(round(2.5, None)))
+
+assert_eq("4", str(round(3.7)))
+assert_eq("3.0", str(round(3.14159, 0)))
+
+assert_eq(7, round(7))
+assert_eq(7, round(7, 2))
+assert_eq(1200, round(1250, -2))
+assert_eq(1400, round(1350, -2))
+assert_eq(-1200, round(-1250, -2))
+assert_eq(0, round(12, -5))
+assert_eq(1, round(True))
@@ -0,0 +1,69 @@
+def len(x):
+    return 42
+
+assert_eq(len([1, 2]), 42)
+
+def len_from_function():
+    return len([1, 2])
+assert_eq(len_from_function(), 42)
+
+del len
+assert_eq(len([1, 2]), 2)
+assert_eq(len_from_function(), 2)
+
+def local_len():
+    len = 7
+    return len
+assert_eq(local_len(), 7)
+assert_eq(len([1, 2, 3]), 3)
+
+def delete_local():
+    x = 1
+    del x
+    try:
+        del x
+    except UnboundLocalError as e:
+        return str(e)
+assert_eq(delete_local(), "cannot access local variable 'x' where it is not associated with a value")
+
+x = 5
+def read_deleted_local():
+    x = 1
+    del x
+    return x
+
+error = None
+try:
+    read_deleted_local()
+except NameError as e:
+    error = str(e)
+assert_eq("cannot access local variable 'x' where it is not associated with a value", error)
+
+def read_global_twice():
+    return [x, x]
+assert_eq([5, 5], read_global_twice())
+
+error = None
+try:
+    del len
+except NameError as e:
+    error = str(e)
+assert_eq(error, "name 'len' is not defined")
+assert_eq(len([]), 0)
+
+def read_undefined():
+    return undefined_name
+
+error = None
+try:
+    read_undefined()
+except NameError as e:
+    error = str(e)
+assert_eq(error, "name 'undefined_name' is not defined")
+
+error = None
+try:
+    [[a, b] for a in [1]]
+except NameError as e:
+    error = str(e)
+assert_eq(error, "name 'b' is not defined")
@@ -0,0 +1,33 @@
+calls = []
+
+def track(value):
+    calls.append(value)
+    return value
+
+assert_eq("" or "default", "default")
+assert_eq("set" or "default", "set")
+assert_eq(1 and 2, 2)
+assert_eq(0 and 2, 0)
+assert_eq(None or 3, 3)
+assert_eq([] or [1], [1])
+assert_eq([5] and "yes", "yes")
+
+track(0) and track(1)
+assert_eq(calls, [0])
+track(2) or track(3)
+assert_eq(calls, [0, 2])
+track(0) or track(4)
+assert_eq(calls, [0, 2, 0, 4])
+
+x = 1
+y = 2
+assert_eq(x == 1 and y == 2, True)
+assert_eq(x == 2 or y == 2, True)
+assert_eq(x == 2 or y == 3 and x == 1, False)
+assert_eq(0 or 0 or 7, 7)
+assert_eq(1 and 2 and 3, 3)
+
+result = "no"
+if x == 1 and y:
+    result = "yes"
+assert_eq(result, "yes")
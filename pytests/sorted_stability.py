@@ -0,0 +1,42 @@
+calls = []
+
+def counted_first(pair, calls=calls):
+    calls.append(pair)
+    return pair[0]
+
+pairs = [(2, "a"), (1, "b"), (2, "c"), (1, "d"), (3, "e"), (2, "f")]
+
+result = sorted(pairs, key=counted_first)
+assert_eq([(1, "b"), (1, "d"), (2, "a"), (2, "c"), (2, "f"), (3, "e")], result)
+assert_eq(6, len(calls))
+assert_eq(pairs, calls)
+
+reverse_calls = []
+
+def counted_reverse_first(pair, calls=reverse_calls):
+    calls.append(pair)
+    return pair[0]
+
+result = sorted(pairs, key=counted_reverse_first, reverse=True)
+assert_eq([(3, "e"), (2, "a"), (2, "c"), (2, "f"), (1, "b"), (1, "d")], result)
+assert_eq(6, len(reverse_calls))
+
+assert_eq([1, 2, 3], sorted([3, 1, 2]))
+assert_eq([3, 2, 1], sorted((1, 3, 2), reverse=True))
+assert_eq([], sorted([]))
+assert_eq([0, 1, 2], sorted(range(3)))
+assert_eq([1, 2.5, 3], sorted([3, 2.5, 1]))
+assert_eq(["a", "bb", "ccc"], sorted(["ccc", "a", "bb"], key=len))
+
+original = [3, 1, 2]
+copy = sorted(original)
+assert_eq([3, 1, 2], original)
+assert_eq([1, 2, 3], copy)
+
+nan = float("nan")
+with_nan = [3.0, nan, 1.0, 2.0, 9.0, 4.0, 8.0, 5.0, 7.0, 6.0, 0.5, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5, 9.5, 0.0, 10.0, 11.0, 12.0, 13.0]
+result = sorted(with_nan)
+assert_eq(25, len(result))
+assert_eq(1, len([x for x in result if x != x]))
+assert_eq(sorted([x for x in with_nan if x == x]), sorted([x for x in result if x == x]))
+assert_eq(25, len(sorted(with_nan, reverse=True)))
@@ -0,0 +1,15 @@
+assert_eq(True, "ell" in "hello")
+assert_eq(False, "elo" in "hello")
+assert_eq(True, "" in "hello")
+assert_eq(True, "hello" in "hello")
+assert_eq(False, "hello!" in "hello")
+assert_eq(True, "xyz" not in "hello")
+assert_eq(True, "ã" in "maçã")
+
+assert_eq(3, "banana".count("a"))
+assert_eq(2, "banana".count("an"))
+assert_eq(1, "aaaa".count("aaa"))
+assert_eq(2, "aaaa".count("aa"))
+assert_eq(0, "banana".count("x"))
+assert_eq(7, "banana".count(""))
+assert_eq(5, "maçã".count(""))
@@ -0,0 +1,9 @@
+assert_eq("héllo", "héllo".encode().decode())
+assert_eq(b"abc", "abc".encode())
+assert_eq(b"h\xc3\xa9llo", "héllo".encode())
+assert_eq(b"h\xc3\xa9llo", "héllo".encode("utf-8"))
+assert_eq(6, len("héllo".encode()))
+assert_eq(b"h\xe9llo", "héllo".encode("latin-1"))
+assert_eq("héllo", "héllo".encode("latin-1").decode("latin-1"))
+assert_eq(b"plain", "plain".encode("ascii"))
+assert_eq(b"", "".encode())
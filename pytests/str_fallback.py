@@ -0,0 +1,20 @@
+class OnlyRepr:
+    def __init__(self, value):
+        self.value = value
+
+    def __repr__(self):
+        return "OnlyRepr(" + str(self.value) + ")"
+
+class Both:
+    def __str__(self):
+        return "both as str"
+
+    def __repr__(self):
+        return "both as repr"
+
+r = OnlyRepr(3)
+assert_eq(str(r), "OnlyRepr(3)")
+assert_eq(repr(r), "OnlyRepr(3)")
+assert_eq(str(Both()), "both as str")
+assert_eq(repr(Both()), "both as repr")
+assert_eq(str([r, Both()]), "[OnlyRepr(3), both as repr]")
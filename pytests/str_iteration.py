@@ -0,0 +1,25 @@
+chars = []
+for c in "abc":
+    chars.append(c)
+assert_eq(["a", "b", "c"], chars)
+
+assert_eq([], list(""))
+assert_eq(["h", "é", "!"], list("hé!"))
+assert_eq(["X", "Y"], [c.upper() for c in "xy"])
+
+iterator = "ok".__iter__()
+assert_eq("o", iterator.__next__())
+assert_eq("k", iterator.__next__())
+assert_eq([], list(iterator))
+
+assert_eq([(0, "a"), (1, "b"), (2, "c")], list(enumerate("abc")))
+assert_eq([(1, "x"), (2, "y")], list(enumerate("xy", 1)))
+assert_eq([(5, "q")], list(enumerate("q", start=5)))
+
+positions = []
+for pair in enumerate("hey"):
+    positions.append(pair[1] * (pair[0] + 1))
+assert_eq(["h", "ee", "yyy"], positions)
+
+assert_eq([(0, 10), (1, 20)], list(enumerate([10, 20])))
+assert_eq(3, len([c for c in "a b" if c != "z"]))
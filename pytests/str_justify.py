@@ -0,0 +1,24 @@
+assert_eq("00042", "42".zfill(5))
+assert_eq("-0042", "-42".zfill(5))
+assert_eq("+0042", "+42".zfill(5))
+assert_eq("12345", "12345".zfill(3))
+assert_eq("000", "".zfill(3))
+assert_eq("-", "-".zfill(1))
+assert_eq("00ab", "ab".zfill(4))
+
+assert_eq(" x ", "x".center(3))
+assert_eq("x", "x".center(0))
+assert_eq("**ab*", "ab".center(5, "*"))
+assert_eq("**ab**", "ab".center(6, "*"))
+assert_eq(" abc  ", "abc".center(6))
+assert_eq("  a  ", "a".center(5))
+assert_eq(" ab ", "ab".center(4))
+assert_eq("  ab ", "ab".center(5))
+assert_eq(" a  ", "a".center(4))
+
+assert_eq("ab   ", "ab".ljust(5))
+assert_eq("ab---", "ab".ljust(5, "-"))
+assert_eq("   ab", "ab".rjust(5))
+assert_eq("...ab", "ab".rjust(5, "."))
+assert_eq("abcdef", "abcdef".rjust(3))
+assert_eq("çã  ", "çã".ljust(4))
@@ -0,0 +1,10 @@
+text = "first line\nsecond line\r\nthird line\rlast"
+assert_eq(["first line", "second line", "third line", "last"], text.splitlines())
+assert_eq(["first line\n", "second line\r\n", "third line\r", "last"], text.splitlines(True))
+assert_eq(["first line\n", "second line\r\n", "third line\r", "last"], text.splitlines(keepends=True))
+assert_eq(["a", "", "b"], "a\n\nb\n".splitlines())
+assert_eq(["a\n", "\n", "b\n"], "a\n\nb\n".splitlines(True))
+assert_eq(["a", "b", "c"], "a\x0bb\x0cc".splitlines())
+assert_eq(["", "", "a"], "\r\ra".splitlines())
+assert_eq([], "".splitlines())
+assert_eq(["no newline"], "no newline".splitlines())
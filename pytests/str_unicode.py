@@ -0,0 +1,17 @@
+word = "héllo"
+assert_eq(5, len(word))
+assert_eq("é", word[1])
+assert_eq("h", word[0])
+assert_eq("o", word[4])
+assert_eq("o", word[-1])
+assert_eq("é", word[-4])
+
+assert_eq(3, len("日本語"))
+assert_eq("本", "日本語"[1])
+assert_eq(1, len("🐍"))
+assert_eq("🐍", "a🐍b"[1])
+assert_eq("b", "a🐍b"[2])
+assert_eq(0, len(""))
+
+assert_eq("É", "é".upper())
+assert_eq(2, len("é".encode()))
@@ -0,0 +1,10 @@
+assert_eq("ababab", "ab" * 3)
+assert_eq("", "ab" * 0)
+assert_eq("", "ab" * -2)
+assert_eq("a", "a" * True)
+assert_eq("", "a" * False)
+
+line = "-" * 5
+assert_eq("-----", line)
+assert_eq(5, len(line))
+assert_eq("ab--", "ab" + "-" * 2)
@@ -0,0 +1,10 @@
+def triple(x):
+	return x * 3
+
+count = 0
+while count < 3:
+	if count == 1:
+		count = count + triple(1)
+	else:
+		count = count + 1
+assert_eq(4, count)
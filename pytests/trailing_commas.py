@@ -0,0 +1,34 @@
+def add(a, b=10,):
+    return a + b
+
+numbers = [1, 2,]
+assert_eq(len(numbers), 2)
+assert_eq(numbers, [1, 2])
+
+single = (1,)
+assert_eq(len(single), 1)
+assert_eq(single[0], 1)
+assert_eq(repr(single), "(1,)")
+assert_eq((1), 1)
+
+pair = (1, "two",)
+assert_eq(len(pair), 2)
+assert_eq(pair, (1, "two"))
+
+assert_eq(add(1,), 11)
+assert_eq(add(1, b=2,), 3)
+
+mapping = {1: 2,}
+assert_eq(len(mapping), 1)
+assert_eq(mapping[1], 2)
+
+config = {
+    "name": "horse",
+    "legs": 4,
+}
+assert_eq(config["legs"], 4)
+assert_eq({"x": 1, "x": 2}, {"x": 2})
+
+nested = {"point": (1, 2), "items": [3, 4,],}
+assert_eq(nested["point"][1], 2)
+assert_eq(nested["items"], [3, 4])
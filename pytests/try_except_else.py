@@ -0,0 +1,105 @@
+class Oops:
+    code = 1
+
+log = []
+try:
+    log.append("body")
+except Oops:
+    log.append("handler")
+else:
+    log.append("else")
+assert_eq(["body", "else"], log)
+
+log = []
+try:
+    log.append("body")
+    raise Oops()
+    log.append("unreachable")
+except Oops as e:
+    log.append("handler")
+    assert_eq(1, e.code)
+else:
+    log.append("else")
+assert_eq(["body", "handler"], log)
+
+log = []
+try:
+    x = "a" + 1
+except Oops:
+    log.append("oops")
+except (Oops, TypeError):
+    log.append("type error")
+except:
+    log.append("bare")
+else:
+    log.append("else")
+assert_eq(["type error"], log)
+
+log = []
+try:
+    try:
+        log.append("body")
+    except Oops:
+        log.append("handler")
+    else:
+        log.append("else")
+        raise Oops()
+    finally:
+        log.append("finally")
+except Oops:
+    log.append("outer")
+assert_eq(["body", "else", "finally", "outer"], log)
+
+for item in [1, 2, 3]:
+    try:
+        if item == 1:
+            continue
+        if item == 2:
+            break
+    except TypeError:
+        log.append("handler")
+    finally:
+        log.append(item)
+
+try:
+    raise Oops()
+except TypeError:
+    log.append("wrong handler")
+except Oops:
+    log.append("caught")
+assert_eq(["body", "else", "finally", "outer", 1, 2, "caught"], log)
+
+caught = None
+try:
+    {"a": 1}["b"]
+except KeyError as e:
+    caught = str(e)
+assert_eq("'b'", caught)
+
+caught = None
+try:
+    {1, 2}.remove(3)
+except KeyError as e:
+    caught = e.args
+assert_eq((3,), caught)
+
+caught = None
+try:
+    x = 7 % 0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("integer modulo by zero", caught)
+
+caught = None
+try:
+    x = 7.5 % 0
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("float modulo", caught)
+
+caught = None
+try:
+    x = (7).__mod__(0)
+except ZeroDivisionError as e:
+    caught = str(e)
+assert_eq("integer modulo by zero", caught)
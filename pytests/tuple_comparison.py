@@ -0,0 +1,24 @@
+assert_eq(True, (1, 2) < (1, 3))
+assert_eq(False, (1, 3) < (1, 2))
+assert_eq(False, (1, 2) < (1, 2))
+assert_eq(True, (1, 2) <= (1, 2))
+assert_eq(True, (2,) > (1, 9, 9))
+assert_eq(True, (1, 2) >= (1, 2))
+assert_eq(False, (1, 2) > (1, 2))
+assert_eq(True, (1, 2) == (1, 2))
+assert_eq(True, (1, 2) != (1, 2, 3))
+
+assert_eq(True, (1, 2) < (1, 2, 3))
+assert_eq(False, (1, 2, 3) < (1, 2))
+assert_eq(True, (1, 2, 3) > (1, 2))
+assert_eq(True, () < (0,))
+assert_eq(True, () <= ())
+assert_eq(False, () < ())
+
+assert_eq(True, (1, (2, 3)) < (1, (2, 4)))
+assert_eq(True, (1, 2.5) < (1, 3))
+assert_eq(True, (0, 5) < (1, 0))
+
+assert_eq([(1, 2), (1, 3), (2, 0)], sorted([(2, 0), (1, 3), (1, 2)]))
+assert_eq((3, 1), max([(1, 9), (3, 1), (2, 5)]))
+assert_eq((1, 2), min((1, 2, 3), (1, 2)))
@@ -0,0 +1,26 @@
+grid = {}
+grid[(1, 2)] = "a"
+grid[(0, 0)] = "origin"
+assert_eq("a", grid[(1, 2)])
+grid[(1, 2)] = "b"
+assert_eq(2, len(grid))
+assert_eq("b", grid[(1, 2)])
+assert_eq(True, (0, 0) in grid)
+assert_eq(False, (0, 1) in grid)
+assert_eq("none", grid.get((5, 5), "none"))
+
+assert_eq(hash((1, 2)), hash((1, 2)))
+assert_eq(hash((1, 2)), hash((1.0, 2)))
+assert_eq(True, hash((1, 2)) != hash((2, 1)))
+assert_eq(hash(((1, "x"), None)), hash(((1, "x"), None)))
+
+nested = {((1, 2), "k"): 1}
+assert_eq(1, nested[((1, 2), "k")])
+
+seen = {(1, 2), (2, 1), (1, 2)}
+assert_eq(2, len(seen))
+assert_eq(True, (2, 1) in seen)
+seen.add((1, 2))
+seen.add((3, 4))
+assert_eq(3, len(seen))
+assert_eq(True, frozenset([(1, 2)]) <= frozenset(seen))
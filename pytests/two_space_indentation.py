@@ -0,0 +1,8 @@
+def double(x):
+  result = x * 2
+  if result > 2:
+    result = result + 1
+  return result
+
+total = double(1) + double(2)
+assert_eq(7, total)
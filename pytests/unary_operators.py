@@ -0,0 +1,22 @@
+assert_eq(-5 == 0 - 5, True)
+assert_eq(-(2 + 3), -5)
+assert_eq(+7, 7)
+assert_eq(-(-4), 4)
+assert_eq(-1.5, 0.0 - 1.5)
+assert_eq(-True, -1)
+
+assert_eq(not 0, True)
+assert_eq(not 3, False)
+assert_eq(not "", True)
+assert_eq(not "abc", False)
+assert_eq(not [], True)
+assert_eq(not [1], False)
+assert_eq(not None, True)
+assert_eq(not 1 == 2, True)
+
+assert_eq(abs(-3), 3)
+assert_eq(abs(3), 3)
+assert_eq(abs(-2.5), 2.5)
+
+x = 10
+assert_eq(-x * 2, -20)
@@ -0,0 +1,19 @@
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+p = Point(3, 4)
+attributes = vars(p)
+assert_eq(len(attributes), 2)
+assert_eq(attributes["x"], 3)
+assert_eq(attributes["y"], 4)
+
+p.x = 10
+assert_eq(vars(p)["x"], 10)
+
+def f(a):
+    b = a + 1
+    return vars()
+
+assert_eq(f(1)["b"], 2)
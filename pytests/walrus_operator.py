@@ -0,0 +1,12 @@
+values = [3, 1, 4]
+count = 0
+while (n := len(values)) > count:
+    count = count + 1
+assert_eq(3, n)
+assert_eq(3, count)
+
+if (first := values[0]) > 2:
+    result = first * 2
+else:
+    result = 0
+assert_eq(6, result)
@@ -0,0 +1,57 @@
+def loop_count(condition):
+    count = 0
+    while condition:
+        count = count + 1
+        break
+    return count
+
+assert_eq(0, loop_count(0))
+assert_eq(0, loop_count(0.0))
+assert_eq(0, loop_count(False))
+assert_eq(0, loop_count(None))
+assert_eq(0, loop_count(""))
+assert_eq(0, loop_count([]))
+assert_eq(0, loop_count(()))
+assert_eq(0, loop_count({}))
+assert_eq(0, loop_count(set()))
+assert_eq(0, loop_count(b""))
+
+assert_eq(1, loop_count(1))
+assert_eq(1, loop_count(-1))
+assert_eq(1, loop_count(0.5))
+assert_eq(1, loop_count(True))
+assert_eq(1, loop_count("a"))
+assert_eq(1, loop_count([1]))
+assert_eq(1, loop_count([0]))
+assert_eq(1, loop_count((0,)))
+assert_eq(1, loop_count({0: 0}))
+assert_eq(1, loop_count({0}))
+assert_eq(1, loop_count(b"\x00"))
+
+ran = 0
+while 0:
+    ran = 1
+while []:
+    ran = 1
+while "":
+    ran = 1
+assert_eq(0, ran)
+
+items = [1, 2, 3]
+passes = 0
+while items:
+    passes = passes + 1
+    items = []
+assert_eq(1, passes)
+
+countdown = 3
+steps = 0
+while countdown:
+    countdown = countdown - 1
+    steps = steps + 1
+assert_eq(3, steps)
+
+text = "abc"
+while text:
+    text = ""
+assert_eq("", text)
@@ -6,9 +6,12 @@ pub enum Operator {
     Minus,
     Multiply,
     Divide,
+    FloorDivide,
+    Power,
     Mod,
     BitShiftLeft,
     BitShiftRight,
+    Invert,
     Not,
     Equals,
     NotEquals,
@@ -20,6 +23,49 @@ pub enum Operator {
     GreaterEquals,
     Less,
     LessEquals,
+    In,
+    NotIn,
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::FloorDivide => "//",
+            Operator::Power => "**",
+            Operator::Mod => "%",
+            Operator::BitShiftLeft => "<<",
+            Operator::BitShiftRight => ">>",
+            Operator::Invert => "~",
+            Operator::Not => "not",
+            Operator::Equals => "==",
+            Operator::NotEquals => "!=",
+            Operator::And => "and",
+            Operator::Or => "or",
+            Operator::Xor => "^",
+            Operator::Greater => ">",
+            Operator::GreaterEquals => ">=",
+            Operator::Less => "<",
+            Operator::LessEquals => "<=",
+            Operator::In => "in",
+            Operator::NotIn => "not in",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum FStringPart {
+    Literal(String),
+    //{expression!conversion:format_spec}, the expression is tokenized but parsed later by the parser
+    Expression {
+        tokens: Vec<Token>,
+        conversion: Option<char>,
+        format_spec: Option<String>,
+    },
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -27,10 +73,13 @@ pub enum Token {
     LiteralFloat(Float),
     LiteralInteger(i128),
     LiteralString(String),
+    LiteralBytes(Vec<u8>),
+    LiteralFString(Vec<FStringPart>),
     Operator(Operator),
     Identifier(String),
     NewLine,
     Assign,
+    AugmentedAssign(Operator),
     True,
     False,
     None,
@@ -40,10 +89,20 @@ pub enum Token {
     IfKeyword,
     ForKeyword,
     RaiseKeyword,
+    AssertKeyword,
+    DelKeyword,
     ReturnKeyword,
+    YieldKeyword,
+    TryKeyword,
+    ExceptKeyword,
+    FinallyKeyword,
+    AsKeyword,
+    FromKeyword,
     InKeyword,
     WhileKeyword,
     BreakKeyword,
+    ContinueKeyword,
+    ImportKeyword,
     ElifKeyword,
     ElseKeyword,
     DefKeyword,
@@ -51,8 +110,14 @@ pub enum Token {
     CloseParen,
     OpenArrayBracket,
     CloseArrayBracket,
+    OpenBrace,
+    CloseBrace,
     MemberAccessor,
     Indentation,
+    Dedent,
+    Walrus,
+    //@, starts a decorator
+    At,
 }
 
 #[derive(Debug)]
@@ -62,12 +127,17 @@ enum PartialToken {
     Operator(String),
     Identifier(String),
     String(String),
+    Bytes(Vec<u8>),
+    FString(Vec<FStringPart>),
     NewLine,
     Comma,
     OpenArrayBracket,
     CloseArrayBracket,
+    OpenBrace,
+    CloseBrace,
     MemberAccessor,
     Colon,
+    At,
 }
 
 impl PartialToken {
@@ -89,19 +159,32 @@ impl PartialToken {
                 "for" => Token::ForKeyword,
                 "def" => Token::DefKeyword,
                 "raise" => Token::RaiseKeyword,
+                "assert" => Token::AssertKeyword,
+                "del" => Token::DelKeyword,
                 "return" => Token::ReturnKeyword,
+                "yield" => Token::YieldKeyword,
+                "try" => Token::TryKeyword,
+                "except" => Token::ExceptKeyword,
+                "finally" => Token::FinallyKeyword,
+                "as" => Token::AsKeyword,
+                "from" => Token::FromKeyword,
                 "in" => Token::InKeyword,
                 "while" => Token::WhileKeyword,
                 "break" => Token::BreakKeyword,
+                "continue" => Token::ContinueKeyword,
+                "import" => Token::ImportKeyword,
                 "class" => Token::ClassDef,
                 _ => Token::Identifier(s),
             },
             Self::Comma => Token::Comma,
             Self::Colon => Token::Colon,
+            Self::At => Token::At,
             Self::NewLine => Token::NewLine,
             Self::MemberAccessor => Token::MemberAccessor,
             Self::OpenArrayBracket => Token::OpenArrayBracket,
             Self::CloseArrayBracket => Token::CloseArrayBracket,
+            Self::OpenBrace => Token::OpenBrace,
+            Self::CloseBrace => Token::CloseBrace,
             Self::LiteralFloat(s) => {
                 if s.contains('.') || s.contains('e') {
                     match s.parse::<f64>() {
@@ -116,17 +199,29 @@ impl PartialToken {
                 }
             }
             Self::String(s) => Token::LiteralString(s),
+            Self::Bytes(b) => Token::LiteralBytes(b),
+            Self::FString(parts) => Token::LiteralFString(parts),
             Self::Operator(s) => match s.as_str() {
                 "+" => Token::Operator(Operator::Plus),
                 "-" => Token::Operator(Operator::Minus),
                 "*" => Token::Operator(Operator::Multiply),
                 "%" => Token::Operator(Operator::Mod),
                 "/" => Token::Operator(Operator::Divide),
+                "//" => Token::Operator(Operator::FloorDivide),
+                "**" => Token::Operator(Operator::Power),
+                "~" => Token::Operator(Operator::Invert),
                 "^" => Token::Operator(Operator::Xor),
                 "<<" => Token::Operator(Operator::BitShiftLeft),
                 ">>" => Token::Operator(Operator::BitShiftRight),
                 "==" => Token::Operator(Operator::Equals),
                 "=" => Token::Assign,
+                "+=" => Token::AugmentedAssign(Operator::Plus),
+                "-=" => Token::AugmentedAssign(Operator::Minus),
+                "*=" => Token::AugmentedAssign(Operator::Multiply),
+                "/=" => Token::AugmentedAssign(Operator::Divide),
+                "%=" => Token::AugmentedAssign(Operator::Mod),
+                "//=" => Token::AugmentedAssign(Operator::FloorDivide),
+                "**=" => Token::AugmentedAssign(Operator::Power),
                 "!=" => Token::Operator(Operator::NotEquals),
                 "(" => Token::OpenParen,
                 ")" => Token::CloseParen,
@@ -146,16 +241,149 @@ pub struct Tokenizer {
     cur_partial_token: PartialToken,
     final_result: Vec<Token>,
     eater_buf: String,
+    //how many (, [ or { are currently open. Newlines inside them don't end the statement.
+    bracket_depth: usize,
+    at_line_start: bool,
+    //widths of the currently open indentation levels, the first one is always 0
+    indent_stack: Vec<usize>,
+    //the same levels measured with tabs as 1 column, like CPython does. If comparing a line against
+    //both stacks gives different answers, the line mixes tabs and spaces inconsistently.
+    alt_indent_stack: Vec<usize>,
+    tab_width: usize,
 }
 
 impl Tokenizer {
     pub fn new(source: &str) -> Tokenizer {
+        Tokenizer::with_tab_width(source, 8)
+    }
+
+    pub fn with_tab_width(source: &str, tab_width: usize) -> Tokenizer {
         Tokenizer {
             index: 0,
             chars: source.chars().collect(),
             cur_partial_token: PartialToken::UndefinedOrWhitespace,
             final_result: vec![],
             eater_buf: String::new(),
+            bracket_depth: 0,
+            at_line_start: false,
+            indent_stack: vec![0],
+            alt_indent_stack: vec![0],
+            tab_width,
+        }
+    }
+
+    fn open_bracket(&mut self) {
+        self.bracket_depth += 1;
+    }
+
+    fn close_bracket(&mut self) {
+        if self.bracket_depth > 0 {
+            self.bracket_depth -= 1;
+        }
+    }
+
+    fn line_and_column(&self) -> (usize, usize) {
+        let mut line = 1;
+        let mut column = 1;
+        for c in self.chars.iter().take(self.index) {
+            if *c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        (line, column)
+    }
+
+    //Dedents are placed right after the last statement of the block that is closing,
+    //before any newlines, so the parser sees them as the end of that statement.
+    fn push_dedents(&mut self, amount: usize) {
+        let mut position = self.final_result.len();
+        while position > 0 && self.final_result[position - 1] == Token::NewLine {
+            position -= 1;
+        }
+        for _i in 0..amount {
+            self.final_result.insert(position, Token::Dedent);
+        }
+    }
+
+    //Eats the leading whitespace of a line and emits one Indentation token per open indentation level,
+    //plus a Dedent for every level closed by this line. Blank lines don't change the indentation.
+    fn handle_indentation(&mut self) -> Result<(), String> {
+        self.at_line_start = false;
+        let mut width = 0;
+        let mut alt_width = 0;
+        while self.can_go() && (self.cur() == ' ' || self.cur() == '\t') {
+            if self.cur() == '\t' {
+                width += self.tab_width - (width % self.tab_width);
+            } else {
+                width += 1;
+            }
+            alt_width += 1;
+            self.next();
+        }
+        if !self.can_go() || self.cur() == '\n' {
+            return Ok(());
+        }
+
+        let current = *self.indent_stack.last().unwrap();
+        let alt_current = *self.alt_indent_stack.last().unwrap();
+        if width > current {
+            if alt_width <= alt_current {
+                return Err(self.tab_error());
+            }
+            self.indent_stack.push(width);
+            self.alt_indent_stack.push(alt_width);
+        } else if width < current {
+            let mut closed_levels = 0;
+            while *self.indent_stack.last().unwrap() > width {
+                self.indent_stack.pop();
+                self.alt_indent_stack.pop();
+                closed_levels += 1;
+            }
+            self.push_dedents(closed_levels);
+            if *self.indent_stack.last().unwrap() != width {
+                let (line, column) = self.line_and_column();
+                return Err(format!(
+                    "IndentationError: unindent does not match any outer indentation level (line {}, column {})",
+                    line, column
+                ));
+            }
+            if *self.alt_indent_stack.last().unwrap() != alt_width {
+                return Err(self.tab_error());
+            }
+        } else if alt_width != alt_current {
+            return Err(self.tab_error());
+        }
+
+        for _i in 1..self.indent_stack.len() {
+            self.final_result.push(Token::Indentation);
+        }
+        Ok(())
+    }
+
+    fn tab_error(&self) -> String {
+        let (line, column) = self.line_and_column();
+        format!(
+            "TabError: inconsistent use of tabs and spaces in indentation (line {}, column {})",
+            line, column
+        )
+    }
+
+    //A ; starts a new statement at the same indentation level as the current line.
+    //A trailing ; is ignored.
+    fn separate_statements(&mut self) {
+        let mut lookahead = self.index;
+        while lookahead < self.chars.len() && (self.chars[lookahead] == ' ' || self.chars[lookahead] == '\t') {
+            lookahead += 1;
+        }
+        if lookahead == self.chars.len() || self.chars[lookahead] == '\n' {
+            return;
+        }
+        self.final_result.push(Token::NewLine);
+        for _i in 1..self.indent_stack.len() {
+            self.final_result.push(Token::Indentation);
         }
     }
 
@@ -222,10 +450,62 @@ impl Tokenizer {
         }
     }
 
-    fn eat_string_literal(&mut self) -> bool {
+    //reads the hex digits of a \x, \u or \U escape in a string literal
+    fn eat_hex_escape(&mut self, digit_count: usize) -> Result<char, String> {
+        let digits: String = self.chars.iter().skip(self.index + 1).take(digit_count).collect();
+        if digits.len() != digit_count || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("truncated \\{}{} escape", self.cur(), "X".repeat(digit_count)));
+        }
+        self.advance(digit_count);
+        match char::from_u32(u32::from_str_radix(&digits, 16).unwrap()) {
+            Some(c) => Ok(c),
+            None => Err(format!("illegal Unicode character \\{}{}", self.chars[self.index - digit_count], digits)),
+        }
+    }
+
+    //the character after a backslash is the current one: pushes what the escape stands for,
+    //leaving the cursor on its last character. Shared by plain strings and f-strings
+    fn eat_escape_sequence(&mut self, buffer: &mut String) -> Result<(), String> {
+        let cur = self.cur();
+        match cur {
+            '\\' | '\'' | '"' => buffer.push(cur),
+            'n' => buffer.push('\n'),
+            'r' => buffer.push('\r'),
+            't' => buffer.push('\t'),
+            'a' => buffer.push('\u{7}'),
+            'b' => buffer.push('\u{8}'),
+            'f' => buffer.push('\u{c}'),
+            'v' => buffer.push('\u{b}'),
+            '\n' => {}
+            'x' => buffer.push(self.eat_hex_escape(2)?),
+            'u' => buffer.push(self.eat_hex_escape(4)?),
+            'U' => buffer.push(self.eat_hex_escape(8)?),
+            '0'..='7' => {
+                let mut value = cur.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.chars.get(self.index + 1).and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            self.next();
+                        }
+                        None => break,
+                    }
+                }
+                buffer.push(char::from_u32(value).unwrap());
+            }
+            //unknown escapes are kept as they are, like in python
+            _ => {
+                buffer.push('\\');
+                buffer.push(cur);
+            }
+        }
+        Ok(())
+    }
+
+    fn eat_string_literal(&mut self) -> Result<bool, String> {
         let stop = self.cur();
         if stop != '\'' && stop != '"' {
-            return false;
+            return Ok(false);
         }
         self.next();
         let mut is_escaping = false;
@@ -238,15 +518,9 @@ impl Tokenizer {
                 continue;
             }
             if is_escaping {
-                if stop == '\'' && cur == '\'' {
-                    self.eater_buf.push('\'');
-                } else if stop == '"' && cur == '"' {
-                    self.eater_buf.push('"');
-                } else if cur == '\\' {
-                    self.eater_buf.push('\\');
-                } else {
-                    panic!("cannot escape char {}", cur);
-                }
+                let mut decoded = String::new();
+                self.eat_escape_sequence(&mut decoded)?;
+                self.eater_buf.push_str(&decoded);
                 is_escaping = false;
                 self.next();
                 continue;
@@ -262,7 +536,187 @@ impl Tokenizer {
             self.eater_buf.push(cur);
             self.next();
         }
-        return finished;
+        return Ok(finished);
+    }
+
+    //b"..." literals: only ASCII characters are allowed, anything else must be written as an escape
+    fn eat_bytes_literal(&mut self) -> Result<Vec<u8>, String> {
+        let stop = self.cur();
+        self.next();
+        let mut bytes = vec![];
+        while self.can_go() {
+            let cur = self.cur();
+            if cur == stop {
+                return Ok(bytes);
+            }
+            if !cur.is_ascii() {
+                return Err(String::from("bytes can only contain ASCII literal characters"));
+            }
+            if cur != '\\' {
+                bytes.push(cur as u8);
+                self.next();
+                continue;
+            }
+            self.next();
+            if !self.can_go() {
+                break;
+            }
+            let escaped = self.cur();
+            self.next();
+            match escaped {
+                '\\' | '\'' | '"' => bytes.push(escaped as u8),
+                'n' => bytes.push(b'\n'),
+                'r' => bytes.push(b'\r'),
+                't' => bytes.push(b'\t'),
+                'a' => bytes.push(0x07),
+                'b' => bytes.push(0x08),
+                'f' => bytes.push(0x0c),
+                'v' => bytes.push(0x0b),
+                '\n' => {}
+                'x' => {
+                    let digits: String = self.chars.iter().skip(self.index).take(2).collect();
+                    match u8::from_str_radix(&digits, 16) {
+                        Ok(byte) if digits.len() == 2 => bytes.push(byte),
+                        _ => return Err(String::from("invalid \\x escape in bytes literal")),
+                    }
+                    self.advance(2);
+                }
+                '0'..='7' => {
+                    let mut value = escaped.to_digit(8).unwrap();
+                    for _ in 0..2 {
+                        match self.chars.get(self.index).and_then(|c| c.to_digit(8)) {
+                            Some(digit) => {
+                                value = value * 8 + digit;
+                                self.next();
+                            }
+                            None => break,
+                        }
+                    }
+                    if value > 0o377 {
+                        return Err(format!("octal escape value \\{:o} out of range in bytes literal", value));
+                    }
+                    bytes.push(value as u8);
+                }
+                //unknown escapes are kept as they are, like in python
+                _ => {
+                    bytes.push(b'\\');
+                    bytes.push(escaped as u8);
+                }
+            }
+        }
+        return Err(String::from("unterminated bytes literal"));
+    }
+
+    fn next_is(&self, c: char) -> bool {
+        self.index + 1 < self.chars.len() && self.cur_offset(1) == c
+    }
+
+    //f"..." literals: text is kept as is, {{ and }} are literal braces, and {...} starts a replacement field
+    fn eat_fstring_literal(&mut self) -> Result<Vec<FStringPart>, String> {
+        let stop = self.cur();
+        self.next();
+        let mut parts = vec![];
+        let mut literal = String::new();
+        while self.can_go() {
+            let cur = self.cur();
+            if cur == stop {
+                if !literal.is_empty() {
+                    parts.push(FStringPart::Literal(literal));
+                }
+                return Ok(parts);
+            }
+            if cur == '\\' {
+                self.next();
+                if !self.can_go() {
+                    break;
+                }
+                self.eat_escape_sequence(&mut literal)?;
+                self.next();
+            } else if (cur == '{' || cur == '}') && self.next_is(cur) {
+                literal.push(cur);
+                self.advance(2);
+            } else if cur == '}' {
+                return Err(String::from("f-string: single '}' is not allowed"));
+            } else if cur == '{' {
+                if !literal.is_empty() {
+                    parts.push(FStringPart::Literal(std::mem::take(&mut literal)));
+                }
+                self.next();
+                parts.push(self.eat_fstring_replacement_field(stop)?);
+            } else {
+                literal.push(cur);
+                self.next();
+            }
+        }
+        return Err(String::from("f-string: unterminated string"));
+    }
+
+    fn eat_fstring_replacement_field(&mut self, stop: char) -> Result<FStringPart, String> {
+        let expecting_brace = || String::from("f-string: expecting '}'");
+        let mut expression = String::new();
+        let mut depth = 0;
+        let mut inner_quote: Option<char> = None;
+        loop {
+            if !self.can_go() || self.cur() == stop {
+                return Err(expecting_brace());
+            }
+            let cur = self.cur();
+            if let Some(quote) = inner_quote {
+                if cur == quote {
+                    inner_quote = None;
+                }
+            } else {
+                match cur {
+                    '\'' | '"' => inner_quote = Some(cur),
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' if depth > 0 => depth -= 1,
+                    '}' | ':' if depth == 0 => break,
+                    '!' if depth == 0 && !self.next_is('=') => break,
+                    _ => {}
+                }
+            }
+            expression.push(cur);
+            self.next();
+        }
+
+        let mut conversion = None;
+        if self.cur() == '!' {
+            self.next();
+            match self.chars.get(self.index) {
+                Some(c) if matches!(c, 'r' | 's' | 'a') => conversion = Some(*c),
+                _ => return Err(String::from("f-string: invalid conversion character: expected 's', 'r', or 'a'")),
+            }
+            self.next();
+        }
+
+        let mut format_spec = None;
+        if self.can_go() && self.cur() == ':' {
+            self.next();
+            let mut spec = String::new();
+            while self.can_go() && self.cur() != '}' && self.cur() != stop {
+                if self.cur() == '{' {
+                    return Err(String::from("f-string: nested replacement fields in format specs are not supported"));
+                }
+                spec.push(self.cur());
+                self.next();
+            }
+            format_spec = Some(spec);
+        }
+
+        if !self.can_go() || self.cur() != '}' {
+            return Err(expecting_brace());
+        }
+        self.next();
+
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return Err(String::from("f-string: empty expression not allowed"));
+        }
+        return Ok(FStringPart::Expression {
+            tokens: tokenize(expression)?,
+            conversion,
+            format_spec,
+        });
     }
 
     fn commit_current_token(&mut self) {
@@ -286,7 +740,7 @@ impl Tokenizer {
         let mut matched_chars = 0;
         let chars: Vec<char> = query.chars().collect();
         for i in 0..query.len() {
-            if self.cur_offset(i as isize) != chars[i] {
+            if self.index + i >= self.chars.len() || self.cur_offset(i as isize) != chars[i] {
                 return (false, 0);
             }
             matched_chars = matched_chars + 1
@@ -307,11 +761,16 @@ impl Tokenizer {
 
     pub fn tokenize(mut self) -> Result<Vec<Token>, String> {
         let operators = &[
-            "+", "-", "*", "%", "/", "<<", ">>", "<=", ">=", ">", "<", "!=", "==", "=", "^", "(",
+            "**=", "//=", "+=", "-=", "*=", "/=", "%=",
+            "**", "//", "+", "-", "*", "%", "/", "<<", ">>", "<=", ">=", ">", "<", "!=", "==", "=", "^", "~", "(",
             ")",
         ];
         while self.can_go() {
             self.commit_current_token();
+            if self.at_line_start {
+                self.handle_indentation()?;
+                continue;
+            }
             if self.cur().is_numeric() {
                 self.reset_eater_buffer();
                 self.eat_numbers();
@@ -326,17 +785,42 @@ impl Tokenizer {
                 self.cur_partial_token = PartialToken::Comma;
                 self.commit_current_token();
                 self.next();
+            } else if self.cur() == ':' && self.index + 1 < self.chars.len() && self.cur_offset(1) == '=' {
+                self.final_result.push(Token::Walrus);
+                self.advance(2);
             } else if self.cur() == ':' {
                 self.cur_partial_token = PartialToken::Colon;
                 self.commit_current_token();
                 self.next();
+            } else if self.cur() == '@' {
+                self.cur_partial_token = PartialToken::At;
+                self.commit_current_token();
+                self.next();
             } else if self.cur() == '[' {
                 self.cur_partial_token = PartialToken::OpenArrayBracket;
                 self.commit_current_token();
+                self.open_bracket();
                 self.next();
             } else if self.cur() == ']' {
                 self.cur_partial_token = PartialToken::CloseArrayBracket;
                 self.commit_current_token();
+                self.close_bracket();
+                self.next();
+            } else if self.cur() == '{' {
+                self.cur_partial_token = PartialToken::OpenBrace;
+                self.commit_current_token();
+                self.open_bracket();
+                self.next();
+            } else if self.cur() == '}' {
+                self.cur_partial_token = PartialToken::CloseBrace;
+                self.commit_current_token();
+                self.close_bracket();
+                self.next();
+            } else if self.cur() == ';' {
+                self.next();
+                self.separate_statements();
+            } else if self.cur() == '\n' && self.bracket_depth > 0 {
+                //implicit line joining: just treat the newline as whitespace
                 self.next();
             } else if self.cur() == '.' {
                 self.cur_partial_token = PartialToken::MemberAccessor;
@@ -345,32 +829,40 @@ impl Tokenizer {
             } else if self.cur() == '\n' {
                 self.cur_partial_token = PartialToken::NewLine;
                 self.commit_current_token();
+                self.at_line_start = true;
                 self.next();
-            } else if self.index > 0 && self.cur_offset(-1) == '\n' && self.cur() == ' ' {
-                let mut current_spaces = 0;
-                while self.can_go() && self.cur() == ' ' {
-                    current_spaces = current_spaces + 1;
-                    self.next();
-                }
-                if current_spaces % 4 != 0 {
-                    panic!("Indentation must be a multiple of 4");
-                }
-                let indents = current_spaces / 4;
-                for _i in 0..indents {
-                    self.final_result.push(Token::Indentation);
-                }
             } else if self.cur().is_whitespace() {
                 //if it's whitespace and there's a pending token, add it
                 self.next();
             } else if let Some(s) = self.match_first_and_advance(operators) {
+                match s {
+                    "(" => self.open_bracket(),
+                    ")" => self.close_bracket(),
+                    _ => {}
+                }
                 self.cur_partial_token = PartialToken::Operator(String::from(s));
                 self.commit_current_token();
+            } else if (self.cur() == 'f' || self.cur() == 'F') && (self.next_is('\'') || self.next_is('"')) {
+                self.next();
+                let parts = self.eat_fstring_literal()?;
+                self.cur_partial_token = PartialToken::FString(parts);
+                self.commit_current_token();
+                self.next();
+            } else if (self.cur() == 'b' || self.cur() == 'B')
+                && self.index + 1 < self.chars.len()
+                && (self.cur_offset(1) == '\'' || self.cur_offset(1) == '"')
+            {
+                self.next();
+                let bytes = self.eat_bytes_literal()?;
+                self.cur_partial_token = PartialToken::Bytes(bytes);
+                self.commit_current_token();
+                self.next();
             } else if self.cur().is_ascii_alphabetic() || self.cur() == '_' {
                 self.eat_identifier();
                 self.cur_partial_token = PartialToken::Identifier(self.clone_buf());
                 self.reset_eater_buffer();
             } else if self.cur() == '\'' || self.cur() == '"' {
-                self.eat_string_literal();
+                self.eat_string_literal()?;
                 self.cur_partial_token = PartialToken::String(self.clone_buf());
                 self.commit_current_token();
                 self.reset_eater_buffer();
@@ -380,6 +872,7 @@ impl Tokenizer {
             }
         }
         self.commit_current_token();
+        self.push_dedents(self.indent_stack.len() - 1);
         Ok(self.final_result)
     }
 }
@@ -492,6 +985,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tokenizer_power_floor_division_and_invert() -> Result<(), String> {
+        let result = tokenize("2 ** ~3 // 4 * 5 / 6")?;
+        assert_eq!(
+            result,
+            [
+                Token::LiteralInteger(2),
+                Token::Operator(Operator::Power),
+                Token::Operator(Operator::Invert),
+                Token::LiteralInteger(3),
+                Token::Operator(Operator::FloorDivide),
+                Token::LiteralInteger(4),
+                Token::Operator(Operator::Multiply),
+                Token::LiteralInteger(5),
+                Token::Operator(Operator::Divide),
+                Token::LiteralInteger(6),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn tokenizer_number_space_operator_space_number() -> Result<(), String> {
         let result = tokenize("6 + 6")?;
@@ -681,6 +1195,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn string_literal_escapes() -> Result<(), String> {
+        let result = tokenize(r#"'\n\t\x41\u00e9\U0001F600\101\0\q\"'"#)?;
+        assert_eq!(result, [Token::LiteralString(String::from("\n\tA\u{e9}\u{1F600}A\0\\q\""))]);
+        assert!(tokenize(r"'\x4'").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_literal() -> Result<(), String> {
+        let result = tokenize("b'abc' B\"x\"")?;
+        assert_eq!(
+            result,
+            [
+                Token::LiteralBytes(b"abc".to_vec()),
+                Token::LiteralBytes(b"x".to_vec())
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_literal_escapes() -> Result<(), String> {
+        let result = tokenize(r"b'\x00\xff\n\t\\\'\101\q'")?;
+        assert_eq!(
+            result,
+            [Token::LiteralBytes(vec![0, 255, b'\n', b'\t', b'\\', b'\'', b'A', b'\\', b'q'])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_literal_rejects_non_ascii() {
+        assert!(tokenize("b'caf\u{e9}'").is_err());
+        assert!(tokenize(r"b'\x4'").is_err());
+    }
+
+    #[test]
+    fn fstring_literal() -> Result<(), String> {
+        let result = tokenize("f'a{{b}} {x!r:>4} {y}'")?;
+        assert_eq!(
+            result,
+            [Token::LiteralFString(vec![
+                FStringPart::Literal(String::from("a{b} ")),
+                FStringPart::Expression {
+                    tokens: vec![Token::Identifier(String::from("x"))],
+                    conversion: Some('r'),
+                    format_spec: Some(String::from(">4")),
+                },
+                FStringPart::Literal(String::from(" ")),
+                FStringPart::Expression {
+                    tokens: vec![Token::Identifier(String::from("y"))],
+                    conversion: None,
+                    format_spec: None,
+                },
+            ])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fstring_field_keeps_nested_brackets_and_comparisons() -> Result<(), String> {
+        let result = tokenize("f\"{d['k']:x} {a != b}\"")?;
+        match &result[..] {
+            [Token::LiteralFString(parts)] => {
+                assert_eq!(parts.len(), 3);
+                assert!(matches!(&parts[0], FStringPart::Expression { format_spec: Some(spec), .. } if spec == "x"));
+                assert!(matches!(&parts[2], FStringPart::Expression { tokens, .. } if tokens.len() == 3));
+            }
+            _ => panic!("expected a single f-string token, got {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fstring_errors() {
+        assert!(tokenize("f'{}'").is_err());
+        assert!(tokenize("f'a}'").is_err());
+        assert!(tokenize("f'{x'").is_err());
+        assert!(tokenize("f'{x!z}'").is_err());
+    }
+
+    #[test]
+    fn identifier_starting_with_b_is_not_bytes() -> Result<(), String> {
+        let result = tokenize("bar")?;
+        assert_eq!(result, [Token::Identifier(String::from("bar"))]);
+        Ok(())
+    }
+
     #[test]
     fn tokenize_if() -> Result<(), String> {
         let result = tokenize(
@@ -701,7 +1304,8 @@ mod tests {
                 Token::Assign,
                 Token::Identifier(String::from("x")),
                 Token::Operator(Operator::Plus),
-                Token::LiteralInteger(1)
+                Token::LiteralInteger(1),
+                Token::Dedent
             ]
         );
         Ok(())
@@ -823,4 +1427,234 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn newlines_inside_brackets_are_ignored() -> Result<(), String> {
+        let result = tokenize(
+            "x = [1,
+    2,
+        3]
+y = 1",
+        )?;
+        assert_eq!(
+            result,
+            [
+                Token::Identifier("x".into()),
+                Token::Assign,
+                Token::OpenArrayBracket,
+                Token::LiteralInteger(1),
+                Token::Comma,
+                Token::LiteralInteger(2),
+                Token::Comma,
+                Token::LiteralInteger(3),
+                Token::CloseArrayBracket,
+                Token::NewLine,
+                Token::Identifier("y".into()),
+                Token::Assign,
+                Token::LiteralInteger(1),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn newlines_inside_parens_are_ignored() -> Result<(), String> {
+        let result = tokenize(
+            "f(1,
+  2)",
+        )?;
+        assert_eq!(
+            result,
+            [
+                Token::Identifier("f".into()),
+                Token::OpenParen,
+                Token::LiteralInteger(1),
+                Token::Comma,
+                Token::LiteralInteger(2),
+                Token::CloseParen,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tab_indentation() -> Result<(), String> {
+        let result = tokenize("if x:\n\tif y:\n\t\tx = 1\n\ty = 2")?;
+        assert_eq!(
+            result,
+            [
+                Token::IfKeyword,
+                Token::Identifier("x".into()),
+                Token::Colon,
+                Token::NewLine,
+                Token::Indentation,
+                Token::IfKeyword,
+                Token::Identifier("y".into()),
+                Token::Colon,
+                Token::NewLine,
+                Token::Indentation,
+                Token::Indentation,
+                Token::Identifier("x".into()),
+                Token::Assign,
+                Token::LiteralInteger(1),
+                Token::Dedent,
+                Token::NewLine,
+                Token::Indentation,
+                Token::Identifier("y".into()),
+                Token::Assign,
+                Token::LiteralInteger(2),
+                Token::Dedent,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn two_space_indentation() -> Result<(), String> {
+        let result = tokenize("if x:\n  if y:\n    x = 1\n\nz = 2")?;
+        assert_eq!(
+            result,
+            [
+                Token::IfKeyword,
+                Token::Identifier("x".into()),
+                Token::Colon,
+                Token::NewLine,
+                Token::Indentation,
+                Token::IfKeyword,
+                Token::Identifier("y".into()),
+                Token::Colon,
+                Token::NewLine,
+                Token::Indentation,
+                Token::Indentation,
+                Token::Identifier("x".into()),
+                Token::Assign,
+                Token::LiteralInteger(1),
+                Token::Dedent,
+                Token::Dedent,
+                Token::NewLine,
+                Token::NewLine,
+                Token::Identifier("z".into()),
+                Token::Assign,
+                Token::LiteralInteger(2),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_dedent_is_an_error() {
+        let result = tokenize("if x:\n    x = 1\n  y = 2");
+        assert_eq!(
+            result,
+            Err(String::from(
+                "IndentationError: unindent does not match any outer indentation level (line 3, column 3)"
+            ))
+        );
+    }
+
+    #[test]
+    fn mixed_tabs_and_spaces_is_an_error() {
+        //a tab and 8 spaces have the same width, but only by accident of the tab size
+        let result = tokenize("if x:\n\ty = 1\n        z = 2");
+        assert_eq!(
+            result,
+            Err(String::from(
+                "TabError: inconsistent use of tabs and spaces in indentation (line 3, column 9)"
+            ))
+        );
+
+        let result = tokenize("if x:\n    if y:\n\tz = 2");
+        assert_eq!(
+            result,
+            Err(String::from(
+                "TabError: inconsistent use of tabs and spaces in indentation (line 3, column 2)"
+            ))
+        );
+    }
+
+    #[test]
+    fn consistent_tabs_and_spaces_are_accepted() {
+        //spaces after the same leading tab are fine
+        assert!(tokenize("if x:\n\tif y:\n\t    z = 2\n\tw = 3").is_ok());
+    }
+
+    #[test]
+    fn semicolon_separates_statements() -> Result<(), String> {
+        let result = tokenize("a = 1; b = 2;")?;
+        assert_eq!(
+            result,
+            [
+                Token::Identifier("a".into()),
+                Token::Assign,
+                Token::LiteralInteger(1),
+                Token::NewLine,
+                Token::Identifier("b".into()),
+                Token::Assign,
+                Token::LiteralInteger(2),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn semicolon_inside_block_keeps_indentation() -> Result<(), String> {
+        let result = tokenize("if x:\n    a = 1; b = 2")?;
+        assert_eq!(
+            result,
+            [
+                Token::IfKeyword,
+                Token::Identifier("x".into()),
+                Token::Colon,
+                Token::NewLine,
+                Token::Indentation,
+                Token::Identifier("a".into()),
+                Token::Assign,
+                Token::LiteralInteger(1),
+                Token::NewLine,
+                Token::Indentation,
+                Token::Identifier("b".into()),
+                Token::Assign,
+                Token::LiteralInteger(2),
+                Token::Dedent,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn walrus_operator() -> Result<(), String> {
+        let result = tokenize("(n := 1):")?;
+        assert_eq!(
+            result,
+            [
+                Token::OpenParen,
+                Token::Identifier("n".into()),
+                Token::Walrus,
+                Token::LiteralInteger(1),
+                Token::CloseParen,
+                Token::Colon,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn augmented_assign_operators() -> Result<(), String> {
+        let result = tokenize("x += 1\nd[k] %= 2")?;
+        assert_eq!(
+            result,
+            [
+                Token::Identifier("x".into()),
+                Token::AugmentedAssign(Operator::Plus),
+                Token::LiteralInteger(1),
+                Token::NewLine,
+                Token::Identifier("d".into()),
+                Token::OpenArrayBracket,
+                Token::Identifier("k".into()),
+                Token::CloseArrayBracket,
+                Token::AugmentedAssign(Operator::Mod),
+                Token::LiteralInteger(2),
+            ]
+        );
+        Ok(())
+    }
 }
@@ -6,6 +6,8 @@ pub enum Expr {
     IntegerValue(i128),
     FloatValue(Float),
     StringValue(String),
+    BytesValue(Vec<u8>),
+    FormattedString(Vec<FStringSegment>),
     BooleanValue(bool),
     None,
     FunctionCall(Box<Expr>, Vec<Expr>),
@@ -16,6 +18,30 @@ pub enum Expr {
     UnaryExpression(Operator, Box<Expr>),
     MemberAccess(Box<Expr>, String),
     Array(Vec<Expr>),
+    Tuple(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
+    Set(Vec<Expr>),
+    NamedExpr(String, Box<Expr>),
+    //only valid as an argument of a function call: f(name=value)
+    KeywordArgument(String, Box<Expr>),
+    //value if condition else otherwise
+    IfExpression(Box<Expr>, Box<Expr>, Box<Expr>),
+    //[element for item_name in iterable if condition ...]
+    ListComprehension(Box<Expr>, String, Box<Expr>, Vec<Expr>),
+    //yield, with an optional value
+    Yield(Option<Box<Expr>>),
+    //a < b <= c: each operand is compared to the next one, middle operands are evaluated once
+    ChainedComparison(Box<Expr>, Vec<(Operator, Expr)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FStringSegment {
+    Literal(String),
+    Value {
+        expr: Expr,
+        conversion: Option<char>,
+        format_spec: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,6 +50,14 @@ pub struct ASTIfStatement {
     pub statements: Vec<AST>,
 }
 
+//except exception_type as name: body, a bare except has no type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExceptHandler {
+    pub exception_type: Option<Expr>,
+    pub name: Option<String>,
+    pub body: Vec<AST>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FunctionParameter {
     Simple(String),
@@ -37,6 +71,25 @@ pub enum AST {
         path: Vec<String>,
         expression: Expr,
     },
+    //path op= expression
+    AugmentedAssign {
+        path: Vec<String>,
+        operator: Operator,
+        expression: Expr,
+    },
+    //indexed[index] = expression
+    SubscriptAssign {
+        indexed: Expr,
+        index: Expr,
+        expression: Expr,
+    },
+    //indexed[index] op= expression
+    AugmentedSubscriptAssign {
+        indexed: Expr,
+        index: Expr,
+        operator: Operator,
+        expression: Expr,
+    },
     IfStatement {
         true_branch: ASTIfStatement,
         elifs: Vec<ASTIfStatement>,
@@ -61,8 +114,34 @@ pub enum AST {
         body: Vec<AST>,
     },
     Break,
+    Continue,
     Return(Option<Expr>),
-    Raise(Expr),
+    //raise exception [from cause], a bare raise re-raises the exception being handled
+    Raise {
+        exception: Option<Expr>,
+        cause: Option<Expr>,
+    },
+    //import a, b: binds each module to its name
+    Import(Vec<String>),
+    //del a, b: unbinds each name
+    Delete(Vec<String>),
+    //@decorator lines above a def or class, in source order
+    Decorated {
+        decorators: Vec<Expr>,
+        definition: Box<AST>,
+    },
+    //assert expression[, message]
+    Assert {
+        expression: Expr,
+        message: Option<Expr>,
+    },
+    //the else body runs when the body didn't raise, the finally body always runs last
+    TryStatement {
+        body: Vec<AST>,
+        handlers: Vec<ExceptHandler>,
+        else_body: Option<Vec<AST>>,
+        finally_body: Option<Vec<AST>>,
+    },
 }
 
 impl Expr {
@@ -86,23 +165,119 @@ impl From<i128> for Box<Expr> {
     }
 }
 
+//same relative order as python: or < and < comparisons < ^ < shifts < +- < */%//< unary +-~ < **
 fn precedence(o: Operator) -> u32 {
     match o {
-        Operator::Multiply => 100,
-        Operator::Divide => 100,
-        _ => 1,
+        Operator::Or => 10,
+        Operator::And => 20,
+        Operator::Not => 30,
+        Operator::Equals
+        | Operator::NotEquals
+        | Operator::Less
+        | Operator::LessEquals
+        | Operator::Greater
+        | Operator::GreaterEquals
+        | Operator::In
+        | Operator::NotIn => 40,
+        Operator::Xor => 50,
+        Operator::BitShiftLeft | Operator::BitShiftRight => 60,
+        Operator::Plus | Operator::Minus => 70,
+        Operator::Multiply | Operator::Divide | Operator::FloorDivide | Operator::Mod => 100,
+        Operator::Invert => 110,
+        Operator::Power => 120,
+    }
+}
+
+fn is_unary_operator(o: Operator) -> bool {
+    return matches!(o, Operator::Plus | Operator::Minus | Operator::Invert | Operator::Not);
+}
+
+fn is_comparison(o: Operator) -> bool {
+    return precedence(o) == precedence(Operator::Less);
+}
+
+fn is_unparenthesized_comparison(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryOperation(_, op, _) => is_comparison(*op),
+        Expr::ChainedComparison(..) => true,
+        _ => false,
+    }
+}
+
+//Attaches rhs to the right spine of lhs, going down while op binds tighter than the operators already there.
+//Operators with the same precedence are left associative, except ** which is right associative.
+fn insert_binary_operation(lhs: Expr, op: Operator, rhs: Expr) -> Expr {
+    match lhs {
+        Expr::BinaryOperation(lhs_down, op_down, rhs_down)
+            if precedence(op) > precedence(op_down) || (op == Operator::Power && op_down == Operator::Power) =>
+        {
+            Expr::BinaryOperation(lhs_down, op_down, Box::new(insert_binary_operation(*rhs_down, op, rhs)))
+        }
+        //not binds looser than comparisons: not a == b is not (a == b)
+        Expr::UnaryExpression(Operator::Not, operand) if precedence(op) > precedence(Operator::Not) => {
+            Expr::UnaryExpression(Operator::Not, Box::new(insert_binary_operation(*operand, op, rhs)))
+        }
+        //the other unary operators only bind looser than **: -2 ** 2 is -(2 ** 2)
+        Expr::UnaryExpression(unary_op, operand) if op == Operator::Power => {
+            Expr::UnaryExpression(unary_op, Box::new(insert_binary_operation(*operand, op, rhs)))
+        }
+        _ => Expr::BinaryOperation(Box::new(lhs), op, Box::new(rhs)),
     }
 }
 
 fn clean_parens(expr: Expr) -> Expr {
+    let clean_box = |e: Box<Expr>| Box::new(clean_parens(*e));
+    let clean_all = |exprs: Vec<Expr>| exprs.into_iter().map(clean_parens).collect();
     match expr {
         Expr::Parenthesized(e) => clean_parens(*e),
-        Expr::UnaryExpression(op, e) => Expr::UnaryExpression(op, Box::new(clean_parens(*e))),
+        Expr::UnaryExpression(op, e) => Expr::UnaryExpression(op, clean_box(e)),
+        //the left side of a comparison is only chained when it is an unparenthesized comparison
+        Expr::BinaryOperation(left, op, right) if is_comparison(op) && is_unparenthesized_comparison(&left) => {
+            let comparison = (op, clean_parens(*right));
+            match clean_parens(*left) {
+                Expr::ChainedComparison(first, mut comparisons) => {
+                    comparisons.push(comparison);
+                    Expr::ChainedComparison(first, comparisons)
+                }
+                Expr::BinaryOperation(first, left_op, second) => {
+                    Expr::ChainedComparison(first, vec![(left_op, *second), comparison])
+                }
+                _ => unreachable!(),
+            }
+        }
         Expr::BinaryOperation(left, op, right) => {
-            let left_clean = Box::new(clean_parens(*left));
-            let right_clean = Box::new(clean_parens(*right));
+            let left_clean = clean_box(left);
+            let right_clean = clean_box(right);
             Expr::BinaryOperation(left_clean, op, right_clean)
         }
+        Expr::FunctionCall(callable, args) => Expr::FunctionCall(clean_box(callable), clean_all(args)),
+        Expr::IndexAccess(indexed, index) => Expr::IndexAccess(clean_box(indexed), clean_box(index)),
+        Expr::MemberAccess(owner, name) => Expr::MemberAccess(clean_box(owner), name),
+        Expr::Array(exprs) => Expr::Array(clean_all(exprs)),
+        Expr::Tuple(exprs) => Expr::Tuple(clean_all(exprs)),
+        Expr::Set(exprs) => Expr::Set(clean_all(exprs)),
+        Expr::Dict(entries) => Expr::Dict(
+            entries
+                .into_iter()
+                .map(|(key, value)| (clean_parens(key), clean_parens(value)))
+                .collect(),
+        ),
+        Expr::NamedExpr(name, value) => Expr::NamedExpr(name, clean_box(value)),
+        Expr::KeywordArgument(name, value) => Expr::KeywordArgument(name, clean_box(value)),
+        Expr::IfExpression(value, condition, otherwise) => {
+            Expr::IfExpression(clean_box(value), clean_box(condition), clean_box(otherwise))
+        }
+        Expr::ListComprehension(element, item_name, iterable, conditions) => {
+            Expr::ListComprehension(clean_box(element), item_name, clean_box(iterable), clean_all(conditions))
+        }
+        Expr::Yield(value) => Expr::Yield(value.map(clean_box)),
+        Expr::ChainedComparison(first, comparisons) => Expr::ChainedComparison(
+            clean_box(first),
+            comparisons
+                .into_iter()
+                .map(|(op, operand)| (op, clean_parens(operand)))
+                .collect(),
+        ),
         _ => expr,
     }
 }
@@ -119,9 +294,17 @@ struct ParsingState {
     current_indent: usize,
 }
 
-#[derive(Debug, Clone)]
-pub enum ParsingError {
-    ExprError(String),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsingError {
+    pub message: String,
+    //index of the token where parsing failed
+    pub token_index: usize,
+}
+
+impl std::fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SyntaxError: {} (at token {})", self.message, self.token_index)
+    }
 }
 
 impl Parser {
@@ -137,6 +320,22 @@ impl Parser {
         }
     }
 
+    fn error(&self, message: impl Into<String>) -> ParsingError {
+        ParsingError {
+            message: message.into(),
+            token_index: self.parsing_state.last().unwrap().index,
+        }
+    }
+
+    //every statement must be followed by a newline or the end of the input
+    fn expect_end_of_statement(&self, statement: &str) -> Result<(), ParsingError> {
+        if !self.is_not_end() || self.cur_is_newline() {
+            Ok(())
+        } else {
+            Err(self.error(format!("Newline or EOF expected after {}, got {:?}", statement, self.cur())))
+        }
+    }
+
     fn new_stack(&mut self) {
         let cur_indent = self.parsing_state.last().unwrap().current_indent;
         self.parsing_state.push(ParsingState {
@@ -213,11 +412,29 @@ impl Parser {
         self.is_not_end() && !self.cur_is_newline()
     }
 
+    //a trailing comma is allowed right before any closing bracket: [1, 2,], f(a,), (1,), {1: 2,}
+    fn at_closing_bracket(&self) -> bool {
+        matches!(
+            self.cur_opt(),
+            Some(Token::CloseParen) | Some(Token::CloseArrayBracket) | Some(Token::CloseBrace)
+        )
+    }
+
     fn cur_is_newline(&self) -> bool {
-        if let Token::NewLine = self.cur() {
-            return true;
+        matches!(self.cur(), Token::NewLine | Token::Dedent)
+    }
+
+    fn cur_is_dedent(&self) -> bool {
+        self.is_not_end() && *self.cur() == Token::Dedent
+    }
+
+    //every indented block is closed by exactly one dedent
+    fn consume_dedent(&mut self) -> Result<(), ParsingError> {
+        if self.cur_is_dedent() {
+            self.next();
+            Ok(())
         } else {
-            return false;
+            Err(self.error("Expected an indented block"))
         }
     }
 
@@ -253,12 +470,12 @@ impl Parser {
         return &mut self.parsing_state.last_mut().unwrap().operator_stack;
     }
 
-    pub fn parse_assign(&mut self) -> Option<AST> {
+    pub fn parse_assign(&mut self) -> Result<Option<AST>, ParsingError> {
         let mut path = vec![];
         while let Token::Identifier(id) = self.cur().clone() {
             path.push(id.clone());
             if self.is_last() {
-                return None;
+                return Ok(None);
             } else {
                 self.next()
             }
@@ -267,207 +484,287 @@ impl Parser {
             }
         }
         if !self.can_go() {
-            return None;
+            return Ok(None);
         }
         if let Token::Assign = self.cur() {
             self.next();
-            let expr = self.parse_expr().expect("Expected expression after assign");
-            Some(AST::Assign {
+            let expr = self.parse_expr()?;
+            Ok(Some(AST::Assign {
+                path: path,
+                expression: expr.resulting_expr,
+            }))
+        } else if let Token::AugmentedAssign(operator) = self.cur().clone() {
+            self.next();
+            let expr = self.parse_expr()?;
+            Ok(Some(AST::AugmentedAssign {
                 path: path,
+                operator: operator,
                 expression: expr.resulting_expr,
-            })
+            }))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    pub fn parse_if_statement(&mut self) -> Option<AST> {
+    pub fn parse_if_statement(&mut self) -> Result<Option<AST>, ParsingError> {
         if let Token::IfKeyword = self.cur().clone() {
             self.next();
             if !self.can_go() {
-                None
+                Ok(None)
             } else {
-                let expr = self.parse_expr().expect("Expected expr").resulting_expr;
-                if let Token::Colon = self.cur() {
-                    self.next();
-                } else {
-                    panic!("Expected colon after if expr");
-                }
-
-                if let Token::NewLine = self.cur() {
-                    self.next();
-                } else {
-                    panic!("Expected newline after colon");
-                }
-
-                self.increment_expected_indent();
-                let ast = self.parse_ast().unwrap();
-                let mut if_statement = AST::IfStatement {
-                    true_branch: ASTIfStatement {
-                        expression: expr,
-                        statements: ast,
-                    },
-                    elifs: vec![],
-                    final_else: None,
+                let expr = self.parse_expr()?.resulting_expr;
+                self.expect_block_start("if expr")?;
+                let ast = self.parse_ast()?;
+                let true_branch = ASTIfStatement {
+                    expression: expr,
+                    statements: ast,
                 };
                 self.decrement_expected_indent();
+                self.consume_dedent()?;
 
-                let cur_identation = self.get_expected_indent();
-                //lets try getting the else statement:
-                self.new_stack();
-                let identation_else = self.skip_whitespace_newline();
+                let mut elifs = vec![];
+                let mut final_else = None;
 
-                if self.can_go() && identation_else == cur_identation {
-                    if let Token::ElseKeyword = self.cur() {
-                        self.next();
-                        if let Token::Colon = self.cur() {
+                //lets try getting the elif and else statements:
+                loop {
+                    let cur_identation = self.get_expected_indent();
+                    self.new_stack();
+                    let identation_else = self.skip_whitespace_newline();
+
+                    if !self.can_go() || identation_else != cur_identation {
+                        self.pop_stack();
+                        break;
+                    }
+
+                    match self.cur() {
+                        Token::ElifKeyword => {
                             self.next();
-                        } else {
-                            panic!("Expected colon after if expr");
+                            let expr = self.parse_expr()?.resulting_expr;
+                            self.expect_block_start("elif")?;
+                            let ast = self.parse_ast()?;
+                            elifs.push(ASTIfStatement {
+                                expression: expr,
+                                statements: ast,
+                            });
+                            self.decrement_expected_indent();
+                            self.consume_dedent()?;
+                            let state = self.pop_stack();
+                            self.set_cur(&state);
                         }
-
-                        if let Token::NewLine = self.cur() {
+                        Token::ElseKeyword => {
                             self.next();
-                        } else {
-                            panic!("Expected newline after colon");
+                            self.expect_block_start("else")?;
+                            final_else = Some(self.parse_ast()?);
+                            self.decrement_expected_indent();
+                            self.consume_dedent()?;
+                            let state = self.pop_stack();
+                            self.set_cur(&state);
+                            break;
                         }
+                        _ => {
+                            self.pop_stack();
+                            break;
+                        }
+                    }
+                }
 
-                        self.increment_expected_indent();
-                        let ast = self.parse_ast().unwrap();
-                        if_statement = match if_statement {
-                            AST::IfStatement {
-                                true_branch,
-                                elifs,
-                                final_else: _,
-                            } => AST::IfStatement {
-                                true_branch,
-                                elifs,
-                                final_else: Some(ast),
-                            },
-                            _ => panic!("Unrecognized ast on if else parsing"),
-                        };
-                        self.decrement_expected_indent();
-                    } else {
-                        self.pop_stack();
+                let if_statement = AST::IfStatement {
+                    true_branch,
+                    elifs,
+                    final_else,
+                };
+                return Ok(Some(if_statement));
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    //the clauses must come in order: except handlers, then else, then finally
+    pub fn parse_try_statement(&mut self) -> Result<Option<AST>, ParsingError> {
+        if let Some(Token::TryKeyword) = self.cur_opt() {
+            self.next();
+        } else {
+            return Ok(None);
+        }
+        self.expect_block_start("try")?;
+        let body = self.parse_ast()?;
+        self.decrement_expected_indent();
+        self.consume_dedent()?;
+
+        let mut handlers: Vec<ExceptHandler> = vec![];
+        let mut else_body = None;
+        let mut finally_body = None;
+        loop {
+            let cur_identation = self.get_expected_indent();
+            self.new_stack();
+            let identation_clause = self.skip_whitespace_newline();
+
+            if !self.can_go() || identation_clause != cur_identation {
+                self.pop_stack();
+                break;
+            }
+
+            match self.cur() {
+                Token::ExceptKeyword if else_body.is_none() && finally_body.is_none() => {
+                    if handlers.last().is_some_and(|handler| handler.exception_type.is_none()) {
+                        return Err(self.error("default 'except:' must be last"));
                     }
-                } else {
+                    self.next();
+                    let mut exception_type = None;
+                    let mut name = None;
+                    if !matches!(self.cur_opt(), Some(Token::Colon)) {
+                        exception_type = Some(self.parse_expr()?.resulting_expr);
+                        if let Some(Token::AsKeyword) = self.cur_opt() {
+                            self.next();
+                            match self.cur_opt() {
+                                Some(Token::Identifier(identifier)) => name = Some(identifier.clone()),
+                                _ => return Err(self.error("Expected name after as in except clause")),
+                            }
+                            self.next();
+                        }
+                    }
+                    self.expect_block_start("except")?;
+                    let body = self.parse_ast()?;
+                    handlers.push(ExceptHandler { exception_type, name, body });
+                }
+                Token::ElseKeyword if !handlers.is_empty() && else_body.is_none() && finally_body.is_none() => {
+                    self.next();
+                    self.expect_block_start("else")?;
+                    else_body = Some(self.parse_ast()?);
+                }
+                Token::FinallyKeyword if finally_body.is_none() => {
+                    self.next();
+                    self.expect_block_start("finally")?;
+                    finally_body = Some(self.parse_ast()?);
+                }
+                Token::ExceptKeyword | Token::ElseKeyword | Token::FinallyKeyword => {
+                    return Err(self.error("try statement clauses must be except, else, finally, in this order"));
+                }
+                _ => {
                     self.pop_stack();
+                    break;
                 }
-                return Some(if_statement);
             }
-        } else {
-            None
+            self.decrement_expected_indent();
+            self.consume_dedent()?;
+            let state = self.pop_stack();
+            self.set_cur(&state);
+        }
+
+        if handlers.is_empty() && finally_body.is_none() {
+            return Err(self.error("Expected except or finally after try block"));
         }
+        return Ok(Some(AST::TryStatement { body, handlers, else_body, finally_body }));
     }
 
-    pub fn parse_classdef(&mut self) -> Option<AST> {
+    pub fn parse_classdef(&mut self) -> Result<Option<AST>, ParsingError> {
         if let Token::ClassDef = self.cur().clone() {
             self.next();
             if !self.can_go() {
-                None
+                Ok(None)
             } else {
                 if let Token::Identifier(name) = self.cur().clone() {
                     self.next();
                     if let Token::Colon = self.cur() {
                         self.next();
                     } else {
-                        panic!("Expected colon after class decl identifier");
+                        return Err(self.error("Expected colon after class decl identifier"));
                     }
                     if let Token::NewLine = self.cur() {
                         self.next();
                     } else {
-                        panic!("Expected newline after colon");
+                        return Err(self.error("Expected newline after colon"));
                     }
                     self.increment_expected_indent();
-                    let ast = self.parse_ast().unwrap();
+                    let ast = self.parse_ast()?;
                     let def_classdecl = AST::ClassDeclaration {
                         class_name: name.clone(),
                         body: ast,
                     };
                     self.decrement_expected_indent();
+                    self.consume_dedent()?;
 
-                    return Some(def_classdecl);
+                    return Ok(Some(def_classdecl));
                 } else {
-                    panic!("Unexpected token: expected identifier, got something else")
+                    return Err(self.error("Unexpected token: expected identifier, got something else"));
                 }
             }
         } else {
-            None
+            Ok(None)
         }
     }
 
-    pub fn parse_while_statement(&mut self) -> Option<AST> {
+    pub fn parse_while_statement(&mut self) -> Result<Option<AST>, ParsingError> {
         if let Token::WhileKeyword = self.cur().clone() {
             self.next();
             if !self.can_go() {
-                None
+                Ok(None)
             } else {
-                let expr = self.parse_expr().expect("Expected expr").resulting_expr;
+                let expr = self.parse_expr()?.resulting_expr;
                 if let Token::Colon = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected colon after while expr");
+                    return Err(self.error("Expected colon after while expr"));
                 }
 
                 if let Token::NewLine = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected newline after colon");
+                    return Err(self.error("Expected newline after colon"));
                 }
 
                 self.increment_expected_indent();
-                let ast = self.parse_ast().unwrap();
+                let ast = self.parse_ast()?;
                 let while_statement = AST::WhileStatement {
                     expression: expr,
                     body: ast,
                 };
                 self.decrement_expected_indent();
+                self.consume_dedent()?;
 
-                return Some(while_statement);
+                return Ok(Some(while_statement));
             }
         } else {
-            None
+            Ok(None)
         }
     }
 
-    pub fn parse_for_statement(&mut self) -> Option<AST> {
+    pub fn parse_for_statement(&mut self) -> Result<Option<AST>, ParsingError> {
         if let Token::ForKeyword = self.cur().clone() {
             self.next();
             if !self.can_go() {
-                None
+                Ok(None)
             } else {
                 let variable_name: String;
                 if let Token::Identifier(name) = self.cur() {
                     variable_name = name.clone();
                     self.next();
                 } else {
-                    panic!("Expected identifier after for keyword")
+                    return Err(self.error("Expected identifier after for keyword"));
                 }
 
                 if let Token::InKeyword = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected in keyword after identifier in for keyword")
+                    return Err(self.error("Expected in keyword after identifier in for keyword"));
                 }
 
-                let expr = self
-                    .parse_expr()
-                    .expect("Expected expr after in keyword in for expression")
-                    .resulting_expr;
+                let expr = self.parse_expr()?.resulting_expr;
                 if let Token::Colon = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected colon after for statement");
+                    return Err(self.error("Expected colon after for statement"));
                 }
 
                 if let Token::NewLine = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected newline after colon");
+                    return Err(self.error("Expected newline after colon"));
                 }
 
                 self.increment_expected_indent();
-                let ast = self.parse_ast().unwrap();
+                let ast = self.parse_ast()?;
 
                 let for_statement = AST::ForStatement {
                     item_name: variable_name,
@@ -475,32 +772,33 @@ impl Parser {
                     body: ast,
                 };
                 self.decrement_expected_indent();
+                self.consume_dedent()?;
 
-                return Some(for_statement);
+                return Ok(Some(for_statement));
             }
         } else {
-            None
+            Ok(None)
         }
     }
 
-    pub fn parse_def_statement(&mut self) -> Option<AST> {
+    pub fn parse_def_statement(&mut self) -> Result<Option<AST>, ParsingError> {
         if let Token::DefKeyword = self.cur().clone() {
             self.next();
             if !self.can_go() {
-                None
+                Ok(None)
             } else {
                 let function_name: String;
                 if let Token::Identifier(name) = self.cur() {
                     function_name = name.clone();
                     self.next();
                 } else {
-                    panic!("Expected function identifier")
+                    return Err(self.error("Expected function identifier"));
                 }
 
                 if let Token::OpenParen = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected open paren function name")
+                    return Err(self.error("Expected open paren function name"));
                 }
                 let mut params: Vec<FunctionParameter> = vec![];
 
@@ -508,6 +806,11 @@ impl Parser {
                     let param_name = name.clone();
                     
                     self.next();
+                    //run_function_kw fills defaults from the end, so they must all come last
+                    let follows_default = matches!(params.last(), Some(FunctionParameter::DefaultValue(..)));
+                    if follows_default && !matches!(self.cur(), Token::Assign) {
+                        return Err(self.error("non-default argument follows default argument"));
+                    }
                     if let Token::Comma = self.cur() {
                         params.push(FunctionParameter::Simple(param_name));
                         self.next();
@@ -515,15 +818,10 @@ impl Parser {
                         self.next();
 
                         //this is potentially a default value expression
-                        let expr = self.parse_expr();
-
-                        match expr {
-                            Ok(expr) => {
-                                params.push(FunctionParameter::DefaultValue(param_name, expr.resulting_expr));
-                            },
-                            Err(e) => {
-                                panic!("Error parsing default value expr: {:?}", e);
-                            }
+                        let expr = self.parse_expr()?;
+                        params.push(FunctionParameter::DefaultValue(param_name, expr.resulting_expr));
+                        if let Token::Comma = self.cur() {
+                            self.next();
                         }
 
                     } else {
@@ -535,17 +833,17 @@ impl Parser {
                 if let Token::CloseParen = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected close paren after parameters in function declaration")
+                    return Err(self.error("Expected close paren after parameters in function declaration"));
                 }
 
                 if let Token::Colon = self.cur() {
                     self.next();
                 } else {
-                    panic!("Expected colon paren after parameters in function declaration")
+                    return Err(self.error("Expected colon paren after parameters in function declaration"));
                 }
 
                 self.increment_expected_indent();
-                let ast = self.parse_ast().unwrap();
+                let ast = self.parse_ast()?;
 
                 let for_statement = AST::DeclareFunction {
                     function_name: function_name,
@@ -553,16 +851,69 @@ impl Parser {
                     body: ast,
                 };
                 self.decrement_expected_indent();
+                self.consume_dedent()?;
 
-                return Some(for_statement);
+                return Ok(Some(for_statement));
             }
         } else {
-            None
+            Ok(None)
         }
     }
 
     //returns the identation level until the first non-whitespace token
     //final state of this function is right at newline, before the identations
+    //consumes the `:` and newline that start a block, and increments the expected indentation
+    fn expect_block_start(&mut self, statement: &str) -> Result<(), ParsingError> {
+        if let Some(Token::Colon) = self.cur_opt() {
+            self.next();
+        } else {
+            return Err(self.error(format!("Expected colon after {}", statement)));
+        }
+
+        if let Some(Token::NewLine) = self.cur_opt() {
+            self.next();
+        } else {
+            return Err(self.error("Expected newline after colon"));
+        }
+        self.increment_expected_indent();
+        Ok(())
+    }
+
+    //one or more @expression lines followed by a def or class
+    fn parse_decorated(&mut self) -> Result<Option<AST>, ParsingError> {
+        let mut decorators = vec![];
+        while let Some(Token::At) = self.cur_opt() {
+            self.next();
+            if !self.can_go() {
+                return Err(self.error("Expected expression after @"));
+            }
+            decorators.push(self.parse_expr()?.resulting_expr);
+            if let Some(Token::NewLine) = self.cur_opt() {
+                self.next();
+            } else {
+                return Err(self.error("Expected newline after decorator"));
+            }
+            let identation_level = self.skip_whitespace_newline();
+            if identation_level != self.get_expected_indent() {
+                return Err(self.error("Expected def or class after decorator"));
+            }
+        }
+        if decorators.is_empty() {
+            return Ok(None);
+        }
+        let definition = match self.parse_def_statement()? {
+            Some(definition) => definition,
+            None => match self.parse_classdef()? {
+                Some(definition) => definition,
+                None => return Err(self.error("Expected def or class after decorator")),
+            },
+        };
+        return Ok(Some(AST::Decorated {
+            decorators,
+            definition: Box::new(definition),
+        }));
+    }
+
     fn skip_whitespace_newline(&mut self) -> usize {
         let mut identation_level = 0;
         while self.is_not_end() {
@@ -588,7 +939,7 @@ impl Parser {
 
             let last_identation = self.skip_whitespace_newline();
             let expected_indent = self.get_expected_indent();
-            if last_identation == expected_indent {
+            if !self.cur_is_dedent() && last_identation == expected_indent {
                 let popped = self.pop_stack();
                 self.set_cur(&popped);
             } else {
@@ -604,16 +955,27 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                if let Some(assign_ast) = self.parse_classdef() {
+                if let Some(decorated) = self.parse_decorated()? {
+                    results.push(decorated);
+                    parsed_successfully = true;
+                    let popped = self.pop_stack();
+                    //correct indentation found: commit
+                    self.set_cur(&popped);
+                    self.expect_end_of_statement("decorated definition")?;
+                } else {
+                    self.pop_stack();
+                }
+            }
+
+            if !parsed_successfully {
+                self.new_stack();
+                if let Some(assign_ast) = self.parse_classdef()? {
                     results.push(assign_ast);
                     parsed_successfully = true;
                     let popped = self.pop_stack();
                     //correct indentation found: commit
                     self.set_cur(&popped);
-                    assert!(
-                        !self.is_not_end() || self.cur_is_newline(),
-                        "Newline or EOF expected after assign"
-                    );
+                    self.expect_end_of_statement("class declaration")?;
                 } else {
                     self.pop_stack();
                 }
@@ -621,16 +983,13 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                if let Some(assign_ast) = self.parse_assign() {
+                if let Some(assign_ast) = self.parse_assign()? {
                     results.push(assign_ast);
                     parsed_successfully = true;
                     let popped = self.pop_stack();
                     //correct indentation found: commit
                     self.set_cur(&popped);
-                    assert!(
-                        !self.is_not_end() || self.cur_is_newline(),
-                        "Newline or EOF expected after assign"
-                    );
+                    self.expect_end_of_statement("assignment")?;
                 } else {
                     self.pop_stack();
                 }
@@ -638,7 +997,7 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                let expr = self.parse_if_statement();
+                let expr = self.parse_if_statement()?;
                 match expr {
                     Some(ast_if) => {
                         results.push(ast_if);
@@ -646,10 +1005,7 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after if block"
-                        );
+                        self.expect_end_of_statement("if block")?;
                     }
                     None => {
                         parsed_successfully = false;
@@ -660,7 +1016,21 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                let expr = self.parse_while_statement();
+                if let Some(try_statement) = self.parse_try_statement()? {
+                    results.push(try_statement);
+                    parsed_successfully = true;
+                    let popped = self.pop_stack();
+                    //correct indentation found: commit
+                    self.set_cur(&popped);
+                    self.expect_end_of_statement("try block")?;
+                } else {
+                    self.pop_stack();
+                }
+            }
+
+            if !parsed_successfully {
+                self.new_stack();
+                let expr = self.parse_while_statement()?;
                 match expr {
                     Some(ast_if) => {
                         results.push(ast_if);
@@ -668,10 +1038,7 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after if block"
-                        );
+                        self.expect_end_of_statement("while block")?;
                     }
                     None => {
                         parsed_successfully = false;
@@ -682,7 +1049,7 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                let expr = self.parse_for_statement();
+                let expr = self.parse_for_statement()?;
                 match expr {
                     Some(ast_for) => {
                         results.push(ast_for);
@@ -690,10 +1057,7 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after for block"
-                        );
+                        self.expect_end_of_statement("for block")?;
                     }
                     None => {
                         parsed_successfully = false;
@@ -704,7 +1068,7 @@ impl Parser {
 
             if !parsed_successfully {
                 self.new_stack();
-                let expr = self.parse_def_statement();
+                let expr = self.parse_def_statement()?;
                 match expr {
                     Some(ast_for) => {
                         results.push(ast_for);
@@ -712,10 +1076,7 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after for block"
-                        );
+                        self.expect_end_of_statement("function declaration")?;
                     }
                     None => {
                         parsed_successfully = false;
@@ -735,11 +1096,60 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after if block, got {:?}",
-                            self.cur_opt()
-                        );
+                        self.expect_end_of_statement("break")?;
+                    }
+                    Token::ContinueKeyword => {
+                        results.push(AST::Continue);
+                        self.next();
+                        parsed_successfully = true;
+                        let popped = self.pop_stack();
+                        //correct indentation found: commit
+                        self.set_cur(&popped);
+                        self.expect_end_of_statement("continue")?;
+                    }
+                    Token::ImportKeyword => {
+                        self.next();
+                        let mut modules = vec![];
+                        loop {
+                            match self.cur_opt() {
+                                Some(Token::Identifier(name)) => modules.push(name.clone()),
+                                _ => return Err(self.error("Expected module name after import")),
+                            }
+                            self.next();
+                            if let Some(Token::Comma) = self.cur_opt() {
+                                self.next();
+                                continue;
+                            }
+                            break;
+                        }
+                        results.push(AST::Import(modules));
+                        parsed_successfully = true;
+                        let popped = self.pop_stack();
+                        //correct indentation found: commit
+                        self.set_cur(&popped);
+                        self.expect_end_of_statement("import")?;
+                    }
+                    Token::DelKeyword => {
+                        self.next();
+                        let mut names = vec![];
+                        loop {
+                            match self.cur_opt() {
+                                Some(Token::Identifier(name)) => names.push(name.clone()),
+                                _ => return Err(self.error("Expected name after del")),
+                            }
+                            self.next();
+                            if let Some(Token::Comma) = self.cur_opt() {
+                                self.next();
+                                continue;
+                            }
+                            break;
+                        }
+                        results.push(AST::Delete(names));
+                        parsed_successfully = true;
+                        let popped = self.pop_stack();
+                        //correct indentation found: commit
+                        self.set_cur(&popped);
+                        self.expect_end_of_statement("del")?;
                     }
                     _ => {
                         parsed_successfully = false;
@@ -764,11 +1174,7 @@ impl Parser {
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after if block, got {:?}",
-                            self.cur_opt()
-                        );
+                        self.expect_end_of_statement("return")?;
                     }
                     _ => {
                         parsed_successfully = false;
@@ -784,20 +1190,46 @@ impl Parser {
                     Token::RaiseKeyword => {
                         self.next();
                         if self.can_go() {
-                            let expr = self.parse_expr()?;
-                            results.push(AST::Raise(expr.resulting_expr));
+                            let exception = self.parse_expr()?.resulting_expr;
+                            let mut cause = None;
+                            if let Some(Token::FromKeyword) = self.cur_opt() {
+                                self.next();
+                                if !self.can_go() {
+                                    return Err(self.error("Expected expression after from in raise"));
+                                }
+                                cause = Some(self.parse_expr()?.resulting_expr);
+                            }
+                            results.push(AST::Raise { exception: Some(exception), cause });
                         } else {
-                            panic!("Must inform expression with raise keyword")
+                            results.push(AST::Raise { exception: None, cause: None });
+                        }
+                        parsed_successfully = true;
+                        let popped = self.pop_stack();
+                        //correct indentation found: commit
+                        self.set_cur(&popped);
+                        self.expect_end_of_statement("raise")?;
+                    }
+                    Token::AssertKeyword => {
+                        self.next();
+                        if !self.can_go() {
+                            return Err(self.error("Must inform expression with assert keyword"));
                         }
+                        let expression = self.parse_expr()?.resulting_expr;
+                        let message = if self.can_go() && self.cur() == &Token::Comma {
+                            self.next();
+                            if !self.can_go() {
+                                return Err(self.error("Must inform message after comma in assert"));
+                            }
+                            Some(self.parse_expr()?.resulting_expr)
+                        } else {
+                            None
+                        };
+                        results.push(AST::Assert { expression, message });
                         parsed_successfully = true;
                         let popped = self.pop_stack();
                         //correct indentation found: commit
                         self.set_cur(&popped);
-                        assert!(
-                            !self.is_not_end() || self.cur_is_newline(),
-                            "Newline or EOF expected after if block, got {:?}",
-                            self.cur_opt()
-                        );
+                        self.expect_end_of_statement("assert")?;
                     }
                     _ => {
                         parsed_successfully = false;
@@ -809,32 +1241,22 @@ impl Parser {
             if !parsed_successfully {
                 self.new_stack();
                 let expr = self.parse_expr()?;
-                results.push(AST::StandaloneExpr(expr.resulting_expr));
+                let statement = self.parse_subscript_assign(expr.resulting_expr)?;
+                results.push(statement);
                 let popped = self.pop_stack();
                 //correct indentation found: commit
                 self.set_cur(&popped);
                 parsed_successfully = true;
-                assert!(
-                    !self.is_not_end() || self.cur_is_newline(),
-                    "Newline or EOF expected after standalone expr, instead found {:?}",
-                    self.cur()
-                );
+                self.expect_end_of_statement("expression")?;
             }
 
             if !parsed_successfully {
-                panic!("Could not parse code")
+                return Err(self.error("Could not parse code"));
             }
 
             if self.is_not_end() {
-                if !self.cur_is_newline() {
-                    panic!(
-                        "is not end but is also not newline, cur = {:?}, parsed = {:?}",
-                        self.cur(),
-                        results
-                    )
-                } else {
-                    continue;
-                }
+                self.expect_end_of_statement("statement")?;
+                continue;
             } else {
                 break;
             }
@@ -850,9 +1272,28 @@ impl Parser {
     *
     */
 
+    //Expressions can only be assigned to when they are index accesses, like `d[k] = v` or `d[k] += v`
+    fn parse_subscript_assign(&mut self, target: Expr) -> Result<AST, ParsingError> {
+        let operator = match self.cur_opt() {
+            Some(Token::Assign) => None,
+            Some(Token::AugmentedAssign(operator)) => Some(operator.clone()),
+            _ => return Ok(AST::StandaloneExpr(target)),
+        };
+        let (indexed, index) = match target {
+            Expr::IndexAccess(indexed, index) => (*indexed, *index),
+            _ => return Err(self.error(String::from("Cannot assign to expression"))),
+        };
+        self.next();
+        let expression = self.parse_expr()?.resulting_expr;
+        Ok(match operator {
+            None => AST::SubscriptAssign { indexed, index, expression },
+            Some(operator) => AST::AugmentedSubscriptAssign { indexed, index, operator, expression },
+        })
+    }
+
     fn index_access_helper(&mut self, expr_list_or_array: &Expr) -> Result<Expr, ParsingError> {
-        if let Token::CloseParen = self.cur() {
-            panic!("Invalid syntax: must inform index value");
+        if let Some(Token::CloseArrayBracket) = self.cur_opt() {
+            return Err(self.error("Invalid syntax: must inform index value"));
         } else {
             self.new_stack();
             let list_of_exprs = self.parse_comma_sep_list_expr();
@@ -864,7 +1305,7 @@ impl Parser {
                     let popped = self.pop_stack();
                     let mut resulting_exprs = expressions.resulting_expr_list;
                     if resulting_exprs.len() > 1 {
-                        panic!("Invalid syntax: must inform only one index");
+                        return Err(self.error("Invalid syntax: must inform only one index"));
                     }
 
                     let fcall = Expr::IndexAccess(
@@ -877,7 +1318,6 @@ impl Parser {
                     return Ok(fcall);
                 }
                 Err(e) => {
-                    eprintln!("Failed parsing exprssion: {:?}", e);
                     return Err(e);
                 }
             }
@@ -885,11 +1325,11 @@ impl Parser {
     }
 
     fn function_call_helper(&mut self, expr_callable: &Expr) -> Result<Expr, ParsingError> {
-        if let Token::CloseParen = self.cur() {
+        if let Some(Token::CloseParen) = self.cur_opt() {
             return Ok(Expr::FunctionCall(Box::new(expr_callable.clone()), vec![]));
         } else {
             self.new_stack();
-            let list_of_exprs = self.parse_comma_sep_list_expr();
+            let list_of_exprs = self.parse_call_arguments();
 
             match list_of_exprs {
                 //try parse stuff
@@ -906,14 +1346,98 @@ impl Parser {
                     return Ok(fcall);
                 }
                 Err(e) => {
-                    eprintln!("Failed parsing exprssion: {:?}", e);
                     return Err(e);
                 }
             }
         }
     }
 
+    //each replacement field was tokenized by the lexer, and must hold exactly one expression
+    fn parse_fstring(&self, parts: Vec<FStringPart>) -> Result<Expr, ParsingError> {
+        let mut segments = vec![];
+        for part in parts {
+            match part {
+                FStringPart::Literal(s) => segments.push(FStringSegment::Literal(s)),
+                FStringPart::Expression { tokens, conversion, format_spec } => {
+                    let as_fstring_error = |e: ParsingError| self.error(format!("f-string: {}", e.message));
+                    check_brackets(&tokens).map_err(as_fstring_error)?;
+                    let mut parser = Parser::new(tokens);
+                    let expr = parser.parse_expr().map_err(as_fstring_error)?.resulting_expr;
+                    if parser.is_not_end() {
+                        return Err(self.error("f-string: expecting '}'"));
+                    }
+                    segments.push(FStringSegment::Value { expr, conversion, format_spec });
+                }
+            }
+        }
+        return Ok(Expr::FormattedString(segments));
+    }
+
+    //a conditional expression has the lowest precedence: `a + b if c else d` is `(a + b) if c else d`,
+    //and the else branch can be another conditional expression
     pub fn parse_expr(&mut self) -> Result<ParseExpressionResult, ParsingError> {
+        if let Some(Token::YieldKeyword) = self.cur_opt() {
+            return self.parse_yield();
+        }
+        let value = self.parse_expr_without_conditional()?.resulting_expr;
+        if !matches!(self.cur_opt(), Some(Token::IfKeyword)) {
+            return Ok(ParseExpressionResult { resulting_expr: value });
+        }
+        self.next();
+        let condition = self.parse_expr_without_conditional()?.resulting_expr;
+        if !matches!(self.cur_opt(), Some(Token::ElseKeyword)) {
+            return Err(self.error("Expected else in conditional expression"));
+        }
+        self.next();
+        let otherwise = self.parse_expr()?.resulting_expr;
+        Ok(ParseExpressionResult {
+            resulting_expr: Expr::IfExpression(Box::new(value), Box::new(condition), Box::new(otherwise)),
+        })
+    }
+
+    //yield takes everything up to the end of the expression, a bare yield yields None
+    fn parse_yield(&mut self) -> Result<ParseExpressionResult, ParsingError> {
+        self.next();
+        let has_value = self.can_go()
+            && !self.at_closing_bracket()
+            && !matches!(self.cur_opt(), Some(Token::Comma) | Some(Token::Colon));
+        let value = if has_value {
+            Some(Box::new(self.parse_expr()?.resulting_expr))
+        } else {
+            None
+        };
+        Ok(ParseExpressionResult { resulting_expr: Expr::Yield(value) })
+    }
+
+    //the iterable and the conditions of a comprehension can't be conditional expressions,
+    //their `if` starts the next condition
+    fn parse_comprehension(&mut self, element: Expr) -> Result<Expr, ParsingError> {
+        self.next();
+        let item_name = match self.cur_opt() {
+            Some(Token::Identifier(name)) => name.clone(),
+            _ => return Err(self.error("Expected a name after for in comprehension")),
+        };
+        self.next();
+        if !matches!(self.cur_opt(), Some(Token::InKeyword)) {
+            return Err(self.error("Expected in after the name in comprehension"));
+        }
+        self.next();
+        let iterable = self.parse_expr_without_conditional()?.resulting_expr;
+        let mut conditions = vec![];
+        while let Some(Token::IfKeyword) = self.cur_opt() {
+            self.next();
+            conditions.push(self.parse_expr_without_conditional()?.resulting_expr);
+        }
+        if !matches!(self.cur_opt(), Some(Token::CloseArrayBracket)) {
+            return Err(self.error("Expected ] after comprehension"));
+        }
+        Ok(Expr::ListComprehension(Box::new(element), item_name, Box::new(iterable), conditions))
+    }
+
+    fn parse_expr_without_conditional(&mut self) -> Result<ParseExpressionResult, ParsingError> {
+        let mut ends_with_operator = false;
+        //at the start of the expression and right after an operator, only unary operators are allowed
+        let mut expects_operand = true;
         loop {
             if !self.can_go() {
                 break;
@@ -925,6 +1449,7 @@ impl Parser {
             {
                 let tok: Token = self.cur().clone();
                 let prev_token = self.prev_token().map(|x| x.clone());
+                let is_operator = matches!(tok, Token::Operator(_) | Token::InKeyword);
                 match tok {
                     Token::OpenParen => {
                         //move to the first token, out of the OpenParen
@@ -940,7 +1465,7 @@ impl Parser {
                         // - `function_call()` and then `(` would work if the function returns another function
                         let mut could_be_fcall = true;
 
-                        if let Some(Token::Operator(_)) = prev_token {
+                        if let Some(Token::Operator(_) | Token::InKeyword) = prev_token {
                             could_be_fcall = false;
                         }
                         if let None = prev_token {
@@ -990,30 +1515,41 @@ impl Parser {
                         } else {
                             self.new_stack(); //new parsing stack/state
                             self.next();
-                            match self.parse_expr() {
-                                //try parse stuff
-                                Ok(expr_result) => {
-                                    //worked
-                                    //commit the result
-                                    let resulting_expr = expr_result.resulting_expr;
-                                    let parenthesized =
-                                        Expr::Parenthesized(Box::new(resulting_expr));
-                                    let popped = self.pop_stack();
-                                    self.push_operand(parenthesized);
-                                    self.set_cur(&popped);
-                                    was_operand = true;
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed parsing exprssion: {:?}", e);
-                                    return Err(e);
-                                }
+                            if let Some(Token::CloseParen) = self.cur_opt() {
+                                let popped = self.pop_stack();
+                                self.push_operand(Expr::Tuple(vec![]));
+                                self.set_cur(&popped);
+                                was_operand = true;
+                            } else {
+                                match self.parse_comma_sep_list_expr() {
+                                    //try parse stuff
+                                    Ok(expressions) => {
+                                        //worked
+                                        //commit the result
+                                        //(x) is just x in parens, (x,) and (x, y) are tuples
+                                        let trailing_comma = matches!(self.prev_token(), Some(Token::Comma));
+                                        let mut resulting_exprs = expressions.resulting_expr_list;
+                                        let resulting_expr = if resulting_exprs.len() == 1 && !trailing_comma {
+                                            Expr::Parenthesized(Box::new(resulting_exprs.pop().unwrap()))
+                                        } else {
+                                            Expr::Tuple(resulting_exprs)
+                                        };
+                                        let popped = self.pop_stack();
+                                        self.push_operand(resulting_expr);
+                                        self.set_cur(&popped);
+                                        was_operand = true;
+                                    }
+                                    Err(e) => {
+                    return Err(e);
+                }
+                                }
                             }
                         }
                     }
                     Token::OpenArrayBracket => {
                         let mut could_be_indexing = true;
 
-                        if let Some(Token::Operator(_)) = prev_token {
+                        if let Some(Token::Operator(_) | Token::InKeyword) = prev_token {
                             //in this case, it could be operators being applied to 2 lists, like a concat
                             could_be_indexing = false;
                         }
@@ -1061,8 +1597,11 @@ impl Parser {
                         } else {
                             self.new_stack(); //new parsing stack/state
                             self.next(); //move to the first token, out of the open array
-                            if let Token::CloseArrayBracket = self.cur() {
+                            was_operand = true;
+                            if let Some(Token::CloseArrayBracket) = self.cur_opt() {
+                                let popped = self.pop_stack();
                                 self.push_operand(Expr::Array(vec![]));
+                                self.set_cur(&popped);
                             } else {
                                 let list_of_exprs = self.parse_comma_sep_list_expr();
                                 match list_of_exprs {
@@ -1070,19 +1609,41 @@ impl Parser {
                                     Ok(expressions) => {
                                         //worked
                                         //commit the result
+                                        let mut resulting_exprs = expressions.resulting_expr_list;
+                                        let resulting_expr = match self.cur_opt() {
+                                            Some(Token::ForKeyword) if resulting_exprs.len() == 1 => {
+                                                self.parse_comprehension(resulting_exprs.pop().unwrap())?
+                                            }
+                                            _ => Expr::Array(resulting_exprs),
+                                        };
                                         let popped = self.pop_stack();
-                                        let resulting_exprs = expressions.resulting_expr_list;
-                                        self.push_operand(Expr::Array(resulting_exprs));
+                                        self.push_operand(resulting_expr);
                                         self.set_cur(&popped);
                                     }
                                     Err(e) => {
-                                        eprintln!("Failed parsing exprssion: {:?}", e);
-                                        return Err(e);
-                                    }
+                    return Err(e);
+                }
                                 }
                             }
                         }
                     }
+                    Token::OpenBrace => {
+                        //{} is always an empty dict, never a set
+                        self.new_stack();
+                        self.next();
+                        if let Some(Token::CloseBrace) = self.cur_opt() {
+                            let popped = self.pop_stack();
+                            self.push_operand(Expr::Dict(vec![]));
+                            self.set_cur(&popped);
+                            was_operand = true;
+                        } else {
+                            let literal = self.parse_brace_literal()?;
+                            let popped = self.pop_stack();
+                            self.push_operand(literal);
+                            self.set_cur(&popped);
+                            was_operand = true;
+                        }
+                    }
                     Token::Identifier(identifier_str) => {
                         self.push_operand(Expr::Variable(identifier_str.to_string()));
                         was_operand = true;
@@ -1099,8 +1660,8 @@ impl Parser {
                             self.push_operand(member_access_expr);
                             was_operand = true;
                         } else {
-                            return Err(ParsingError::ExprError(
-                                "Failed parsing member acessor".into(),
+                            return Err(self.error(
+                                "Failed parsing member acessor",
                             ));
                         }
                     }
@@ -1116,6 +1677,15 @@ impl Parser {
                         self.push_operand(Expr::StringValue(f));
                         was_operand = true;
                     }
+                    Token::LiteralBytes(b) => {
+                        self.push_operand(Expr::BytesValue(b));
+                        was_operand = true;
+                    }
+                    Token::LiteralFString(parts) => {
+                        let formatted = self.parse_fstring(parts)?;
+                        self.push_operand(formatted);
+                        was_operand = true;
+                    }
                     Token::None => {
                         self.push_operand(Expr::None);
                         was_operand = true;
@@ -1128,14 +1698,52 @@ impl Parser {
                         self.push_operand(Expr::BooleanValue(false));
                         was_operand = true;
                     }
+                    Token::Walrus => {
+                        //the assignment expression takes everything on its right side as the value
+                        let target = self.operand_stack_mut().pop();
+                        if let (Some(Expr::Variable(name)), true) = (target, self.operator_stack().is_empty()) {
+                            self.new_stack();
+                            self.next();
+                            let value = self.parse_expr()?.resulting_expr;
+                            let popped = self.pop_stack();
+                            self.set_cur(&popped);
+                            self.push_operand(Expr::NamedExpr(name, Box::new(value)));
+                            break;
+                        } else {
+                            return Err(self.error(
+                                "Invalid target for assignment expression",
+                            ));
+                        }
+                    }
                     Token::CloseParen | Token::CloseArrayBracket => {
                         not_part_of_expr = true;
                     }
+                    //not in is two tokens: the not is only unary when it isn't followed by in
+                    Token::Operator(Operator::Not)
+                        if matches!(self.cur_offset_opt(1), Some(Token::InKeyword)) =>
+                    {
+                        self.next();
+                        self.push_operator(Operator::NotIn);
+                    }
+                    Token::Operator(o) if expects_operand && !is_unary_operator(o) => {
+                        return Err(self.error(format!("Operator {} cannot be used as a unary operator", o)));
+                    }
+                    Token::Operator(o @ (Operator::Invert | Operator::Not)) if !expects_operand => {
+                        return Err(self.error(format!("Operator {} cannot be used as a binary operator", o)));
+                    }
+                    Token::InKeyword if expects_operand => {
+                        return Err(self.error("Expected an expression before in"));
+                    }
+                    Token::InKeyword => self.push_operator(Operator::In),
                     Token::Operator(o) => self.push_operator(o),
                     _ => {
                         not_part_of_expr = true;
                     }
                 }
+                if !not_part_of_expr {
+                    ends_with_operator = is_operator;
+                    expects_operand = is_operator;
+                }
             }
             if not_part_of_expr {
                 break;
@@ -1173,26 +1781,7 @@ impl Parser {
                     let lhs_root = self.operand_stack_mut().pop().unwrap();
                     let op = self.operator_stack_mut().pop().unwrap();
 
-                    let mut bin_op = Expr::BinaryOperation(
-                        Box::new(lhs_root.clone()),
-                        op,
-                        Box::new(rhs_root.clone()),
-                    );
-                    if let Expr::BinaryOperation(lhs_down, op_down, rhs_down) = &lhs_root {
-                        let precedence_down = precedence(*op_down);
-                        let precedence_root = precedence(op);
-                        if precedence_root > precedence_down {
-                            bin_op = Expr::BinaryOperation(
-                                lhs_down.clone(),
-                                *op_down,
-                                Box::new(Expr::BinaryOperation(
-                                    rhs_down.clone(),
-                                    op,
-                                    Box::new(rhs_root.clone()),
-                                )),
-                            );
-                        }
-                    }
+                    let mut bin_op = insert_binary_operation(lhs_root.clone(), op, rhs_root.clone());
                     if let Expr::BinaryOperation(lhs_down, op_down, rhs_down) = &rhs_root {
                         let precedence_down = precedence(*op_down);
                         let precedence_root = precedence(op);
@@ -1213,6 +1802,10 @@ impl Parser {
             }
         }
 
+        if ends_with_operator {
+            return Err(self.error("Expected an expression after operator"));
+        }
+
         //consume the remaining operators
         if self.operand_stack().len() == 1 {
             while self.operator_stack().len() > 0 {
@@ -1223,7 +1816,7 @@ impl Parser {
         }
 
         if !self.operator_stack().is_empty() {
-            return Err(ParsingError::ExprError(format!(
+            return Err(self.error(format!(
                 "Unparsed operators: {:?}, operands = {:?}",
                 self.operator_stack(),
                 self.operand_stack()
@@ -1231,14 +1824,14 @@ impl Parser {
         }
 
         if self.operand_stack().len() > 1 {
-            return Err(ParsingError::ExprError(format!(
+            return Err(self.error(format!(
                 "Unparsed operands: {:?}",
                 self.operand_stack()
             )));
         }
 
         if self.operand_stack().is_empty() {
-            return Err(ParsingError::ExprError(String::from(
+            return Err(self.error(String::from(
                 "Empty operand stack, didn't parse anything",
             )));
         }
@@ -1251,6 +1844,101 @@ impl Parser {
     }
 
     //expr, expr, ..., expr
+    //Same as parse_comma_sep_list_expr, but also accepts keyword arguments like `sep="-"`.
+    //Keyword arguments must come after all positional ones.
+    fn parse_call_arguments(&mut self) -> Result<ParseListExpressionResult, ParsingError> {
+        let mut expressions = vec![];
+        let mut found_keyword = false;
+        loop {
+            let keyword = match (self.cur_opt(), self.cur_offset_opt(1)) {
+                (Some(Token::Identifier(name)), Some(Token::Assign)) => Some(name.clone()),
+                _ => None,
+            };
+
+            if let Some(name) = keyword {
+                self.advance(2);
+                let value = self.parse_expr()?.resulting_expr;
+                expressions.push(Expr::KeywordArgument(name, Box::new(value)));
+                found_keyword = true;
+            } else {
+                let value = self.parse_expr()?.resulting_expr;
+                if found_keyword {
+                    return Err(self.error(String::from(
+                        "Positional argument follows keyword argument",
+                    )));
+                }
+                expressions.push(value);
+            }
+
+            if self.can_go() {
+                if let Token::Comma = self.cur() {
+                    self.next();
+                    if self.at_closing_bracket() {
+                        break;
+                    }
+                    continue;
+                }
+            }
+            break;
+        }
+
+        Ok(ParseListExpressionResult {
+            resulting_expr_list: expressions,
+        })
+    }
+
+    //parses key: value pairs up to the closing brace, leaving the cursor on it
+    //a non-empty brace literal is a dict if its first element is followed by a colon, a set otherwise
+    fn parse_brace_literal(&mut self) -> Result<Expr, ParsingError> {
+        let first = self.parse_expr()?.resulting_expr;
+        if let Some(Token::Colon) = self.cur_opt() {
+            self.next();
+            return Ok(Expr::Dict(self.parse_dict_entries(first)?));
+        }
+        let mut elements = vec![first];
+        while let Some(Token::Comma) = self.cur_opt() {
+            self.next();
+            if self.at_closing_bracket() {
+                break;
+            }
+            elements.push(self.parse_expr()?.resulting_expr);
+        }
+        if let Some(Token::CloseBrace) = self.cur_opt() {
+            Ok(Expr::Set(elements))
+        } else {
+            Err(self.error(String::from("Expected '}' at the end of set literal")))
+        }
+    }
+
+    //called after the first key and its colon were consumed
+    fn parse_dict_entries(&mut self, first_key: Expr) -> Result<Vec<(Expr, Expr)>, ParsingError> {
+        let mut entries = vec![];
+        let mut key = first_key;
+        loop {
+            let value = self.parse_expr()?.resulting_expr;
+            entries.push((key, value));
+
+            if let Some(Token::Comma) = self.cur_opt() {
+                self.next();
+                if !self.at_closing_bracket() {
+                    key = self.parse_expr()?.resulting_expr;
+                    if let Some(Token::Colon) = self.cur_opt() {
+                        self.next();
+                        continue;
+                    }
+                    return Err(self.error(String::from("Expected ':' after dict key")));
+                }
+            }
+            break;
+        }
+
+        if let Some(Token::CloseBrace) = self.cur_opt() {
+            Ok(entries)
+        } else {
+            Err(self.error(String::from("Expected '}' at the end of dict literal")))
+        }
+    }
+
     fn parse_comma_sep_list_expr(&mut self) -> Result<ParseListExpressionResult, ParsingError> {
         let mut expressions = vec![];
         loop {
@@ -1261,14 +1949,16 @@ impl Parser {
                     expressions.push(r.resulting_expr);
                 }
                 Err(e) => {
-                    eprintln!("Error on parse: {:?}", e);
-                    break;
+                    return Err(e);
                 }
             }
 
             if self.can_go() {
                 if let Token::Comma = self.cur() {
                     self.next();
+                    if self.at_closing_bracket() {
+                        break;
+                    }
                     continue;
                 } else {
                     break;
@@ -1279,7 +1969,7 @@ impl Parser {
         }
 
         if expressions.is_empty() {
-            return Err(ParsingError::ExprError(String::from("While parsing list of expressions: no expression was found. Deal with edge cases before calling this expr.")));
+            return Err(self.error(String::from("While parsing list of expressions: no expression was found. Deal with edge cases before calling this expr.")));
         }
 
         Ok(ParseListExpressionResult {
@@ -1298,78 +1988,496 @@ pub struct ParseExpressionResult {
     resulting_expr: Expr,
 }
 
-pub fn parse_ast(tokens: Vec<Token>) -> Vec<AST> {
+fn bracket_str(token: &Token) -> &'static str {
+    match token {
+        Token::OpenParen => "(",
+        Token::CloseParen => ")",
+        Token::OpenArrayBracket => "[",
+        Token::CloseArrayBracket => "]",
+        Token::OpenBrace => "{",
+        Token::CloseBrace => "}",
+        _ => "",
+    }
+}
+
+//Checks that every bracket is closed by the matching one before parsing, so that the error
+//can point to the opening bracket instead of wherever the parser got confused
+fn check_brackets(tokens: &[Token]) -> Result<(), ParsingError> {
+    let mut open_brackets: Vec<usize> = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        let expected_opening = match token {
+            Token::OpenParen | Token::OpenArrayBracket | Token::OpenBrace => {
+                open_brackets.push(index);
+                continue;
+            }
+            Token::CloseParen => Token::OpenParen,
+            Token::CloseArrayBracket => Token::OpenArrayBracket,
+            Token::CloseBrace => Token::OpenBrace,
+            _ => continue,
+        };
+        match open_brackets.pop() {
+            Some(opened_at) if tokens[opened_at] == expected_opening => {}
+            Some(opened_at) => {
+                return Err(ParsingError {
+                    message: format!(
+                        "unbalanced bracket: '{}' does not match '{}' opened at token {}",
+                        bracket_str(token),
+                        bracket_str(&tokens[opened_at]),
+                        opened_at
+                    ),
+                    token_index: index,
+                });
+            }
+            None => {
+                return Err(ParsingError {
+                    message: format!("unbalanced bracket: unmatched '{}'", bracket_str(token)),
+                    token_index: index,
+                });
+            }
+        }
+    }
+    if let Some(opened_at) = open_brackets.pop() {
+        return Err(ParsingError {
+            message: format!(
+                "unexpected EOF: '{}' opened at token {} was never closed",
+                bracket_str(&tokens[opened_at]),
+                opened_at
+            ),
+            token_index: opened_at,
+        });
+    }
+    return Ok(());
+}
+
+pub fn parse_ast(tokens: Vec<Token>) -> Result<Vec<AST>, ParsingError> {
+    check_brackets(&tokens)?;
     let mut parser = Parser::new(tokens);
-    return parser.parse_ast().unwrap();
+    return parser.parse_ast();
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Parses a single expression
+    fn parse(tokens: Vec<Token>) -> Expr {
+        let mut parser = Parser::new(tokens);
+        return parser.parse_expr().unwrap().resulting_expr;
+    }
+
+    #[test]
+    fn malformed_inputs_return_errors() {
+        let inputs = [
+            "x = ",
+            "x = 1 +",
+            "x = 1 2",
+            "def (",
+            "def f(x)\n    return x",
+            "if x\n    y = 1",
+            "if x:\ny = 1",
+            "while x\n    y = 1",
+            "for 1 in x:\n    y = 1",
+            "class :\n    x = 1",
+            "a[]",
+            "raise )",
+            "d = {1: }",
+            "f(a=1, 2)",
+            "1 = 2",
+            "x = 1 if y",
+            "x = [a for in b]",
+            "x = [a for a b]",
+            "x = *3",
+            "x = 1 + / 2",
+            "x = 1 ~ 2",
+        ];
+        for input in inputs.iter() {
+            let tokens = tokenize(input).unwrap();
+            assert!(parse_ast(tokens).is_err(), "expected a syntax error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn pretty_print_if_statement() {
+        let tokens = tokenize("if x > 1:\n    y = f(x)\nelse:\n    y = 'small'").unwrap();
+        let ast = parse_ast(tokens).unwrap();
+        let expected = "\
+IfStatement
+  if:
+    BinaryOperation >
+      Variable x
+      IntegerValue 1
+  then:
+    Assign y
+      FunctionCall
+        Variable f
+        args:
+          Variable x
+  else:
+    Assign y
+      StringValue \"small\"
+";
+        assert_eq!(ast[0].to_string(), expected);
+    }
+
+    #[test]
+    fn syntax_error_reports_message_and_position() {
+        let tokens = tokenize("x = 1 +").unwrap();
+        let error = parse_ast(tokens).unwrap_err();
+        assert_eq!(
+            error,
+            ParsingError {
+                message: String::from("Expected an expression after operator"),
+                token_index: 4,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "SyntaxError: Expected an expression after operator (at token 4)"
+        );
+    }
+
+    #[test]
+    fn non_default_parameter_after_default_is_an_error() {
+        let error = parse_ast(tokenize("def f(a, b=1, c):\n    return a").unwrap()).unwrap_err();
+        assert_eq!(error.message, "non-default argument follows default argument");
+        assert!(parse_ast(tokenize("def f(a, b=1, c=2):\n    return a").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn unclosed_brackets_report_opening_position() {
+        let error = parse_ast(tokenize("(1 + 2").unwrap()).unwrap_err();
+        assert_eq!(
+            error,
+            ParsingError {
+                message: String::from("unexpected EOF: '(' opened at token 0 was never closed"),
+                token_index: 0,
+            }
+        );
+
+        let error = parse_ast(tokenize("x = [1, 2").unwrap()).unwrap_err();
+        assert_eq!(
+            error,
+            ParsingError {
+                message: String::from("unexpected EOF: '[' opened at token 2 was never closed"),
+                token_index: 2,
+            }
+        );
+
+        //newlines inside brackets are ignored, so the bracket swallows the rest of the file
+        let error = parse_ast(tokenize("x = (1 +\n2\ny = 3").unwrap()).unwrap_err();
+        assert_eq!(error.token_index, 2);
+
+        for input in ["(", "[", "{", "f(", "{1: 2"].iter() {
+            let error = parse_ast(tokenize(input).unwrap()).unwrap_err();
+            assert!(error.message.starts_with("unexpected EOF"), "{:?}: {}", input, error);
+        }
+    }
+
+    #[test]
+    fn mismatched_brackets_are_errors() {
+        let error = parse_ast(tokenize("print(a[0)").unwrap()).unwrap_err();
+        assert_eq!(
+            error,
+            ParsingError {
+                message: String::from("unbalanced bracket: ')' does not match '[' opened at token 3"),
+                token_index: 5,
+            }
+        );
+
+        let error = parse_ast(tokenize("1)").unwrap()).unwrap_err();
+        assert_eq!(error.message, "unbalanced bracket: unmatched ')'");
+        assert_eq!(error.token_index, 1);
+
+        for input in ["(1 + 2]", "[1, 2)", "{1: 2)", "f(1]"].iter() {
+            assert!(parse_ast(tokenize(input).unwrap()).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn nested_parens_do_not_leak() {
+        let tokens = tokenize("x = f([(1)], (2).real)").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::Assign {
+            path: vec!["x".into()],
+            expression: Expr::FunctionCall(
+                Box::new(Expr::Variable("f".into())),
+                vec![
+                    Expr::Array(vec![Expr::IntegerValue(1)]),
+                    Expr::MemberAccess(Box::new(Expr::IntegerValue(2)), "real".into()),
+                ],
+            ),
+        }];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn multiline_code() {
+        let tokens = tokenize(
+            "
+x = 'abc' + 'cde'
+y = x + str(True)",
+        )
+        .unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![
+            AST::Assign {
+                path: vec![String::from("x")],
+                expression: Expr::BinaryOperation(
+                    Box::new(Expr::StringValue(String::from("abc"))),
+                    Operator::Plus,
+                    Box::new(Expr::StringValue(String::from("cde"))),
+                ),
+            },
+            AST::Assign {
+                path: vec![String::from("y")],
+                expression: Expr::BinaryOperation(
+                    Box::new(Expr::Variable(String::from("x"))),
+                    Operator::Plus,
+                    Box::new(Expr::FunctionCall(
+                        Box::new(Expr::Variable(String::from("str"))),
+                        vec![Expr::BooleanValue(true)],
+                    )),
+                ),
+            },
+        ];
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn while_statement() {
+        let tokens = tokenize(
+            "
+while True:
+    x = 1
+    break
+",
+        )
+        .unwrap();
+
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::WhileStatement {
+            expression: Expr::BooleanValue(true),
+            body: vec![
+                AST::Assign {
+                    path: vec![String::from("x")],
+                    expression: Expr::IntegerValue(1),
+                },
+                AST::Break,
+            ],
+        }];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn import_statement() {
+        let tokens = tokenize("import copy, json").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::Import(vec![String::from("copy"), String::from("json")])];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn del_statement() {
+        let tokens = tokenize("del len, x").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::Delete(vec![String::from("len"), String::from("x")])];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_unary_minus() {
+        let tokens = tokenize("-2 ** 3 ** ~x // 4").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::BinaryOperation(
+            Box::new(Expr::UnaryExpression(
+                Operator::Minus,
+                Box::new(Expr::BinaryOperation(
+                    Box::new(Expr::IntegerValue(2)),
+                    Operator::Power,
+                    Box::new(Expr::BinaryOperation(
+                        Box::new(Expr::IntegerValue(3)),
+                        Operator::Power,
+                        Box::new(Expr::UnaryExpression(Operator::Invert, Box::new(Expr::Variable(String::from("x"))))),
+                    )),
+                )),
+            )),
+            Operator::FloorDivide,
+            Box::new(Expr::IntegerValue(4)),
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn conditional_expression_has_lowest_precedence() {
+        let tokens = tokenize("a + 1 if b else c if d else 2").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::IfExpression(
+            Box::new(Expr::BinaryOperation(
+                Box::new(Expr::Variable(String::from("a"))),
+                Operator::Plus,
+                Box::new(Expr::IntegerValue(1)),
+            )),
+            Box::new(Expr::Variable(String::from("b"))),
+            Box::new(Expr::IfExpression(
+                Box::new(Expr::Variable(String::from("c"))),
+                Box::new(Expr::Variable(String::from("d"))),
+                Box::new(Expr::IntegerValue(2)),
+            )),
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn list_comprehension_with_conditional_value_and_filters() {
+        let tokens = tokenize("[x if x > 0 else -x for x in xs if x if y]").unwrap();
+        let result = parse(tokens);
+        let x = || Box::new(Expr::Variable(String::from("x")));
+        let expected = Expr::ListComprehension(
+            Box::new(Expr::IfExpression(
+                x(),
+                Box::new(Expr::BinaryOperation(x(), Operator::Greater, Box::new(Expr::IntegerValue(0)))),
+                Box::new(Expr::UnaryExpression(Operator::Minus, x())),
+            )),
+            String::from("x"),
+            Box::new(Expr::Variable(String::from("xs"))),
+            vec![*x(), Expr::Variable(String::from("y"))],
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn yield_takes_the_whole_expression_and_can_be_bare() {
+        let tokens = tokenize("yield a + 1 if b else 2").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::Yield(Some(Box::new(Expr::IfExpression(
+            Box::new(Expr::BinaryOperation(
+                Box::new(Expr::Variable(String::from("a"))),
+                Operator::Plus,
+                Box::new(Expr::IntegerValue(1)),
+            )),
+            Box::new(Expr::Variable(String::from("b"))),
+            Box::new(Expr::IntegerValue(2)),
+        ))));
+        assert_eq!(expected, result);
+
+        let tokens = tokenize("f((yield), 1)").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::FunctionCall(
+            Box::new(Expr::Variable(String::from("f"))),
+            vec![Expr::Yield(None), Expr::IntegerValue(1)],
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn comparisons_chain_unless_parenthesized() {
+        let var = |name: &str| Expr::Variable(String::from(name));
+        let tokens = tokenize("a <= b < c + 1").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::ChainedComparison(
+            Box::new(var("a")),
+            vec![
+                (Operator::LessEquals, var("b")),
+                (Operator::Less, Expr::BinaryOperation(Box::new(var("c")), Operator::Plus, Box::new(Expr::IntegerValue(1)))),
+            ],
+        );
+        assert_eq!(expected, result);
 
-    //Parses a single expression
-    fn parse(tokens: Vec<Token>) -> Expr {
-        let mut parser = Parser::new(tokens);
-        return parser.parse_expr().unwrap().resulting_expr;
+        let tokens = tokenize("(a < b) < c").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::BinaryOperation(
+            Box::new(Expr::BinaryOperation(Box::new(var("a")), Operator::Less, Box::new(var("b")))),
+            Operator::Less,
+            Box::new(var("c")),
+        );
+        assert_eq!(expected, result);
     }
 
     #[test]
-    fn multiline_code() {
+    fn stacked_decorators() {
         let tokens = tokenize(
             "
-x = 'abc' + 'cde'
-y = x + str(True)",
+@outer
+@inner(1)
+def f():
+    return 1
+",
         )
         .unwrap();
-        let result = parse_ast(tokens);
-        let expected = vec![
-            AST::Assign {
-                path: vec![String::from("x")],
-                expression: Expr::BinaryOperation(
-                    Box::new(Expr::StringValue(String::from("abc"))),
-                    Operator::Plus,
-                    Box::new(Expr::StringValue(String::from("cde"))),
-                ),
-            },
-            AST::Assign {
-                path: vec![String::from("y")],
-                expression: Expr::BinaryOperation(
-                    Box::new(Expr::Variable(String::from("x"))),
-                    Operator::Plus,
-                    Box::new(Expr::FunctionCall(
-                        Box::new(Expr::Variable(String::from("str"))),
-                        vec![Expr::BooleanValue(true)],
-                    )),
-                ),
-            },
-        ];
 
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::Decorated {
+            decorators: vec![
+                Expr::Variable(String::from("outer")),
+                Expr::FunctionCall(
+                    Box::new(Expr::Variable(String::from("inner"))),
+                    vec![Expr::IntegerValue(1)],
+                ),
+            ],
+            definition: Box::new(AST::DeclareFunction {
+                function_name: String::from("f"),
+                parameters: vec![],
+                body: vec![AST::Return(Some(Expr::IntegerValue(1)))],
+            }),
+        }];
         assert_eq!(expected, result);
     }
 
     #[test]
-    fn while_statement() {
+    fn decorator_must_precede_definition() {
+        assert!(parse_ast(tokenize("@d
+x = 1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn try_statement_with_else() {
         let tokens = tokenize(
-            "
-while True:
+            "try:
     x = 1
-    break
+except TypeError as e:
+    x = 2
+else:
+    x = 3
 ",
         )
         .unwrap();
 
-        let result = parse_ast(tokens);
-        let expected = vec![AST::WhileStatement {
-            expression: Expr::BooleanValue(true),
-            body: vec![
-                AST::Assign {
-                    path: vec![String::from("x")],
-                    expression: Expr::IntegerValue(1),
-                },
-                AST::Break,
-            ],
+        let assign = |value| AST::Assign {
+            path: vec![String::from("x")],
+            expression: Expr::IntegerValue(value),
+        };
+        let expected = vec![AST::TryStatement {
+            body: vec![assign(1)],
+            handlers: vec![ExceptHandler {
+                exception_type: Some(Expr::Variable(String::from("TypeError"))),
+                name: Some(String::from("e")),
+                body: vec![assign(2)],
+            }],
+            else_body: Some(vec![assign(3)]),
+            finally_body: None,
+        }];
+        assert_eq!(expected, parse_ast(tokens).unwrap());
+    }
+
+    #[test]
+    fn raise_with_cause() {
+        let result = parse_ast(tokenize("raise Wrapped() from e").unwrap()).unwrap();
+        let expected = vec![AST::Raise {
+            exception: Some(Expr::FunctionCall(Box::new(Expr::Variable(String::from("Wrapped"))), vec![])),
+            cause: Some(Expr::Variable(String::from("e"))),
         }];
         assert_eq!(expected, result);
+        assert!(parse_ast(tokenize("raise Wrapped() from").unwrap()).is_err());
+    }
+
+    #[test]
+    fn try_clauses_out_of_order_are_errors() {
+        //else needs an except before it, and must come before finally
+        assert!(parse_ast(tokenize("try:\n    x = 1\nelse:\n    x = 2\n").unwrap()).is_err());
+        assert!(parse_ast(tokenize("try:\n    x = 1\nexcept:\n    x = 2\nfinally:\n    x = 3\nelse:\n    x = 4\n").unwrap()).is_err());
+        assert!(parse_ast(tokenize("try:\n    x = 1\nexcept:\n    x = 2\nelse:\n    x = 3\nexcept E:\n    x = 4\n").unwrap()).is_err());
+        assert!(parse_ast(tokenize("try:\n    x = 1\nexcept:\n    x = 2\nexcept E:\n    x = 3\n").unwrap()).is_err());
     }
 
     #[test]
@@ -1383,7 +2491,7 @@ while True:
         )
         .unwrap();
 
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::WhileStatement {
             expression: Expr::BinaryOperation(
                 Box::new(Expr::Variable("x".to_string())),
@@ -1425,7 +2533,7 @@ print(x)
         )
         .unwrap();
 
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![
             AST::IfStatement {
                 true_branch: ASTIfStatement {
@@ -1499,7 +2607,7 @@ print(x)
 
         let tokens = tokenize(source_replaced.as_str()).unwrap();
 
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![
             AST::IfStatement {
                 true_branch: ASTIfStatement {
@@ -1553,7 +2661,7 @@ if x == 0:
     x = x + 1",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::IfStatement {
             true_branch: ASTIfStatement {
                 expression: Expr::BinaryOperation(
@@ -1584,7 +2692,7 @@ if x == 0:
 print(x)",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![
             AST::IfStatement {
                 true_branch: ASTIfStatement {
@@ -1621,7 +2729,7 @@ y = x + str(True)
 print(y)",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![
             AST::Assign {
                 path: vec![String::from("x")],
@@ -1921,6 +3029,49 @@ print(y)",
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn parse_boolean_operators_bind_looser_than_comparisons() {
+        //x == 1 or y == 2 and z + 1 < 3
+        let tokens = tokenize("x == 1 or y == 2 and z + 1 < 3").unwrap();
+        let result = parse(tokens);
+
+        let var = |name: &str| Box::new(Expr::Variable(String::from(name)));
+        let expected = Expr::BinaryOperation(
+            Box::new(Expr::BinaryOperation(var("x"), Operator::Equals, 1.into())),
+            Operator::Or,
+            Box::new(Expr::BinaryOperation(
+                Box::new(Expr::BinaryOperation(var("y"), Operator::Equals, 2.into())),
+                Operator::And,
+                Box::new(Expr::BinaryOperation(
+                    Box::new(Expr::BinaryOperation(var("z"), Operator::Plus, 1.into())),
+                    Operator::Less,
+                    3.into(),
+                )),
+            )),
+        );
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn parse_not_binds_looser_than_comparisons() {
+        //not a == b and c
+        let tokens = tokenize("not a == b and c").unwrap();
+        let result = parse(tokens);
+
+        let var = |name: &str| Box::new(Expr::Variable(String::from(name)));
+        let expected = Expr::BinaryOperation(
+            Box::new(Expr::UnaryExpression(
+                Operator::Not,
+                Box::new(Expr::BinaryOperation(var("a"), Operator::Equals, var("b"))),
+            )),
+            Operator::And,
+            var("c"),
+        );
+
+        assert_eq!(expected, result);
+    }
+
     #[test]
     fn parse_literal_parens() {
         //(1)
@@ -2515,7 +3666,7 @@ print(y)",
     #[test]
     fn test_assign() {
         let tokens = tokenize("x = 1").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
             expression: Expr::IntegerValue(1),
@@ -2526,7 +3677,7 @@ print(y)",
     #[test]
     fn test_parse_ast_first_token_is_identifier() {
         let tokens = tokenize("x * 1").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::BinaryOperation(
             Box::new(Expr::Variable(String::from("x"))),
             Operator::Multiply,
@@ -2538,7 +3689,7 @@ print(y)",
     #[test]
     fn test_parse_assign_expr() {
         let tokens = tokenize("x = x * 1").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
             expression: Expr::BinaryOperation(
@@ -2553,7 +3704,7 @@ print(y)",
     #[test]
     fn test_parse_just_id_ast() {
         let tokens = tokenize("x").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::Variable(String::from("x")))];
         assert_eq!(expected, result);
     }
@@ -2595,7 +3746,7 @@ print(y)",
     #[test]
     fn assign_boolean_expr() {
         let tokens = tokenize("x = not (True and False) or (False)").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
             expression: Expr::BinaryOperation(
@@ -2618,7 +3769,7 @@ print(y)",
     #[test]
     fn assign_string_expr() {
         let tokens = tokenize("x = 'abc'").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
             expression: Expr::StringValue(String::from("abc")),
@@ -2630,7 +3781,7 @@ print(y)",
     #[test]
     fn assign_string_concat_expr() {
         let tokens = tokenize("x = 'abc' + 'cde'").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
             expression: Expr::BinaryOperation(
@@ -2656,6 +3807,96 @@ print(y)",
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn empty_collection_literals() {
+        assert_eq!(parse(tokenize("[]").unwrap()), Expr::Array(vec![]));
+        assert_eq!(parse(tokenize("{}").unwrap()), Expr::Dict(vec![]));
+        assert_eq!(parse(tokenize("()").unwrap()), Expr::Tuple(vec![]));
+    }
+
+    #[test]
+    fn empty_literals_as_call_arguments() {
+        let tokens = tokenize("f((), {}, [])").unwrap();
+        let result = parse(tokens);
+        let expected = Expr::FunctionCall(
+            Box::new(Expr::Variable("f".into())),
+            vec![Expr::Tuple(vec![]), Expr::Dict(vec![]), Expr::Array(vec![])],
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn trailing_comma_in_list() {
+        let result = parse(tokenize("[1, 2,]").unwrap());
+        let expected = Expr::Array(vec![Expr::IntegerValue(1), Expr::IntegerValue(2)]);
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn trailing_comma_makes_single_element_tuple() {
+        assert_eq!(
+            parse(tokenize("(1,)").unwrap()),
+            Expr::Tuple(vec![Expr::IntegerValue(1)])
+        );
+        assert_eq!(
+            parse(tokenize("(1, 2)").unwrap()),
+            Expr::Tuple(vec![Expr::IntegerValue(1), Expr::IntegerValue(2)])
+        );
+        assert_eq!(parse(tokenize("(1)").unwrap()), Expr::IntegerValue(1));
+    }
+
+    #[test]
+    fn trailing_comma_in_call() {
+        let result = parse(tokenize("f(a,)").unwrap());
+        let expected = Expr::FunctionCall(
+            Box::new(Expr::Variable("f".into())),
+            vec![Expr::Variable("a".into())],
+        );
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn dict_literal_with_trailing_comma() {
+        let result = parse(tokenize("{1: 2, 'a': x + 1,}").unwrap());
+        let expected = Expr::Dict(vec![
+            (Expr::IntegerValue(1), Expr::IntegerValue(2)),
+            (
+                Expr::StringValue("a".into()),
+                Expr::BinaryOperation(
+                    Box::new(Expr::Variable("x".into())),
+                    Operator::Plus,
+                    Box::new(Expr::IntegerValue(1)),
+                ),
+            ),
+        ]);
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn brace_literal_is_a_set_without_colon() {
+        let result = parse(tokenize("{1, x + 1,}").unwrap());
+        let expected = Expr::Set(vec![
+            Expr::IntegerValue(1),
+            Expr::BinaryOperation(
+                Box::new(Expr::Variable("x".into())),
+                Operator::Plus,
+                Box::new(Expr::IntegerValue(1)),
+            ),
+        ]);
+        assert_eq!(expected, result);
+        assert_eq!(parse(tokenize("{(1)}").unwrap()), Expr::Set(vec![Expr::IntegerValue(1)]));
+        assert_eq!(
+            parse(tokenize("{1: 2}").unwrap()),
+            Expr::Dict(vec![(Expr::IntegerValue(1), Expr::IntegerValue(2))])
+        );
+    }
+
+    #[test]
+    fn brace_literal_cannot_mix_set_and_dict_elements() {
+        assert!(parse_ast(tokenize("x = {1: 2, 3}").unwrap()).is_err());
+        assert!(parse_ast(tokenize("x = {1, 2: 3}").unwrap()).is_err());
+    }
+
     #[test]
     fn array_of_strings() {
         let tokens = tokenize("[\"one\",\"two\",\"3\"]").unwrap();
@@ -2686,7 +3927,7 @@ print(y)",
     #[test]
     fn assign_array() {
         let tokens = tokenize("x = [1, 2]").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expr = Expr::Array(vec![Expr::IntegerValue(1), Expr::IntegerValue(2)]);
         let expected = vec![AST::Assign {
             path: vec![String::from("x")],
@@ -2699,7 +3940,7 @@ print(y)",
     fn member_acessor() {
         let tokens = tokenize("obj.prop").unwrap();
         println!("{:?}", tokens);
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::MemberAccess(
             Box::new(Expr::Variable("obj".into())),
             "prop".into(),
@@ -2711,7 +3952,7 @@ print(y)",
     fn assign_member() {
         let tokens = tokenize("obj.prop = 1").unwrap();
         println!("{:?}", tokens);
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::Assign {
             path: vec!["obj".into(), "prop".into()],
             expression: Expr::IntegerValue(1),
@@ -2723,7 +3964,7 @@ print(y)",
     fn member_compare() {
         let tokens = tokenize("self.current >= self.max").unwrap();
         println!("{:?}", tokens);
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(
             Expr::BinaryOperation(
                 Box::new(Expr::MemberAccess(
@@ -2749,7 +3990,7 @@ for item in list:
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::ForStatement {
             item_name: "item".into(),
             list_expression: Expr::Variable("list".into()),
@@ -2770,7 +4011,7 @@ def function(x):
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![FunctionParameter::Simple("x".into())],
@@ -2791,7 +4032,7 @@ def function():
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![],
@@ -2812,7 +4053,7 @@ def function(x,y,z):
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![FunctionParameter::Simple("x".into()), 
@@ -2835,7 +4076,7 @@ def function(x):
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![FunctionParameter::Simple("x".into())],
@@ -2853,7 +4094,7 @@ def function(x):
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![FunctionParameter::Simple("x".into())],
@@ -2875,7 +4116,7 @@ def function(x, y = None):
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::DeclareFunction {
             function_name: "function".into(),
             parameters: vec![FunctionParameter::Simple("x".into()), FunctionParameter::DefaultValue("y".into(), Expr::None)],
@@ -2906,7 +4147,7 @@ class SomeClass:
 ",
         )
         .unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::ClassDeclaration {
             class_name: "SomeClass".into(),
             body: vec![
@@ -2949,7 +4190,7 @@ class SomeClass:
     #[test]
     fn access_at_index() {
         let tokens = tokenize("list[1]").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::IndexAccess(
             Box::new(Expr::Variable("list".into())),
             Box::new(Expr::IntegerValue(1)),
@@ -2960,7 +4201,7 @@ class SomeClass:
     #[test]
     fn access_at_string() {
         let tokens = tokenize("a_map[\"value\"]").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::IndexAccess(
             Box::new(Expr::Variable("a_map".into())),
             Box::new(Expr::StringValue("value".into())),
@@ -2972,7 +4213,7 @@ class SomeClass:
     fn access_at_list() {
         //this is crazy
         let tokens = tokenize("a_map[[]]").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::IndexAccess(
             Box::new(Expr::Variable("a_map".into())),
             Box::new(Expr::Array(vec![])),
@@ -2983,7 +4224,7 @@ class SomeClass:
     #[test]
     fn function_return_indexed() {
         let tokens = tokenize("some_call()[1]").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::IndexAccess(
             Box::new(Expr::FunctionCall(
                 Box::new(Expr::Variable("some_call".into())),
@@ -2997,7 +4238,7 @@ class SomeClass:
     #[test]
     fn function_argument_is_indexed() {
         let tokens = tokenize("some_call(var[1])").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(
             Expr::FunctionCall(
                 Box::new(Expr::Variable("some_call".into())),
@@ -3013,7 +4254,7 @@ class SomeClass:
     #[test]
     fn method_call_empty() {
         let tokens = tokenize("method.call()").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::FunctionCall(
             Box::new(Expr::MemberAccess(
                 Box::new(Expr::Variable("method".into())),
@@ -3026,7 +4267,7 @@ class SomeClass:
     #[test]
     fn method_call_oneparam() {
         let tokens = tokenize("method.call(1)").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::FunctionCall(
             Box::new(Expr::MemberAccess(
                 Box::new(Expr::Variable("method".into())),
@@ -3040,7 +4281,7 @@ class SomeClass:
     #[test]
     fn method_call_manyparam() {
         let tokens = tokenize("method.call(1, 2)").unwrap();
-        let result = parse_ast(tokens);
+        let result = parse_ast(tokens).unwrap();
         let expected = vec![AST::StandaloneExpr(Expr::FunctionCall(
             Box::new(Expr::MemberAccess(
                 Box::new(Expr::Variable("method".into())),
@@ -3050,4 +4291,213 @@ class SomeClass:
         ))];
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn postfix_chain_composes_left_to_right() {
+        let tokens = tokenize("a.b()[0].c").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::StandaloneExpr(Expr::MemberAccess(
+            Box::new(Expr::IndexAccess(
+                Box::new(Expr::FunctionCall(
+                    Box::new(Expr::MemberAccess(
+                        Box::new(Expr::Variable("a".into())),
+                        "b".into(),
+                    )),
+                    vec![],
+                )),
+                Box::new(Expr::IntegerValue(0)),
+            )),
+            "c".into(),
+        ))];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn call_on_call_result() {
+        let tokens = tokenize("f()(1)[2](3)").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::StandaloneExpr(Expr::FunctionCall(
+            Box::new(Expr::IndexAccess(
+                Box::new(Expr::FunctionCall(
+                    Box::new(Expr::FunctionCall(
+                        Box::new(Expr::Variable("f".into())),
+                        vec![],
+                    )),
+                    vec![Expr::IntegerValue(1)],
+                )),
+                Box::new(Expr::IntegerValue(2)),
+            )),
+            vec![Expr::IntegerValue(3)],
+        ))];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn nested_ifs_dedenting_to_different_levels() {
+        let tokens = tokenize(
+            "if a:
+    if b:
+        if c:
+            x = 1
+    y = 2
+z = 3",
+        )
+        .unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![
+            AST::IfStatement {
+                true_branch: ASTIfStatement {
+                    expression: Expr::Variable("a".into()),
+                    statements: vec![
+                        AST::IfStatement {
+                            true_branch: ASTIfStatement {
+                                expression: Expr::Variable("b".into()),
+                                statements: vec![AST::IfStatement {
+                                    true_branch: ASTIfStatement {
+                                        expression: Expr::Variable("c".into()),
+                                        statements: vec![AST::Assign {
+                                            path: vec!["x".into()],
+                                            expression: Expr::IntegerValue(1),
+                                        }],
+                                    },
+                                    elifs: vec![],
+                                    final_else: None,
+                                }],
+                            },
+                            elifs: vec![],
+                            final_else: None,
+                        },
+                        AST::Assign {
+                            path: vec!["y".into()],
+                            expression: Expr::IntegerValue(2),
+                        },
+                    ],
+                },
+                elifs: vec![],
+                final_else: None,
+            },
+            AST::Assign {
+                path: vec!["z".into()],
+                expression: Expr::IntegerValue(3),
+            },
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn walrus_in_if_condition() {
+        let tokens = tokenize("if (n := len(lst)) > 0:\n    x = n").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::IfStatement {
+            true_branch: ASTIfStatement {
+                expression: Expr::BinaryOperation(
+                    Box::new(Expr::NamedExpr(
+                        "n".into(),
+                        Box::new(Expr::FunctionCall(
+                            Box::new(Expr::Variable("len".into())),
+                            vec![Expr::Variable("lst".into())],
+                        )),
+                    )),
+                    Operator::Greater,
+                    Box::new(Expr::IntegerValue(0)),
+                ),
+                statements: vec![AST::Assign {
+                    path: vec!["x".into()],
+                    expression: Expr::Variable("n".into()),
+                }],
+            },
+            elifs: vec![],
+            final_else: None,
+        }];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn function_call_with_keyword_arguments() {
+        let tokens = tokenize("print(a, b, sep=\"-\", end=\"\")").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![AST::StandaloneExpr(Expr::FunctionCall(
+            Box::new(Expr::Variable("print".into())),
+            vec![
+                Expr::Variable("a".into()),
+                Expr::Variable("b".into()),
+                Expr::KeywordArgument("sep".into(), Box::new(Expr::StringValue("-".into()))),
+                Expr::KeywordArgument("end".into(), Box::new(Expr::StringValue("".into()))),
+            ],
+        ))];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn if_elif_else_chain() {
+        let tokens = tokenize(
+            "
+if x == 0:
+    y = 1
+elif x == 1:
+    y = 2
+elif x == 2:
+    y = 3
+else:
+    y = 4
+print(y)
+",
+        )
+        .unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let assign_y = |value| AST::Assign {
+            path: vec![String::from("y")],
+            expression: Expr::IntegerValue(value),
+        };
+        let x_equals = |value| Expr::BinaryOperation(
+            Box::new(Expr::Variable(String::from("x"))),
+            Operator::Equals,
+            Box::new(Expr::IntegerValue(value)),
+        );
+        let expected = vec![
+            AST::IfStatement {
+                true_branch: ASTIfStatement {
+                    expression: x_equals(0),
+                    statements: vec![assign_y(1)],
+                },
+                elifs: vec![
+                    ASTIfStatement {
+                        expression: x_equals(1),
+                        statements: vec![assign_y(2)],
+                    },
+                    ASTIfStatement {
+                        expression: x_equals(2),
+                        statements: vec![assign_y(3)],
+                    },
+                ],
+                final_else: Some(vec![assign_y(4)]),
+            },
+            AST::StandaloneExpr(Expr::FunctionCall(
+                Box::new(Expr::Variable(String::from("print"))),
+                vec![Expr::Variable(String::from("y"))],
+            )),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn assert_with_and_without_message() {
+        let tokens = tokenize("assert x\nassert x == 1, \"x must be 1\"").unwrap();
+        let result = parse_ast(tokens).unwrap();
+        let expected = vec![
+            AST::Assert {
+                expression: Expr::Variable(String::from("x")),
+                message: None,
+            },
+            AST::Assert {
+                expression: Expr::BinaryOperation(
+                    Box::new(Expr::Variable(String::from("x"))),
+                    Operator::Equals,
+                    Box::new(Expr::IntegerValue(1)),
+                ),
+                message: Some(Expr::StringValue(String::from("x must be 1"))),
+            },
+        ];
+        assert_eq!(expected, result);
+    }
 }
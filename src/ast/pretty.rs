@@ -0,0 +1,292 @@
+use crate::ast::parser::*;
+use std::fmt::{Display, Formatter, Result};
+
+//Indented tree form of the parse tree, for --dump-ast: one node per line, named after its variant,
+//with its children one level deeper. Children that need a name are grouped under a label line.
+
+fn line(f: &mut Formatter<'_>, indent: usize, text: &str) -> Result {
+    writeln!(f, "{}{}", "  ".repeat(indent), text)
+}
+
+fn labeled_expr(f: &mut Formatter<'_>, indent: usize, label: &str, expr: &Expr) -> Result {
+    line(f, indent, &format!("{}:", label))?;
+    write_expr(f, indent + 1, expr)
+}
+
+fn labeled_exprs(f: &mut Formatter<'_>, indent: usize, label: &str, exprs: &[Expr]) -> Result {
+    line(f, indent, &format!("{}:", label))?;
+    for expr in exprs {
+        write_expr(f, indent + 1, expr)?;
+    }
+    Ok(())
+}
+
+fn labeled_body(f: &mut Formatter<'_>, indent: usize, label: &str, body: &[AST]) -> Result {
+    line(f, indent, &format!("{}:", label))?;
+    for ast in body {
+        write_ast(f, indent + 1, ast)?;
+    }
+    Ok(())
+}
+
+fn write_expr(f: &mut Formatter<'_>, indent: usize, expr: &Expr) -> Result {
+    match expr {
+        Expr::IntegerValue(i) => line(f, indent, &format!("IntegerValue {}", i)),
+        Expr::FloatValue(value) => line(f, indent, &format!("FloatValue {:?}", value.0)),
+        Expr::StringValue(s) => line(f, indent, &format!("StringValue {:?}", s)),
+        Expr::BytesValue(b) => line(f, indent, &format!("BytesValue {:?}", b)),
+        Expr::BooleanValue(b) => line(f, indent, &format!("BooleanValue {}", if *b { "True" } else { "False" })),
+        Expr::None => line(f, indent, "None"),
+        Expr::FormattedString(segments) => {
+            line(f, indent, "FormattedString")?;
+            for segment in segments {
+                match segment {
+                    FStringSegment::Literal(s) => line(f, indent + 1, &format!("Literal {:?}", s))?,
+                    FStringSegment::Value { expr, conversion, format_spec } => {
+                        let mut header = String::from("Value");
+                        if let Some(conversion) = conversion {
+                            header.push_str(&format!(" !{}", conversion));
+                        }
+                        if let Some(format_spec) = format_spec {
+                            header.push_str(&format!(" :{}", format_spec));
+                        }
+                        line(f, indent + 1, &header)?;
+                        write_expr(f, indent + 2, expr)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Expr::FunctionCall(function, args) => {
+            line(f, indent, "FunctionCall")?;
+            write_expr(f, indent + 1, function)?;
+            if !args.is_empty() {
+                labeled_exprs(f, indent + 1, "args", args)?;
+            }
+            Ok(())
+        }
+        Expr::IndexAccess(value, index) => {
+            line(f, indent, "IndexAccess")?;
+            write_expr(f, indent + 1, value)?;
+            labeled_expr(f, indent + 1, "index", index)
+        }
+        Expr::Variable(name) => line(f, indent, &format!("Variable {}", name)),
+        Expr::BinaryOperation(left, operator, right) => {
+            line(f, indent, &format!("BinaryOperation {}", operator))?;
+            write_expr(f, indent + 1, left)?;
+            write_expr(f, indent + 1, right)
+        }
+        Expr::Parenthesized(value) => {
+            line(f, indent, "Parenthesized")?;
+            write_expr(f, indent + 1, value)
+        }
+        Expr::UnaryExpression(operator, operand) => {
+            line(f, indent, &format!("UnaryExpression {}", operator))?;
+            write_expr(f, indent + 1, operand)
+        }
+        Expr::MemberAccess(value, attr) => {
+            line(f, indent, &format!("MemberAccess .{}", attr))?;
+            write_expr(f, indent + 1, value)
+        }
+        Expr::Array(elements) | Expr::Tuple(elements) | Expr::Set(elements) => {
+            let name = match expr {
+                Expr::Array(_) => "Array",
+                Expr::Tuple(_) => "Tuple",
+                _ => "Set",
+            };
+            line(f, indent, name)?;
+            for element in elements {
+                write_expr(f, indent + 1, element)?;
+            }
+            Ok(())
+        }
+        Expr::Dict(entries) => {
+            line(f, indent, "Dict")?;
+            for (key, value) in entries {
+                line(f, indent + 1, "entry:")?;
+                write_expr(f, indent + 2, key)?;
+                write_expr(f, indent + 2, value)?;
+            }
+            Ok(())
+        }
+        Expr::NamedExpr(name, value) => {
+            line(f, indent, &format!("NamedExpr {}", name))?;
+            write_expr(f, indent + 1, value)
+        }
+        Expr::KeywordArgument(name, value) => {
+            line(f, indent, &format!("KeywordArgument {}", name))?;
+            write_expr(f, indent + 1, value)
+        }
+        Expr::IfExpression(value, condition, otherwise) => {
+            line(f, indent, "IfExpression")?;
+            write_expr(f, indent + 1, value)?;
+            labeled_expr(f, indent + 1, "if", condition)?;
+            labeled_expr(f, indent + 1, "else", otherwise)
+        }
+        Expr::ListComprehension(element, item_name, iterable, conditions) => {
+            line(f, indent, &format!("ListComprehension for {}", item_name))?;
+            write_expr(f, indent + 1, element)?;
+            labeled_expr(f, indent + 1, "in", iterable)?;
+            if !conditions.is_empty() {
+                labeled_exprs(f, indent + 1, "if", conditions)?;
+            }
+            Ok(())
+        }
+        Expr::Yield(value) => {
+            line(f, indent, "Yield")?;
+            match value {
+                Some(value) => write_expr(f, indent + 1, value),
+                None => Ok(()),
+            }
+        }
+        Expr::ChainedComparison(left, comparisons) => {
+            let operators: Vec<String> = comparisons.iter().map(|(operator, _)| operator.to_string()).collect();
+            line(f, indent, &format!("ChainedComparison {}", operators.join(" ")))?;
+            write_expr(f, indent + 1, left)?;
+            for (_, operand) in comparisons {
+                write_expr(f, indent + 1, operand)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_ast(f: &mut Formatter<'_>, indent: usize, ast: &AST) -> Result {
+    match ast {
+        AST::StandaloneExpr(expr) => {
+            line(f, indent, "StandaloneExpr")?;
+            write_expr(f, indent + 1, expr)
+        }
+        AST::Assign { path, expression } => {
+            line(f, indent, &format!("Assign {}", path.join(".")))?;
+            write_expr(f, indent + 1, expression)
+        }
+        AST::AugmentedAssign { path, operator, expression } => {
+            line(f, indent, &format!("AugmentedAssign {} {}=", path.join("."), operator))?;
+            write_expr(f, indent + 1, expression)
+        }
+        AST::SubscriptAssign { indexed, index, expression } => {
+            line(f, indent, "SubscriptAssign")?;
+            write_expr(f, indent + 1, indexed)?;
+            labeled_expr(f, indent + 1, "index", index)?;
+            labeled_expr(f, indent + 1, "value", expression)
+        }
+        AST::AugmentedSubscriptAssign { indexed, index, operator, expression } => {
+            line(f, indent, &format!("AugmentedSubscriptAssign {}=", operator))?;
+            write_expr(f, indent + 1, indexed)?;
+            labeled_expr(f, indent + 1, "index", index)?;
+            labeled_expr(f, indent + 1, "value", expression)
+        }
+        AST::IfStatement { true_branch, elifs, final_else } => {
+            line(f, indent, "IfStatement")?;
+            labeled_expr(f, indent + 1, "if", &true_branch.expression)?;
+            labeled_body(f, indent + 1, "then", &true_branch.statements)?;
+            for elif in elifs {
+                labeled_expr(f, indent + 1, "elif", &elif.expression)?;
+                labeled_body(f, indent + 1, "then", &elif.statements)?;
+            }
+            match final_else {
+                Some(body) => labeled_body(f, indent + 1, "else", body),
+                None => Ok(()),
+            }
+        }
+        AST::WhileStatement { expression, body } => {
+            line(f, indent, "WhileStatement")?;
+            write_expr(f, indent + 1, expression)?;
+            labeled_body(f, indent + 1, "body", body)
+        }
+        AST::ForStatement { item_name, list_expression, body } => {
+            line(f, indent, &format!("ForStatement {}", item_name))?;
+            labeled_expr(f, indent + 1, "in", list_expression)?;
+            labeled_body(f, indent + 1, "body", body)
+        }
+        AST::ClassDeclaration { class_name, body } => {
+            line(f, indent, &format!("ClassDeclaration {}", class_name))?;
+            labeled_body(f, indent + 1, "body", body)
+        }
+        AST::DeclareFunction { function_name, parameters, body } => {
+            line(f, indent, &format!("DeclareFunction {}", function_name))?;
+            if !parameters.is_empty() {
+                line(f, indent + 1, "parameters:")?;
+                for parameter in parameters {
+                    match parameter {
+                        FunctionParameter::Simple(name) => line(f, indent + 2, name)?,
+                        FunctionParameter::DefaultValue(name, default) => {
+                            line(f, indent + 2, &format!("{} =", name))?;
+                            write_expr(f, indent + 3, default)?;
+                        }
+                    }
+                }
+            }
+            labeled_body(f, indent + 1, "body", body)
+        }
+        AST::Break => line(f, indent, "Break"),
+        AST::Continue => line(f, indent, "Continue"),
+        AST::Return(value) => {
+            line(f, indent, "Return")?;
+            match value {
+                Some(value) => write_expr(f, indent + 1, value),
+                None => Ok(()),
+            }
+        }
+        AST::Raise { exception, cause } => {
+            line(f, indent, "Raise")?;
+            if let Some(exception) = exception {
+                write_expr(f, indent + 1, exception)?;
+            }
+            match cause {
+                Some(cause) => labeled_expr(f, indent + 1, "from", cause),
+                None => Ok(()),
+            }
+        }
+        AST::Import(modules) => line(f, indent, &format!("Import {}", modules.join(", "))),
+        AST::Delete(names) => line(f, indent, &format!("Delete {}", names.join(", "))),
+        AST::Decorated { decorators, definition } => {
+            line(f, indent, "Decorated")?;
+            labeled_exprs(f, indent + 1, "decorators", decorators)?;
+            write_ast(f, indent + 1, definition)
+        }
+        AST::Assert { expression, message } => {
+            line(f, indent, "Assert")?;
+            write_expr(f, indent + 1, expression)?;
+            match message {
+                Some(message) => labeled_expr(f, indent + 1, "message", message),
+                None => Ok(()),
+            }
+        }
+        AST::TryStatement { body, handlers, else_body, finally_body } => {
+            line(f, indent, "TryStatement")?;
+            labeled_body(f, indent + 1, "body", body)?;
+            for handler in handlers {
+                let header = match &handler.name {
+                    Some(name) => format!("except as {}:", name),
+                    None => String::from("except:"),
+                };
+                line(f, indent + 1, &header)?;
+                if let Some(exception_type) = &handler.exception_type {
+                    write_expr(f, indent + 2, exception_type)?;
+                }
+                labeled_body(f, indent + 2, "body", &handler.body)?;
+            }
+            if let Some(else_body) = else_body {
+                labeled_body(f, indent + 1, "else", else_body)?;
+            }
+            match finally_body {
+                Some(finally_body) => labeled_body(f, indent + 1, "finally", finally_body),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write_expr(f, 0, self)
+    }
+}
+
+impl Display for AST {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write_ast(f, 0, self)
+    }
+}
@@ -0,0 +1,16 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_type, new_exception};
+
+//the message is kept as it is, assert x, 42 has args (42,)
+pub fn raise_assertion_error(vm: &VM, message: Option<MemoryAddress>) -> MemoryAddress {
+    let exception = new_exception(vm, vm.builtin_type_addrs.assertion_err, message.into_iter().collect());
+    vm.raise_exception(exception);
+    return exception;
+}
+
+pub fn register_assertionerr_type(vm: &mut VM) -> MemoryAddress {
+    let assertion_err = create_exception_type(vm, "AssertionError");
+    vm.builtin_type_addrs.assertion_err = assertion_err;
+    return assertion_err;
+}
@@ -0,0 +1,327 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::ast::lexer::{tokenize, Operator};
+use crate::ast::parser::*;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::builtin_types::syntax_error::raise_syntax_error;
+
+pub const AST_MODULE: &str = "ast";
+
+//one type per AST and Expr variant, plus the structs they hold. None is NoneValue, None is a keyword in python
+const NODE_TYPES: &[&str] = &[
+    "Module", "IfBranch", "ExceptHandler", "Parameter", "FStringLiteral", "FStringValue",
+    "IntegerValue", "FloatValue", "StringValue", "BytesValue", "FormattedString", "BooleanValue", "NoneValue",
+    "FunctionCall", "IndexAccess", "Variable", "BinaryOperation", "Parenthesized", "UnaryExpression",
+    "MemberAccess", "Array", "Tuple", "Dict", "Set", "NamedExpr", "KeywordArgument", "IfExpression",
+    "ListComprehension", "Yield", "ChainedComparison",
+    "StandaloneExpr", "Assign", "AugmentedAssign", "SubscriptAssign", "AugmentedSubscriptAssign",
+    "IfStatement", "WhileStatement", "ForStatement", "ClassDeclaration", "DeclareFunction", "Break",
+    "Continue", "Return", "Raise", "Import", "Delete", "Decorated", "Assert", "TryStatement",
+];
+
+fn string(vm: &VM, value: &str) -> MemoryAddress {
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(value.to_owned()))
+}
+
+fn list(vm: &VM, elements: Vec<MemoryAddress>) -> MemoryAddress {
+    for element in elements.iter() {
+        vm.increase_refcount(*element);
+    }
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(elements))
+}
+
+fn optional(vm: &VM, value: Option<MemoryAddress>) -> MemoryAddress {
+    value.unwrap_or(vm.special_values[&SpecialValue::NoneValue])
+}
+
+fn char_or_none(vm: &VM, value: Option<char>) -> MemoryAddress {
+    optional(vm, value.map(|c| string(vm, &c.to_string())))
+}
+
+fn operator(vm: &VM, operator: &Operator) -> MemoryAddress {
+    string(vm, &operator.to_string())
+}
+
+//an instance of the node type, _fields lists the attribute names in order like python's ast
+fn node(vm: &VM, type_name: &str, fields: Vec<(&str, MemoryAddress)>) -> MemoryAddress {
+    let type_addr = vm.find_in_module(AST_MODULE, type_name).unwrap();
+    let node = vm.allocate_type_byaddr_raw(type_addr, BuiltInTypeData::ClassInstance);
+    let field_names: Vec<MemoryAddress> = fields.iter().map(|(name, _)| string(vm, name)).collect();
+    for name in field_names.iter() {
+        vm.increase_refcount(*name);
+    }
+    let field_names = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.tuple, BuiltInTypeData::Tuple(field_names));
+    vm.set_attribute(node, "_fields", field_names);
+    vm.increase_refcount(field_names);
+    for (name, value) in fields {
+        vm.set_attribute(node, name, value);
+        vm.increase_refcount(value);
+    }
+    return node;
+}
+
+fn expressions(vm: &VM, exprs: &[Expr]) -> MemoryAddress {
+    list(vm, exprs.iter().map(|expr| expression(vm, expr)).collect())
+}
+
+fn expression(vm: &VM, expr: &Expr) -> MemoryAddress {
+    match expr {
+        Expr::IntegerValue(i) => node(vm, "IntegerValue", vec![
+            ("value", vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(*i))),
+        ]),
+        Expr::FloatValue(f) => node(vm, "FloatValue", vec![
+            ("value", vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(*f))),
+        ]),
+        Expr::StringValue(s) => node(vm, "StringValue", vec![("value", string(vm, s))]),
+        Expr::BytesValue(b) => node(vm, "BytesValue", vec![
+            ("value", vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.bytes, BuiltInTypeData::Bytes(b.clone()))),
+        ]),
+        Expr::FormattedString(segments) => {
+            let parts = segments.iter().map(|segment| match segment {
+                FStringSegment::Literal(s) => node(vm, "FStringLiteral", vec![("value", string(vm, s))]),
+                FStringSegment::Value { expr, conversion, format_spec } => node(vm, "FStringValue", vec![
+                    ("value", expression(vm, expr)),
+                    ("conversion", char_or_none(vm, *conversion)),
+                    ("format_spec", optional(vm, format_spec.as_ref().map(|spec| string(vm, spec)))),
+                ]),
+            }).collect();
+            node(vm, "FormattedString", vec![("parts", list(vm, parts))])
+        }
+        Expr::BooleanValue(b) => {
+            let value = if *b { vm.builtin_type_addrs.true_val } else { vm.builtin_type_addrs.false_val };
+            node(vm, "BooleanValue", vec![("value", value)])
+        }
+        Expr::None => node(vm, "NoneValue", vec![]),
+        Expr::FunctionCall(function, args) => node(vm, "FunctionCall", vec![
+            ("function", expression(vm, function)),
+            ("args", expressions(vm, args)),
+        ]),
+        Expr::IndexAccess(value, index) => node(vm, "IndexAccess", vec![
+            ("value", expression(vm, value)),
+            ("index", expression(vm, index)),
+        ]),
+        Expr::Variable(name) => node(vm, "Variable", vec![("name", string(vm, name))]),
+        Expr::BinaryOperation(left, op, right) => node(vm, "BinaryOperation", vec![
+            ("left", expression(vm, left)),
+            ("operator", operator(vm, op)),
+            ("right", expression(vm, right)),
+        ]),
+        Expr::Parenthesized(value) => node(vm, "Parenthesized", vec![("value", expression(vm, value))]),
+        Expr::UnaryExpression(op, operand) => node(vm, "UnaryExpression", vec![
+            ("operator", operator(vm, op)),
+            ("operand", expression(vm, operand)),
+        ]),
+        Expr::MemberAccess(value, attr) => node(vm, "MemberAccess", vec![
+            ("value", expression(vm, value)),
+            ("attr", string(vm, attr)),
+        ]),
+        Expr::Array(elements) => node(vm, "Array", vec![("elements", expressions(vm, elements))]),
+        Expr::Tuple(elements) => node(vm, "Tuple", vec![("elements", expressions(vm, elements))]),
+        Expr::Set(elements) => node(vm, "Set", vec![("elements", expressions(vm, elements))]),
+        Expr::Dict(entries) => {
+            let keys = entries.iter().map(|(key, _)| expression(vm, key)).collect();
+            let values = entries.iter().map(|(_, value)| expression(vm, value)).collect();
+            node(vm, "Dict", vec![("keys", list(vm, keys)), ("values", list(vm, values))])
+        }
+        Expr::NamedExpr(name, value) => node(vm, "NamedExpr", vec![
+            ("name", string(vm, name)),
+            ("value", expression(vm, value)),
+        ]),
+        Expr::KeywordArgument(name, value) => node(vm, "KeywordArgument", vec![
+            ("name", string(vm, name)),
+            ("value", expression(vm, value)),
+        ]),
+        Expr::IfExpression(value, condition, otherwise) => node(vm, "IfExpression", vec![
+            ("value", expression(vm, value)),
+            ("condition", expression(vm, condition)),
+            ("otherwise", expression(vm, otherwise)),
+        ]),
+        Expr::ListComprehension(element, item_name, iterable, conditions) => node(vm, "ListComprehension", vec![
+            ("element", expression(vm, element)),
+            ("item_name", string(vm, item_name)),
+            ("iterable", expression(vm, iterable)),
+            ("conditions", expressions(vm, conditions)),
+        ]),
+        Expr::Yield(value) => node(vm, "Yield", vec![
+            ("value", optional(vm, value.as_ref().map(|value| expression(vm, value)))),
+        ]),
+        Expr::ChainedComparison(left, comparisons) => {
+            let operators = comparisons.iter().map(|(op, _)| operator(vm, op)).collect();
+            let operands = comparisons.iter().map(|(_, operand)| expression(vm, operand)).collect();
+            node(vm, "ChainedComparison", vec![
+                ("left", expression(vm, left)),
+                ("operators", list(vm, operators)),
+                ("operands", list(vm, operands)),
+            ])
+        }
+    }
+}
+
+fn optional_expression(vm: &VM, expr: &Option<Expr>) -> MemoryAddress {
+    optional(vm, expr.as_ref().map(|expr| expression(vm, expr)))
+}
+
+fn names(vm: &VM, names: &[String]) -> MemoryAddress {
+    list(vm, names.iter().map(|name| string(vm, name)).collect())
+}
+
+fn statements(vm: &VM, body: &[AST]) -> MemoryAddress {
+    list(vm, body.iter().map(|ast| statement(vm, ast)).collect())
+}
+
+fn optional_statements(vm: &VM, body: &Option<Vec<AST>>) -> MemoryAddress {
+    optional(vm, body.as_ref().map(|body| statements(vm, body)))
+}
+
+fn if_branch(vm: &VM, branch: &ASTIfStatement) -> MemoryAddress {
+    node(vm, "IfBranch", vec![
+        ("expression", expression(vm, &branch.expression)),
+        ("statements", statements(vm, &branch.statements)),
+    ])
+}
+
+fn statement(vm: &VM, ast: &AST) -> MemoryAddress {
+    match ast {
+        AST::StandaloneExpr(expr) => node(vm, "StandaloneExpr", vec![("value", expression(vm, expr))]),
+        AST::Assign { path, expression: expr } => node(vm, "Assign", vec![
+            ("path", names(vm, path)),
+            ("expression", expression(vm, expr)),
+        ]),
+        AST::AugmentedAssign { path, operator: op, expression: expr } => node(vm, "AugmentedAssign", vec![
+            ("path", names(vm, path)),
+            ("operator", operator(vm, op)),
+            ("expression", expression(vm, expr)),
+        ]),
+        AST::SubscriptAssign { indexed, index, expression: expr } => node(vm, "SubscriptAssign", vec![
+            ("indexed", expression(vm, indexed)),
+            ("index", expression(vm, index)),
+            ("expression", expression(vm, expr)),
+        ]),
+        AST::AugmentedSubscriptAssign { indexed, index, operator: op, expression: expr } => node(vm, "AugmentedSubscriptAssign", vec![
+            ("indexed", expression(vm, indexed)),
+            ("index", expression(vm, index)),
+            ("operator", operator(vm, op)),
+            ("expression", expression(vm, expr)),
+        ]),
+        AST::IfStatement { true_branch, elifs, final_else } => node(vm, "IfStatement", vec![
+            ("true_branch", if_branch(vm, true_branch)),
+            ("elifs", list(vm, elifs.iter().map(|branch| if_branch(vm, branch)).collect())),
+            ("final_else", optional_statements(vm, final_else)),
+        ]),
+        AST::WhileStatement { expression: expr, body } => node(vm, "WhileStatement", vec![
+            ("expression", expression(vm, expr)),
+            ("body", statements(vm, body)),
+        ]),
+        AST::ForStatement { item_name, list_expression, body } => node(vm, "ForStatement", vec![
+            ("item_name", string(vm, item_name)),
+            ("list_expression", expression(vm, list_expression)),
+            ("body", statements(vm, body)),
+        ]),
+        AST::ClassDeclaration { class_name, body } => node(vm, "ClassDeclaration", vec![
+            ("class_name", string(vm, class_name)),
+            ("body", statements(vm, body)),
+        ]),
+        AST::DeclareFunction { function_name, parameters, body } => {
+            let parameters = parameters.iter().map(|parameter| match parameter {
+                FunctionParameter::Simple(name) => node(vm, "Parameter", vec![
+                    ("name", string(vm, name)),
+                    ("default", optional(vm, None)),
+                ]),
+                FunctionParameter::DefaultValue(name, default) => node(vm, "Parameter", vec![
+                    ("name", string(vm, name)),
+                    ("default", expression(vm, default)),
+                ]),
+            }).collect();
+            node(vm, "DeclareFunction", vec![
+                ("function_name", string(vm, function_name)),
+                ("parameters", list(vm, parameters)),
+                ("body", statements(vm, body)),
+            ])
+        }
+        AST::Break => node(vm, "Break", vec![]),
+        AST::Continue => node(vm, "Continue", vec![]),
+        AST::Return(value) => node(vm, "Return", vec![("value", optional_expression(vm, value))]),
+        AST::Raise { exception, cause } => node(vm, "Raise", vec![
+            ("exception", optional_expression(vm, exception)),
+            ("cause", optional_expression(vm, cause)),
+        ]),
+        AST::Import(modules) => node(vm, "Import", vec![("names", names(vm, modules))]),
+        AST::Delete(deleted) => node(vm, "Delete", vec![("names", names(vm, deleted))]),
+        AST::Decorated { decorators, definition } => node(vm, "Decorated", vec![
+            ("decorators", expressions(vm, decorators)),
+            ("definition", statement(vm, definition)),
+        ]),
+        AST::Assert { expression: expr, message } => node(vm, "Assert", vec![
+            ("expression", expression(vm, expr)),
+            ("message", optional_expression(vm, message)),
+        ]),
+        AST::TryStatement { body, handlers, else_body, finally_body } => {
+            let handlers = handlers.iter().map(|handler| node(vm, "ExceptHandler", vec![
+                ("exception_type", optional_expression(vm, &handler.exception_type)),
+                ("name", optional(vm, handler.name.as_ref().map(|name| string(vm, name)))),
+                ("body", statements(vm, &handler.body)),
+            ])).collect();
+            node(vm, "TryStatement", vec![
+                ("body", statements(vm, body)),
+                ("handlers", list(vm, handlers)),
+                ("else_body", optional_statements(vm, else_body)),
+                ("finally_body", optional_statements(vm, finally_body)),
+            ])
+        }
+    }
+}
+
+//Name(field=value, ...), like ast.dump
+fn node_repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let node = call_params.bound_pyobj;
+    let fields_addr = vm.get_obj_property(node, "_fields").unwrap();
+    let fields = vm.get_raw_data_of_pyobj(fields_addr).take_tuple().clone();
+    let mut shown = vec![];
+    for field in fields {
+        let name = vm.get_raw_data_of_pyobj(field).take_string().clone();
+        let value = vm.get_obj_property(node, &name).unwrap();
+        let (repr, _) = vm.call_method(value, "__repr__", PositionalParameters::empty()).unwrap();
+        shown.push(format!("{}={}", name, vm.get_raw_data_of_pyobj(repr).take_string()));
+    }
+    let buffer = format!("{}({})", vm.get_pyobj_type_name(node), shown.join(", "));
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer))
+}
+
+fn create_parse_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let source = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+                BuiltInTypeData::String(source) => source.clone(),
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+                    return raise_type_error(vm, format!("parse() arg 1 must be a string, not {}", type_name));
+                }
+            };
+            let tokens = match tokenize(&source) {
+                Ok(tokens) => tokens,
+                Err(message) => return raise_syntax_error(vm, message),
+            };
+            match parse_ast(tokens) {
+                Ok(ast) => node(vm, "Module", vec![("body", statements(vm, &ast))]),
+                Err(e) => raise_syntax_error(vm, format!("{} (at token {})", e.message, e.token_index)),
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("parse".to_string()));
+}
+
+pub fn register_ast_module(vm: &mut VM) {
+    vm.create_module(AST_MODULE);
+    let base_type = vm.create_type(AST_MODULE, "AST", None);
+    vm.register_bounded_func_on_addr(base_type, "__repr__", node_repr);
+    for type_name in NODE_TYPES {
+        vm.create_type(AST_MODULE, type_name, Some(base_type));
+    }
+    let parse_fn = create_parse_fn(vm);
+    vm.add_to_module(AST_MODULE, "parse", parse_fn);
+}
@@ -224,24 +224,6 @@ fn to_float(vm: &VM, params: CallParams) -> MemoryAddress {
     }
 }
 
-macro_rules! create_unary_function {
-    ($name:tt, $param_a:tt, $func:expr) => {
-        fn $name(vm: &VM, params: CallParams) -> MemoryAddress {
-            let call_params = params.as_method();
-            check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
-            let self_data = vm
-                .get_raw_data_of_pyobj(call_params.bound_pyobj)
-                .take_int();
-            let $param_a = self_data;
-            vm.allocate_type_byaddr_raw(
-                vm.builtin_type_addrs.int,
-                BuiltInTypeData::Int($func),
-            )
-        }
-    };
-}
-create_unary_function!(negation, a, if a == 1 { 0 } else { 1 });
-
 pub fn register_boolean_type(vm: &mut VM) -> MemoryAddress {
     //bool inherits from int
 
@@ -254,7 +236,6 @@ pub fn register_boolean_type(vm: &mut VM) -> MemoryAddress {
     vm.register_bounded_func(BUILTIN_MODULE, "bool", "__or__", or_method);
     vm.register_bounded_func(BUILTIN_MODULE, "bool", "__xor__", xor_method);
     vm.register_bounded_func(BUILTIN_MODULE, "bool", "__not__", not_method);
-    vm.register_bounded_func(BUILTIN_MODULE, "bool", "__neg__", negation);
 
     vm.register_bounded_func(BUILTIN_MODULE, "bool", "__bool__", to_boolean);
     vm.register_bounded_func(BUILTIN_MODULE, "bool", "__str__", to_str);
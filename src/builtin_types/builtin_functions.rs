@@ -1,30 +1,72 @@
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::type_error::{raise_type_error, raise_unhashable_type_error};
+use crate::builtin_types::zero_division_error::raise_zero_division_error;
+use crate::builtin_types::value_error::raise_value_error;
+use crate::builtin_types::syntax_error::raise_syntax_error;
+use crate::bytecode::compiler::{compile_source, compile_eval_source};
+use crate::bytecode::program::Program;
+use crate::runtime::interpreter::{execute_code_object, register_codeobj_consts};
+use crate::commons::arithmetic::{python_mod, python_mod_pow};
+use crate::commons::float::Float;
+use std::convert::TryFrom;
+
+fn call_str(vm: &VM, addr: MemoryAddress) -> String {
+    vm.get_raw_data_of_pyobj(vm.call_str(addr)).take_string().clone()
+}
 
 fn create_print_fn(vm: &VM) -> MemoryAddress {
     let func = PyCallable {
         code: Box::new(move |vm, params| -> MemoryAddress {
             let call_params = params.as_function();
-            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
-            let (str_call_result, _) = vm
-                .call_method(call_params.params[0], "__str__", PositionalParameters::empty())
-                .unwrap();
-            let str_raw = vm.get_raw_data_of_pyobj(str_call_result);
-            match str_raw {
-                BuiltInTypeData::String(s) => {
-                    println!("{}", s);
+            let none = vm.special_values[&SpecialValue::NoneValue];
+
+            let get_separator = |name: &str, default: &str| -> String {
+                match params.keyword_params.get(name) {
+                    Some(addr) if addr != none => call_str(vm, addr),
+                    _ => default.to_string(),
                 }
-                _ => {
-                    panic!("__str__ returned something else than string");
+            };
+
+            for (name, _) in params.keyword_params.params.iter() {
+                if name != "sep" && name != "end" && name != "flush" {
+                    panic!("print() got an unexpected keyword argument {}", name);
                 }
             }
-            return vm.special_values[&SpecialValue::NoneValue];
+
+            let sep = get_separator("sep", " ");
+            let end = get_separator("end", "\n");
+
+            //stdout is always flushed after writing, so flush=True changes nothing
+            let strs: Vec<String> = call_params.params.iter().map(|addr| call_str(vm, *addr)).collect();
+            vm.write_stdout(&(strs.join(&sep) + &end));
+            return none;
         }),
     };
     return vm.create_unbounded_callable_pyobj(func, Some("print".to_string()));
 }
 
+//the prompt goes through the same flushed stdout as print, so prompts and prints appear in order
+fn create_input_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            if call_params.params.len() > 1 {
+                panic!("TypeError: input expected at most 1 argument, got {}", call_params.params.len());
+            }
+            if let Some(prompt) = call_params.params.first() {
+                vm.write_stdout(&call_str(vm, *prompt));
+            }
+            match vm.read_stdin_line() {
+                Some(line) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(line)),
+                None => panic!("EOFError: EOF when reading a line"),
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("input".to_string()));
+}
+
 fn create_printstack_fn(vm: &VM) -> MemoryAddress {
     let func = PyCallable {
         code: Box::new(move |vm, params| -> MemoryAddress {
@@ -45,6 +87,84 @@ fn create_traceback_fn(vm: &VM) -> MemoryAddress {
     };
     return vm.create_unbounded_callable_pyobj(func, Some("traceback".to_string()));
 }
+fn create_repr_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let (repr_call_result, _) = vm
+                .call_method(call_params.params[0], "__repr__", PositionalParameters::empty())
+                .unwrap();
+            return repr_call_result;
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("repr".to_string()));
+}
+fn names_to_dict(vm: &VM, names: Vec<(String, MemoryAddress)>) -> MemoryAddress {
+    let dict = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.dict, BuiltInTypeData::Dict(vec![]));
+    for (name, addr) in names {
+        let key = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(name));
+        crate::builtin_types::dict_type::dict_set(vm, dict, key, addr);
+    }
+    return dict;
+}
+
+fn caller_locals(vm: &VM) -> MemoryAddress {
+    //the top frame belongs to the native call, the caller is right below it
+    let caller_frame = vm.stack.borrow().len() - 2;
+    if caller_frame == 0 {
+        //at module scope, locals are the module globals
+        return names_to_dict(vm, vm.module_namespace(MAIN_MODULE));
+    }
+    return names_to_dict(vm, vm.frame_locals(caller_frame));
+}
+
+fn create_locals_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            check_builtin_func_params!(params.func_name.unwrap(), 0, params.params.len());
+            return caller_locals(vm);
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("locals".to_string()));
+}
+fn create_globals_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            check_builtin_func_params!(params.func_name.unwrap(), 0, params.params.len());
+            return names_to_dict(vm, vm.module_namespace(MAIN_MODULE));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("globals".to_string()));
+}
+fn create_vars_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            if call_params.params.is_empty() {
+                return caller_locals(vm);
+            }
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let attributes = vm.get_pyobj_byaddr(call_params.params[0]).properties
+                .iter()
+                .map(|(name, addr)| (name.clone(), *addr))
+                .collect();
+            return names_to_dict(vm, attributes);
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("vars".to_string()));
+}
+fn create_id_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let id = vm.memory.object_id(call_params.params[0]) as i128;
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(id));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("id".to_string()));
+}
 fn create_len_fn(vm: &VM) -> MemoryAddress {
     let func = PyCallable {
         code: Box::new(move |vm, params| -> MemoryAddress {
@@ -59,6 +179,465 @@ fn create_len_fn(vm: &VM) -> MemoryAddress {
     return vm.create_unbounded_callable_pyobj(func, Some("len".to_string()));
 }
 
+fn create_abs_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            match vm.call_method(call_params.params[0], "__abs__", PositionalParameters::empty()) {
+                Some((result, _)) => result,
+                None => {
+                    let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+                    raise_type_error(vm, format!("bad operand type for abs(): '{}'", type_name))
+                }
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("abs".to_string()));
+}
+
+fn as_f64(data: &BuiltInTypeData) -> Option<f64> {
+    match data {
+        BuiltInTypeData::Int(i) => Some(*i as f64),
+        BuiltInTypeData::Float(f) => Some(f.0),
+        _ => None,
+    }
+}
+
+//(a // b, a % b), with the quotient rounded down so that q * b + r == a holds like in python
+fn create_divmod_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 2, call_params.params.len());
+            let (lhs, rhs) = (call_params.params[0], call_params.params[1]);
+            let lhs_data = vm.get_raw_data_of_pyobj(lhs);
+            let rhs_data = vm.get_raw_data_of_pyobj(rhs);
+
+            let (quotient, remainder) = if let (BuiltInTypeData::Int(a), BuiltInTypeData::Int(b)) = (lhs_data, rhs_data) {
+                if *b == 0 {
+                    return raise_zero_division_error(vm, String::from("integer division or modulo by zero"));
+                }
+                let remainder = python_mod(*a, *b);
+                (
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int((a - remainder) / b)),
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(remainder)),
+                )
+            } else if let (Some(a), Some(b)) = (as_f64(lhs_data), as_f64(rhs_data)) {
+                if b == 0.0 {
+                    return raise_zero_division_error(vm, String::from("float divmod()"));
+                }
+                let remainder = python_mod(a, b);
+                (
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(((a - remainder) / b).round()))),
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(remainder))),
+                )
+            } else {
+                return raise_type_error(vm, format!(
+                    "unsupported operand type(s) for divmod(): '{}' and '{}'",
+                    vm.get_pyobj_type_name(lhs),
+                    vm.get_pyobj_type_name(rhs)
+                ));
+            };
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.tuple, BuiltInTypeData::Tuple(vec![quotient, remainder]));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("divmod".to_string()));
+}
+
+//round half to even like python; an int comes back when ndigits is omitted or None, otherwise the input type is kept
+fn create_round_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            let received = call_params.params.len();
+            if received != 1 && received != 2 {
+                panic!("{}() expected 1 or 2 arguments, got {}", params.func_name.unwrap(), received);
+            }
+            let number = call_params.params[0];
+            let none = vm.special_values[&SpecialValue::NoneValue];
+
+            let ndigits = match call_params.params.get(1) {
+                Some(addr) if *addr != none => match vm.get_raw_data_of_pyobj(*addr) {
+                    BuiltInTypeData::Int(n) => Some(*n),
+                    _ => {
+                        let type_name = vm.get_pyobj_type_name(*addr);
+                        return raise_type_error(vm, format!("'{}' object cannot be interpreted as an integer", type_name));
+                    }
+                },
+                _ => None,
+            };
+
+            match (vm.get_raw_data_of_pyobj(number), ndigits) {
+                (BuiltInTypeData::Int(i), Some(n)) if n < 0 => {
+                    let rounded = match 10i128.checked_pow((-n).min(u32::MAX as i128) as u32) {
+                        Some(step) => {
+                            let remainder = python_mod(*i, step);
+                            let down = i - remainder;
+                            if remainder > step - remainder || (remainder == step - remainder && (down / step) % 2 != 0) {
+                                down + step
+                            } else {
+                                down
+                            }
+                        }
+                        None => 0,
+                    };
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(rounded))
+                }
+                (BuiltInTypeData::Int(i), _) => {
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(*i))
+                }
+                (BuiltInTypeData::Float(f), None) => {
+                    if f.0.is_nan() {
+                        return raise_value_error(vm, String::from("cannot convert float NaN to integer"));
+                    }
+                    if f.0.is_infinite() {
+                        panic!("OverflowError: cannot convert float infinity to integer");
+                    }
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(f.0.round_ties_even() as i128))
+                }
+                (BuiltInTypeData::Float(f), Some(n)) => {
+                    let x = f.0;
+                    let rounded = if !x.is_finite() || n > 400 {
+                        x
+                    } else if n >= 0 {
+                        //formatting rounds the exact decimal value of x, which is what python does too
+                        format!("{:.*}", n as usize, x).parse::<f64>().unwrap()
+                    } else {
+                        let step = 10f64.powi((-n).min(400) as i32);
+                        if step.is_infinite() {
+                            0.0f64.copysign(x)
+                        } else {
+                            (x / step).round_ties_even() * step
+                        }
+                    };
+                    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(rounded)))
+                }
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(number);
+                    raise_type_error(vm, format!("type {} doesn't define __round__ method", type_name))
+                }
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("round".to_string()));
+}
+
+//base ** exp, also what int and float use for __pow__
+pub fn power(vm: &VM, base: MemoryAddress, exp: MemoryAddress) -> MemoryAddress {
+    let base_data = vm.get_raw_data_of_pyobj(base);
+    let exp_data = vm.get_raw_data_of_pyobj(exp);
+    if let (BuiltInTypeData::Int(b), BuiltInTypeData::Int(e)) = (base_data, exp_data) {
+        if *e >= 0 {
+            let result = u32::try_from(*e).ok().and_then(|e| b.checked_pow(e));
+            return match result {
+                Some(result) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(result)),
+                None => panic!("OverflowError: integer result of pow() is too large"),
+            };
+        }
+    }
+
+    match (as_f64(base_data), as_f64(exp_data)) {
+        (Some(b), Some(e)) => {
+            if b == 0.0 && e < 0.0 {
+                return raise_zero_division_error(vm, String::from("0.0 cannot be raised to a negative power"));
+            }
+            if b < 0.0 && e.fract() != 0.0 {
+                panic!("pow() of a negative number to a fractional power would be complex, which is not supported");
+            }
+            vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(b.powf(e))))
+        }
+        _ => raise_type_error(vm, format!(
+            "unsupported operand type(s) for ** or pow(): '{}' and '{}'",
+            vm.get_pyobj_type_name(base),
+            vm.get_pyobj_type_name(exp)
+        )),
+    }
+}
+
+//pow(base, exp) behaves like base ** exp, pow(base, exp, mod) does modular exponentiation on ints
+fn create_pow_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            let received = call_params.params.len();
+            if received != 2 && received != 3 {
+                panic!("{}() expected 2 or 3 arguments, got {}", params.func_name.unwrap(), received);
+            }
+            let (base, exp) = (call_params.params[0], call_params.params[1]);
+            let base_data = vm.get_raw_data_of_pyobj(base);
+            let exp_data = vm.get_raw_data_of_pyobj(exp);
+            let none = vm.special_values[&SpecialValue::NoneValue];
+
+            if let Some(modulus) = call_params.params.get(2).filter(|addr| **addr != none) {
+                let (b, e, m) = match (base_data, exp_data, vm.get_raw_data_of_pyobj(*modulus)) {
+                    (BuiltInTypeData::Int(b), BuiltInTypeData::Int(e), BuiltInTypeData::Int(m)) => (*b, *e, *m),
+                    _ => return raise_type_error(vm, String::from("pow() 3rd argument not allowed unless all arguments are integers")),
+                };
+                if m == 0 {
+                    return raise_value_error(vm, String::from("pow() 3rd argument cannot be 0"));
+                }
+                return match python_mod_pow(b, e, m) {
+                    Some(result) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(result)),
+                    None => raise_value_error(vm, String::from("base is not invertible for the given modulus")),
+                };
+            }
+
+            return power(vm, base, exp);
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("pow".to_string()));
+}
+
+fn create_format_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            let received = call_params.params.len();
+            if received != 1 && received != 2 {
+                panic!("{}() expected 1 or 2 arguments, got {}", params.func_name.unwrap(), received);
+            }
+            let spec = match call_params.params.get(1) {
+                Some(addr) => match vm.get_raw_data_of_pyobj(*addr) {
+                    BuiltInTypeData::String(_) => *addr,
+                    _ => {
+                        let type_name = vm.get_pyobj_type_name(*addr);
+                        return raise_type_error(vm, format!("format() argument 2 must be str, not {}", type_name));
+                    }
+                },
+                None => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(String::new())),
+            };
+            return vm.call_format(call_params.params[0], spec);
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("format".to_string()));
+}
+
+fn create_hash_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            match vm.call_hash(call_params.params[0]) {
+                Some(hash) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash)),
+                None => raise_unhashable_type_error(vm, call_params.params[0]),
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("hash".to_string()));
+}
+
+//consumes the iterable, Err holds the exception raised along the way
+//iterable.__iter__(), Err holds the exception it raised or the TypeError when there is none
+pub fn get_iterator(vm: &VM, iterable: MemoryAddress) -> Result<MemoryAddress, MemoryAddress> {
+    match vm.call_method(iterable, "__iter__", PositionalParameters::empty()) {
+        Some((_, frame)) if frame.exception.is_some() => Err(frame.exception.unwrap()),
+        Some((iterator, _)) => Ok(iterator),
+        None => {
+            let type_name = vm.get_pyobj_type_name(iterable);
+            Err(raise_type_error(vm, format!("'{}' object is not iterable", type_name)))
+        }
+    }
+}
+
+pub fn collect_iterable(vm: &VM, iterable: MemoryAddress) -> Result<Vec<MemoryAddress>, MemoryAddress> {
+    let iterator = get_iterator(vm, iterable)?;
+    let mut elements = vec![];
+    loop {
+        let (next, frame) = vm.call_method(iterator, "__next__", PositionalParameters::empty()).unwrap();
+        match frame.exception {
+            Some(exception) if vm.is_stop_iteration(exception) => return Ok(elements),
+            Some(exception) => return Err(exception),
+            None => elements.push(next),
+        }
+    }
+}
+
+//min and max keep the first of equal elements, an element only replaces the current one
+//when element.compare_method(current) is true
+fn find_extreme(vm: &VM, params: CallParams, compare_method: &str, operator: &str) -> MemoryAddress {
+    let call_params = params.as_function();
+    let func_name = params.func_name.unwrap();
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    for (name, _) in params.keyword_params.params.iter() {
+        if name != "key" && name != "default" {
+            return raise_type_error(vm, format!("{}() got an unexpected keyword argument '{}'", func_name, name));
+        }
+    }
+    let key = params.keyword_params.get("key").filter(|key| *key != none);
+    let default = params.keyword_params.get("default");
+
+    let elements = match call_params.params.len() {
+        0 => return raise_type_error(vm, format!("{} expected at least 1 argument, got 0", func_name)),
+        1 => match collect_iterable(vm, call_params.params[0]) {
+            Ok(elements) => elements,
+            Err(exception) => {
+                vm.raise_exception(exception);
+                return exception;
+            }
+        },
+        _ if default.is_some() => {
+            return raise_type_error(vm, format!("Cannot specify a default for {}() with multiple positional arguments", func_name));
+        }
+        _ => call_params.params.clone(),
+    };
+
+    let mut best: Option<(MemoryAddress, MemoryAddress)> = None;
+    for element in elements {
+        let element_key = match key {
+            Some(key) => {
+                let (result, frame) = vm.run_function(PositionalParameters::single(element), key, None);
+                if let Some(exception) = frame.exception {
+                    vm.raise_exception(exception);
+                    return exception;
+                }
+                result
+            }
+            None => element,
+        };
+        best = match best {
+            None => Some((element, element_key)),
+            Some((best_element, best_key)) => {
+                let replaces = match vm.call_method(element_key, compare_method, PositionalParameters::single(best_key)) {
+                    Some((_, frame)) if frame.exception.is_some() => {
+                        let exception = frame.exception.unwrap();
+                        vm.raise_exception(exception);
+                        return exception;
+                    }
+                    Some((result, _)) => vm.is_truthy(result),
+                    None => {
+                        let (element_type, best_type) = (vm.get_pyobj_type_name(element_key), vm.get_pyobj_type_name(best_key));
+                        return raise_type_error(vm, format!("'{}' not supported between instances of '{}' and '{}'", operator, element_type, best_type));
+                    }
+                };
+                if replaces { Some((element, element_key)) } else { Some((best_element, best_key)) }
+            }
+        };
+    }
+
+    match (best, default) {
+        (Some((element, _)), _) => element,
+        (None, Some(default)) => default,
+        (None, None) => panic!("ValueError: {}() iterable argument is empty", func_name),
+    }
+}
+
+fn create_min_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            return find_extreme(vm, params, "__lt__", "<");
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("min".to_string()));
+}
+
+fn create_max_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            return find_extreme(vm, params, "__gt__", ">");
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("max".to_string()));
+}
+
+//element.__lt__(other), Err holds the exception raised by the comparison
+fn less_than(vm: &VM, element: MemoryAddress, other: MemoryAddress) -> Result<bool, MemoryAddress> {
+    match vm.call_method(element, "__lt__", PositionalParameters::single(other)) {
+        Some((_, frame)) if frame.exception.is_some() => Err(frame.exception.unwrap()),
+        Some((result, _)) => Ok(vm.is_truthy(result)),
+        None => {
+            let (element_type, other_type) = (vm.get_pyobj_type_name(element), vm.get_pyobj_type_name(other));
+            Err(raise_type_error(vm, format!("'<' not supported between instances of '{}' and '{}'", element_type, other_type)))
+        }
+    }
+}
+
+//a merge sort that only needs right_first to answer, unlike slice::sort_by it can't panic when the
+//comparisons aren't a total order, like with NaN or an inconsistent __lt__. The first error stops it
+fn merge_sort<T: Copy, E>(items: Vec<T>, right_first: &mut impl FnMut(T, T) -> Result<bool, E>) -> Result<Vec<T>, E> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let left = merge_sort(left, right_first)?;
+    let right = merge_sort(right, right_first)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if right_first(left[i], right[j])? {
+            merged.push(right[j]);
+            j += 1;
+        } else {
+            merged.push(left[i]);
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+    return Ok(merged);
+}
+
+//stable even when reversed, the key is called exactly once per element and only < is used
+fn create_sorted_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let none = vm.special_values[&SpecialValue::NoneValue];
+            for (name, _) in params.keyword_params.params.iter() {
+                if name != "key" && name != "reverse" {
+                    return raise_type_error(vm, format!("sorted() got an unexpected keyword argument '{}'", name));
+                }
+            }
+            let key = params.keyword_params.get("key").filter(|key| *key != none);
+            let reverse = params.keyword_params.get("reverse").is_some_and(|reverse| vm.is_truthy(reverse));
+
+            let elements = match collect_iterable(vm, call_params.params[0]) {
+                Ok(elements) => elements,
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    return exception;
+                }
+            };
+            let mut keyed = vec![];
+            for element in elements {
+                let element_key = match key {
+                    Some(key) => {
+                        let (result, frame) = vm.run_function(PositionalParameters::single(element), key, None);
+                        if let Some(exception) = frame.exception {
+                            vm.raise_exception(exception);
+                            return exception;
+                        }
+                        result
+                    }
+                    None => element,
+                };
+                keyed.push((element, element_key));
+            }
+
+            //only asks whether the right one goes first, so equal keys keep their order either way
+            let result = merge_sort(keyed, &mut |(_, left), (_, right)| {
+                if reverse { less_than(vm, left, right) } else { less_than(vm, right, left) }
+            });
+            let keyed = match result {
+                Ok(keyed) => keyed,
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    return exception;
+                }
+            };
+
+            let sorted: Vec<MemoryAddress> = keyed.into_iter().map(|(element, _)| element).collect();
+            for element in sorted.iter() {
+                vm.increase_refcount(*element);
+            }
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(sorted));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("sorted".to_string()));
+}
+
 fn create_panic_fn(vm: &VM) -> MemoryAddress {
     let func = PyCallable {
         code: Box::new(move |vm, params| -> MemoryAddress {
@@ -81,15 +660,193 @@ fn create_panic_fn(vm: &VM) -> MemoryAddress {
     return vm.create_unbounded_callable_pyobj(func, Some("print".to_string()));
 }
 
-pub fn register_builtin_functions(vm: &mut VM) {
+//the code object of the program's main code, Err holds the SyntaxError raised
+fn to_code_object(vm: &VM, compiled: Result<Program, String>) -> Result<MemoryAddress, MemoryAddress> {
+    match compiled {
+        Ok(program) => {
+            let main_code = program.code_objects.iter().find(|x| x.main).unwrap();
+            let code = register_codeobj_consts(vm, main_code);
+            Ok(vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.code_object, BuiltInTypeData::CodeObject(code)))
+        }
+        Err(message) => Err(raise_syntax_error(vm, message)),
+    }
+}
+
+//eval and exec see the locals of the function that calls them, at module scope they work on the globals
+fn caller_namespace(vm: &VM) -> Option<Namespace> {
+    //the top frame belongs to the native call, the caller is right below it
+    let caller_frame = vm.stack.borrow().len() - 2;
+    {
+        let stack = vm.stack.borrow();
+        let caller = &stack[caller_frame];
+        if caller.namespace.is_some() {
+            return caller.namespace.clone();
+        }
+        if caller_frame == 0 || caller.function_name == "<module>" {
+            return None;
+        }
+    }
+    return Some(vm.frame_locals(caller_frame).into_iter().collect());
+}
+
+//runs the code in a new frame with the caller's namespace, Err holds the exception it raised
+fn run_code_object(vm: &VM, code_addr: MemoryAddress) -> Result<MemoryAddress, MemoryAddress> {
+    let code = match vm.get_raw_data_of_pyobj(code_addr) {
+        BuiltInTypeData::CodeObject(code) => code.clone(),
+        _ => panic!("Not a code object at addr: {:?}", code_addr),
+    };
+    let namespace = caller_namespace(vm);
+    vm.new_stack_frame("<module>");
+    if let Some(namespace) = namespace {
+        vm.set_frame_namespace(namespace);
+    }
+    execute_code_object(vm, &code);
+    //the result or an uncaught exception may only be referenced by the frame's stack, keep them alive past the pop
+    let result = match vm.get_current_exception() {
+        Some(exception) => Err(exception),
+        None => Ok(vm.top_stack()),
+    };
+    match result {
+        Ok(addr) | Err(addr) => vm.increase_refcount(addr),
+    }
+    vm.pop_stack_frame();
+    return result;
+}
+
+fn create_compile_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let source = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+                BuiltInTypeData::String(source) => source.clone(),
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+                    return raise_type_error(vm, format!("compile() arg 1 must be a string, not {}", type_name));
+                }
+            };
+            match to_code_object(vm, compile_source(&source)) {
+                Ok(code) | Err(code) => code,
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("compile".to_string()));
+}
+
+//runs source or a code object from compile() in the caller's scope. Inside a function, the names it
+//assigns go to a copy of the function's locals and are gone when exec returns, like in python.
+fn create_exec_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let code_addr = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+                BuiltInTypeData::String(source) => match to_code_object(vm, compile_source(&source.clone())) {
+                    Ok(code) => code,
+                    Err(exception) => return exception,
+                },
+                BuiltInTypeData::CodeObject(_) => call_params.params[0],
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+                    return raise_type_error(vm, format!("exec() arg 1 must be a string or code object, not {}", type_name));
+                }
+            };
+            match run_code_object(vm, code_addr) {
+                Ok(_) => vm.special_values[&SpecialValue::NoneValue],
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    exception
+                }
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("exec".to_string()));
+}
+
+//evaluates a single expression in the caller's scope
+fn create_eval_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let source = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+                BuiltInTypeData::String(source) => source.clone(),
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+                    return raise_type_error(vm, format!("eval() arg 1 must be a string, not {}", type_name));
+                }
+            };
+            let code_addr = match to_code_object(vm, compile_eval_source(&source)) {
+                Ok(code) => code,
+                Err(exception) => return exception,
+            };
+            match run_code_object(vm, code_addr) {
+                Ok(result) => result,
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    exception
+                }
+            }
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("eval".to_string()));
+}
+
+//functions that only compute on their arguments, safe to give to sandboxed code
+pub fn register_core_builtin_functions(vm: &mut VM) {
+    let len_fn = create_len_fn(vm);
+    let repr_fn = create_repr_fn(vm);
+    let locals_fn = create_locals_fn(vm);
+    let globals_fn = create_globals_fn(vm);
+    let id_fn = create_id_fn(vm);
+    let vars_fn = create_vars_fn(vm);
+    let abs_fn = create_abs_fn(vm);
+    let divmod_fn = create_divmod_fn(vm);
+    let round_fn = create_round_fn(vm);
+    let pow_fn = create_pow_fn(vm);
+    let format_fn = create_format_fn(vm);
+    let hash_fn = create_hash_fn(vm);
+    let min_fn = create_min_fn(vm);
+    let max_fn = create_max_fn(vm);
+    let sorted_fn = create_sorted_fn(vm);
+    vm.add_to_module(BUILTIN_MODULE, "len", len_fn);
+    vm.add_to_module(BUILTIN_MODULE, "repr", repr_fn);
+    vm.add_to_module(BUILTIN_MODULE, "locals", locals_fn);
+    vm.add_to_module(BUILTIN_MODULE, "globals", globals_fn);
+    vm.add_to_module(BUILTIN_MODULE, "id", id_fn);
+    vm.add_to_module(BUILTIN_MODULE, "vars", vars_fn);
+    vm.add_to_module(BUILTIN_MODULE, "abs", abs_fn);
+    vm.add_to_module(BUILTIN_MODULE, "divmod", divmod_fn);
+    vm.add_to_module(BUILTIN_MODULE, "round", round_fn);
+    vm.add_to_module(BUILTIN_MODULE, "pow", pow_fn);
+    vm.add_to_module(BUILTIN_MODULE, "format", format_fn);
+    vm.add_to_module(BUILTIN_MODULE, "hash", hash_fn);
+    vm.add_to_module(BUILTIN_MODULE, "min", min_fn);
+    vm.add_to_module(BUILTIN_MODULE, "max", max_fn);
+    vm.add_to_module(BUILTIN_MODULE, "sorted", sorted_fn);
+}
+
+//functions that compile and run source code given at runtime. Unlike the core ones, what they do is decided
+//by their argument instead of the host, so they are opt-in like the I/O ones.
+pub fn register_dynamic_code_builtin_functions(vm: &mut VM) {
+    let compile_fn = create_compile_fn(vm);
+    let exec_fn = create_exec_fn(vm);
+    let eval_fn = create_eval_fn(vm);
+    vm.add_to_module(BUILTIN_MODULE, "compile", compile_fn);
+    vm.add_to_module(BUILTIN_MODULE, "exec", exec_fn);
+    vm.add_to_module(BUILTIN_MODULE, "eval", eval_fn);
+}
+
+//functions that write to stdout or abort the host process
+pub fn register_io_builtin_functions(vm: &mut VM) {
     let print_fn = create_print_fn(vm);
+    let input_fn = create_input_fn(vm);
     let printstack_fn = create_printstack_fn(vm);
     let traceback_fn = create_traceback_fn(vm);
-    let len_fn = create_len_fn(vm);
     let panic_fn = create_panic_fn(vm);
     vm.add_to_module(BUILTIN_MODULE, "print", print_fn);
+    vm.add_to_module(BUILTIN_MODULE, "input", input_fn);
     vm.add_to_module(BUILTIN_MODULE, "printstack", printstack_fn);
     vm.add_to_module(BUILTIN_MODULE, "traceback", traceback_fn);
     vm.add_to_module(BUILTIN_MODULE, "panic", panic_fn);
-    vm.add_to_module(BUILTIN_MODULE, "len", len_fn);
 }
@@ -0,0 +1,217 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::commons::hash::hash_bytes;
+use crate::builtin_types::index_error::raise_index_error;
+
+
+fn len(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_bytes();
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.int,
+        BuiltInTypeData::Int(self_data.len() as i128),
+    )
+}
+
+//indexing bytes gives back ints, not 1-length bytes
+fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_bytes();
+
+    let index = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::Int(i) => *i,
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            return raise_type_error(vm, format!("byte indices must be integers or slices, not {}", type_name));
+        }
+    };
+    let resolved = if index < 0 { index + self_data.len() as i128 } else { index };
+
+    if resolved < 0 || resolved >= self_data.len() as i128 {
+        return raise_index_error(vm, String::from("index out of range"));
+    }
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.int,
+        BuiltInTypeData::Int(self_data[resolved as usize] as i128),
+    )
+}
+
+fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //list_iterator only needs len() and indexing, so it works for bytes too
+    let iterator_class = vm.find_in_module(BUILTIN_MODULE, "list_iterator").expect("list_iterator type not found");
+    let new = vm.try_load_function_addr(iterator_class);
+    let (result, _) = vm.run_function(PositionalParameters::single(call_params.bound_pyobj), new, None);
+    return result;
+}
+
+//"UTF-8", "utf_8" and "utf8" all name the same codec
+pub fn normalize_encoding(name: &str) -> String {
+    return name.to_lowercase().replace(['-', '_'], "");
+}
+
+fn decode(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    if call_params.params.len() > 1 {
+        panic!("{}() expected at most 1 argument, got {}", params.func_name.unwrap(), call_params.params.len());
+    }
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_bytes();
+
+    let encoding = match call_params.params.first() {
+        Some(addr) => match vm.get_raw_data_of_pyobj(*addr) {
+            BuiltInTypeData::String(s) => normalize_encoding(s),
+            _ => {
+                let type_name = vm.get_pyobj_type_name(*addr);
+                return raise_type_error(vm, format!("decode() argument 'encoding' must be str, not {}", type_name));
+            }
+        },
+        None => String::from("utf8"),
+    };
+
+    let decoded = match encoding.as_str() {
+        "utf8" => match std::str::from_utf8(self_data) {
+            Ok(s) => s.to_string(),
+            Err(e) => panic!(
+                "UnicodeDecodeError: 'utf-8' codec can't decode byte 0x{:02x} in position {}",
+                self_data[e.valid_up_to()],
+                e.valid_up_to()
+            ),
+        },
+        "ascii" => match self_data.iter().position(|b| !b.is_ascii()) {
+            None => self_data.iter().map(|b| *b as char).collect(),
+            Some(position) => panic!(
+                "UnicodeDecodeError: 'ascii' codec can't decode byte 0x{:02x} in position {}",
+                self_data[position], position
+            ),
+        },
+        "latin1" | "iso88591" => self_data.iter().map(|b| *b as char).collect(),
+        _ => panic!("LookupError: unknown encoding: {}", encoding),
+    };
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(decoded))
+}
+
+fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm.get_raw_data_of_pyobj(call_params.bound_pyobj);
+    match vm.get_pyobj_byaddr(call_params.params[0]).try_get_builtin() {
+        Some(other_data @ BuiltInTypeData::Bytes(_)) if self_data == other_data => {
+            return vm.builtin_type_addrs.true_val;
+        }
+        _ => {
+            return vm.builtin_type_addrs.false_val;
+        }
+    }
+}
+
+fn not_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let result = equals(vm, params);
+    if result == vm.builtin_type_addrs.true_val {
+        return vm.builtin_type_addrs.false_val;
+    } else {
+        return vm.builtin_type_addrs.true_val;
+    }
+}
+
+fn concat(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_bytes();
+
+    match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::Bytes(other) => {
+            let mut result = self_data.clone();
+            result.extend(other.iter());
+            vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.bytes, BuiltInTypeData::Bytes(result))
+        }
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            raise_type_error(vm, format!("can't concat {} to bytes", type_name))
+        }
+    }
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_bytes();
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.string,
+        BuiltInTypeData::String(repr_bytes(self_data)),
+    )
+}
+
+//same quoting rules as str, and every non printable byte is written as \xhh
+fn repr_bytes(bytes: &[u8]) -> String {
+    let quote = if bytes.contains(&b'\'') && !bytes.contains(&b'"') { b'"' } else { b'\'' };
+    let mut buffer = String::from("b");
+    buffer.push(quote as char);
+    for byte in bytes {
+        match *byte {
+            b'\\' => buffer.push_str("\\\\"),
+            b'\n' => buffer.push_str("\\n"),
+            b'\r' => buffer.push_str("\\r"),
+            b'\t' => buffer.push_str("\\t"),
+            b if b == quote => {
+                buffer.push('\\');
+                buffer.push(b as char);
+            }
+            b if (0x20..0x7f).contains(&b) => buffer.push(b as char),
+            b => buffer.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    buffer.push(quote as char);
+    return buffer;
+}
+
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_bytes();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_bytes(self_data)))
+}
+
+pub fn register_bytes_type(vm: &mut VM) -> MemoryAddress {
+    let bytes_type = vm.create_type(BUILTIN_MODULE, "bytes", None);
+    vm.builtin_type_addrs.bytes = bytes_type;
+
+    vm.register_bounded_func_on_addr(bytes_type, "__len__", len);
+    vm.register_bounded_func_on_addr(bytes_type, "__getitem__", getitem);
+    vm.register_bounded_func_on_addr(bytes_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(bytes_type, "__eq__", equals);
+    vm.register_bounded_func_on_addr(bytes_type, "__ne__", not_equals);
+    vm.register_bounded_func_on_addr(bytes_type, "__hash__", hash);
+    vm.register_bounded_func_on_addr(bytes_type, "__add__", concat);
+    vm.register_bounded_func_on_addr(bytes_type, "__repr__", repr);
+    vm.register_bounded_func_on_addr(bytes_type, "__str__", repr);
+    vm.register_bounded_func_on_addr(bytes_type, "decode", decode);
+    return bytes_type;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repr_escapes_like_python() {
+        assert_eq!(repr_bytes(b"abc"), "b'abc'");
+        assert_eq!(repr_bytes(b"it's"), "b\"it's\"");
+        assert_eq!(repr_bytes(b"'\""), "b'\\'\"'");
+        assert_eq!(repr_bytes(&[0, 0x7f, 0xff, b'\n', b'\\']), "b'\\x00\\x7f\\xff\\n\\\\'");
+    }
+}
@@ -0,0 +1,20 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+
+//the wrapped function receives the class as its first parameter, whether it's called on the class or on an instance
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!("classmethod", 1, params.params.len());
+    let wrapper = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.classmethod, BuiltInTypeData::ClassInstance);
+    let function = params.params.params[0];
+    vm.increase_refcount(function);
+    vm.set_attribute(wrapper, "__func__", function);
+    return wrapper;
+}
+
+pub fn register_classmethod_type(vm: &mut VM) -> MemoryAddress {
+    let classmethod_type = vm.create_type(BUILTIN_MODULE, "classmethod", None);
+    vm.builtin_type_addrs.classmethod = classmethod_type;
+    vm.register_type_unbounded_func(classmethod_type, "__new__", create_new);
+    return classmethod_type;
+}
@@ -0,0 +1,157 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use std::collections::BTreeMap;
+
+pub const COPY_MODULE: &str = "copy";
+
+fn allocate_copy(vm: &VM, original: MemoryAddress, raw_data: BuiltInTypeData) -> MemoryAddress {
+    let type_addr = vm.get_pyobj_byaddr(original).type_addr;
+    vm.allocate_type_byaddr_raw(type_addr, raw_data)
+}
+
+fn increase_refcounts(vm: &VM, addrs: &[MemoryAddress]) {
+    for addr in addrs {
+        vm.increase_refcount(*addr);
+    }
+}
+
+//immutable values (numbers, strings, bytes, tuples, frozensets, functions...) are returned as they are
+fn shallow_copy(vm: &VM, addr: MemoryAddress) -> MemoryAddress {
+    let raw_data = match vm.get_pyobj_byaddr(addr).try_get_builtin() {
+        Some(raw_data) => raw_data,
+        None => return addr,
+    };
+    match raw_data {
+        BuiltInTypeData::List(elements) => {
+            increase_refcounts(vm, elements);
+            allocate_copy(vm, addr, BuiltInTypeData::List(elements.clone()))
+        }
+        BuiltInTypeData::Set(elements) => {
+            increase_refcounts(vm, elements);
+            allocate_copy(vm, addr, BuiltInTypeData::Set(elements.clone()))
+        }
+        BuiltInTypeData::Dict(entries) => {
+            for (key, value) in entries {
+                increase_refcounts(vm, &[*key, *value]);
+            }
+            allocate_copy(vm, addr, BuiltInTypeData::Dict(entries.clone()))
+        }
+        BuiltInTypeData::ClassInstance => {
+            let instance = allocate_copy(vm, addr, BuiltInTypeData::ClassInstance);
+            for (name, value) in vm.get_pyobj_byaddr(addr).properties.clone() {
+                vm.increase_refcount(value);
+                vm.set_attribute(instance, &name, value);
+            }
+            instance
+        }
+        _ => addr,
+    }
+}
+
+//memo maps each original container to its copy, so shared references stay shared and cycles end.
+//Mutable containers are registered in the memo before their elements are copied.
+fn deep_copy(vm: &VM, addr: MemoryAddress, memo: &mut BTreeMap<MemoryAddress, MemoryAddress>) -> MemoryAddress {
+    if let Some(copied) = memo.get(&addr) {
+        return *copied;
+    }
+    let raw_data = match vm.get_pyobj_byaddr(addr).try_get_builtin() {
+        Some(raw_data) => raw_data,
+        None => return addr,
+    };
+    match raw_data {
+        BuiltInTypeData::List(elements) => {
+            let elements = elements.clone();
+            let copy = allocate_copy(vm, addr, BuiltInTypeData::List(vec![]));
+            memo.insert(addr, copy);
+            let copied: Vec<MemoryAddress> = elements.iter().map(|element| deep_copy(vm, *element, memo)).collect();
+            increase_refcounts(vm, &copied);
+            *vm.get_raw_data_of_pyobj_mut(copy).take_list_mut() = copied;
+            copy
+        }
+        BuiltInTypeData::Dict(entries) => {
+            let entries = entries.clone();
+            let copy = allocate_copy(vm, addr, BuiltInTypeData::Dict(vec![]));
+            memo.insert(addr, copy);
+            let mut copied = vec![];
+            for (key, value) in entries {
+                let (key, value) = (deep_copy(vm, key, memo), deep_copy(vm, value, memo));
+                increase_refcounts(vm, &[key, value]);
+                copied.push((key, value));
+            }
+            *vm.get_raw_data_of_pyobj_mut(copy).take_dict_mut() = copied;
+            copy
+        }
+        BuiltInTypeData::Set(elements) => {
+            let elements = elements.clone();
+            let copy = allocate_copy(vm, addr, BuiltInTypeData::Set(vec![]));
+            memo.insert(addr, copy);
+            let copied: Vec<MemoryAddress> = elements.iter().map(|element| deep_copy(vm, *element, memo)).collect();
+            increase_refcounts(vm, &copied);
+            *vm.get_raw_data_of_pyobj_mut(copy).take_set_mut() = copied;
+            copy
+        }
+        //immutable containers are only copied when one of their elements had to be
+        BuiltInTypeData::Tuple(elements) | BuiltInTypeData::FrozenSet(elements) => {
+            let is_tuple = matches!(raw_data, BuiltInTypeData::Tuple(_));
+            let elements = elements.clone();
+            let copied: Vec<MemoryAddress> = elements.iter().map(|element| deep_copy(vm, *element, memo)).collect();
+            //an element may have copied this container already, through a cycle
+            if let Some(copy) = memo.get(&addr) {
+                return *copy;
+            }
+            if copied == elements {
+                return addr;
+            }
+            increase_refcounts(vm, &copied);
+            let copy = if is_tuple {
+                allocate_copy(vm, addr, BuiltInTypeData::Tuple(copied))
+            } else {
+                allocate_copy(vm, addr, BuiltInTypeData::FrozenSet(copied))
+            };
+            memo.insert(addr, copy);
+            copy
+        }
+        BuiltInTypeData::ClassInstance => {
+            let instance = allocate_copy(vm, addr, BuiltInTypeData::ClassInstance);
+            memo.insert(addr, instance);
+            for (name, value) in vm.get_pyobj_byaddr(addr).properties.clone() {
+                let copied = deep_copy(vm, value, memo);
+                vm.increase_refcount(copied);
+                vm.set_attribute(instance, &name, copied);
+            }
+            instance
+        }
+        _ => addr,
+    }
+}
+
+fn create_copy_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            return shallow_copy(vm, call_params.params[0]);
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("copy".to_string()));
+}
+
+fn create_deepcopy_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            return deep_copy(vm, call_params.params[0], &mut BTreeMap::new());
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("deepcopy".to_string()));
+}
+
+pub fn register_copy_module(vm: &mut VM) {
+    vm.create_module(COPY_MODULE);
+    let copy_fn = create_copy_fn(vm);
+    let deepcopy_fn = create_deepcopy_fn(vm);
+    vm.add_to_module(COPY_MODULE, "copy", copy_fn);
+    vm.add_to_module(COPY_MODULE, "deepcopy", deepcopy_fn);
+}
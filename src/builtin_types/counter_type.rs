@@ -0,0 +1,163 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::dict_type::{check_hashable, dict_set, lookup};
+
+pub const COLLECTIONS_MODULE: &str = "collections";
+
+fn allocate_int(vm: &VM, value: i128) -> MemoryAddress {
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(value))
+}
+
+fn count_of(vm: &VM, counter: MemoryAddress, key: MemoryAddress) -> i128 {
+    match lookup(vm, counter, key) {
+        Some(count) => vm.get_raw_data_of_pyobj(count).take_int(),
+        None => 0,
+    }
+}
+
+//adds (or subtracts, with sign = -1) the counts of a mapping, or one for each character of a string
+//or element of any other iterable
+fn add_counts(vm: &VM, counter: MemoryAddress, source: MemoryAddress, sign: i128) -> Option<MemoryAddress> {
+    let counted: Vec<(MemoryAddress, i128)> = match vm.get_pyobj_byaddr(source).try_get_builtin() {
+        Some(BuiltInTypeData::Dict(entries)) => entries
+            .iter()
+            .map(|(key, count)| (*key, vm.get_raw_data_of_pyobj(*count).take_int()))
+            .collect(),
+        Some(BuiltInTypeData::String(text)) => text
+            .chars()
+            .map(|c| (vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(c.to_string())), 1))
+            .collect(),
+        _ => {
+            let list_new = vm.try_load_function_addr(vm.builtin_type_addrs.list);
+            let (as_list, _) = vm.run_function(PositionalParameters::single(source), list_new, None);
+            vm.get_raw_data_of_pyobj(as_list).take_list().iter().map(|element| (*element, 1)).collect()
+        }
+    };
+    for (key, count) in counted {
+        if let Some(exception) = check_hashable(vm, key) {
+            return Some(exception);
+        }
+        let new_count = allocate_int(vm, count_of(vm, counter, key) + sign * count);
+        dict_set(vm, counter, key, new_count);
+    }
+    return None;
+}
+
+fn create_new(vm: &VM, params: CallParams, counter_type: MemoryAddress) -> MemoryAddress {
+    let counter = vm.allocate_type_byaddr_raw(counter_type, BuiltInTypeData::Dict(vec![]));
+    if params.params.len() == 0 {
+        return counter;
+    }
+    check_builtin_func_params!("Counter", 1, params.params.len());
+    if let Some(exception) = add_counts(vm, counter, params.params.params[0], 1) {
+        return exception;
+    }
+    return counter;
+}
+
+//missing elements count as zero, without being added
+fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = check_hashable(vm, call_params.params[0]) {
+        return exception;
+    }
+    match lookup(vm, call_params.bound_pyobj, call_params.params[0]) {
+        Some(count) => count,
+        None => allocate_int(vm, 0),
+    }
+}
+
+fn update(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = add_counts(vm, call_params.bound_pyobj, call_params.params[0], 1) {
+        return exception;
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn subtract(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = add_counts(vm, call_params.bound_pyobj, call_params.params[0], -1) {
+        return exception;
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn total(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+    let sum = entries.iter().map(|(_, count)| vm.get_raw_data_of_pyobj(*count).take_int()).sum();
+    return allocate_int(vm, sum);
+}
+
+//highest counts first, elements with equal counts stay in the order they were first counted
+fn sorted_entries(vm: &VM, counter: MemoryAddress) -> Vec<(MemoryAddress, MemoryAddress)> {
+    let mut entries = vm.get_raw_data_of_pyobj(counter).take_dict().clone();
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(vm.get_raw_data_of_pyobj(*count).take_int()));
+    return entries;
+}
+
+fn most_common(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    let mut entries = sorted_entries(vm, call_params.bound_pyobj);
+    match call_params.params.len() {
+        0 => {}
+        1 if call_params.params[0] == vm.special_values[&SpecialValue::NoneValue] => {}
+        1 => {
+            let n = vm.get_raw_data_of_pyobj(call_params.params[0]).take_int().max(0) as usize;
+            entries.truncate(n);
+        }
+        n => panic!("TypeError: most_common() takes at most 1 argument ({} given)", n),
+    }
+    let pairs = entries
+        .into_iter()
+        .map(|(key, count)| {
+            vm.increase_refcount(key);
+            vm.increase_refcount(count);
+            vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.tuple, BuiltInTypeData::Tuple(vec![key, count]))
+        })
+        .collect();
+    return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(pairs));
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let entries = sorted_entries(vm, call_params.bound_pyobj);
+
+    let repr_of = |addr: MemoryAddress| -> String {
+        let (as_string, _) = vm.call_method(addr, "__repr__", PositionalParameters::empty()).unwrap();
+        vm.get_raw_data_of_pyobj(as_string).take_string().clone()
+    };
+
+    let buffer = if entries.is_empty() {
+        String::from("Counter()")
+    } else {
+        let all_reprs: Vec<String> = entries
+            .iter()
+            .map(|(key, count)| format!("{}: {}", repr_of(*key), repr_of(*count)))
+            .collect();
+        format!("Counter({{{}}})", all_reprs.join(", "))
+    };
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer))
+}
+
+//a dict subclass, so the other dict methods work on it as they are
+pub fn register_counter_type(vm: &mut VM) -> MemoryAddress {
+    vm.create_module(COLLECTIONS_MODULE);
+    let counter_type = vm.create_type(COLLECTIONS_MODULE, "Counter", Some(vm.builtin_type_addrs.dict));
+    vm.register_type_unbounded_func(counter_type, "__new__", move |vm, params| create_new(vm, params, counter_type));
+    vm.register_bounded_func_on_addr(counter_type, "__getitem__", getitem);
+    vm.register_bounded_func_on_addr(counter_type, "__repr__", repr);
+    vm.register_bounded_func_on_addr(counter_type, "__str__", repr);
+    vm.register_bounded_func_on_addr(counter_type, "update", update);
+    vm.register_bounded_func_on_addr(counter_type, "subtract", subtract);
+    vm.register_bounded_func_on_addr(counter_type, "total", total);
+    vm.register_bounded_func_on_addr(counter_type, "most_common", most_common);
+    return counter_type;
+}
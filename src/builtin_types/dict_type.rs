@@ -0,0 +1,249 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::frozenset_type::to_bool;
+use crate::builtin_types::type_error::raise_unhashable_type_error;
+use crate::builtin_types::key_error::raise_key_error;
+
+//keys must be hashable, but are still found by a linear search using __eq__, and insertion order is kept
+fn find_key(vm: &VM, entries: &[(MemoryAddress, MemoryAddress)], key: MemoryAddress) -> Option<usize> {
+    entries.iter().position(|(entry_key, _)| {
+        if *entry_key == key {
+            return true;
+        }
+        match vm.call_method(*entry_key, "__eq__", PositionalParameters::single(key)) {
+            Some((result, _)) => result == vm.builtin_type_addrs.true_val,
+            None => false,
+        }
+    })
+}
+
+pub fn lookup(vm: &VM, dict: MemoryAddress, key: MemoryAddress) -> Option<MemoryAddress> {
+    let entries = vm.get_raw_data_of_pyobj(dict).take_dict();
+    find_key(vm, entries, key).map(|index| entries[index].1)
+}
+
+//raises TypeError for unhashable keys, like a list or a tuple holding one
+pub fn check_hashable(vm: &VM, key: MemoryAddress) -> Option<MemoryAddress> {
+    match vm.call_hash(key) {
+        Some(_) => None,
+        None => Some(raise_unhashable_type_error(vm, key)),
+    }
+}
+
+//returns the raised exception when the key is unhashable
+pub fn dict_set(vm: &VM, dict: MemoryAddress, key: MemoryAddress, value: MemoryAddress) -> Option<MemoryAddress> {
+    if let Some(exception) = check_hashable(vm, key) {
+        return Some(exception);
+    }
+    let index = find_key(vm, vm.get_raw_data_of_pyobj(dict).take_dict(), key);
+    vm.increase_refcount(value);
+    let entries = vm.get_raw_data_of_pyobj_mut(dict).take_dict_mut();
+    match index {
+        Some(index) => entries[index].1 = value,
+        None => {
+            vm.increase_refcount(key);
+            entries.push((key, value));
+        }
+    }
+    return None;
+}
+
+fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = check_hashable(vm, call_params.params[0]) {
+        return exception;
+    }
+    match lookup(vm, call_params.bound_pyobj, call_params.params[0]) {
+        Some(value) => value,
+        None => raise_key_error(vm, call_params.params[0]),
+    }
+}
+
+fn setitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 2, call_params.params.len());
+    if let Some(exception) = dict_set(vm, call_params.bound_pyobj, call_params.params[0], call_params.params[1]) {
+        return exception;
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn get(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    if call_params.params.len() != 1 {
+        check_builtin_func_params!(params.func_name.unwrap(), 2, call_params.params.len());
+    }
+    if let Some(exception) = check_hashable(vm, call_params.params[0]) {
+        return exception;
+    }
+    match lookup(vm, call_params.bound_pyobj, call_params.params[0]) {
+        Some(value) => value,
+        None => match call_params.params.get(1) {
+            Some(default) => *default,
+            None => vm.special_values[&SpecialValue::NoneValue],
+        },
+    }
+}
+
+fn contains(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = check_hashable(vm, call_params.params[0]) {
+        return exception;
+    }
+    match lookup(vm, call_params.bound_pyobj, call_params.params[0]) {
+        Some(_) => vm.builtin_type_addrs.true_val,
+        None => vm.builtin_type_addrs.false_val,
+    }
+}
+
+fn len(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.int,
+        BuiltInTypeData::Int(entries.len() as i128),
+    )
+}
+
+fn keys_list(vm: &VM, dict: MemoryAddress) -> MemoryAddress {
+    let entries = vm.get_raw_data_of_pyobj(dict).take_dict();
+    let keys = entries.iter().map(|(key, _)| *key).collect();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(keys))
+}
+
+fn keys(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    keys_list(vm, call_params.bound_pyobj)
+}
+
+fn values(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+    let values = entries.iter().map(|(_, value)| *value).collect();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(values))
+}
+
+//a list of (key, value) tuples, in insertion order like keys() and values()
+fn items(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+    let items = entries
+        .iter()
+        .map(|(key, value)| vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.tuple, BuiltInTypeData::Tuple(vec![*key, *value])))
+        .collect();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(items))
+}
+
+fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //iterates over a snapshot of the keys
+    let keys = keys_list(vm, call_params.bound_pyobj);
+    let (iterator, _) = vm.call_method(keys, "__iter__", PositionalParameters::empty()).unwrap();
+    return iterator;
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    if !vm.repr_enter(call_params.bound_pyobj) {
+        return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String("{...}".into()));
+    }
+    let entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+
+    let repr_of = |addr: MemoryAddress| -> String {
+        let (as_string, _) = vm.call_method(addr, "__repr__", PositionalParameters::empty()).unwrap();
+        vm.get_raw_data_of_pyobj(as_string).take_string().clone()
+    };
+
+    let all_reprs: Vec<String> = entries
+        .iter()
+        .map(|(key, value)| format!("{}: {}", repr_of(*key), repr_of(*value)))
+        .collect();
+    vm.repr_leave(call_params.bound_pyobj);
+
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.string,
+        BuiltInTypeData::String(format!("{{{}}}", all_reprs.join(", "))),
+    )
+}
+
+fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_entries = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_dict();
+    let other_entries = match vm.get_pyobj_byaddr(call_params.params[0]).try_get_builtin() {
+        Some(BuiltInTypeData::Dict(entries)) => entries,
+        _ => return vm.builtin_type_addrs.false_val,
+    };
+
+    if this_entries.len() != other_entries.len() {
+        return vm.builtin_type_addrs.false_val;
+    }
+    if !vm.eq_enter(call_params.bound_pyobj, call_params.params[0]) {
+        return vm.builtin_type_addrs.true_val;
+    }
+
+    let all_equal = this_entries.iter().all(|(key, value)| {
+        let other_value = match lookup(vm, call_params.params[0], *key) {
+            Some(other_value) => other_value,
+            None => return false,
+        };
+        if other_value == *value {
+            return true;
+        }
+        let result = vm.call_method(*value, "__eq__", PositionalParameters::single(other_value));
+        return matches!(result, Some((result, _)) if result == vm.builtin_type_addrs.true_val);
+    });
+    vm.eq_leave(call_params.bound_pyobj, call_params.params[0]);
+    return to_bool(vm, all_equal);
+}
+
+fn not_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    if equals(vm, params) == vm.builtin_type_addrs.true_val {
+        return vm.builtin_type_addrs.false_val;
+    }
+    return vm.builtin_type_addrs.true_val;
+}
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    let dict = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.dict, BuiltInTypeData::Dict(vec![]));
+    if params.params.len() == 1 {
+        //copy constructor
+        let entries = vm.get_raw_data_of_pyobj(params.params.params[0]).take_dict().clone();
+        for (key, value) in entries {
+            dict_set(vm, dict, key, value);
+        }
+    } else {
+        check_builtin_func_params!("dict", 0, params.params.len());
+    }
+    return dict;
+}
+
+pub fn register_dict_type(vm: &mut VM) -> MemoryAddress {
+    let dict_type = vm.create_type(BUILTIN_MODULE, "dict", None);
+    vm.builtin_type_addrs.dict = dict_type;
+
+    vm.register_type_unbounded_func(dict_type, "__new__", create_new);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__getitem__", getitem);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__setitem__", setitem);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__contains__", contains);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__len__", len);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__iter__", iter);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__eq__", equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__ne__", not_equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__repr__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "__str__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "get", get);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "keys", keys);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "values", values);
+    vm.register_bounded_func(BUILTIN_MODULE, "dict", "items", items);
+    return dict_type;
+}
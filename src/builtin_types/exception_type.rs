@@ -0,0 +1,128 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+
+//exceptions keep the arguments they were created with in args, a tuple
+pub fn new_exception(vm: &VM, type_addr: MemoryAddress, args: Vec<MemoryAddress>) -> MemoryAddress {
+    for arg in args.iter() {
+        vm.increase_refcount(*arg);
+    }
+    let args_tuple = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.tuple, BuiltInTypeData::Tuple(args));
+    let exception = vm.allocate_type_byaddr_raw(type_addr, BuiltInTypeData::ClassInstance);
+    vm.set_attribute(exception, "args", args_tuple);
+    vm.increase_refcount(args_tuple);
+    return exception;
+}
+
+pub fn raise_with_message(vm: &VM, type_addr: MemoryAddress, message: String) -> MemoryAddress {
+    let message_addr = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(message));
+    let exception = new_exception(vm, type_addr, vec![message_addr]);
+    vm.raise_exception(exception);
+    return exception;
+}
+
+fn get_args(vm: &VM, exception: MemoryAddress) -> Vec<MemoryAddress> {
+    let args = vm.get_obj_property(exception, "args").unwrap();
+    vm.get_raw_data_of_pyobj(args).take_tuple().clone()
+}
+
+fn call_method_to_string(vm: &VM, addr: MemoryAddress, method: &str) -> String {
+    let (as_string, _) = vm.call_method(addr, method, PositionalParameters::empty()).unwrap();
+    vm.get_raw_data_of_pyobj(as_string).take_string().clone()
+}
+
+//like traceback's last line: "TypeError: message", or just the type name when the message is empty
+pub fn format_exception(vm: &VM, exception: MemoryAddress) -> String {
+    let type_name = vm.get_pyobj_type_name(exception).to_string();
+    let message = vm.get_raw_data_of_pyobj(vm.call_str(exception)).take_string().clone();
+    if message.is_empty() {
+        type_name
+    } else {
+        format!("{}: {}", type_name, message)
+    }
+}
+
+fn chained_exception(vm: &VM, exception: MemoryAddress, attr: &str, seen: &[MemoryAddress]) -> Option<MemoryAddress> {
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    vm.get_obj_property(exception, attr).filter(|addr| *addr != none && !seen.contains(addr))
+}
+
+//what gets reported for an exception nobody caught: the exceptions it was raised from or while handling
+//come first, like python's traceback but without the frames
+pub fn format_uncaught_exception(vm: &VM, exception: MemoryAddress) -> String {
+    format_exception_chain(vm, exception, &mut vec![])
+}
+
+//the chain can loop back to an exception already printed, which ends it
+fn format_exception_chain(vm: &VM, exception: MemoryAddress, seen: &mut Vec<MemoryAddress>) -> String {
+    if let PyObjectStructure::Type { name, .. } = &vm.get_pyobj_byaddr(exception).structure {
+        return name.clone();
+    }
+    seen.push(exception);
+    let suppress_context = vm.get_obj_property(exception, "__suppress_context__")
+        .is_some_and(|addr| vm.is_truthy(addr));
+    let mut buffer = String::new();
+    if let Some(cause) = chained_exception(vm, exception, "__cause__", seen) {
+        buffer.push_str(&format_exception_chain(vm, cause, seen));
+        buffer.push_str("\n\nThe above exception was the direct cause of the following exception:\n\n");
+    } else if let Some(context) = chained_exception(vm, exception, "__context__", seen).filter(|_| !suppress_context) {
+        buffer.push_str(&format_exception_chain(vm, context, seen));
+        buffer.push_str("\n\nDuring handling of the above exception, another exception occurred:\n\n");
+    }
+    buffer.push_str(&format_exception(vm, exception));
+    return buffer;
+}
+
+//no args is an empty message, a single one is shown by itself, more are shown as the tuple
+pub fn to_str(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let args = get_args(vm, call_params.bound_pyobj);
+    match args.len() {
+        0 => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(String::new())),
+        1 => vm.call_str(args[0]),
+        _ => {
+            let args_tuple = vm.get_obj_property(call_params.bound_pyobj, "args").unwrap();
+            vm.call_str(args_tuple)
+        }
+    }
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let args = get_args(vm, call_params.bound_pyobj);
+    let all_reprs: Vec<String> = args.iter().map(|arg| call_method_to_string(vm, *arg, "__repr__")).collect();
+    let type_name = vm.get_pyobj_type_name(call_params.bound_pyobj);
+    let buffer = format!("{}({})", type_name, all_reprs.join(", "));
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer))
+}
+
+//the builtin exception types inherit from Exception, each with its own __new__ so instances get the right type
+pub fn create_exception_type(vm: &VM, name: &str) -> MemoryAddress {
+    create_exception_subtype(vm, name, vm.builtin_type_addrs.exception)
+}
+
+pub fn create_exception_subtype(vm: &VM, name: &str, supertype: MemoryAddress) -> MemoryAddress {
+    let exception_type = vm.create_type(BUILTIN_MODULE, name, Some(supertype));
+    register_exception_new(vm, exception_type);
+    return exception_type;
+}
+
+fn register_exception_new(vm: &VM, type_addr: MemoryAddress) {
+    vm.register_type_unbounded_func(type_addr, "__new__", move |vm: &VM, params: CallParams| -> MemoryAddress {
+        new_exception(vm, type_addr, params.params.params.clone())
+    });
+}
+
+//like python, except Exception doesn't catch BaseException subtypes such as KeyboardInterrupt
+pub fn register_exception_type(vm: &mut VM) -> MemoryAddress {
+    let base_exception_type = vm.create_type(BUILTIN_MODULE, "BaseException", None);
+    register_exception_new(vm, base_exception_type);
+    vm.register_bounded_func(BUILTIN_MODULE, "BaseException", "__str__", to_str);
+    vm.register_bounded_func(BUILTIN_MODULE, "BaseException", "__repr__", repr);
+    vm.builtin_type_addrs.base_exception = base_exception_type;
+    let exception_type = create_exception_subtype(vm, "Exception", base_exception_type);
+    vm.builtin_type_addrs.exception = exception_type;
+    return exception_type;
+}
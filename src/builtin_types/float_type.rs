@@ -1,7 +1,13 @@
 use crate::commons::float::Float;
+use crate::commons::arithmetic::{python_mod, FloorDiv};
+use crate::commons::format_spec;
+use crate::commons::hash::hash_float;
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::builtin_functions::power;
+use crate::builtin_types::value_error::raise_value_error;
+use crate::builtin_types::zero_division_error::{raise_if_modulo_by_zero, raise_if_floor_division_by_zero, raise_if_division_by_zero};
 
 
 macro_rules! create_compare_function {
@@ -18,24 +24,21 @@ macro_rules! create_compare_function {
                     let other_int = vm.get_raw_data_of_pyobj(call_params.params[0]).take_int();
                     let $param_a = self_data;
                     let $param_b = other_int as f64;
-                    let result_compare = $compare;
-                    let result_as_int: i128 = if result_compare { 1 } else { 0 };
-
-                    vm.allocate_builtin_type_byname_raw(
-                        "bool",
-                        BuiltInTypeData::Int(result_as_int),
-                    )
+                    if $compare {
+                        vm.builtin_type_addrs.true_val
+                    } else {
+                        vm.builtin_type_addrs.false_val
+                    }
                 }
                 "float" => {
                     let other_float = vm.get_raw_data_of_pyobj(call_params.params[0]).take_float();
                     let $param_a = self_data;
                     let $param_b = other_float;
-                    let result_compare = $compare;
-                    let result_as_int: i128 = if result_compare { 1 } else { 0 };
-                    vm.allocate_builtin_type_byname_raw(
-                        "bool",
-                        BuiltInTypeData::Int(result_as_int),
-                    )
+                    if $compare {
+                        vm.builtin_type_addrs.true_val
+                    } else {
+                        vm.builtin_type_addrs.false_val
+                    }
                 }
                 _ => vm.special_values[&SpecialValue::NotImplementedValue],
             };
@@ -95,17 +98,52 @@ create_compare_function!(greater_than, a, b, a > b);
 create_compare_function!(less_than, a, b, a < b);
 create_compare_function!(equals, a, b, a == b);
 create_compare_function!(less_equals, a, b, a <= b);
-create_compare_function!(greater_equals, a, b, a <= b);
+create_compare_function!(greater_equals, a, b, a >= b);
 create_compare_function!(not_equals, a, b, a != b);
 
 create_binop_function!(add, a, b, a + b);
-create_binop_function!(modulus, a, b, a % b);
+create_binop_function!(unchecked_modulus, a, b, python_mod(a, b));
+
+fn modulus(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    if let Some(exception) = raise_if_modulo_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return unchecked_modulus(vm, params);
+}
 create_binop_function!(sub, a, b, a - b);
 create_binop_function!(mul, a, b, a * b);
-create_binop_function!(truediv, a, b, a / b);
+create_binop_function!(unchecked_floordiv, a, b, a.floor_div(b));
+
+fn floordiv(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    if let Some(exception) = raise_if_floor_division_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return unchecked_floordiv(vm, params);
+}
+
+fn pow(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    return power(vm, call_params.bound_pyobj, call_params.params[0]);
+}
+create_binop_function!(unchecked_truediv, a, b, a / b);
+
+fn truediv(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    if let Some(exception) = raise_if_division_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return unchecked_truediv(vm, params);
+}
 
 create_unary_function!(negation, a, a * -1.0);
 create_unary_function!(positive, a, a);
+create_unary_function!(absolute, a, a.abs());
 
 fn to_boolean(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
@@ -135,13 +173,68 @@ fn to_int(vm: &VM, params: CallParams) -> MemoryAddress {
     vm.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(self_data as i128))
 }
 
+//Rust prints NaN as "NaN", python as "nan". inf and -inf are already the same.
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        return String::from("nan");
+    }
+    return format!("{:?}", value);
+}
+
+//accepts what python's float() accepts, except for digit separators: surrounding whitespace,
+//a sign, and case-insensitive inf, infinity and nan
+fn parse_float(source: &str) -> Option<f64> {
+    let trimmed = source.trim();
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let special = match unsigned.to_lowercase().as_str() {
+        "inf" | "infinity" => Some(f64::INFINITY),
+        "nan" => Some(f64::NAN),
+        _ => None,
+    };
+    if let Some(value) = special {
+        return Some(sign * value);
+    }
+    //without this, "--1" would parse as 1 after the first sign is stripped
+    if unsigned.starts_with('+') || unsigned.starts_with('-') {
+        return None;
+    }
+    return unsigned.parse::<f64>().ok().map(|value| sign * value);
+}
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    if params.params.len() == 0 {
+        return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(0.0)));
+    }
+    check_builtin_func_params!("float", 1, params.params.len());
+    let arg = params.params.params[0];
+    let value = match vm.get_raw_data_of_pyobj(arg) {
+        BuiltInTypeData::Float(_) => return arg,
+        BuiltInTypeData::Int(i) => *i as f64,
+        BuiltInTypeData::String(s) => match parse_float(s) {
+            Some(value) => value,
+            None => panic!("could not convert string to float: '{}'", s),
+        },
+        _ => match vm.call_method(arg, "__float__", PositionalParameters::empty()) {
+            Some((result, _)) => return result,
+            None => panic!(
+                "float() argument must be a string or a number, not '{}'",
+                vm.get_pyobj_type_name(arg)
+            ),
+        },
+    };
+    return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(value)));
+}
+
 fn to_str(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
     check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
     let self_data = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
         .take_float();
-    let formatted = format!("{:?}", self_data);
+    let formatted = format_float(self_data);
     vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(formatted))
 }
 
@@ -151,12 +244,39 @@ fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
     let self_data = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
         .take_float();
-    let formatted = format!("{:?}", self_data);
+    let formatted = format_float(self_data);
     vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(formatted))
 }
 
+//an empty spec is the same as str(self), which also keeps format(True, "") == "True"
+fn format(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let spec = vm.get_raw_data_of_pyobj(call_params.params[0]).take_string();
+    if spec.is_empty() {
+        return vm.call_str(call_params.bound_pyobj);
+    }
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_float();
+    match format_spec::parse_format_spec(spec).and_then(|spec| format_spec::format_float(self_data, &spec)) {
+        Ok(formatted) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(formatted)),
+        Err(message) => raise_value_error(vm, message),
+    }
+}
+
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_float();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_float(self_data)))
+}
+
 pub fn register_float_type(vm: &mut VM) -> MemoryAddress {
     let float_type = vm.create_type(BUILTIN_MODULE, "float", None);
+    vm.builtin_type_addrs.float = float_type;
+
+    vm.register_type_unbounded_func(float_type, "__new__", create_new);
 
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__eq__", equals);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__gt__", greater_than);
@@ -170,15 +290,20 @@ pub fn register_float_type(vm: &mut VM) -> MemoryAddress {
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__sub__", sub);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__mul__", mul);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__truediv__", truediv);
+    vm.register_bounded_func(BUILTIN_MODULE, "float", "__floordiv__", floordiv);
+    vm.register_bounded_func(BUILTIN_MODULE, "float", "__pow__", pow);
 
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__neg__", negation);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__pos__", positive);
+    vm.register_bounded_func(BUILTIN_MODULE, "float", "__abs__", absolute);
 
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__bool__", to_boolean);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__int__", to_int);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__float__", to_float);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__str__", to_str);
     vm.register_bounded_func(BUILTIN_MODULE, "float", "__repr__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "float", "__format__", format);
+    vm.register_bounded_func(BUILTIN_MODULE, "float", "__hash__", hash);
 
     vm.builtin_type_addrs.float = float_type;
 
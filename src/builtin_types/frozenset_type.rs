@@ -0,0 +1,332 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::type_error::{raise_type_error, raise_unhashable_type_error};
+use crate::commons::hash::hash_set;
+
+
+pub fn contains_element(vm: &VM, elements: &[MemoryAddress], item: MemoryAddress) -> bool {
+    elements.iter().any(|element| {
+        if *element == item {
+            return true;
+        }
+        match vm.call_method(*element, "__eq__", PositionalParameters::single(item)) {
+            Some((result, _)) => result == vm.builtin_type_addrs.true_val,
+            None => false,
+        }
+    })
+}
+
+//the elements of a set or frozenset
+pub fn set_elements(vm: &VM, addr: MemoryAddress) -> Option<&Vec<MemoryAddress>> {
+    match vm.get_pyobj_byaddr(addr).try_get_builtin() {
+        Some(BuiltInTypeData::Set(elements)) | Some(BuiltInTypeData::FrozenSet(elements)) => Some(elements),
+        _ => None,
+    }
+}
+
+//consumes the iterable with list(), keeping the first of each group of equal elements.
+//Err holds the raised TypeError when an element is unhashable.
+pub fn collect_unique_elements(vm: &VM, iterable: MemoryAddress) -> Result<Vec<MemoryAddress>, MemoryAddress> {
+    if let Some(elements) = set_elements(vm, iterable) {
+        return Ok(elements.clone());
+    }
+    let list_new = vm.try_load_function_addr(vm.builtin_type_addrs.list);
+    let (as_list, _) = vm.run_function(PositionalParameters::single(iterable), list_new, None);
+    let mut unique = vec![];
+    for element in vm.get_raw_data_of_pyobj(as_list).take_list().iter() {
+        if vm.call_hash(*element).is_none() {
+            return Err(raise_unhashable_type_error(vm, *element));
+        }
+        if !contains_element(vm, &unique, *element) {
+            unique.push(*element);
+        }
+    }
+    return Ok(unique);
+}
+
+fn allocate_frozenset(vm: &VM, elements: Vec<MemoryAddress>) -> MemoryAddress {
+    for element in elements.iter() {
+        vm.increase_refcount(*element);
+    }
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.frozenset, BuiltInTypeData::FrozenSet(elements))
+}
+
+pub fn allocate_set(vm: &VM, elements: Vec<MemoryAddress>) -> MemoryAddress {
+    for element in elements.iter() {
+        vm.increase_refcount(*element);
+    }
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.set, BuiltInTypeData::Set(elements))
+}
+
+//results of operations have the type of the left operand: set | frozenset is a set
+fn allocate_like(vm: &VM, like: MemoryAddress, elements: Vec<MemoryAddress>) -> MemoryAddress {
+    match vm.get_raw_data_of_pyobj(like) {
+        BuiltInTypeData::Set(_) => allocate_set(vm, elements),
+        _ => allocate_frozenset(vm, elements),
+    }
+}
+
+pub fn to_bool(vm: &VM, value: bool) -> MemoryAddress {
+    if value {
+        return vm.builtin_type_addrs.true_val;
+    }
+    return vm.builtin_type_addrs.false_val;
+}
+
+fn unsupported_operand(vm: &VM, symbol: &str, this: MemoryAddress, other: MemoryAddress) -> MemoryAddress {
+    let this_name = vm.get_pyobj_type_name(this).to_string();
+    let other_name = vm.get_pyobj_type_name(other).to_string();
+    return raise_type_error(vm, format!("unsupported operand type(s) for {}: '{}' and '{}'", symbol, this_name, other_name));
+}
+
+fn not_supported_between(vm: &VM, symbol: &str, this: MemoryAddress, other: MemoryAddress) -> MemoryAddress {
+    let this_name = vm.get_pyobj_type_name(this).to_string();
+    let other_name = vm.get_pyobj_type_name(other).to_string();
+    return raise_type_error(vm, format!("'{}' not supported between instances of '{}' and '{}'", symbol, this_name, other_name));
+}
+
+pub fn is_subset(vm: &VM, elements: &[MemoryAddress], other: &[MemoryAddress]) -> bool {
+    elements.len() <= other.len() && elements.iter().all(|element| contains_element(vm, other, *element))
+}
+
+pub fn union_of(vm: &VM, elements: &[MemoryAddress], other: &[MemoryAddress]) -> Vec<MemoryAddress> {
+    let mut result = elements.to_vec();
+    for element in other.iter() {
+        if !contains_element(vm, &result, *element) {
+            result.push(*element);
+        }
+    }
+    return result;
+}
+
+pub fn intersection_of(vm: &VM, elements: &[MemoryAddress], other: &[MemoryAddress]) -> Vec<MemoryAddress> {
+    elements.iter().filter(|element| contains_element(vm, other, **element)).copied().collect()
+}
+
+pub fn difference_of(vm: &VM, elements: &[MemoryAddress], other: &[MemoryAddress]) -> Vec<MemoryAddress> {
+    elements.iter().filter(|element| !contains_element(vm, other, **element)).copied().collect()
+}
+
+pub fn symmetric_difference_of(vm: &VM, elements: &[MemoryAddress], other: &[MemoryAddress]) -> Vec<MemoryAddress> {
+    let mut result = difference_of(vm, elements, other);
+    result.extend(difference_of(vm, other, elements));
+    return result;
+}
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    if params.params.len() == 0 {
+        return allocate_frozenset(vm, vec![]);
+    }
+    check_builtin_func_params!("frozenset", 1, params.params.len());
+    match collect_unique_elements(vm, params.params.params[0]) {
+        Ok(elements) => allocate_frozenset(vm, elements),
+        Err(exception) => exception,
+    }
+}
+
+fn contains(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let item = call_params.params[0];
+    if vm.call_hash(item).is_none() {
+        return raise_unhashable_type_error(vm, item);
+    }
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+    return to_bool(vm, contains_element(vm, elements, item));
+}
+
+fn len(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(elements.len() as i128))
+}
+
+fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //iterate over a list snapshot of the elements
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap().clone();
+    let as_list = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(elements));
+    let (iterator, _) = vm.call_method(as_list, "__iter__", PositionalParameters::empty()).unwrap();
+    return iterator;
+}
+
+fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+    match set_elements(vm, call_params.params[0]) {
+        Some(other) => to_bool(vm, elements.len() == other.len() && is_subset(vm, elements, other)),
+        None => vm.builtin_type_addrs.false_val,
+    }
+}
+
+fn not_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let (eq_result, _) = vm
+        .call_method(call_params.bound_pyobj, "__eq__", PositionalParameters::single(call_params.params[0]))
+        .unwrap();
+    return to_bool(vm, eq_result == vm.builtin_type_addrs.false_val);
+}
+
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+    //elements were checked to be hashable when the frozenset was built
+    let element_hashes: Vec<i128> = elements.iter().map(|element| vm.call_hash(*element).unwrap()).collect();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_set(&element_hashes)))
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+
+    let buffer = if elements.is_empty() {
+        String::from("frozenset()")
+    } else {
+        let mut all_reprs = vec![];
+        for element in elements.iter() {
+            let (repr_addr, _) = vm.call_method(*element, "__repr__", PositionalParameters::empty()).unwrap();
+            all_reprs.push(vm.get_raw_data_of_pyobj(repr_addr).take_string().clone());
+        }
+        format!("frozenset({{{}}})", all_reprs.join(", "))
+    };
+
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer))
+}
+
+macro_rules! create_compare_function {
+    ($name:tt, $symbol:expr, $elements:tt, $other:tt, $vm:tt, $compare:expr) => {
+        fn $name($vm: &VM, params: CallParams) -> MemoryAddress {
+            let call_params = params.as_method();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let $elements = set_elements($vm, call_params.bound_pyobj).unwrap();
+            match set_elements($vm, call_params.params[0]) {
+                Some($other) => to_bool($vm, $compare),
+                None => not_supported_between($vm, $symbol, call_params.bound_pyobj, call_params.params[0]),
+            }
+        }
+    };
+}
+
+create_compare_function!(less_equals, "<=", a, b, vm, is_subset(vm, a, b));
+create_compare_function!(less_than, "<", a, b, vm, a.len() < b.len() && is_subset(vm, a, b));
+create_compare_function!(greater_equals, ">=", a, b, vm, is_subset(vm, b, a));
+create_compare_function!(greater_than, ">", a, b, vm, b.len() < a.len() && is_subset(vm, b, a));
+
+macro_rules! create_operator_function {
+    ($name:tt, $symbol:expr, $operation:tt) => {
+        fn $name(vm: &VM, params: CallParams) -> MemoryAddress {
+            let call_params = params.as_method();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+            match set_elements(vm, call_params.params[0]) {
+                Some(other) => allocate_like(vm, call_params.bound_pyobj, $operation(vm, elements, other)),
+                None => unsupported_operand(vm, $symbol, call_params.bound_pyobj, call_params.params[0]),
+            }
+        }
+    };
+}
+
+create_operator_function!(or_operator, "|", union_of);
+create_operator_function!(and_operator, "&", intersection_of);
+create_operator_function!(sub_operator, "-", difference_of);
+create_operator_function!(xor_operator, "^", symmetric_difference_of);
+
+//the named methods take any iterables, union/intersection/difference take any number of them
+macro_rules! create_method_function {
+    ($name:tt, $operation:tt) => {
+        fn $name(vm: &VM, params: CallParams) -> MemoryAddress {
+            let call_params = params.as_method();
+            let mut result = set_elements(vm, call_params.bound_pyobj).unwrap().clone();
+            for iterable in call_params.params.iter() {
+                match collect_unique_elements(vm, *iterable) {
+                    Ok(other) => result = $operation(vm, &result, &other),
+                    Err(exception) => return exception,
+                }
+            }
+            return allocate_like(vm, call_params.bound_pyobj, result);
+        }
+    };
+}
+
+create_method_function!(union, union_of);
+create_method_function!(intersection, intersection_of);
+create_method_function!(difference, difference_of);
+
+fn symmetric_difference(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+    match collect_unique_elements(vm, call_params.params[0]) {
+        Ok(other) => allocate_like(vm, call_params.bound_pyobj, symmetric_difference_of(vm, elements, &other)),
+        Err(exception) => exception,
+    }
+}
+
+macro_rules! create_predicate_function {
+    ($name:tt, $elements:tt, $other:tt, $vm:tt, $predicate:expr) => {
+        fn $name($vm: &VM, params: CallParams) -> MemoryAddress {
+            let call_params = params.as_method();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let $elements = set_elements($vm, call_params.bound_pyobj).unwrap();
+            match collect_unique_elements($vm, call_params.params[0]) {
+                Ok($other) => to_bool($vm, $predicate),
+                Err(exception) => exception,
+            }
+        }
+    };
+}
+
+create_predicate_function!(issubset, a, b, vm, is_subset(vm, a, &b));
+create_predicate_function!(issuperset, a, b, vm, is_subset(vm, &b, a));
+create_predicate_function!(isdisjoint, a, b, vm, intersection_of(vm, a, &b).is_empty());
+
+fn copy(_vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //immutable, so the copy can be the same object
+    return call_params.bound_pyobj;
+}
+
+//the read-only operations, shared by set and frozenset
+pub fn register_set_operations(vm: &mut VM, type_addr: MemoryAddress) {
+    vm.register_bounded_func_on_addr(type_addr, "__contains__", contains);
+    vm.register_bounded_func_on_addr(type_addr, "__len__", len);
+    vm.register_bounded_func_on_addr(type_addr, "__iter__", iter);
+    vm.register_bounded_func_on_addr(type_addr, "__eq__", equals);
+    vm.register_bounded_func_on_addr(type_addr, "__ne__", not_equals);
+    vm.register_bounded_func_on_addr(type_addr, "__le__", less_equals);
+    vm.register_bounded_func_on_addr(type_addr, "__lt__", less_than);
+    vm.register_bounded_func_on_addr(type_addr, "__ge__", greater_equals);
+    vm.register_bounded_func_on_addr(type_addr, "__gt__", greater_than);
+    vm.register_bounded_func_on_addr(type_addr, "__or__", or_operator);
+    vm.register_bounded_func_on_addr(type_addr, "__and__", and_operator);
+    vm.register_bounded_func_on_addr(type_addr, "__sub__", sub_operator);
+    vm.register_bounded_func_on_addr(type_addr, "__xor__", xor_operator);
+    vm.register_bounded_func_on_addr(type_addr, "union", union);
+    vm.register_bounded_func_on_addr(type_addr, "intersection", intersection);
+    vm.register_bounded_func_on_addr(type_addr, "difference", difference);
+    vm.register_bounded_func_on_addr(type_addr, "symmetric_difference", symmetric_difference);
+    vm.register_bounded_func_on_addr(type_addr, "issubset", issubset);
+    vm.register_bounded_func_on_addr(type_addr, "issuperset", issuperset);
+    vm.register_bounded_func_on_addr(type_addr, "isdisjoint", isdisjoint);
+}
+
+pub fn register_frozenset_type(vm: &mut VM) -> MemoryAddress {
+    let frozenset_type = vm.create_type(BUILTIN_MODULE, "frozenset", None);
+    vm.builtin_type_addrs.frozenset = frozenset_type;
+
+    vm.register_type_unbounded_func(frozenset_type, "__new__", create_new);
+    register_set_operations(vm, frozenset_type);
+    vm.register_bounded_func_on_addr(frozenset_type, "__hash__", hash);
+    vm.register_bounded_func_on_addr(frozenset_type, "__repr__", repr);
+    vm.register_bounded_func_on_addr(frozenset_type, "__str__", repr);
+    vm.register_bounded_func_on_addr(frozenset_type, "copy", copy);
+    return frozenset_type;
+}
@@ -0,0 +1,61 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::builtin_types::builtin_functions::get_iterator;
+
+pub const FUNCTOOLS_MODULE: &str = "functools";
+
+//folds from the left: reduce(f, [a, b, c], i) is f(f(f(i, a), b), c)
+fn reduce(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_function();
+    match call_params.params.len() {
+        2 | 3 => {}
+        n if n < 2 => panic!("TypeError: reduce expected at least 2 arguments, got {}", n),
+        n => panic!("TypeError: reduce expected at most 3 arguments, got {}", n),
+    }
+    let func = call_params.params[0];
+    let iterable = call_params.params[1];
+
+    let iterator = match get_iterator(vm, iterable) {
+        Ok(iterator) => iterator,
+        Err(exception) => {
+            vm.raise_exception(exception);
+            return exception;
+        }
+    };
+
+    let mut accumulated = call_params.params.get(2).copied();
+    loop {
+        let (next, frame) = vm.call_method(iterator, "__next__", PositionalParameters::empty()).unwrap();
+        match frame.exception {
+            Some(exception) if vm.is_stop_iteration(exception) => break,
+            Some(exception) => {
+                vm.raise_exception(exception);
+                return exception;
+            }
+            None => {}
+        }
+        accumulated = match accumulated {
+            None => Some(next),
+            Some(previous) => {
+                let (result, frame) = vm.run_function(PositionalParameters { params: vec![previous, next] }, func, None);
+                if let Some(exception) = frame.exception {
+                    vm.raise_exception(exception);
+                    return exception;
+                }
+                Some(result)
+            }
+        };
+    }
+
+    match accumulated {
+        Some(result) => result,
+        None => raise_type_error(vm, "reduce() of empty iterable with no initial value".to_string()),
+    }
+}
+
+pub fn register_functools_module(vm: &mut VM) {
+    vm.create_module(FUNCTOOLS_MODULE);
+    let reduce_fn = vm.create_unbounded_callable_pyobj(PyCallable { code: Box::new(reduce) }, Some("reduce".to_string()));
+    vm.add_to_module(FUNCTOOLS_MODULE, "reduce", reduce_fn);
+}
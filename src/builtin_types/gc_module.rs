@@ -0,0 +1,115 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use std::collections::BTreeSet;
+use crate::builtin_types::exception_type::format_exception;
+
+pub const GC_MODULE: &str = "gc";
+
+//everything reachable from the loaded modules and from the running frames
+fn mark_reachable(vm: &VM) -> BTreeSet<MemoryAddress> {
+    let mut pending: Vec<MemoryAddress> = vm.modules.values().copied().collect();
+    for frame in vm.stack.borrow().iter() {
+        pending.extend(frame.local_namespace.iter());
+        pending.extend(frame.stack.iter());
+        pending.extend(frame.exception.iter());
+    }
+
+    let mut reachable = BTreeSet::new();
+    while let Some(addr) = pending.pop() {
+        if !vm.memory.is_valid(addr) || !reachable.insert(addr) {
+            continue;
+        }
+        let pyobj = vm.get_pyobj_byaddr(addr);
+        pending.push(pyobj.type_addr);
+        pending.extend(pyobj.properties.values());
+        match &pyobj.structure {
+            PyObjectStructure::Object { raw_data, .. } => match raw_data {
+                BuiltInTypeData::List(elements)
+                | BuiltInTypeData::Tuple(elements)
+                | BuiltInTypeData::Set(elements)
+                | BuiltInTypeData::FrozenSet(elements) => pending.extend(elements.iter()),
+                BuiltInTypeData::Dict(entries) => {
+                    for (key, value) in entries {
+                        pending.push(*key);
+                        pending.push(*value);
+                    }
+                }
+                BuiltInTypeData::Generator(GeneratorState { frame: Some(frame), .. }) => {
+                    pending.extend(frame.local_namespace.iter());
+                    pending.extend(frame.stack.iter());
+                }
+                _ => {}
+            },
+            PyObjectStructure::UserDefinedFunction { defaults, .. } => pending.extend(defaults.iter()),
+            PyObjectStructure::BoundMethod { function_address, bound_address } => {
+                pending.push(*function_address);
+                pending.push(*bound_address);
+            }
+            PyObjectStructure::Type { functions, supertype, .. } => {
+                pending.extend(functions.values());
+                pending.extend(supertype.iter());
+            }
+            PyObjectStructure::Module { global_namespace, .. } => pending.extend(global_namespace.values()),
+            _ => {}
+        }
+    }
+    return reachable;
+}
+
+//Err holds the exception raised by __del__. Nothing to run when the class no longer has a __del__.
+fn finalize(vm: &VM, instance: MemoryAddress) -> Result<(), MemoryAddress> {
+    match vm.call_method(instance, "__del__", PositionalParameters::empty()) {
+        Some((_, frame)) => match frame.exception {
+            Some(exception) => Err(exception),
+            None => Ok(()),
+        },
+        None => Ok(()),
+    }
+}
+
+//runs __del__ on the unreachable instances. Each one is finalized only once, even if __del__ makes it reachable again,
+//and exceptions raised by __del__ are reported and ignored, like in python. The instances nothing refers to anymore
+//are freed afterwards. Returns how many objects were finalized.
+pub fn collect(vm: &VM) -> usize {
+    let reachable = mark_reachable(vm);
+    let mut unreachable: Vec<MemoryAddress> = vm.finalizable.borrow()
+        .iter()
+        .filter(|addr| !reachable.contains(addr))
+        .copied()
+        .collect();
+    //sorted so the order finalizers run in doesn't depend on hashing
+    unreachable.sort();
+    for instance in unreachable.iter() {
+        if let Err(exception) = finalize(vm, *instance) {
+            eprintln!(
+                "Exception ignored in: <function {}.__del__>\n{}",
+                vm.get_pyobj_type_name(*instance),
+                format_exception(vm, exception)
+            );
+        }
+        vm.finalizable.borrow_mut().remove(instance);
+        if vm.get_refcount(*instance) == 0 {
+            vm.memory.deallocate(*instance);
+        }
+    }
+    return unreachable.len();
+}
+
+fn create_collect_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+            let collected = collect(vm);
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(collected as i128));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("collect".to_string()));
+}
+
+pub fn register_gc_module(vm: &mut VM) {
+    vm.create_module(GC_MODULE);
+    let collect_fn = create_collect_fn(vm);
+    vm.add_to_module(GC_MODULE, "collect", collect_fn);
+}
@@ -0,0 +1,95 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::runtime::interpreter::execute_code_object;
+use crate::builtin_types::type_error::raise_type_error;
+
+fn generator_state(vm: &VM, generator: MemoryAddress) -> &mut GeneratorState {
+    match vm.get_raw_data_of_pyobj_mut(generator) {
+        BuiltInTypeData::Generator(state) => state,
+        _ => panic!("TypeError: descriptor requires a 'generator' object"),
+    }
+}
+
+//StopIteration instances carry the value the generator returned
+fn stop_iteration_with_value(vm: &VM, value: MemoryAddress) -> MemoryAddress {
+    let stop_iteration = vm.allocate_type_byaddr_raw(
+        vm.special_values[&SpecialValue::StopIterationType],
+        BuiltInTypeData::ClassInstance,
+    );
+    vm.increase_refcount(value);
+    vm.set_attribute(stop_iteration, "value", value);
+    vm.raise_exception(stop_iteration);
+    return stop_iteration;
+}
+
+//runs the generator frame until the next yield, `sent` is the value of the yield expression it was suspended at
+fn resume(vm: &VM, generator: MemoryAddress, sent: MemoryAddress) -> MemoryAddress {
+    let state = generator_state(vm, generator);
+    let mut frame = match state.frame.take() {
+        Some(frame) => frame,
+        None => {
+            let stop_iteration = vm.special_values[&SpecialValue::StopIterationType];
+            vm.raise_exception(stop_iteration);
+            return stop_iteration;
+        }
+    };
+    let code = state.code.clone();
+    if let Some(resume_pc) = frame.resume_pc.take() {
+        frame.stack.push(sent);
+        frame.prog_counter.set(resume_pc);
+    }
+
+    vm.push_stack_frame(frame);
+    execute_code_object(vm, &code);
+    let mut frame = vm.take_stack_frame();
+
+    if frame.resume_pc.is_some() {
+        let yielded = frame.stack.pop().unwrap();
+        generator_state(vm, generator).frame = Some(frame);
+        return yielded;
+    }
+    if let Some(exception) = frame.exception {
+        vm.raise_exception(exception);
+        return exception;
+    }
+    let returned = *frame.stack.last().unwrap();
+    return stop_iteration_with_value(vm, returned);
+}
+
+fn iter(_vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    return call_params.bound_pyobj;
+}
+
+fn next(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    return resume(vm, call_params.bound_pyobj, vm.special_values[&SpecialValue::NoneValue]);
+}
+
+//the first resume runs from the start of the body, there is no yield to receive the value yet
+fn send(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let generator = call_params.bound_pyobj;
+    let value = call_params.params[0];
+    let just_started = match &generator_state(vm, generator).frame {
+        Some(frame) => frame.resume_pc.is_none(),
+        None => false,
+    };
+    if just_started && value != vm.special_values[&SpecialValue::NoneValue] {
+        return raise_type_error(vm, "can't send non-None value to a just-started generator".to_string());
+    }
+    return resume(vm, generator, value);
+}
+
+pub fn register_generator_type(vm: &mut VM) -> MemoryAddress {
+    let generator_type = vm.create_type(BUILTIN_MODULE, "generator", None);
+    vm.register_bounded_func_on_addr(generator_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(generator_type, "__next__", next);
+    vm.register_bounded_func_on_addr(generator_type, "send", send);
+    vm.builtin_type_addrs.generator = generator_type;
+    return generator_type;
+}
@@ -1,7 +1,13 @@
 use crate::commons::float::Float;
+use crate::commons::arithmetic::{python_mod, FloorDiv};
+use crate::commons::format_spec::{parse_format_spec, format_int};
+use crate::commons::hash::hash_int;
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::builtin_functions::power;
+use crate::builtin_types::value_error::raise_value_error;
+use crate::builtin_types::zero_division_error::{raise_if_modulo_by_zero, raise_if_floor_division_by_zero, raise_if_division_by_zero};
 
 
 macro_rules! create_compare_function {
@@ -98,13 +104,41 @@ create_compare_function!(greater_equals, a, b, a <= b);
 create_compare_function!(not_equals, a, b, a != b);
 
 create_binop_function!(add, a, b, a + b);
-create_binop_function!(modulus, a, b, a % b);
+create_binop_function!(unchecked_modulus, a, b, python_mod(a, b));
+
+fn modulus(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    if let Some(exception) = raise_if_modulo_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return unchecked_modulus(vm, params);
+}
 create_binop_function!(sub, a, b, a - b);
 create_binop_function!(mul, a, b, a * b);
+create_binop_function!(unchecked_floordiv, a, b, a.floor_div(b));
+
+fn floordiv(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    if let Some(exception) = raise_if_floor_division_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return unchecked_floordiv(vm, params);
+}
+
+fn pow(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
+    let call_params = params.as_method();
+    return power(vm, call_params.bound_pyobj, call_params.params[0]);
+}
 
 fn truediv(vm: &VM, params: CallParams) -> MemoryAddress {
     check_builtin_func_params!(params.func_name.as_ref().unwrap(), 2, params.params.len());
     let call_params = params.as_method();
+    if let Some(exception) = raise_if_division_by_zero(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
     let other_type_name = vm.get_pyobj_type_name(call_params.params[0]);
     let self_data = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
@@ -131,6 +165,8 @@ fn truediv(vm: &VM, params: CallParams) -> MemoryAddress {
 
 create_unary_function!(negation, a, a * -1);
 create_unary_function!(positive, a, a);
+create_unary_function!(absolute, a, a.abs());
+create_unary_function!(invert, a, !a);
 
 fn int(_vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
@@ -177,17 +213,41 @@ fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
 
 fn to_boolean(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
-    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
     let self_data = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
         .take_int();
-    if self_data == 1 {
+    if self_data != 0 {
         return vm.builtin_type_addrs.true_val;
     } else {
         return vm.builtin_type_addrs.false_val;
     }
 }
 
+//an empty spec is the same as str(self), which also keeps format(True, "") == "True"
+fn format(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let spec = vm.get_raw_data_of_pyobj(call_params.params[0]).take_string();
+    if spec.is_empty() {
+        return vm.call_str(call_params.bound_pyobj);
+    }
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_int();
+    match parse_format_spec(spec).and_then(|spec| format_int(self_data, &spec)) {
+        Ok(formatted) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(formatted)),
+        Err(message) => raise_value_error(vm, message),
+    }
+}
+
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_int();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_int(self_data)))
+}
+
 pub fn register_int_type(vm: &mut VM) -> MemoryAddress {
     let int_type = vm.create_type(BUILTIN_MODULE, "int", None);
 
@@ -203,17 +263,24 @@ pub fn register_int_type(vm: &mut VM) -> MemoryAddress {
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__sub__", sub);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__mul__", mul);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__truediv__", truediv);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__floordiv__", floordiv);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__pow__", pow);
 
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__neg__", negation);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__pos__", positive);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__abs__", absolute);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__invert__", invert);
 
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__bool__", to_boolean);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__int__", int);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__float__", float);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__str__", to_str);
     vm.register_bounded_func(BUILTIN_MODULE, "int", "__repr__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__format__", format);
+    vm.register_bounded_func(BUILTIN_MODULE, "int", "__hash__", hash);
 
     vm.builtin_type_addrs.int = int_type;
+    vm.intern_small_ints();
 
     return int_type;
 }
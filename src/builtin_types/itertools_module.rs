@@ -0,0 +1,173 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::builtin_functions::get_iterator;
+
+pub const ITERTOOLS_MODULE: &str = "itertools";
+
+//the iterators keep their state as properties of the instance, and only advance when __next__ is called
+
+fn state_of(vm: &VM, iterator: MemoryAddress, name: &str) -> MemoryAddress {
+    return *vm.get_pyobj_byaddr(iterator).properties.get(name).unwrap();
+}
+
+fn set_state(vm: &VM, iterator: MemoryAddress, name: &str, value: MemoryAddress) {
+    vm.increase_refcount(value);
+    vm.set_attribute(iterator, name, value);
+}
+
+fn stop_iteration(vm: &VM) -> MemoryAddress {
+    let stop_iteration = vm.special_values[&SpecialValue::StopIterationType];
+    vm.raise_exception(stop_iteration);
+    return stop_iteration;
+}
+
+//positional argument, or keyword argument with the same name, or the default
+fn argument(params: &CallParams, index: usize, name: &str, default: MemoryAddress) -> MemoryAddress {
+    match params.params.params.get(index) {
+        Some(addr) => *addr,
+        None => params.keyword_params.get(name).unwrap_or(default),
+    }
+}
+
+fn iter(_vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    return call_params.bound_pyobj;
+}
+
+fn create_chain(vm: &VM, params: CallParams, chain_type: MemoryAddress) -> MemoryAddress {
+    let chain = vm.allocate_type_byaddr_raw(chain_type, BuiltInTypeData::ClassInstance);
+    for iterable in params.params.params.iter() {
+        vm.increase_refcount(*iterable);
+    }
+    let iterables = vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.tuple,
+        BuiltInTypeData::Tuple(params.params.params.clone()),
+    );
+    set_state(vm, chain, "iterables", iterables);
+    set_state(vm, chain, "index", vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(0)));
+    set_state(vm, chain, "current", vm.special_values[&SpecialValue::NoneValue]);
+    return chain;
+}
+
+//__iter__ is only called on an iterable once the previous one is exhausted
+fn chain_next(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let chain = call_params.bound_pyobj;
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    loop {
+        let mut current = state_of(vm, chain, "current");
+        if current == none {
+            let iterables = vm.get_raw_data_of_pyobj(state_of(vm, chain, "iterables")).take_tuple().clone();
+            let index = vm.get_raw_data_of_pyobj(state_of(vm, chain, "index")).take_int() as usize;
+            if index >= iterables.len() {
+                return stop_iteration(vm);
+            }
+            current = match get_iterator(vm, iterables[index]) {
+                Ok(iterator) => iterator,
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    return exception;
+                }
+            };
+            set_state(vm, chain, "current", current);
+            set_state(vm, chain, "index", vm.allocate_type_byaddr_raw(
+                vm.builtin_type_addrs.int, BuiltInTypeData::Int(index as i128 + 1)));
+        }
+        let (next, frame) = vm.call_method(current, "__next__", PositionalParameters::empty()).unwrap();
+        match frame.exception {
+            Some(exception) if vm.is_stop_iteration(exception) => {
+                set_state(vm, chain, "current", none);
+            }
+            Some(exception) => {
+                vm.raise_exception(exception);
+                return exception;
+            }
+            None => return next,
+        }
+    }
+}
+
+fn create_count(vm: &VM, params: CallParams, count_type: MemoryAddress) -> MemoryAddress {
+    if params.params.len() > 2 {
+        panic!("TypeError: count() takes at most 2 arguments ({} given)", params.params.len());
+    }
+    let count = vm.allocate_type_byaddr_raw(count_type, BuiltInTypeData::ClassInstance);
+    let zero = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(0));
+    let one = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(1));
+    set_state(vm, count, "current", argument(&params, 0, "start", zero));
+    set_state(vm, count, "step", argument(&params, 1, "step", one));
+    return count;
+}
+
+//never stops, the next value is computed with __add__ so floats also work
+fn count_next(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let count = call_params.bound_pyobj;
+    let current = state_of(vm, count, "current");
+    let step = state_of(vm, count, "step");
+    let (next, frame) = vm.call_method(current, "__add__", PositionalParameters::single(step)).unwrap();
+    if let Some(exception) = frame.exception {
+        vm.raise_exception(exception);
+        return exception;
+    }
+    set_state(vm, count, "current", next);
+    return current;
+}
+
+fn create_repeat(vm: &VM, params: CallParams, repeat_type: MemoryAddress) -> MemoryAddress {
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    if params.params.len() == 0 || params.params.len() > 2 {
+        panic!("TypeError: repeat() takes 1 or 2 arguments ({} given)", params.params.len());
+    }
+    let repeat = vm.allocate_type_byaddr_raw(repeat_type, BuiltInTypeData::ClassInstance);
+    set_state(vm, repeat, "element", params.params.params[0]);
+    //None repeats forever, negative counts repeat nothing
+    let times = argument(&params, 1, "times", none);
+    let remaining = if times == none {
+        none
+    } else {
+        let times = vm.get_raw_data_of_pyobj(times).take_int().max(0);
+        vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(times))
+    };
+    set_state(vm, repeat, "remaining", remaining);
+    return repeat;
+}
+
+fn repeat_next(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let repeat = call_params.bound_pyobj;
+    let remaining = state_of(vm, repeat, "remaining");
+    if remaining != vm.special_values[&SpecialValue::NoneValue] {
+        let remaining = vm.get_raw_data_of_pyobj(remaining).take_int();
+        if remaining == 0 {
+            return stop_iteration(vm);
+        }
+        set_state(vm, repeat, "remaining", vm.allocate_type_byaddr_raw(
+            vm.builtin_type_addrs.int, BuiltInTypeData::Int(remaining - 1)));
+    }
+    return state_of(vm, repeat, "element");
+}
+
+pub fn register_itertools_module(vm: &mut VM) {
+    vm.create_module(ITERTOOLS_MODULE);
+
+    let chain_type = vm.create_type(ITERTOOLS_MODULE, "chain", None);
+    vm.register_type_unbounded_func(chain_type, "__new__", move |vm, params| create_chain(vm, params, chain_type));
+    vm.register_bounded_func_on_addr(chain_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(chain_type, "__next__", chain_next);
+
+    let count_type = vm.create_type(ITERTOOLS_MODULE, "count", None);
+    vm.register_type_unbounded_func(count_type, "__new__", move |vm, params| create_count(vm, params, count_type));
+    vm.register_bounded_func_on_addr(count_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(count_type, "__next__", count_next);
+
+    let repeat_type = vm.create_type(ITERTOOLS_MODULE, "repeat", None);
+    vm.register_type_unbounded_func(repeat_type, "__new__", move |vm, params| create_repeat(vm, params, repeat_type));
+    vm.register_bounded_func_on_addr(repeat_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(repeat_type, "__next__", repeat_next);
+}
@@ -0,0 +1,435 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::dict_type::dict_set;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::commons::float::Float;
+
+pub const JSON_MODULE: &str = "json";
+
+fn escape_string(value: &str, buffer: &mut String) {
+    buffer.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            '\u{8}' => buffer.push_str("\\b"),
+            '\u{c}' => buffer.push_str("\\f"),
+            //like python's ensure_ascii: everything else outside printable ascii becomes \uXXXX,
+            //with characters outside the BMP written as a surrogate pair
+            c if (c as u32) < 0x20 || (c as u32) > 0x7e => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    buffer.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            c => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
+fn dump_float(vm: &VM, addr: MemoryAddress) -> String {
+    let value = vm.get_raw_data_of_pyobj(addr).take_float();
+    if value.is_nan() {
+        return String::from("NaN");
+    }
+    if value.is_infinite() {
+        return String::from(if value > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+    let (repr, _) = vm.call_method(addr, "__repr__", PositionalParameters::empty()).unwrap();
+    return vm.get_raw_data_of_pyobj(repr).take_string().clone();
+}
+
+//dict keys are always written as strings, so keys of the other scalar types are converted first
+fn dump_key(vm: &VM, key: MemoryAddress, buffer: &mut String) -> Result<(), MemoryAddress> {
+    let key_obj = vm.get_pyobj_byaddr(key);
+    if key == vm.special_values[&SpecialValue::NoneValue] {
+        escape_string("null", buffer);
+    } else if key_obj.type_addr == vm.builtin_type_addrs.boolean {
+        let value = vm.get_raw_data_of_pyobj(key).take_int();
+        escape_string(if value == 1 { "true" } else { "false" }, buffer);
+    } else {
+        match key_obj.try_get_builtin() {
+            Some(BuiltInTypeData::String(s)) => escape_string(s, buffer),
+            Some(BuiltInTypeData::Int(i)) => escape_string(&i.to_string(), buffer),
+            Some(BuiltInTypeData::Float(_)) => escape_string(&dump_float(vm, key), buffer),
+            _ => {
+                let type_name = vm.get_pyobj_type_name(key);
+                return Err(raise_type_error(vm, format!("keys must be str, int, float, bool or None, not {}", type_name)));
+            }
+        }
+    }
+    return Ok(());
+}
+
+//containers holds the lists and dicts currently being written, to detect cycles
+fn dump_value(vm: &VM, addr: MemoryAddress, buffer: &mut String, containers: &mut Vec<MemoryAddress>) -> Result<(), MemoryAddress> {
+    let pyobj = vm.get_pyobj_byaddr(addr);
+    if addr == vm.special_values[&SpecialValue::NoneValue] {
+        buffer.push_str("null");
+        return Ok(());
+    }
+    if pyobj.type_addr == vm.builtin_type_addrs.boolean {
+        let value = vm.get_raw_data_of_pyobj(addr).take_int();
+        buffer.push_str(if value == 1 { "true" } else { "false" });
+        return Ok(());
+    }
+    match pyobj.try_get_builtin() {
+        Some(BuiltInTypeData::Int(i)) => buffer.push_str(&i.to_string()),
+        Some(BuiltInTypeData::Float(_)) => buffer.push_str(&dump_float(vm, addr)),
+        Some(BuiltInTypeData::String(s)) => escape_string(s, buffer),
+        Some(BuiltInTypeData::List(elements)) | Some(BuiltInTypeData::Tuple(elements)) => {
+            if containers.contains(&addr) {
+                panic!("ValueError: Circular reference detected");
+            }
+            containers.push(addr);
+            buffer.push('[');
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    buffer.push_str(", ");
+                }
+                dump_value(vm, *element, buffer, containers)?;
+            }
+            buffer.push(']');
+            containers.pop();
+        }
+        Some(BuiltInTypeData::Dict(entries)) => {
+            if containers.contains(&addr) {
+                panic!("ValueError: Circular reference detected");
+            }
+            containers.push(addr);
+            buffer.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    buffer.push_str(", ");
+                }
+                dump_key(vm, *key, buffer)?;
+                buffer.push_str(": ");
+                dump_value(vm, *value, buffer, containers)?;
+            }
+            buffer.push('}');
+            containers.pop();
+        }
+        _ => {
+            let type_name = vm.get_pyobj_type_name(addr);
+            return Err(raise_type_error(vm, format!("Object of type {} is not JSON serializable", type_name)));
+        }
+    }
+    return Ok(());
+}
+
+struct JsonParser<'a> {
+    vm: &'a VM,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    //same message format as python's JSONDecodeError
+    fn fail(&self, message: &str, pos: usize) -> ! {
+        let line = self.chars[..pos].iter().filter(|c| **c == '\n').count() + 1;
+        let column = match self.chars[..pos].iter().rposition(|c| *c == '\n') {
+            Some(newline) => pos - newline,
+            None => pos + 1,
+        };
+        panic!("JSONDecodeError: {}: line {} column {} (char {})", message, line, column, pos);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn consume_literal(&mut self, literal: &str) -> bool {
+        let literal: Vec<char> = literal.chars().collect();
+        if self.chars[self.pos..].starts_with(&literal) {
+            self.pos += literal.len();
+            return true;
+        }
+        return false;
+    }
+
+    fn allocate_string(&self, value: String) -> MemoryAddress {
+        self.vm.allocate_type_byaddr_raw(self.vm.builtin_type_addrs.string, BuiltInTypeData::String(value))
+    }
+
+    fn allocate_float(&self, value: f64) -> MemoryAddress {
+        self.vm.allocate_type_byaddr_raw(self.vm.builtin_type_addrs.float, BuiltInTypeData::Float(Float(value)))
+    }
+
+    fn parse_value(&mut self) -> MemoryAddress {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some('{') => return self.parse_object(),
+            Some('[') => return self.parse_array(),
+            Some('"') => {
+                let value = self.parse_string();
+                return self.allocate_string(value);
+            }
+            Some('-' | '0'..='9') => {
+                if self.consume_literal("-Infinity") {
+                    return self.allocate_float(f64::NEG_INFINITY);
+                }
+                return self.parse_number();
+            }
+            _ => {}
+        }
+        if self.consume_literal("null") {
+            return self.vm.special_values[&SpecialValue::NoneValue];
+        }
+        if self.consume_literal("true") {
+            return self.vm.builtin_type_addrs.true_val;
+        }
+        if self.consume_literal("false") {
+            return self.vm.builtin_type_addrs.false_val;
+        }
+        if self.consume_literal("NaN") {
+            return self.allocate_float(f64::NAN);
+        }
+        if self.consume_literal("Infinity") {
+            return self.allocate_float(f64::INFINITY);
+        }
+        self.fail("Expecting value", start);
+    }
+
+    fn consume_digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+        return self.pos - start;
+    }
+
+    //-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][-+]?[0-9]+)?
+    fn parse_number(&mut self) -> MemoryAddress {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        let integer_start = self.pos;
+        if self.consume_digits() == 0 {
+            self.fail("Expecting value", start);
+        }
+        //a leading zero ends the number, so "01" is 0 followed by extra data
+        if self.chars[integer_start] == '0' {
+            self.pos = integer_start + 1;
+        }
+        let mut is_float = false;
+        let before_fraction = self.pos;
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            if self.consume_digits() == 0 {
+                self.pos = before_fraction;
+            } else {
+                is_float = true;
+            }
+        }
+        let before_exponent = self.pos;
+        if let Some('e' | 'E') = self.peek() {
+            self.pos += 1;
+            if let Some('+' | '-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.consume_digits() == 0 {
+                self.pos = before_exponent;
+            } else {
+                is_float = true;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if is_float {
+            return self.allocate_float(text.parse::<f64>().unwrap());
+        }
+        match text.parse::<i128>() {
+            Ok(value) => self.vm.allocate_type_byaddr_raw(self.vm.builtin_type_addrs.int, BuiltInTypeData::Int(value)),
+            Err(_) => panic!("OverflowError: JSON integer {} does not fit in an int", text),
+        }
+    }
+
+    fn parse_hex4(&mut self, escape_start: usize) -> u32 {
+        let digits: String = self.chars.iter().skip(self.pos).take(4).collect();
+        if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            self.fail("Invalid \\uXXXX escape", escape_start);
+        }
+        self.pos += 4;
+        return u32::from_str_radix(&digits, 16).unwrap();
+    }
+
+    fn parse_string(&mut self) -> String {
+        let start = self.pos;
+        self.pos += 1;
+        let mut result = String::new();
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => self.fail("Unterminated string starting at", start),
+            };
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return result;
+                }
+                '\\' => {
+                    let escape_start = self.pos;
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.pos += 1;
+                            let unit = self.parse_hex4(escape_start);
+                            let mut code_point = unit;
+                            //a high surrogate followed by an escaped low surrogate is one character
+                            if (0xd800..0xdc00).contains(&unit) && self.chars[self.pos..].starts_with(&['\\', 'u']) {
+                                let second_start = self.pos;
+                                self.pos += 2;
+                                let low = self.parse_hex4(second_start);
+                                if (0xdc00..0xe000).contains(&low) {
+                                    code_point = 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00);
+                                } else {
+                                    self.pos = second_start;
+                                }
+                            }
+                            //lone surrogates can't be stored in a rust String
+                            result.push(char::from_u32(code_point).unwrap_or('\u{fffd}'));
+                            continue;
+                        }
+                        None => self.fail("Unterminated string starting at", start),
+                        Some(_) => self.fail("Invalid \\escape", escape_start),
+                    };
+                    result.push(escaped);
+                    self.pos += 1;
+                }
+                c if (c as u32) < 0x20 => self.fail("Invalid control character at", self.pos),
+                c => {
+                    result.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> MemoryAddress {
+        self.pos += 1;
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+        } else {
+            loop {
+                let element = self.parse_value();
+                self.vm.increase_refcount(element);
+                elements.push(element);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(',') => self.pos += 1,
+                    _ => self.fail("Expecting ',' delimiter", self.pos),
+                }
+            }
+        }
+        return self.vm.allocate_type_byaddr_raw(self.vm.builtin_type_addrs.list, BuiltInTypeData::List(elements));
+    }
+
+    fn parse_object(&mut self) -> MemoryAddress {
+        self.pos += 1;
+        let dict = self.vm.allocate_type_byaddr_raw(self.vm.builtin_type_addrs.dict, BuiltInTypeData::Dict(vec![]));
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return dict;
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                self.fail("Expecting property name enclosed in double quotes", self.pos);
+            }
+            let key = self.parse_string();
+            let key = self.allocate_string(key);
+            self.skip_whitespace();
+            if self.peek() != Some(':') {
+                self.fail("Expecting ':' delimiter", self.pos);
+            }
+            self.pos += 1;
+            let value = self.parse_value();
+            //later duplicates overwrite earlier ones, like in python
+            dict_set(self.vm, dict, key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    return dict;
+                }
+                Some(',') => self.pos += 1,
+                _ => self.fail("Expecting ',' delimiter", self.pos),
+            }
+        }
+    }
+}
+
+fn create_dumps_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let mut buffer = String::new();
+            if let Err(exception) = dump_value(vm, call_params.params[0], &mut buffer, &mut vec![]) {
+                return exception;
+            }
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer));
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("dumps".to_string()));
+}
+
+fn create_loads_fn(vm: &VM) -> MemoryAddress {
+    let func = PyCallable {
+        code: Box::new(move |vm, params| -> MemoryAddress {
+            let call_params = params.as_function();
+            check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+            let source = call_params.params[0];
+            let chars = match vm.get_pyobj_byaddr(source).try_get_builtin() {
+                Some(BuiltInTypeData::String(s)) => s.chars().collect(),
+                _ => {
+                    let type_name = vm.get_pyobj_type_name(source);
+                    return raise_type_error(vm, format!("the JSON object must be str, not {}", type_name));
+                }
+            };
+            let mut parser = JsonParser { vm, chars, pos: 0 };
+            let value = parser.parse_value();
+            parser.skip_whitespace();
+            if parser.pos < parser.chars.len() {
+                parser.fail("Extra data", parser.pos);
+            }
+            return value;
+        }),
+    };
+    return vm.create_unbounded_callable_pyobj(func, Some("loads".to_string()));
+}
+
+pub fn register_json_module(vm: &mut VM) {
+    vm.create_module(JSON_MODULE);
+    let dumps_fn = create_dumps_fn(vm);
+    let loads_fn = create_loads_fn(vm);
+    vm.add_to_module(JSON_MODULE, "dumps", dumps_fn);
+    vm.add_to_module(JSON_MODULE, "loads", loads_fn);
+}
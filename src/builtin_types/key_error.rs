@@ -0,0 +1,30 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::runtime::datamodel::*;
+use crate::builtin_types::exception_type::{create_exception_type, new_exception, to_str};
+
+//the exception holds the missing key itself, like python
+pub fn raise_key_error(vm: &VM, key: MemoryAddress) -> MemoryAddress {
+    let exception = new_exception(vm, vm.builtin_type_addrs.key_err, vec![key]);
+    vm.raise_exception(exception);
+    return exception;
+}
+
+//a single key is shown with its repr, so d["a"] fails with KeyError: 'a'
+fn key_error_str(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    let args = vm.get_obj_property(call_params.bound_pyobj, "args").unwrap();
+    let args = vm.get_raw_data_of_pyobj(args).take_tuple().clone();
+    if args.len() == 1 {
+        let (repr, _) = vm.call_method(args[0], "__repr__", PositionalParameters::empty()).unwrap();
+        return repr;
+    }
+    return to_str(vm, params);
+}
+
+pub fn register_keyerr_type(vm: &mut VM) -> MemoryAddress {
+    let key_err = create_exception_type(vm, "KeyError");
+    vm.builtin_type_addrs.key_err = key_err;
+    vm.register_bounded_func(BUILTIN_MODULE, "KeyError", "__str__", key_error_str);
+    return key_err;
+}
@@ -0,0 +1,15 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_subtype, new_exception};
+
+pub fn raise_keyboard_interrupt(vm: &VM) -> MemoryAddress {
+    let exception = new_exception(vm, vm.builtin_type_addrs.keyboard_interrupt, vec![]);
+    vm.raise_exception(exception);
+    return exception;
+}
+
+pub fn register_keyboardinterrupt_type(vm: &mut VM) -> MemoryAddress {
+    let keyboard_interrupt = create_exception_subtype(vm, "KeyboardInterrupt", vm.builtin_type_addrs.base_exception);
+    vm.builtin_type_addrs.keyboard_interrupt = keyboard_interrupt;
+    return keyboard_interrupt;
+}
@@ -1,6 +1,9 @@
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::frozenset_type::to_bool;
+use crate::builtin_types::builtin_functions::collect_iterable;
+use crate::builtin_types::index_error::raise_index_error;
 
 
 fn concat(vm: &VM, params: CallParams) -> MemoryAddress {
@@ -35,24 +38,27 @@ fn concat(vm: &VM, params: CallParams) -> MemoryAddress {
 fn extend(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
     check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
-    let other_data = vm.get_raw_data_of_pyobj(call_params.params[0]);
-    match other_data {
-        BuiltInTypeData::List(values) => {
-            let cloned = values.clone();
-            let self_data = vm
-                .get_raw_data_of_pyobj_mut(call_params.bound_pyobj)
-                .take_list_mut();
-            (*self_data).extend(cloned);
-            return call_params.bound_pyobj;
-        }
-        _ => {
-            let other_type_name = vm.get_pyobj_type_name(call_params.params[0]);
-            panic!(
-                "horse only supports extending from list (not \"{}\") for now",
-                other_type_name
-            );
-        }
-    }
+    //the elements are collected before extending, so extending a list with itself terminates
+    let elements = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::List(values) => values.clone(),
+        _ => match collect_iterable(vm, call_params.params[0]) {
+            Ok(elements) => elements,
+            Err(exception) => {
+                vm.raise_exception(exception);
+                return exception;
+            }
+        },
+    };
+    let self_data = vm
+        .get_raw_data_of_pyobj_mut(call_params.bound_pyobj)
+        .take_list_mut();
+    (*self_data).extend(elements);
+    return call_params.bound_pyobj;
+}
+
+//lst += other extends in place, so every name bound to the list sees the new elements
+fn iadd(vm: &VM, params: CallParams) -> MemoryAddress {
+    return extend(vm, params);
 }
 
 fn append(vm: &VM, params: CallParams) -> MemoryAddress {
@@ -62,7 +68,8 @@ fn append(vm: &VM, params: CallParams) -> MemoryAddress {
         .get_raw_data_of_pyobj_mut(call_params.bound_pyobj)
         .take_list_mut();
     self_data.push(call_params.params[0]);
-    return call_params.bound_pyobj;
+    vm.increase_refcount(call_params.params[0]);
+    return vm.special_values[&SpecialValue::NoneValue];
 }
 
 fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
@@ -71,38 +78,25 @@ fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
     let this_list = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
         .take_list();
-    let other_data = vm.get_raw_data_of_pyobj(call_params.params[0]);
+    let other_data = vm.get_pyobj_byaddr(call_params.params[0]).try_get_builtin();
 
     match other_data {
-        BuiltInTypeData::List(other_list) => {
+        Some(BuiltInTypeData::List(other_list)) => {
             if this_list.len() != other_list.len() {
                 return vm.builtin_type_addrs.false_val;
             }
-            let mut list_equals = true;
-            for ptr_self in this_list.iter() {
-                for ptr_other in other_list.iter() {
-                    if ptr_self == ptr_other {
-                        continue;
-                    }
-                    let result = vm.call_method(*ptr_self, "__eq__", PositionalParameters::single(*ptr_other));
-                    match result {
-                        Some((eq_result, _)) => {
-                            if eq_result == vm.builtin_type_addrs.false_val {
-                                list_equals = false;
-                                break;
-                            }
-                        }
-                        None => {
-                            list_equals = false;
-                        }
-                    }
-                }
-            }
-            if list_equals {
-                return vm.builtin_type_addrs.false_val;
-            } else {
+            if !vm.eq_enter(call_params.bound_pyobj, call_params.params[0]) {
                 return vm.builtin_type_addrs.true_val;
             }
+            let all_equal = this_list.iter().zip(other_list.iter()).all(|(ptr_self, ptr_other)| {
+                if ptr_self == ptr_other {
+                    return true;
+                }
+                let result = vm.call_method(*ptr_self, "__eq__", PositionalParameters::single(*ptr_other));
+                return matches!(result, Some((eq_result, _)) if eq_result == vm.builtin_type_addrs.true_val);
+            });
+            vm.eq_leave(call_params.bound_pyobj, call_params.params[0]);
+            return to_bool(vm, all_equal);
         }
         _ => {
             return vm.builtin_type_addrs.false_val;
@@ -129,43 +123,17 @@ fn not_equals(vm: &VM, params: CallParams) -> MemoryAddress {
 }
 
 fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
-    let call_params = params.as_method();
-    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
-    let this_list = vm
-        .get_raw_data_of_pyobj(call_params.bound_pyobj)
-        .take_list();
-    let mut buffer = String::from("[");
-
-    let all_reprs: Vec<String> = this_list
-        .iter()
-        .map(|ptr_self| {
-            let (as_string, _) = vm.call_method(*ptr_self, "__repr__", PositionalParameters::empty()).unwrap();
-            return vm
-                .get_pyobj_byaddr(as_string)
-                .try_get_builtin()
-                .unwrap()
-                .take_string()
-                .clone();
-        })
-        .collect();
-
-    buffer = buffer + all_reprs.join(",").as_str();
-    buffer.push(']');
-
-    vm.allocate_type_byaddr_raw(
-        vm.builtin_type_addrs.string,
-        BuiltInTypeData::String(buffer),
-    )
-}
-
-fn to_str(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
     check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    if !vm.repr_enter(call_params.bound_pyobj) {
+        return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String("[...]".into()));
+    }
     let this_list = vm
         .get_raw_data_of_pyobj(call_params.bound_pyobj)
         .take_list();
     let mut buffer = String::from("[");
 
+    //like CPython, str(list) is the same as repr(list): elements are always shown with their repr
     let all_reprs: Vec<String> = this_list
         .iter()
         .map(|ptr_self| {
@@ -176,6 +144,7 @@ fn to_str(vm: &VM, params: CallParams) -> MemoryAddress {
                 .clone();
         })
         .collect();
+    vm.repr_leave(call_params.bound_pyobj);
 
     buffer = buffer + all_reprs.join(", ").as_str();
     buffer.push(']');
@@ -205,12 +174,32 @@ fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
     check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
     //construct a list_iterator
     //find it in builtin module
-    let iterator_class = vm.find_in_module(MAIN_MODULE, "list_iterator").expect("list_iterator type not found");
+    let iterator_class = vm.find_in_module(BUILTIN_MODULE, "list_iterator").expect("list_iterator type not found");
     let new = vm.try_load_function_addr(iterator_class); //this is the __new__ method, try_load_function_addr automatically gets the __new__ function
     let (result, _) = vm.run_function(PositionalParameters::single(call_params.bound_pyobj), new, None);
     return result;
 }
 
+fn contains(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_list = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_list();
+    let item = call_params.params[0];
+    for element in this_list.iter() {
+        if *element == item {
+            return vm.builtin_type_addrs.true_val;
+        }
+        if let Some((eq_result, _)) = vm.call_method(*element, "__eq__", PositionalParameters::single(item)) {
+            if eq_result == vm.builtin_type_addrs.true_val {
+                return vm.builtin_type_addrs.true_val;
+            }
+        }
+    }
+    return vm.builtin_type_addrs.false_val;
+}
+
 fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
     let call_params = params.as_method();
     check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
@@ -219,18 +208,35 @@ fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
         .take_list();
     
     let index = vm.get_raw_data_of_pyobj(call_params.params[0]).take_int();
+    let resolved = if index < 0 { index + this_list.len() as i128 } else { index };
 
-    if index as usize >= this_list.len() {
-        let exception = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.index_err, BuiltInTypeData::String("list index out of range".into()));
-        vm.raise_exception(exception);
-        return exception;
+    if resolved < 0 || resolved >= this_list.len() as i128 {
+        return raise_index_error(vm, String::from("list index out of range"));
     } else {
-        let value_at_index = this_list[index as usize];
+        let value_at_index = this_list[resolved as usize];
         return value_at_index
     }
 
 }
 
+fn setitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 2, call_params.params.len());
+    let index = vm.get_raw_data_of_pyobj(call_params.params[0]).take_int();
+    let this_list = vm
+        .get_raw_data_of_pyobj_mut(call_params.bound_pyobj)
+        .take_list_mut();
+
+    let resolved = if index < 0 { index + this_list.len() as i128 } else { index };
+
+    if resolved < 0 || resolved >= this_list.len() as i128 {
+        return raise_index_error(vm, String::from("list assignment index out of range"));
+    }
+    this_list[resolved as usize] = call_params.params[1];
+    vm.increase_refcount(call_params.params[1]);
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
 fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
     if params.params.len() == 0 {
         return vm
@@ -243,22 +249,13 @@ fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
             );
         } else {
             check_builtin_func_params!("list", 1, params.params.len());
-            let iterator_call = vm.call_method(params.params.params[0], "__iter__", PositionalParameters::empty());
-            let mut results = vec![];
-            loop {
-                match iterator_call {
-                    Some((addr, _)) => {
-                        //start consuming the iterator
-                        let (result, frame) = vm.call_method(addr, "__next__", PositionalParameters::empty()).unwrap();
-                        if frame.exception.is_some() {
-                            break;
-                        } else {
-                            results.push(result);
-                        }
-                    },
-                    None => panic!("Object passed to list does not have __iter__ method"),
+            let results = match collect_iterable(vm, params.params.params[0]) {
+                Ok(results) => results,
+                Err(exception) => {
+                    vm.raise_exception(exception);
+                    return exception;
                 }
-            }   
+            };
             return vm.allocate_type_byaddr_raw(
                 vm.builtin_type_addrs.list,
                 BuiltInTypeData::List(results),
@@ -276,14 +273,17 @@ pub fn register_list_type(vm: &mut VM) -> MemoryAddress {
 
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__add__", concat);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__eq__", equals);
-    vm.register_bounded_func(BUILTIN_MODULE, "list", "__neq__", not_equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "list", "__ne__", not_equals);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__repr__", repr);
-    vm.register_bounded_func(BUILTIN_MODULE, "list", "__str__", to_str);
+    vm.register_bounded_func(BUILTIN_MODULE, "list", "__str__", repr);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__len__", len);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__getitem__", getitem);
+    vm.register_bounded_func(BUILTIN_MODULE, "list", "__setitem__", setitem);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "__iter__", iter);
+    vm.register_bounded_func(BUILTIN_MODULE, "list", "__contains__", contains);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "append", append);
     vm.register_bounded_func(BUILTIN_MODULE, "list", "extend", extend);
+    vm.register_bounded_func(BUILTIN_MODULE, "list", "__iadd__", iadd);
     vm.builtin_type_addrs.list = list_type;
     return list_type;
 }
@@ -1,4 +1,5 @@
 use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
 use crate::ast::lexer;
 use crate::ast::parser;
 use crate::bytecode::compiler::*;
@@ -12,10 +13,20 @@ pub fn run_loader(vm: &mut VM) {
         //println!("Loading source {:?}", dir.path());
         let source = std::fs::read_to_string(dir.path());
         let tokens = lexer::tokenize(&source.unwrap()).unwrap();
-        let expr = parser::parse_ast(tokens);
-        let program = compile(expr);
+        let expr = parser::parse_ast(tokens).unwrap_or_else(|e| panic!("{} in {:?}", e, dir.path()));
+        let program = compile(expr).unwrap_or_else(|e| panic!("{} in {:?}", e, dir.path()));
         interpreter::execute_program(vm, program);
         vm.clear_stacks();
     }
-    
+
+    //the stdlib runs as a main program, so what it defines lands in __main__: move it to __builtins__,
+    //where unqualified names fall back to and where user globals can shadow it
+    for (name, addr) in vm.module_namespace(MAIN_MODULE) {
+        if name == "__name__" {
+            continue;
+        }
+        vm.set_in_module(BUILTIN_MODULE, &name, addr);
+        vm.remove_from_module(MAIN_MODULE, &name);
+    }
+
 }
@@ -8,21 +8,112 @@ pub mod builtin_math;
 pub mod float_type;
 pub mod int_type;
 pub mod list_type;
+pub mod tuple_type;
+pub mod dict_type;
+pub mod set_type;
+pub mod frozenset_type;
+pub mod classmethod_type;
+pub mod staticmethod_type;
+pub mod property_type;
+pub mod copy_module;
+pub mod json_module;
+pub mod ast_module;
+pub mod gc_module;
+pub mod counter_type;
+pub mod itertools_module;
+pub mod functools_module;
 pub mod string_type;
+pub mod string_iterator_type;
+pub mod bytes_type;
+pub mod exception_type;
 pub mod index_error;
+pub mod key_error;
+pub mod type_error;
+pub mod assertion_error;
+pub mod zero_division_error;
+pub mod value_error;
+pub mod name_error;
+pub mod syntax_error;
+pub mod keyboard_interrupt;
 pub mod code_object;
+pub mod generator_type;
 pub mod loader;
 pub mod none_type;
 
-pub fn register_builtins(vm: &mut VM) {
+//types and pure functions only: no I/O, nothing that can abort the host, and no compile, exec or eval
+pub fn register_core_builtins(vm: &mut VM) {
     int_type::register_int_type(vm);
     float_type::register_float_type(vm);
     builtin_math::register_builtin_functions(vm);
-    builtin_functions::register_builtin_functions(vm);
+    builtin_functions::register_core_builtin_functions(vm);
     boolean_type::register_boolean_type(vm);
     string_type::register_string_type(vm);
+    string_iterator_type::register_string_iterator_type(vm);
+    bytes_type::register_bytes_type(vm);
     list_type::register_list_type(vm);
+    tuple_type::register_tuple_type(vm);
+    dict_type::register_dict_type(vm);
+    set_type::register_set_type(vm);
+    frozenset_type::register_frozenset_type(vm);
+    classmethod_type::register_classmethod_type(vm);
+    staticmethod_type::register_staticmethod_type(vm);
+    property_type::register_property_type(vm);
+    exception_type::register_exception_type(vm);
     index_error::register_indexerr_type(vm);
+    key_error::register_keyerr_type(vm);
+    type_error::register_typeerr_type(vm);
+    assertion_error::register_assertionerr_type(vm);
+    zero_division_error::register_zerodivisionerr_type(vm);
+    value_error::register_valueerr_type(vm);
+    name_error::register_nameerr_type(vm);
+    syntax_error::register_syntaxerr_type(vm);
+    keyboard_interrupt::register_keyboardinterrupt_type(vm);
     code_object::register_codeobject_type(vm);
+    generator_type::register_generator_type(vm);
     none_type::register_none_type_methods(vm);
+    copy_module::register_copy_module(vm);
+    json_module::register_json_module(vm);
+    ast_module::register_ast_module(vm);
+    gc_module::register_gc_module(vm);
+    counter_type::register_counter_type(vm);
+    itertools_module::register_itertools_module(vm);
+    functools_module::register_functools_module(vm);
+    vm.set_module_names();
+}
+
+//the core builtins plus I/O and the functions that run code given as source
+pub fn register_builtins(vm: &mut VM) {
+    register_core_builtins(vm);
+    builtin_functions::register_io_builtin_functions(vm);
+    builtin_functions::register_dynamic_code_builtin_functions(vm);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::datamodel::BUILTIN_MODULE;
+
+    #[test]
+    fn core_builtins_exclude_io() {
+        let mut vm = VM::new();
+        register_core_builtins(&mut vm);
+        assert!(vm.find_in_module(BUILTIN_MODULE, "len").is_some());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "int").is_some());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "open").is_none());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "print").is_none());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "panic").is_none());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "compile").is_none());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "exec").is_none());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "eval").is_none());
+    }
+
+    #[test]
+    fn full_builtins_include_io() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        assert!(vm.find_in_module(BUILTIN_MODULE, "len").is_some());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "print").is_some());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "exec").is_some());
+        assert!(vm.find_in_module(BUILTIN_MODULE, "eval").is_some());
+    }
 }
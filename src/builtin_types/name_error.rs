@@ -0,0 +1,20 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_type, create_exception_subtype, raise_with_message};
+
+pub fn raise_name_error(vm: &VM, name: &str) -> MemoryAddress {
+    return raise_with_message(vm, vm.builtin_type_addrs.name_err, format!("name '{}' is not defined", name));
+}
+
+//a local the function deletes or hasn't assigned yet
+pub fn raise_unbound_local_error(vm: &VM, name: &str) -> MemoryAddress {
+    let message = format!("cannot access local variable '{}' where it is not associated with a value", name);
+    return raise_with_message(vm, vm.builtin_type_addrs.unbound_local_err, message);
+}
+
+pub fn register_nameerr_type(vm: &mut VM) -> MemoryAddress {
+    let name_err = create_exception_type(vm, "NameError");
+    vm.builtin_type_addrs.name_err = name_err;
+    vm.builtin_type_addrs.unbound_local_err = create_exception_subtype(vm, "UnboundLocalError", name_err);
+    return name_err;
+}
@@ -11,7 +11,8 @@ fn to_str(vm: &VM, params: CallParams) -> MemoryAddress {
     )
 }
 fn to_boolean(vm: &VM, params: CallParams) -> MemoryAddress {
-    check_builtin_func_params!(params.func_name.unwrap(), 0, params.params.len());
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
     return vm.builtin_type_addrs.false_val;
 }
 
@@ -26,10 +27,19 @@ fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
     }
 }
 
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //any constant works, there is only one None
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(0xFCA86420))
+}
+
 pub fn register_none_type_methods(vm: &mut VM) {
     let none_type_addr = vm.special_values[&SpecialValue::NoneType];
 
     vm.register_bounded_func_on_addr(none_type_addr, "__str__", to_str);
+    vm.register_bounded_func_on_addr(none_type_addr, "__repr__", to_str);
     vm.register_bounded_func_on_addr(none_type_addr, "__eq__", equals);
+    vm.register_bounded_func_on_addr(none_type_addr, "__hash__", hash);
     vm.register_bounded_func_on_addr(none_type_addr, "__bool__", to_boolean);
 }
@@ -0,0 +1,38 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+
+//fget is called when the attribute is loaded from an instance, fset when it's assigned. A missing fset is None.
+fn allocate_property(vm: &VM, fget: MemoryAddress, fset: MemoryAddress) -> MemoryAddress {
+    let property = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.property, BuiltInTypeData::ClassInstance);
+    vm.increase_refcount(fget);
+    vm.increase_refcount(fset);
+    vm.set_attribute(property, "fget", fget);
+    vm.set_attribute(property, "fset", fset);
+    return property;
+}
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    match params.params.len() {
+        1 => allocate_property(vm, params.params.params[0], none),
+        2 => allocate_property(vm, params.params.params[0], params.params.params[1]),
+        n => panic!("TypeError: property expected at most 2 arguments, got {}", n),
+    }
+}
+
+//@x.setter: a copy of the property with the decorated function as its setter
+fn setter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let fget = vm.get_obj_property(call_params.bound_pyobj, "fget").unwrap();
+    return allocate_property(vm, fget, call_params.params[0]);
+}
+
+pub fn register_property_type(vm: &mut VM) -> MemoryAddress {
+    let property_type = vm.create_type(BUILTIN_MODULE, "property", None);
+    vm.builtin_type_addrs.property = property_type;
+    vm.register_type_unbounded_func(property_type, "__new__", create_new);
+    vm.register_bounded_func_on_addr(property_type, "setter", setter);
+    return property_type;
+}
@@ -0,0 +1,159 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::type_error::raise_unhashable_type_error;
+use crate::builtin_types::key_error::raise_key_error;
+use crate::builtin_types::frozenset_type::{
+    allocate_set, collect_unique_elements, contains_element, register_set_operations, set_elements,
+};
+
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    if params.params.len() == 0 {
+        return allocate_set(vm, vec![]);
+    }
+    check_builtin_func_params!("set", 1, params.params.len());
+    match collect_unique_elements(vm, params.params.params[0]) {
+        Ok(elements) => allocate_set(vm, elements),
+        Err(exception) => exception,
+    }
+}
+
+//adds the element unless an equal one is already there
+pub fn set_add(vm: &VM, set: MemoryAddress, element: MemoryAddress) -> Option<MemoryAddress> {
+    if vm.call_hash(element).is_none() {
+        return Some(raise_unhashable_type_error(vm, element));
+    }
+    if !contains_element(vm, set_elements(vm, set).unwrap(), element) {
+        vm.increase_refcount(element);
+        vm.get_raw_data_of_pyobj_mut(set).take_set_mut().push(element);
+    }
+    return None;
+}
+
+fn add(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    if let Some(exception) = set_add(vm, call_params.bound_pyobj, call_params.params[0]) {
+        return exception;
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn update(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    for iterable in call_params.params.iter() {
+        let elements = match collect_unique_elements(vm, *iterable) {
+            Ok(elements) => elements,
+            Err(exception) => return exception,
+        };
+        for element in elements {
+            set_add(vm, call_params.bound_pyobj, element);
+        }
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+//removes the element equal to item, returns whether there was one
+fn remove_element(vm: &VM, set: MemoryAddress, item: MemoryAddress) -> bool {
+    let elements = set_elements(vm, set).unwrap();
+    let position = elements.iter().position(|element| contains_element(vm, &[*element], item));
+    match position {
+        Some(index) => {
+            let removed = vm.get_raw_data_of_pyobj_mut(set).take_set_mut().remove(index);
+            vm.decrease_refcount(removed);
+            return true;
+        }
+        None => return false,
+    }
+}
+
+fn remove(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let item = call_params.params[0];
+    if vm.call_hash(item).is_none() {
+        return raise_unhashable_type_error(vm, item);
+    }
+    if !remove_element(vm, call_params.bound_pyobj, item) {
+        return raise_key_error(vm, item);
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn discard(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let item = call_params.params[0];
+    if vm.call_hash(item).is_none() {
+        return raise_unhashable_type_error(vm, item);
+    }
+    remove_element(vm, call_params.bound_pyobj, item);
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn pop(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = vm.get_raw_data_of_pyobj_mut(call_params.bound_pyobj).take_set_mut();
+    if elements.is_empty() {
+        let message = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(String::from("pop from an empty set")));
+        return raise_key_error(vm, message);
+    }
+    return elements.remove(0);
+}
+
+fn clear(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let removed: Vec<MemoryAddress> = vm.get_raw_data_of_pyobj_mut(call_params.bound_pyobj).take_set_mut().drain(..).collect();
+    for element in removed {
+        vm.decrease_refcount(element);
+    }
+    return vm.special_values[&SpecialValue::NoneValue];
+}
+
+fn copy(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap().clone();
+    return allocate_set(vm, elements);
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let elements = set_elements(vm, call_params.bound_pyobj).unwrap();
+
+    //{} is an empty dict, so the empty set has to be spelled out
+    let buffer = if elements.is_empty() {
+        String::from("set()")
+    } else {
+        let mut all_reprs = vec![];
+        for element in elements.iter() {
+            let (repr_addr, _) = vm.call_method(*element, "__repr__", PositionalParameters::empty()).unwrap();
+            all_reprs.push(vm.get_raw_data_of_pyobj(repr_addr).take_string().clone());
+        }
+        format!("{{{}}}", all_reprs.join(", "))
+    };
+
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(buffer))
+}
+
+pub fn register_set_type(vm: &mut VM) -> MemoryAddress {
+    let set_type = vm.create_type(BUILTIN_MODULE, "set", None);
+    vm.builtin_type_addrs.set = set_type;
+
+    vm.register_type_unbounded_func(set_type, "__new__", create_new);
+    register_set_operations(vm, set_type);
+    vm.register_bounded_func_on_addr(set_type, "__repr__", repr);
+    vm.register_bounded_func_on_addr(set_type, "__str__", repr);
+    vm.register_bounded_func_on_addr(set_type, "add", add);
+    vm.register_bounded_func_on_addr(set_type, "update", update);
+    vm.register_bounded_func_on_addr(set_type, "remove", remove);
+    vm.register_bounded_func_on_addr(set_type, "discard", discard);
+    vm.register_bounded_func_on_addr(set_type, "pop", pop);
+    vm.register_bounded_func_on_addr(set_type, "clear", clear);
+    vm.register_bounded_func_on_addr(set_type, "copy", copy);
+    return set_type;
+}
@@ -0,0 +1,20 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+
+//the wrapped function is called without self or the class
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    check_builtin_func_params!("staticmethod", 1, params.params.len());
+    let wrapper = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.staticmethod, BuiltInTypeData::ClassInstance);
+    let function = params.params.params[0];
+    vm.increase_refcount(function);
+    vm.set_attribute(wrapper, "__func__", function);
+    return wrapper;
+}
+
+pub fn register_staticmethod_type(vm: &mut VM) -> MemoryAddress {
+    let staticmethod_type = vm.create_type(BUILTIN_MODULE, "staticmethod", None);
+    vm.builtin_type_addrs.staticmethod = staticmethod_type;
+    vm.register_type_unbounded_func(staticmethod_type, "__new__", create_new);
+    return staticmethod_type;
+}
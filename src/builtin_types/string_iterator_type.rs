@@ -0,0 +1,53 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+
+//keeps the string and the byte offset of the next character, so each step is O(1) even with multibyte characters
+
+pub fn create_string_iterator(vm: &VM, string: MemoryAddress) -> MemoryAddress {
+    let iterator = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string_iterator, BuiltInTypeData::ClassInstance);
+    vm.increase_refcount(string);
+    vm.set_attribute(iterator, "string", string);
+    set_offset(vm, iterator, 0);
+    return iterator;
+}
+
+fn set_offset(vm: &VM, iterator: MemoryAddress, offset: usize) {
+    let offset = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(offset as i128));
+    vm.increase_refcount(offset);
+    vm.set_attribute(iterator, "offset", offset);
+}
+
+fn iter(_vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    return call_params.bound_pyobj;
+}
+
+fn next(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let iterator = call_params.bound_pyobj;
+    let properties = &vm.get_pyobj_byaddr(iterator).properties;
+    let string = vm.get_raw_data_of_pyobj(properties["string"]).take_string();
+    let offset = vm.get_raw_data_of_pyobj(properties["offset"]).take_int() as usize;
+    match string[offset..].chars().next() {
+        Some(c) => {
+            set_offset(vm, iterator, offset + c.len_utf8());
+            return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(c.to_string()));
+        }
+        None => {
+            let stop_iteration = vm.special_values[&SpecialValue::StopIterationType];
+            vm.raise_exception(stop_iteration);
+            return stop_iteration;
+        }
+    }
+}
+
+pub fn register_string_iterator_type(vm: &mut VM) -> MemoryAddress {
+    let string_iterator_type = vm.create_type(BUILTIN_MODULE, "str_iterator", None);
+    vm.register_bounded_func_on_addr(string_iterator_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(string_iterator_type, "__next__", next);
+    vm.builtin_type_addrs.string_iterator = string_iterator_type;
+    return string_iterator_type;
+}
@@ -1,7 +1,14 @@
 use crate::commons::float::Float;
+use crate::commons::format_spec::{parse_format_spec, format_str};
+use crate::commons::hash::hash_bytes;
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::builtin_types::bytes_type::normalize_encoding;
+use crate::builtin_types::string_iterator_type::create_string_iterator;
+use crate::builtin_types::index_error::raise_index_error;
+use crate::builtin_types::value_error::raise_value_error;
 
 
 fn create_concat(vm: &VM, params: CallParams) -> MemoryAddress {
@@ -19,11 +26,27 @@ fn create_concat(vm: &VM, params: CallParams) -> MemoryAddress {
         let new_str = format!("{}{}", self_data, other_str);
         vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(new_str))
     } else {
-        panic!(
-            "can only concatenate str (not \"{}\") to str, {:?}",
-            other_type_name,
-            unsafe {&*call_params.params[0]}
-        );
+        raise_type_error(vm, format!("can only concatenate str (not \"{}\") to str", other_type_name))
+    }
+}
+
+fn create_repeat(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+
+    //bools are ints here, so "a" * True works like in python
+    match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::Int(times) => {
+            let new_str = self_data.repeat((*times).max(0) as usize);
+            vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(new_str))
+        }
+        _ => {
+            let other_type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            raise_type_error(vm, format!("can't multiply sequence by non-int of type '{}'", other_type_name))
+        }
     }
 }
 
@@ -60,7 +83,7 @@ fn create_neq(vm: &VM, params: CallParams) -> MemoryAddress {
             return vm.builtin_type_addrs.true_val;
         }
     } else {
-        return vm.builtin_type_addrs.false_val;
+        return vm.builtin_type_addrs.true_val;
     }
 }
 
@@ -99,30 +122,82 @@ fn create_repr(vm: &VM, params: CallParams) -> MemoryAddress {
         .clone();
     vm.allocate_builtin_type_byname_raw(
         "str",
-        BuiltInTypeData::String(format!("\'{}\'", self_data)),
+        BuiltInTypeData::String(repr_string(&self_data)),
     )
 }
 
+//same quoting rules as CPython: single quotes unless the string has single quotes but no double quotes
+fn repr_string(s: &str) -> String {
+    let quote = if s.contains('\'') && !s.contains('"') { '"' } else { '\'' };
+    let mut buffer = String::new();
+    buffer.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if c == quote => {
+                buffer.push('\\');
+                buffer.push(c);
+            }
+            c if c.is_control() => buffer.push_str(&format!("\\x{:02x}", c as u32)),
+            c => buffer.push(c),
+        }
+    }
+    buffer.push(quote);
+    buffer
+}
+
+fn len(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    vm.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(self_data.chars().count() as i128))
+}
+
+//indexes count chars (unicode scalar values), not the bytes of the utf-8 representation
+fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+
+    let index = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::Int(i) => *i,
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            return raise_type_error(vm, format!("string indices must be integers, not '{}'", type_name));
+        }
+    };
+    let char_count = self_data.chars().count() as i128;
+    let resolved = if index < 0 { index + char_count } else { index };
+
+    if resolved < 0 || resolved >= char_count {
+        return raise_index_error(vm, String::from("string index out of range"));
+    }
+    let character = self_data.chars().nth(resolved as usize).unwrap();
+    vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(character.to_string()))
+}
+
 fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
     if params.params.len() == 0 {
         return vm
             .allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(String::from("")));
     } else {
         check_builtin_func_params!("str", 1, params.params.len());
-        //try call the __str__ method on the parameter
-        let string_call = vm.call_method(params.params.params[0], "__str__", PositionalParameters::empty());
-        match string_call {
-            Some((addr, _)) => addr,
-            None => panic!("Object passed to str does not have __str__ method"),
-        }
+        vm.call_str(params.params.params[0])
     }
 }
 
 macro_rules! create_transform_function {
     ($name:tt, $param_a:tt, $func:expr) => {
         fn $name(vm: &VM, params: CallParams) -> MemoryAddress {
-            check_builtin_func_params!(params.func_name.unwrap(), 0, params.params.len());
             let call_params = params.as_method();
+            check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
             let self_data = vm
                 .get_raw_data_of_pyobj(call_params.bound_pyobj)
                 .take_string();
@@ -133,22 +208,283 @@ macro_rules! create_transform_function {
     };
 }
 
+fn encode(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    if call_params.params.len() > 1 {
+        panic!("{}() expected at most 1 argument, got {}", params.func_name.unwrap(), call_params.params.len());
+    }
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+
+    let encoding = match call_params.params.first() {
+        Some(addr) => match vm.get_raw_data_of_pyobj(*addr) {
+            BuiltInTypeData::String(s) => normalize_encoding(s),
+            _ => {
+                let type_name = vm.get_pyobj_type_name(*addr);
+                return raise_type_error(vm, format!("encode() argument 'encoding' must be str, not {}", type_name));
+            }
+        },
+        None => String::from("utf8"),
+    };
+
+    let encode_single_byte = |codec: &str, limit: u32| -> Vec<u8> {
+        self_data
+            .chars()
+            .enumerate()
+            .map(|(position, c)| {
+                let code = c as u32;
+                if code >= limit {
+                    let escaped = if code < 0x100 {
+                        format!("\\x{:02x}", code)
+                    } else if code < 0x10000 {
+                        format!("\\u{:04x}", code)
+                    } else {
+                        format!("\\U{:08x}", code)
+                    };
+                    panic!(
+                        "UnicodeEncodeError: '{}' codec can't encode character '{}' in position {}: ordinal not in range({})",
+                        codec, escaped, position, limit
+                    );
+                }
+                c as u8
+            })
+            .collect()
+    };
+
+    let encoded = match encoding.as_str() {
+        "utf8" => self_data.as_bytes().to_vec(),
+        "ascii" => encode_single_byte("ascii", 0x80),
+        "latin1" | "iso88591" => encode_single_byte("latin-1", 0x100),
+        _ => panic!("LookupError: unknown encoding: {}", encoding),
+    };
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.bytes, BuiltInTypeData::Bytes(encoded))
+}
+
+fn format(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let spec = vm.get_raw_data_of_pyobj(call_params.params[0]).take_string();
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    match parse_format_spec(spec).and_then(|spec| format_str(self_data, &spec)) {
+        Ok(formatted) => vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(formatted)),
+        Err(message) => raise_value_error(vm, message),
+    }
+}
+
+fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    return create_string_iterator(vm, call_params.bound_pyobj);
+}
+
+fn contains(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::String(substring) => {
+            if self_data.contains(substring.as_str()) {
+                vm.builtin_type_addrs.true_val
+            } else {
+                vm.builtin_type_addrs.false_val
+            }
+        }
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            raise_type_error(vm, format!("'in <string>' requires string as left operand, not {}", type_name))
+        }
+    }
+}
+
+//non-overlapping occurrences, the empty string is found between every char and at both ends
+fn count(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::String(substring) => {
+            let occurrences = self_data.matches(substring.as_str()).count();
+            vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(occurrences as i128))
+        }
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            raise_type_error(vm, format!("must be str, not {}", type_name))
+        }
+    }
+}
+
+//same line boundaries as python, \r\n counts as a single one
+fn is_line_boundary(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\x0b' | '\x0c' | '\x1c' | '\x1d' | '\x1e' | '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+fn splitlines(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    if call_params.params.len() > 1 {
+        panic!("TypeError: splitlines() takes at most 1 argument ({} given)", call_params.params.len());
+    }
+    let keepends = match call_params.params.first().copied().or_else(|| params.keyword_params.get("keepends")) {
+        Some(addr) => vm.is_truthy(addr),
+        None => false,
+    };
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+
+    let mut lines = vec![];
+    let mut current = String::new();
+    let mut chars = self_data.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !is_line_boundary(c) {
+            current.push(c);
+            continue;
+        }
+        let mut terminator = c.to_string();
+        if c == '\r' && chars.peek() == Some(&'\n') {
+            terminator.push(chars.next().unwrap());
+        }
+        if keepends {
+            current.push_str(&terminator);
+        }
+        lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    let line_addrs = lines
+        .into_iter()
+        .map(|line| vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(line)))
+        .collect();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.list, BuiltInTypeData::List(line_addrs))
+}
+
+fn width_argument(vm: &VM, addr: MemoryAddress) -> Result<usize, MemoryAddress> {
+    match vm.get_raw_data_of_pyobj(addr) {
+        BuiltInTypeData::Int(width) => Ok((*width).max(0) as usize),
+        _ => {
+            let type_name = vm.get_pyobj_type_name(addr);
+            Err(raise_type_error(vm, format!("'{}' object cannot be interpreted as an integer", type_name)))
+        }
+    }
+}
+
+//center, ljust and rjust take a width and an optional fill char, which defaults to a space
+fn justify(vm: &VM, params: CallParams, pad: fn(&str, usize, char) -> String) -> MemoryAddress {
+    let call_params = params.as_method();
+    let func_name = params.func_name.unwrap();
+    if call_params.params.is_empty() || call_params.params.len() > 2 {
+        panic!("TypeError: {}() takes 1 or 2 arguments ({} given)", func_name, call_params.params.len());
+    }
+    let width = match width_argument(vm, call_params.params[0]) {
+        Ok(width) => width,
+        Err(exception) => return exception,
+    };
+    let fill = match call_params.params.get(1) {
+        None => ' ',
+        Some(addr) => match vm.get_raw_data_of_pyobj(*addr) {
+            BuiltInTypeData::String(fill) if fill.chars().count() == 1 => fill.chars().next().unwrap(),
+            BuiltInTypeData::String(_) => {
+                return raise_type_error(vm, "The fill character must be exactly one character long".to_string());
+            }
+            _ => {
+                let type_name = vm.get_pyobj_type_name(*addr);
+                return raise_type_error(vm, format!("{}() argument 2 must be str, not {}", func_name, type_name));
+            }
+        },
+    };
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    let padding = width.saturating_sub(self_data.chars().count());
+    let padded = pad(self_data, padding, fill);
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(padded))
+}
+
+fn fill_str(fill: char, count: usize) -> String {
+    std::iter::repeat_n(fill, count).collect()
+}
+
+//like cpython, the odd padding char goes to the left only when the width is odd
+fn center(vm: &VM, params: CallParams) -> MemoryAddress {
+    justify(vm, params, |text, padding, fill| {
+        let width = padding + text.chars().count();
+        let left = padding / 2 + (padding & width & 1);
+        format!("{}{}{}", fill_str(fill, left), text, fill_str(fill, padding - left))
+    })
+}
+
+fn ljust(vm: &VM, params: CallParams) -> MemoryAddress {
+    justify(vm, params, |text, padding, fill| format!("{}{}", text, fill_str(fill, padding)))
+}
+
+fn rjust(vm: &VM, params: CallParams) -> MemoryAddress {
+    justify(vm, params, |text, padding, fill| format!("{}{}", fill_str(fill, padding), text))
+}
+
+//the zeros go after a leading sign
+fn zfill(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let width = match width_argument(vm, call_params.params[0]) {
+        Ok(width) => width,
+        Err(exception) => return exception,
+    };
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_string();
+    let padding = fill_str('0', width.saturating_sub(self_data.chars().count()));
+    let padded = match self_data.chars().next() {
+        Some(sign @ ('+' | '-')) => format!("{}{}{}", sign, padding, &self_data[1..]),
+        _ => format!("{}{}", padding, self_data),
+    };
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(padded))
+}
+
 create_transform_function!(str_lower, a, a.to_lowercase());
 create_transform_function!(str_upper, a, a.to_uppercase());
 
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let self_data = vm.get_raw_data_of_pyobj(call_params.bound_pyobj).take_string();
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_bytes(self_data.as_bytes())))
+}
+
 pub fn register_string_type(vm: &mut VM) -> MemoryAddress {
     let string_type = vm.create_type(BUILTIN_MODULE, "str", None);
 
     vm.register_type_unbounded_func(string_type, "__new__", create_new);
     vm.register_bounded_func_on_addr(string_type, "__add__", create_concat);
+    vm.register_bounded_func_on_addr(string_type, "__mul__", create_repeat);
     vm.register_bounded_func_on_addr(string_type, "__eq__", create_eq);
-    vm.register_bounded_func_on_addr(string_type, "__neq__", create_neq);
+    vm.register_bounded_func_on_addr(string_type, "__ne__", create_neq);
+    vm.register_bounded_func_on_addr(string_type, "__hash__", hash);
     vm.register_bounded_func_on_addr(string_type, "__int__", create_to_int);
     vm.register_bounded_func_on_addr(string_type, "__float__", create_to_float);
     vm.register_bounded_func_on_addr(string_type, "__repr__", create_repr);
     vm.register_bounded_func_on_addr(string_type, "__str__", create_to_str);
+    vm.register_bounded_func_on_addr(string_type, "__len__", len);
+    vm.register_bounded_func_on_addr(string_type, "__getitem__", getitem);
+    vm.register_bounded_func_on_addr(string_type, "__contains__", contains);
+    vm.register_bounded_func_on_addr(string_type, "__iter__", iter);
+    vm.register_bounded_func_on_addr(string_type, "count", count);
+    vm.register_bounded_func_on_addr(string_type, "splitlines", splitlines);
+    vm.register_bounded_func_on_addr(string_type, "zfill", zfill);
+    vm.register_bounded_func_on_addr(string_type, "center", center);
+    vm.register_bounded_func_on_addr(string_type, "ljust", ljust);
+    vm.register_bounded_func_on_addr(string_type, "rjust", rjust);
     vm.register_bounded_func_on_addr(string_type, "lower", str_lower);
     vm.register_bounded_func_on_addr(string_type, "upper", str_upper);
+    vm.register_bounded_func_on_addr(string_type, "encode", encode);
+    vm.register_bounded_func_on_addr(string_type, "__format__", format);
     vm.builtin_type_addrs.string = string_type;
 
     return string_type;
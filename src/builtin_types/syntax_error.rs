@@ -0,0 +1,13 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_type, raise_with_message};
+
+pub fn raise_syntax_error(vm: &VM, message: String) -> MemoryAddress {
+    return raise_with_message(vm, vm.builtin_type_addrs.syntax_err, message);
+}
+
+pub fn register_syntaxerr_type(vm: &mut VM) -> MemoryAddress {
+    let syntax_err = create_exception_type(vm, "SyntaxError");
+    vm.builtin_type_addrs.syntax_err = syntax_err;
+    return syntax_err;
+}
@@ -0,0 +1,298 @@
+use crate::runtime::vm::*;
+use crate::runtime::datamodel::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::frozenset_type::to_bool;
+use crate::builtin_types::type_error::{raise_type_error, raise_unhashable_type_error};
+use crate::commons::hash::hash_tuple;
+use crate::builtin_types::index_error::raise_index_error;
+
+
+fn concat(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let self_data = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple()
+        .clone();
+    let other_data = vm.get_raw_data_of_pyobj(call_params.params[0]);
+
+    match other_data {
+        BuiltInTypeData::Tuple(values) => {
+            let mut result = vec![];
+            result.extend(self_data);
+            result.extend(values.iter().cloned());
+            return vm.allocate_type_byaddr_raw(
+                vm.builtin_type_addrs.tuple,
+                BuiltInTypeData::Tuple(result),
+            );
+        }
+        _ => {
+            let other_type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            panic!(
+                "can only concatenate tuple (not \"{}\") to tuple",
+                other_type_name
+            );
+        }
+    }
+}
+
+fn equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+
+    match vm.get_pyobj_byaddr(call_params.params[0]).try_get_builtin() {
+        Some(BuiltInTypeData::Tuple(other_tuple)) => {
+            if this_tuple.len() != other_tuple.len() {
+                return vm.builtin_type_addrs.false_val;
+            }
+            if !vm.eq_enter(call_params.bound_pyobj, call_params.params[0]) {
+                return vm.builtin_type_addrs.true_val;
+            }
+            let all_equal = this_tuple.iter().zip(other_tuple.iter()).all(|(ptr_self, ptr_other)| {
+                if ptr_self == ptr_other {
+                    return true;
+                }
+                let result = vm.call_method(*ptr_self, "__eq__", PositionalParameters::single(*ptr_other));
+                return matches!(result, Some((eq_result, _)) if eq_result == vm.builtin_type_addrs.true_val);
+            });
+            vm.eq_leave(call_params.bound_pyobj, call_params.params[0]);
+            return to_bool(vm, all_equal);
+        }
+        _ => {
+            return vm.builtin_type_addrs.false_val;
+        }
+    }
+}
+
+fn not_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let (eq_result, _) = vm
+        .call_method(call_params.bound_pyobj, "__eq__", PositionalParameters::single(call_params.params[0]))
+        .unwrap();
+    if eq_result == vm.builtin_type_addrs.true_val {
+        return vm.builtin_type_addrs.false_val;
+    } else {
+        return vm.builtin_type_addrs.true_val;
+    }
+}
+
+//lexicographic: the first pair of elements that are not equal decides, otherwise the shorter tuple is smaller
+fn compare(vm: &VM, params: CallParams, method: &str, operator: &str, compare_lengths: fn(usize, usize) -> bool) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+    let other_tuple = match vm.get_pyobj_byaddr(call_params.params[0]).try_get_builtin() {
+        Some(BuiltInTypeData::Tuple(other_tuple)) => other_tuple,
+        _ => {
+            let other_type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            return raise_type_error(vm, format!("'{}' not supported between instances of 'tuple' and '{}'", operator, other_type_name));
+        }
+    };
+
+    for (ptr_self, ptr_other) in this_tuple.iter().zip(other_tuple.iter()) {
+        if ptr_self == ptr_other {
+            continue;
+        }
+        match vm.call_method(*ptr_self, "__eq__", PositionalParameters::single(*ptr_other)) {
+            Some((eq_result, _)) if eq_result == vm.builtin_type_addrs.true_val => continue,
+            _ => {}
+        }
+        return match vm.call_method(*ptr_self, method, PositionalParameters::single(*ptr_other)) {
+            Some((_, frame)) if frame.exception.is_some() => {
+                let exception = frame.exception.unwrap();
+                vm.raise_exception(exception);
+                exception
+            }
+            Some((result, _)) => result,
+            None => {
+                let (self_type_name, other_type_name) = (vm.get_pyobj_type_name(*ptr_self), vm.get_pyobj_type_name(*ptr_other));
+                raise_type_error(vm, format!("'{}' not supported between instances of '{}' and '{}'", operator, self_type_name, other_type_name))
+            }
+        };
+    }
+
+    if compare_lengths(this_tuple.len(), other_tuple.len()) {
+        return vm.builtin_type_addrs.true_val;
+    } else {
+        return vm.builtin_type_addrs.false_val;
+    }
+}
+
+fn less_than(vm: &VM, params: CallParams) -> MemoryAddress {
+    compare(vm, params, "__lt__", "<", |a, b| a < b)
+}
+
+fn less_than_or_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    compare(vm, params, "__le__", "<=", |a, b| a <= b)
+}
+
+fn greater_than(vm: &VM, params: CallParams) -> MemoryAddress {
+    compare(vm, params, "__gt__", ">", |a, b| a > b)
+}
+
+fn greater_than_or_equals(vm: &VM, params: CallParams) -> MemoryAddress {
+    compare(vm, params, "__ge__", ">=", |a, b| a >= b)
+}
+
+fn repr(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    if !vm.repr_enter(call_params.bound_pyobj) {
+        return vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String("(...)".into()));
+    }
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+
+    let all_reprs: Vec<String> = this_tuple
+        .iter()
+        .map(|ptr_self| {
+            let (as_string, _) = vm.call_method(*ptr_self, "__repr__", PositionalParameters::empty()).unwrap();
+            return vm
+                .get_raw_data_of_pyobj(as_string)
+                .take_string()
+                .clone();
+        })
+        .collect();
+    vm.repr_leave(call_params.bound_pyobj);
+
+    //a single element tuple needs the trailing comma to not be confused with a parenthesized expression
+    let buffer = if all_reprs.len() == 1 {
+        format!("({},)", all_reprs[0])
+    } else {
+        format!("({})", all_reprs.join(", "))
+    };
+
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.string,
+        BuiltInTypeData::String(buffer),
+    )
+}
+
+fn len(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+    vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.int,
+        BuiltInTypeData::Int(this_tuple.len() as i128),
+    )
+}
+
+fn iter(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    //list_iterator only needs len() and indexing, so it works for tuples too
+    let iterator_class = vm.find_in_module(BUILTIN_MODULE, "list_iterator").expect("list_iterator type not found");
+    let new = vm.try_load_function_addr(iterator_class);
+    let (result, _) = vm.run_function(PositionalParameters::single(call_params.bound_pyobj), new, None);
+    return result;
+}
+
+fn contains(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+    let item = call_params.params[0];
+    for element in this_tuple.iter() {
+        if *element == item {
+            return vm.builtin_type_addrs.true_val;
+        }
+        if let Some((eq_result, _)) = vm.call_method(*element, "__eq__", PositionalParameters::single(item)) {
+            if eq_result == vm.builtin_type_addrs.true_val {
+                return vm.builtin_type_addrs.true_val;
+            }
+        }
+    }
+    return vm.builtin_type_addrs.false_val;
+}
+
+fn getitem(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 1, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+
+    let index = match vm.get_raw_data_of_pyobj(call_params.params[0]) {
+        BuiltInTypeData::Int(i) => *i,
+        _ => {
+            let type_name = vm.get_pyobj_type_name(call_params.params[0]);
+            return raise_type_error(vm, format!("tuple indices must be integers or slices, not {}", type_name));
+        }
+    };
+    let resolved = if index < 0 { index + this_tuple.len() as i128 } else { index };
+
+    if resolved < 0 || resolved >= this_tuple.len() as i128 {
+        return raise_index_error(vm, String::from("tuple index out of range"));
+    } else {
+        return this_tuple[resolved as usize];
+    }
+}
+
+fn create_new(vm: &VM, params: CallParams) -> MemoryAddress {
+    if params.params.len() == 0 {
+        return vm.allocate_type_byaddr_raw(
+            vm.builtin_type_addrs.tuple,
+            BuiltInTypeData::Tuple(vec![]),
+        );
+    }
+    check_builtin_func_params!("tuple", 1, params.params.len());
+    //reuse list() to consume the iterable
+    let list_new = vm.try_load_function_addr(vm.builtin_type_addrs.list);
+    let (as_list, _) = vm.run_function(PositionalParameters::single(params.params.params[0]), list_new, None);
+    let elements = vm.get_raw_data_of_pyobj(as_list).take_list().clone();
+    return vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.tuple,
+        BuiltInTypeData::Tuple(elements),
+    );
+}
+
+fn hash(vm: &VM, params: CallParams) -> MemoryAddress {
+    let call_params = params.as_method();
+    check_builtin_func_params!(params.func_name.unwrap(), 0, call_params.params.len());
+    let this_tuple = vm
+        .get_raw_data_of_pyobj(call_params.bound_pyobj)
+        .take_tuple();
+    let mut element_hashes = vec![];
+    for element in this_tuple.iter() {
+        match vm.call_hash(*element) {
+            Some(element_hash) => element_hashes.push(element_hash),
+            None => return raise_unhashable_type_error(vm, *element),
+        }
+    }
+    vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.int, BuiltInTypeData::Int(hash_tuple(&element_hashes)))
+}
+
+pub fn register_tuple_type(vm: &mut VM) -> MemoryAddress {
+    let tuple_type = vm.create_type(BUILTIN_MODULE, "tuple", None);
+    vm.builtin_type_addrs.tuple = tuple_type;
+
+    vm.register_type_unbounded_func(tuple_type, "__new__", create_new);
+
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__add__", concat);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__eq__", equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__ne__", not_equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__lt__", less_than);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__le__", less_than_or_equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__gt__", greater_than);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__ge__", greater_than_or_equals);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__hash__", hash);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__repr__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__str__", repr);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__len__", len);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__getitem__", getitem);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__iter__", iter);
+    vm.register_bounded_func(BUILTIN_MODULE, "tuple", "__contains__", contains);
+    return tuple_type;
+}
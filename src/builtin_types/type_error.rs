@@ -0,0 +1,22 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_type, raise_with_message};
+
+pub fn raise_type_error(vm: &VM, message: String) -> MemoryAddress {
+    return raise_with_message(vm, vm.builtin_type_addrs.type_err, message);
+}
+
+//keeps the exception a failing __hash__ already raised, so the innermost unhashable type is reported
+pub fn raise_unhashable_type_error(vm: &VM, addr: MemoryAddress) -> MemoryAddress {
+    if let Some(exception) = vm.get_current_exception() {
+        return exception;
+    }
+    let type_name = vm.get_pyobj_type_name(addr).to_string();
+    return raise_type_error(vm, format!("unhashable type: '{}'", type_name));
+}
+
+pub fn register_typeerr_type(vm: &mut VM) -> MemoryAddress {
+    let type_err = create_exception_type(vm, "TypeError");
+    vm.builtin_type_addrs.type_err = type_err;
+    return type_err;
+}
@@ -0,0 +1,13 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::builtin_types::exception_type::{create_exception_type, raise_with_message};
+
+pub fn raise_value_error(vm: &VM, message: String) -> MemoryAddress {
+    return raise_with_message(vm, vm.builtin_type_addrs.value_err, message);
+}
+
+pub fn register_valueerr_type(vm: &mut VM) -> MemoryAddress {
+    let value_err = create_exception_type(vm, "ValueError");
+    vm.builtin_type_addrs.value_err = value_err;
+    return value_err;
+}
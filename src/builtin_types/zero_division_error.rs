@@ -0,0 +1,56 @@
+use crate::runtime::vm::*;
+use crate::runtime::memory::*;
+use crate::runtime::datamodel::*;
+use crate::builtin_types::exception_type::{create_exception_type, raise_with_message};
+
+pub fn raise_zero_division_error(vm: &VM, message: String) -> MemoryAddress {
+    return raise_with_message(vm, vm.builtin_type_addrs.zero_division_err, message);
+}
+
+pub fn register_zerodivisionerr_type(vm: &mut VM) -> MemoryAddress {
+    let zero_division_err = create_exception_type(vm, "ZeroDivisionError");
+    vm.builtin_type_addrs.zero_division_err = zero_division_err;
+    return zero_division_err;
+}
+
+//x % 0 between numbers raises before python_mod gets to it, Rust panics on an integer remainder by zero
+pub fn raise_if_modulo_by_zero(vm: &VM, lhs: MemoryAddress, rhs: MemoryAddress) -> Option<MemoryAddress> {
+    return raise_if_divisor_is_zero(vm, lhs, rhs, "integer modulo by zero", "float modulo");
+}
+
+pub fn raise_if_floor_division_by_zero(vm: &VM, lhs: MemoryAddress, rhs: MemoryAddress) -> Option<MemoryAddress> {
+    return raise_if_divisor_is_zero(vm, lhs, rhs, "integer division or modulo by zero", "float floor division by zero");
+}
+
+//without this, x / 0 would be inf or nan
+pub fn raise_if_division_by_zero(vm: &VM, lhs: MemoryAddress, rhs: MemoryAddress) -> Option<MemoryAddress> {
+    return raise_if_divisor_is_zero(vm, lhs, rhs, "division by zero", "float division by zero");
+}
+
+fn raise_if_divisor_is_zero(
+    vm: &VM,
+    lhs: MemoryAddress,
+    rhs: MemoryAddress,
+    int_message: &str,
+    float_message: &str,
+) -> Option<MemoryAddress> {
+    let lhs_data = vm.get_pyobj_byaddr(lhs).try_get_builtin();
+    let rhs_data = vm.get_pyobj_byaddr(rhs).try_get_builtin();
+    let divisor_is_zero = match rhs_data {
+        Some(BuiltInTypeData::Int(i)) => *i == 0,
+        Some(BuiltInTypeData::Float(f)) => f.0 == 0.0,
+        _ => false,
+    };
+    if !divisor_is_zero {
+        return None;
+    }
+    match (lhs_data, rhs_data) {
+        (Some(BuiltInTypeData::Int(_)), Some(BuiltInTypeData::Int(_))) => {
+            Some(raise_zero_division_error(vm, String::from(int_message)))
+        }
+        (Some(BuiltInTypeData::Int(_) | BuiltInTypeData::Float(_)), _) => {
+            Some(raise_zero_division_error(vm, String::from(float_message)))
+        }
+        _ => None,
+    }
+}
@@ -2,7 +2,48 @@ use crate::bytecode::program::*;
 use crate::ast::lexer::*;
 use crate::ast::parser::*;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+//like python -O: strip_asserts compiles assert statements to nothing
+#[derive(Debug, Clone, Default)]
+pub struct CompilerOptions {
+    pub strip_asserts: bool,
+}
+
+//errors the parser can't see because they depend on where a statement is, like return outside a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub message: String,
+}
+
+impl std::fmt::Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SyntaxError: {}", self.message)
+    }
+}
+
+//a try statement whose handler is active around the statements being compiled
+#[derive(Clone)]
+pub enum EnclosingBlock {
+    //the body of a try with except clauses
+    Except,
+    //anything protected by a finally, which has to run when jumping out of it
+    Finally(Vec<AST>),
+    //the body of an except clause, while its exception is being handled
+    Handler,
+}
+
+//what surrounds the statements being compiled
+#[derive(Clone)]
+pub struct CompileContext<'a> {
+    pub options: &'a CompilerOptions,
+    pub in_function: bool,
+    pub in_loop: bool,
+    //innermost last, only the ones inside the current function or class body
+    pub blocks: Vec<EnclosingBlock>,
+    //how many of the blocks were already there when the innermost loop started
+    pub loop_blocks: usize,
+}
 
 fn process_constval(constval: Const, const_map: &mut BTreeMap<Const, usize>) -> Vec<Instruction> {
    let loadconst_idx = if !const_map.contains_key(&constval) {
@@ -16,6 +57,38 @@ fn process_constval(constval: Const, const_map: &mut BTreeMap<Const, usize>) ->
     return vec![Instruction::LoadConst(loadconst_idx)];
 }
 
+//augmented assignments prefer the in-place version of the operator when there is one
+fn inplace_operator_instruction(op: &Operator) -> Instruction {
+    match op {
+        Operator::Plus => Instruction::InplaceAdd,
+        _ => binary_operator_instruction(op),
+    }
+}
+
+fn binary_operator_instruction(op: &Operator) -> Instruction {
+    match op {
+        Operator::Plus => Instruction::BinaryAdd,
+        Operator::Mod => Instruction::BinaryModulus,
+        Operator::Minus => Instruction::BinarySubtract,
+        Operator::Multiply => Instruction::BinaryMultiply,
+        Operator::Divide => Instruction::BinaryTrueDivision,
+        Operator::FloorDivide => Instruction::BinaryFloorDivision,
+        Operator::Power => Instruction::BinaryPower,
+        Operator::Less => Instruction::CompareLessThan,
+        Operator::Greater => Instruction::CompareGreaterThan,
+        Operator::Equals => Instruction::CompareEquals,
+        Operator::GreaterEquals => Instruction::CompareGreaterEquals,
+        Operator::LessEquals => Instruction::CompareLessEquals,
+        Operator::NotEquals => Instruction::CompareNotEquals,
+        Operator::In => Instruction::CompareIn,
+        Operator::NotIn => Instruction::CompareNotIn,
+        Operator::And | Operator::Or | Operator::Xor | Operator::BitShiftLeft | Operator::BitShiftRight => {
+            unreachable!("{} is compiled without a binary instruction", op)
+        }
+        Operator::Not | Operator::Invert => unreachable!("the parser rejects {} as a binary operator", op),
+    }
+}
+
 fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Instruction> {
     match expr {
         //TODO change Expr to Const(Const::Integer) so that it 
@@ -36,6 +109,32 @@ fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Inst
             let constval = Const::String(s.clone());
             return process_constval(constval, const_map);
         },
+        Expr::BytesValue(b) => {
+            let constval = Const::Bytes(b.clone());
+            return process_constval(constval, const_map);
+        },
+        Expr::FormattedString(segments) => {
+            let mut final_instructions = vec![];
+            for segment in segments {
+                match segment {
+                    FStringSegment::Literal(s) => {
+                        final_instructions.extend(process_constval(Const::String(s.clone()), const_map));
+                    }
+                    FStringSegment::Value { expr, conversion, format_spec } => {
+                        final_instructions.append(&mut compile_expr(expr, const_map));
+                        if let Some(spec) = format_spec {
+                            final_instructions.extend(process_constval(Const::String(spec.clone()), const_map));
+                        }
+                        final_instructions.push(Instruction::FormatValue {
+                            conversion: *conversion,
+                            has_spec: format_spec.is_some(),
+                        });
+                    }
+                }
+            }
+            final_instructions.push(Instruction::BuildString { number_parts: segments.len() });
+            return final_instructions;
+        },
         Expr::None => {
             let constval = Const::None;
             return process_constval(constval, const_map);         
@@ -49,13 +148,29 @@ fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Inst
         }
         Expr::BinaryOperation(lhs, op, rhs) => {
             match op {
-                Operator::And | Operator::Or |  Operator::Xor => {
-                    let mut load_attr: Vec<Instruction> = match op {
-                        Operator::And => vec![Instruction::LoadAttr(String::from("__and__"))],
-                        Operator::Or => vec![Instruction::LoadAttr(String::from("__or__"))],
-                        Operator::Xor => vec![Instruction::LoadAttr(String::from("__xor__"))],
-                        _ => panic!("operator not implemented: {:?}", op),
+                Operator::And | Operator::Or => {
+                    let mut lhs_program: Vec<Instruction> = compile_expr(lhs, const_map);
+                    let mut rhs_program: Vec<Instruction> = compile_expr(rhs, const_map);
+
+                    let jump = match op {
+                        Operator::And => Instruction::JumpIfFalseOrPop(rhs_program.len()),
+                        _ => Instruction::JumpIfTrueOrPop(rhs_program.len()),
+                    };
+
+                    let mut final_instructions = vec![];
+                    final_instructions.append(&mut lhs_program);
+                    final_instructions.push(jump);
+                    final_instructions.append(&mut rhs_program);
+
+                    return final_instructions;
+                },
+                Operator::Xor | Operator::BitShiftLeft | Operator::BitShiftRight => {
+                    let method_name = match op {
+                        Operator::Xor => "__xor__",
+                        Operator::BitShiftLeft => "__lshift__",
+                        _ => "__rshift__",
                     };
+                    let mut load_attr: Vec<Instruction> = vec![Instruction::LoadAttr(String::from(method_name))];
 
                     let mut lhs_program: Vec<Instruction> = compile_expr(lhs, const_map);
                     let mut rhs_program: Vec<Instruction> = compile_expr(rhs, const_map);
@@ -79,45 +194,56 @@ fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Inst
 
                     final_instructions.append(&mut lhs_program);
                     final_instructions.append(&mut rhs_program);
-                    let opcode = match op {
-                        Operator::Plus => Instruction::BinaryAdd,
-                        Operator::Mod => Instruction::BinaryModulus,
-                        Operator::Minus => Instruction::BinarySubtract,
-                        Operator::Multiply => Instruction::BinaryMultiply,
-                        Operator::Divide => Instruction::BinaryTrueDivision,
-                        Operator::Less => Instruction::CompareLessThan,
-                        Operator::Greater => Instruction::CompareGreaterThan,
-                        Operator::Equals => Instruction::CompareEquals,
-                        Operator::GreaterEquals => Instruction::CompareGreaterEquals,
-                        Operator::LessEquals => Instruction::CompareLessEquals,
-                        Operator::NotEquals => Instruction::CompareNotEquals,
-                        _ => {
-                            panic!("Operator not implemented: {:?}", op)
-                        }
-                    };
-                    final_instructions.push(opcode);
+                    final_instructions.push(binary_operator_instruction(op));
 
                     return final_instructions;
                 }
             }
         }
+        Expr::ChainedComparison(first, comparisons) => {
+            //each middle operand is duplicated below its comparison so the next one can use it.
+            //a false comparison jumps to the cleanup, which drops the operand left below the result
+            let mut final_instructions = compile_expr(first, const_map);
+            let mut compiled: Vec<Vec<Instruction>> = comparisons.iter().map(|(op, operand)| {
+                let mut instructions = compile_expr(operand, const_map);
+                instructions.push(binary_operator_instruction(op));
+                instructions
+            }).collect();
+            let last = compiled.pop().unwrap();
+            //the last comparison and the JumpForward over the cleanup
+            let mut remaining = last.len() + 1;
+            for instructions in compiled.iter() {
+                remaining += instructions.len() + 3;
+            }
+            for mut instructions in compiled {
+                remaining -= instructions.len() + 3;
+                let compare = instructions.pop().unwrap();
+                final_instructions.append(&mut instructions);
+                final_instructions.push(Instruction::DupTop);
+                final_instructions.push(Instruction::RotThree);
+                final_instructions.push(compare);
+                final_instructions.push(Instruction::JumpIfFalseOrPop(remaining));
+            }
+            final_instructions.extend(last);
+            final_instructions.push(Instruction::JumpForward(2));
+            final_instructions.push(Instruction::RotTwo);
+            final_instructions.push(Instruction::PopTop);
+            return final_instructions;
+        }
         Expr::UnaryExpression(op, rhs) => {
-            let mut load_attr: Vec<Instruction> = match op {
-                Operator::Plus => vec![Instruction::LoadAttr(String::from("__pos__"))],
-                Operator::Not => vec![Instruction::LoadAttr(String::from("__not__"))],
-                Operator::Minus => vec![Instruction::LoadAttr(String::from("__neg__"))],
-                _ => panic!("operator not implemented: {:?}", op),
+            let instruction = match op {
+                Operator::Plus => Instruction::UnaryPositive,
+                Operator::Not => Instruction::UnaryNot,
+                Operator::Minus => Instruction::UnaryNegative,
+                Operator::Invert => Instruction::UnaryInvert,
+                _ => unreachable!("the parser rejects {} as a unary operator", op),
             };
 
             let mut rhs_program: Vec<Instruction> = compile_expr(rhs, const_map);
-            let call = Instruction::CallFunction {
-                number_arguments: 0
-            };
 
             let mut final_instructions = vec![];
             final_instructions.append(&mut rhs_program);
-            final_instructions.append(&mut load_attr);
-            final_instructions.push(call);
+            final_instructions.push(instruction);
 
             return final_instructions;
         }
@@ -128,14 +254,27 @@ fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Inst
             let method_to_call_instrs: Vec<Instruction> = compile_expr(fcall_expr, const_map);
             final_instructions.extend(method_to_call_instrs);
             let len_params = params.len();
+            let mut keyword_names = vec![];
         
             for param_expr in params {
-                final_instructions.append(&mut compile_expr(param_expr, const_map));
+                if let Expr::KeywordArgument(name, value) = param_expr {
+                    keyword_names.push(name.clone());
+                    final_instructions.append(&mut compile_expr(value, const_map));
+                } else {
+                    final_instructions.append(&mut compile_expr(param_expr, const_map));
+                }
             }
 
-            final_instructions.push(Instruction::CallFunction {
-                number_arguments: len_params,
-            });
+            if keyword_names.is_empty() {
+                final_instructions.push(Instruction::CallFunction {
+                    number_arguments: len_params,
+                });
+            } else {
+                final_instructions.push(Instruction::CallFunctionKw {
+                    number_arguments: len_params,
+                    keyword_names,
+                });
+            }
             return final_instructions;
         },
         Expr::IndexAccess(expr, index) =>  {
@@ -157,8 +296,114 @@ fn compile_expr(expr: &Expr, const_map: &mut BTreeMap<Const, usize>) -> Vec<Inst
             final_instructions.push(Instruction::BuildList { number_elements });
             return final_instructions;
         },
+        Expr::Tuple(exprs) => {
+            let mut final_instructions = vec![];
+            let number_elements = exprs.len();
+            for expr in exprs {
+                final_instructions.append(&mut compile_expr(expr, const_map));
+            }
+
+            final_instructions.push(Instruction::BuildTuple { number_elements });
+            return final_instructions;
+        },
+        Expr::Dict(entries) => {
+            let mut final_instructions = vec![];
+            let number_entries = entries.len();
+            for (key, value) in entries {
+                final_instructions.append(&mut compile_expr(key, const_map));
+                final_instructions.append(&mut compile_expr(value, const_map));
+            }
+
+            final_instructions.push(Instruction::BuildDict { number_entries });
+            return final_instructions;
+        },
+        Expr::Set(exprs) => {
+            let mut final_instructions = vec![];
+            let number_elements = exprs.len();
+            for expr in exprs {
+                final_instructions.append(&mut compile_expr(expr, const_map));
+            }
+
+            final_instructions.push(Instruction::BuildSet { number_elements });
+            return final_instructions;
+        },
         Expr::Variable(var_name) => vec![Instruction::UnresolvedLoadName(var_name.clone())],
+        Expr::NamedExpr(var_name, value) => {
+            let mut final_instructions = compile_expr(value, const_map);
+            final_instructions.push(Instruction::DupTop);
+            final_instructions.push(Instruction::UnresolvedStoreName(var_name.clone()));
+            return final_instructions;
+        }
+        Expr::IfExpression(value, condition, otherwise) => {
+            let mut final_instructions = compile_expr(condition, const_map);
+            let mut value_program = compile_expr(value, const_map);
+            let mut otherwise_program = compile_expr(otherwise, const_map);
+            //+1 to also skip the jump over the else branch
+            final_instructions.push(Instruction::PopJumpForwardIfFalse(value_program.len() + 1));
+            final_instructions.append(&mut value_program);
+            final_instructions.push(Instruction::JumpForward(otherwise_program.len()));
+            final_instructions.append(&mut otherwise_program);
+            return final_instructions;
+        }
+        Expr::ListComprehension(element, item_name, iterable, conditions) => {
+            //comprehensions don't get their own scope, so the loop variable is renamed to something user code
+            //can't write: .i, or ..i when a comprehension inside this one already uses .i. It is bound before
+            //the loop and deleted after it, so the enclosing scope never sees it.
+            //the list stays below the iterator, and each element is appended to it
+            let compiled_conditions: Vec<Vec<Instruction>> = conditions.iter().map(|condition| compile_expr(condition, const_map)).collect();
+            let element_program = compile_expr(element, const_map);
+            let level = element_program.iter().chain(compiled_conditions.iter().flatten())
+                .filter_map(|instruction| match instruction {
+                    Instruction::UnresolvedStoreName(name) => Some(name.chars().take_while(|c| *c == '.').count()),
+                    _ => None,
+                })
+                .max().unwrap_or(0) + 1;
+            let hidden_name = format!("{}{}", ".".repeat(level), item_name);
+            let rename = |instructions: Vec<Instruction>| -> Vec<Instruction> {
+                instructions.into_iter().map(|instruction| match instruction {
+                    Instruction::UnresolvedLoadName(name) if &name == item_name => Instruction::UnresolvedLoadName(hidden_name.clone()),
+                    other => other,
+                }).collect()
+            };
+            let compiled_conditions: Vec<Vec<Instruction>> = compiled_conditions.into_iter().map(rename).collect();
+            let element_program = rename(element_program);
+
+            let mut final_instructions = process_constval(Const::None, const_map);
+            final_instructions.push(Instruction::UnresolvedStoreName(hidden_name.clone()));
+            final_instructions.push(Instruction::BuildList { number_elements: 0 });
+            final_instructions.append(&mut compile_expr(iterable, const_map));
+            final_instructions.push(Instruction::LoadAttr("__iter__".into()));
+            final_instructions.push(Instruction::CallFunction { number_arguments: 0 });
+
+            //a false condition jumps to the JumpBackward, skipping the next conditions and the append
+            let mut remaining = compiled_conditions.iter().map(|condition| condition.len() + 1).sum::<usize>() + element_program.len() + 1;
+            let mut body = vec![Instruction::UnresolvedStoreName(hidden_name.clone())];
+            for condition in compiled_conditions {
+                remaining -= condition.len() + 1;
+                body.extend(condition);
+                body.push(Instruction::PopJumpForwardIfFalse(remaining));
+            }
+            body.extend(element_program);
+            body.push(Instruction::ListAppend(2));
+
+            final_instructions.push(Instruction::ForIterForward(body.len() + 1));
+            let loop_length = body.len() + 1;
+            final_instructions.extend(body);
+            final_instructions.push(Instruction::JumpBackward(loop_length));
+            final_instructions.push(Instruction::PopTop);
+            final_instructions.push(Instruction::UnresolvedDeleteName(hidden_name));
+            return final_instructions;
+        }
+        Expr::Yield(value) => {
+            let mut final_instructions = match value {
+                Some(value) => compile_expr(value, const_map),
+                None => process_constval(Const::None, const_map),
+            };
+            final_instructions.push(Instruction::YieldValue);
+            return final_instructions;
+        }
         Expr::Parenthesized(_) => panic!("Parenthesized expr should not leak to compiler"),
+        Expr::KeywordArgument(name, _) => panic!("Keyword argument {} used outside of a function call", name),
         
     }
 }
@@ -176,12 +421,16 @@ pub fn resolve_loads_stores(code: &mut CodeObject) {
     }
 
 
+    //the names a function assigns or deletes are its locals, every other name it loads is a global
+    let mut local_names: BTreeSet<String> = code.params.iter().cloned().collect();
+
     //Find all variable stores and set slots for each one of them
     for instruction in code.instructions.iter() {
-        if let Instruction::UnresolvedStoreName(name) = instruction {
+        if let Instruction::UnresolvedStoreName(name) | Instruction::UnresolvedDeleteName(name) = instruction {
             if !names_indices.contains_key(name) {
                 names_indices.insert(name.clone(), names_indices.len());
             }
+            local_names.insert(name.clone());
         }
         if let Instruction::UnresolvedStoreAttr(attr) = instruction {
             if !names_indices.contains_key(attr) {
@@ -194,23 +443,32 @@ pub fn resolve_loads_stores(code: &mut CodeObject) {
     //integer slots 
     let new_instructions: Vec<Instruction> = code.instructions.iter().map(|instruction| {
         return if let Instruction::UnresolvedLoadName(name) = instruction {
-            match names_indices.get(name) {
-                Some(idx) => Instruction::LoadName(*idx),
-                None => {
-                    let cur_size = names_indices.len();
-                    names_indices.insert(name.clone(), cur_size);
-
-                    if code.params.contains(&name) {
-                        Instruction::LoadName(cur_size)
-                    } else {
-                        Instruction::LoadGlobal(cur_size)
-                    }
-                }
+            if !names_indices.contains_key(name) {
+                names_indices.insert(name.clone(), names_indices.len());
+            }
+            let idx = names_indices[name];
+            if !code.main && local_names.contains(name) {
+                Instruction::LoadName(idx)
+            } else {
+                Instruction::LoadGlobal(idx)
             }
         }
         else if let Instruction::UnresolvedStoreName(name) = instruction {
             let idx = names_indices.get(name).unwrap();
-            Instruction::StoreName(*idx)
+            //at module scope, names are stored in the module namespace instead of local slots
+            if code.main {
+                Instruction::StoreGlobal(*idx)
+            } else {
+                Instruction::StoreName(*idx)
+            }
+        }
+        else if let Instruction::UnresolvedDeleteName(name) = instruction {
+            let idx = names_indices.get(name).unwrap();
+            if code.main {
+                Instruction::DeleteGlobal(*idx)
+            } else {
+                Instruction::DeleteName(*idx)
+            }
         }
         else if let Instruction::UnresolvedStoreAttr(name) = instruction {
             let idx = names_indices.get(name).unwrap();
@@ -231,10 +489,9 @@ pub fn resolve_loads_stores(code: &mut CodeObject) {
     code.names = indices_names;
 }
 
-pub fn compile_repl(ast: Vec<AST>) -> Program {
+pub fn compile_repl(ast: Vec<AST>) -> Result<Program, CompileError> {
 
-    let mut compiled = compile(ast);
-    println!("{:?}", compiled.code_objects);
+    let mut compiled = compile(ast)?;
     let instructions = &mut compiled.code_objects.last_mut().unwrap().instructions;
 
     let last_pop_location = instructions.len() - 3;
@@ -243,13 +500,43 @@ pub fn compile_repl(ast: Vec<AST>) -> Program {
         instructions.remove(last_pop_location);
         compiled.code_objects.last_mut().unwrap().instructions.pop();
     }
-    return compiled;
+    return Ok(compiled);
+}
+
+fn parse_source(source: &str) -> Result<Vec<AST>, String> {
+    let tokens = tokenize(source)?;
+    parse_ast(tokens).map_err(|e| format!("{} (at token {})", e.message, e.token_index))
+}
+
+//tokenize, parse and compile in one go, for the compile and exec builtins. Err is the SyntaxError message
+pub fn compile_source(source: &str) -> Result<Program, String> {
+    compile(parse_source(source)?).map_err(|e| e.message)
+}
+
+//for eval: the source must be a single expression, and its value is returned instead of popped
+pub fn compile_eval_source(source: &str) -> Result<Program, String> {
+    let ast = parse_source(source)?;
+    if !matches!(ast.as_slice(), [AST::StandaloneExpr(_)]) {
+        return Err(String::from("invalid syntax"));
+    }
+    let mut compiled = compile(ast).map_err(|e| e.message)?;
+    let main_code = compiled.code_objects.iter_mut().find(|x| x.main).unwrap();
+    //replaces the PopTop, LoadConst None, ReturnValue at the end
+    let expression_len = main_code.instructions.len() - 3;
+    main_code.instructions.truncate(expression_len);
+    main_code.instructions.push(Instruction::ReturnValue);
+    return Ok(compiled);
+}
+
+pub fn compile(ast: Vec<AST>) -> Result<Program, CompileError> {
+    return compile_with_options(ast, &CompilerOptions::default());
 }
 
-pub fn compile(ast: Vec<AST>) -> Program {
+pub fn compile_with_options(ast: Vec<AST>, options: &CompilerOptions) -> Result<Program, CompileError> {
 
     let mut all_results = vec![];
-    let mut compile_result = compile_ast(ast, 0, &mut all_results, &mut BTreeMap::new());
+    let context = CompileContext { options, in_function: false, in_loop: false, blocks: vec![], loop_blocks: 0 };
+    let mut compile_result = compile_ast(ast, 0, &mut all_results, &mut BTreeMap::new(), &context)?;
     compile_result.main = true;
     resolve_loads_stores(&mut compile_result);
     
@@ -263,10 +550,10 @@ pub fn compile(ast: Vec<AST>) -> Program {
 
     all_results.insert(0, compile_result);
 
-    Program {
+    Ok(Program {
         version: 1,
         code_objects: all_results
-    }
+    })
 }
 
 
@@ -297,7 +584,7 @@ fn build_fully_qualified_name(prefix: Option<String>, name: &str) -> String {
     }
 }
 
-pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Option<String>, ensure_return: bool, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>) -> CodeObject {
+pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Option<String>, ensure_return: bool, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>, context: &CompileContext) -> Result<CodeObject, CompileError> {
     let mut all_instructions = vec![];
     for ast_item in ast {
         match ast_item {
@@ -313,23 +600,81 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                     all_instructions.extend(instructions_for_assign);
                 }
             }
+            AST::AugmentedAssign {
+                path,
+                operator,
+                expression,
+            } => {
+                if path.len() == 1 {
+                    all_instructions.push(Instruction::UnresolvedLoadName(path[0].clone()));
+                    all_instructions.append(&mut compile_expr(&expression, const_map));
+                    all_instructions.push(inplace_operator_instruction(&operator));
+                    all_instructions.push(Instruction::UnresolvedStoreName(path[0].clone()));
+                } else {
+                    //the object that owns the attribute is evaluated only once
+                    let (attr, owner_path) = path.split_last().unwrap();
+                    all_instructions.push(Instruction::UnresolvedLoadName(owner_path[0].clone()));
+                    for part in owner_path.iter().skip(1) {
+                        all_instructions.push(Instruction::LoadAttr(part.clone()));
+                    }
+                    all_instructions.push(Instruction::DupTop);
+                    all_instructions.push(Instruction::LoadAttr(attr.clone()));
+                    all_instructions.append(&mut compile_expr(&expression, const_map));
+                    all_instructions.push(inplace_operator_instruction(&operator));
+                    all_instructions.push(Instruction::RotTwo);
+                    all_instructions.push(Instruction::UnresolvedStoreAttr(attr.clone()));
+                }
+            }
+            AST::SubscriptAssign {
+                indexed,
+                index,
+                expression,
+            } => {
+                all_instructions.append(&mut compile_expr(&expression, const_map));
+                all_instructions.append(&mut compile_expr(&indexed, const_map));
+                all_instructions.append(&mut compile_expr(&index, const_map));
+                all_instructions.push(Instruction::StoreSubscript);
+            }
+            AST::AugmentedSubscriptAssign {
+                indexed,
+                index,
+                operator,
+                expression,
+            } => {
+                //the container and the key are evaluated only once
+                all_instructions.append(&mut compile_expr(&indexed, const_map));
+                all_instructions.append(&mut compile_expr(&index, const_map));
+                all_instructions.push(Instruction::DupTopTwo);
+                all_instructions.push(Instruction::IndexAccess);
+                all_instructions.append(&mut compile_expr(&expression, const_map));
+                all_instructions.push(inplace_operator_instruction(&operator));
+                all_instructions.push(Instruction::RotThree);
+                all_instructions.push(Instruction::StoreSubscript);
+            }
             AST::StandaloneExpr(expr) => {
                 all_instructions.append(&mut compile_expr(&expr, const_map));
                 all_instructions.push(Instruction::PopTop);
             },
-            AST::Return(Some(expr)) => {
-                all_instructions.append(&mut compile_expr(&expr, const_map));
-                all_instructions.push(Instruction::ReturnValue);
+            AST::Return(_) if !context.in_function => {
+                return Err(CompileError { message: String::from("'return' outside function") });
             }
-            AST::Return(None) => {
-                all_instructions.push(Instruction::LoadConst(const_map[&Const::None]));
+            AST::Return(expr) => {
+                match expr {
+                    Some(expr) => all_instructions.append(&mut compile_expr(&expr, const_map)),
+                    None => all_instructions.append(&mut process_constval(Const::None, const_map)),
+                }
+                //finally bodies run with the value already on the stack, and one that returns
+                //pushes its own value on top, which is the one returned
+                let mut exits = compile_block_exits(0, offset + all_instructions.len(), &qualified_prefix, results, const_map, context)?;
+                all_instructions.append(&mut exits);
                 all_instructions.push(Instruction::ReturnValue);
             }
             AST::ClassDeclaration{class_name, body} => {
                 let qualname = build_fully_qualified_name(qualified_prefix.clone(), &class_name);
 
                 let mut new_const_map = BTreeMap::new();
-                let mut class_decl_function = compile_ast_internal(body, 0, Some(qualname.clone()), true, results, &mut new_const_map);
+                let class_context = CompileContext { in_function: false, in_loop: false, blocks: vec![], loop_blocks: 0, ..context.clone() };
+                let mut class_decl_function = compile_ast_internal(body, 0, Some(qualname.clone()), true, results, &mut new_const_map, &class_context)?;
                 class_decl_function.main = false;
                 resolve_loads_stores(&mut class_decl_function);
                 let constval_code = Const::CodeObject(class_decl_function);
@@ -346,8 +691,11 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let qualname = build_fully_qualified_name(qualified_prefix.clone(), &function_name);
 
                 let mut new_const_map = BTreeMap::new();
-                let mut func_instructions = compile_ast_internal(body, 0, Some(qualname.clone()), true, results, &mut new_const_map);
+                let function_context = CompileContext { in_function: true, in_loop: false, blocks: vec![], loop_blocks: 0, ..context.clone() };
+                let mut func_instructions = compile_ast_internal(body, 0, Some(qualname.clone()), true, results, &mut new_const_map, &function_context)?;
                 func_instructions.main = false;
+                //any yield in the body makes calling the function create a generator
+                func_instructions.is_generator = func_instructions.instructions.contains(&Instruction::YieldValue);
                
                 func_instructions.params = parameters.iter()
                     .map(|x| match x {
@@ -373,14 +721,37 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let constval_name = Const::String(qualname.clone());
                 let mut name_idx = process_constval(constval_name, const_map);
 
-                all_instructions.extend(default_instructions);
-                all_instructions.push(Instruction::BuildList { number_elements:number_of_default_parameters });
+                //MakeFunction only pops the defaults list when there is one
+                if number_of_default_parameters > 0 {
+                    all_instructions.extend(default_instructions);
+                    all_instructions.push(Instruction::BuildList { number_elements:number_of_default_parameters });
+                }
                 all_instructions.append(&mut code_idx);
                 all_instructions.append(&mut name_idx);
                 all_instructions.push(Instruction::MakeFunction(number_of_default_parameters > 0));
                 all_instructions.push(Instruction::UnresolvedStoreName(function_name.clone()));
                 
             }
+            AST::Decorated{decorators, definition} => {
+                let name = match definition.as_ref() {
+                    AST::DeclareFunction{function_name, ..} => function_name.clone(),
+                    AST::ClassDeclaration{class_name, ..} => class_name.clone(),
+                    _ => panic!("Only functions and classes can be decorated"),
+                };
+                //like python: the decorators are evaluated first, then the definition,
+                //and the decorators are applied bottom-up before the name is bound
+                for decorator in decorators.iter() {
+                    all_instructions.append(&mut compile_expr(decorator, const_map));
+                }
+                let mut compiled_definition = compile_ast_internal(vec![*definition], offset + all_instructions.len(), qualified_prefix.clone(), false, results, const_map, context)?;
+                //drop the store of the undecorated definition
+                compiled_definition.instructions.pop();
+                all_instructions.append(&mut compiled_definition.instructions);
+                for _ in decorators.iter() {
+                    all_instructions.push(Instruction::CallFunction{ number_arguments: 1 });
+                }
+                all_instructions.push(Instruction::UnresolvedStoreName(name));
+            }
             AST::ForStatement{item_name, list_expression, body} => {
                 //this should behave like this:
                 /*
@@ -412,7 +783,9 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 //Need to compute the body first to get an offset
                 //and then we add to the beginning of the loop the ForIter instruction
 
-                let compiled_body = compile_ast_internal(body, 0, qualified_prefix.clone(), false, results, const_map);
+                //the body starts after the ForIter and the store of the item
+                let loop_context = CompileContext { in_loop: true, loop_blocks: context.blocks.len(), ..context.clone() };
+                let compiled_body = compile_ast_internal(body, offset_before_for + 2, qualified_prefix.clone(), false, results, const_map, &loop_context)?;
                 let mut body_instructions = vec![];
                 body_instructions.push(Instruction::UnresolvedStoreName(item_name.clone()));
                 body_instructions.extend(compiled_body.instructions);
@@ -424,10 +797,10 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let mut compiled_body_with_resolved_breaks: Vec<Instruction> = body_instructions
                     .into_iter()
                     .map(|instr| -> Instruction {
-                        if let Instruction::UnresolvedBreak = instr {
-                            Instruction::JumpUnconditional(offset_after_loop)
-                        } else {
-                            instr
+                        match instr {
+                            Instruction::UnresolvedBreak => Instruction::JumpUnconditional(offset_after_loop),
+                            Instruction::UnresolvedContinue => Instruction::JumpUnconditional(offset_before_for),
+                            _ => instr
                         }
                     })
                     .collect();
@@ -436,15 +809,27 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 compiled_body_with_resolved_breaks.insert(0, Instruction::ForIter(offset_after_loop));
                 //this has to jump back to the ForIter instruction so it loops
                 compiled_body_with_resolved_breaks.push(Instruction::JumpUnconditional(offset_before_for));
+                //both the end of the iteration and break land here, the iterator must not stay on the stack
+                //or an enclosing loop would call __next__ on it
+                compiled_body_with_resolved_breaks.push(Instruction::PopTop);
        
                 all_instructions.extend(compiled_body_with_resolved_breaks);
             
             },
             AST::IfStatement {
                 true_branch,
-                elifs: _,
+                elifs,
                 final_else,
             } => {
+                //elif chains are compiled as nested if/else, so conditions are evaluated top to bottom
+                //and evaluation stops at the first true condition
+                let final_else = elifs.into_iter().rev().fold(final_else, |else_branch, elif| {
+                    Some(vec![AST::IfStatement {
+                        true_branch: elif,
+                        elifs: vec![],
+                        final_else: else_branch,
+                    }])
+                });
                 let mut if_expr_compiled = compile_expr(&true_branch.expression, const_map);
                 all_instructions.append(&mut if_expr_compiled);
 
@@ -453,7 +838,7 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let offset_before_if = offset + all_instructions.len() + 1;
 
                 let mut true_branch_compiled =
-                    compile_ast_internal(true_branch.statements, offset_before_if, qualified_prefix.clone(), false, results, const_map);
+                    compile_ast_internal(true_branch.statements, offset_before_if, qualified_prefix.clone(), false, results, const_map, context)?;
                 //generate a jump to the code right after the true branch
 
                 //if there is an else: statement, the true branch must jump to after the false branch
@@ -467,7 +852,7 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                     ));
                     all_instructions.append(&mut true_branch_compiled.instructions);
 
-                    let mut false_branch_compiled = compile_ast_internal(else_ast, offset_after_true_branch, qualified_prefix.clone(), false, results, const_map);
+                    let mut false_branch_compiled = compile_ast_internal(else_ast, offset_after_true_branch, qualified_prefix.clone(), false, results, const_map, context)?;
 
                     //+1 because there will be an instruction
                     //in the true branch that will jump to *after* the false branch
@@ -488,8 +873,9 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let offset_before_while = all_instructions.len() + offset;
                 let mut compiled_expr = compile_expr(&expression, const_map);
                 //+1 for the jump if false
-                let offset_after_expr = all_instructions.len() + compiled_expr.len() + 1;
-                let compiled_body = compile_ast_internal(body, offset_after_expr, qualified_prefix.clone(), false, results, const_map);
+                let offset_after_expr = offset + all_instructions.len() + compiled_expr.len() + 1;
+                let loop_context = CompileContext { in_loop: true, loop_blocks: context.blocks.len(), ..context.clone() };
+                let compiled_body = compile_ast_internal(body, offset_after_expr, qualified_prefix.clone(), false, results, const_map, &loop_context)?;
                 all_instructions.append(&mut compiled_expr);
                 let offset_after_body = offset_after_expr + compiled_body.instructions.len() + 1;
                 all_instructions.push(Instruction::JumpIfFalseAndPopStack(offset_after_body));
@@ -497,10 +883,10 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 let mut compiled_body_with_resolved_breaks: Vec<Instruction> = compiled_body.instructions
                     .into_iter()
                     .map(|instr| -> Instruction {
-                        if let Instruction::UnresolvedBreak = instr {
-                            Instruction::JumpUnconditional(offset_after_body)
-                        } else {
-                            instr
+                        match instr {
+                            Instruction::UnresolvedBreak => Instruction::JumpUnconditional(offset_after_body),
+                            Instruction::UnresolvedContinue => Instruction::JumpUnconditional(offset_before_while),
+                            _ => instr
                         }
                     })
                     .collect();
@@ -508,17 +894,65 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 all_instructions.append(&mut compiled_body_with_resolved_breaks);
                 all_instructions.push(Instruction::JumpUnconditional(offset_before_while));
             }
-            AST::Raise(expr) => {
+            AST::Import(modules) => {
+                for module in modules {
+                    all_instructions.push(Instruction::ImportName(module.clone()));
+                    all_instructions.push(Instruction::UnresolvedStoreName(module.clone()));
+                }
+            }
+            AST::Delete(names) => {
+                for name in names {
+                    all_instructions.push(Instruction::UnresolvedDeleteName(name.clone()));
+                }
+            }
+            AST::Raise { exception: Some(expr), cause } => {
                 let mut if_expr_compiled = compile_expr(&expr, const_map);
                 all_instructions.append(&mut if_expr_compiled);
-                all_instructions.push(Instruction::Raise);
-                if !const_map.contains_key(&Const::None) {
-                    const_map.insert(Const::None, const_map.len());
+                match cause {
+                    Some(cause) => {
+                        all_instructions.append(&mut compile_expr(&cause, const_map));
+                        all_instructions.push(Instruction::RaiseFrom);
+                    }
+                    None => all_instructions.push(Instruction::Raise),
                 }
-                //println!("{:#?}", new_const_map);
-                all_instructions.push(Instruction::LoadConst(const_map[&Const::None]));
+                all_instructions.append(&mut process_constval(Const::None, const_map));
                 all_instructions.push(Instruction::ReturnValue);
             }
+            AST::Raise { exception: None, .. } => {
+                all_instructions.push(Instruction::Reraise);
+                all_instructions.append(&mut process_constval(Const::None, const_map));
+                all_instructions.push(Instruction::ReturnValue);
+            }
+            AST::Assert { expression, message } => {
+                if context.options.strip_asserts {
+                    continue;
+                }
+                let mut expr_compiled = compile_expr(&expression, const_map);
+                all_instructions.append(&mut expr_compiled);
+                all_instructions.push(Instruction::UnaryNot);
+                let mut message_compiled = match &message {
+                    Some(message) => compile_expr(message, const_map),
+                    None => vec![]
+                };
+                //+2 for the jump itself and the raise after the message
+                let offset_after_assert = offset + all_instructions.len() + message_compiled.len() + 2;
+                all_instructions.push(Instruction::JumpIfFalseAndPopStack(offset_after_assert));
+                all_instructions.append(&mut message_compiled);
+                all_instructions.push(Instruction::RaiseAssertionError { has_message: message.is_some() });
+            }
+            AST::Break | AST::Continue if !context.in_loop => {
+                let keyword = if let AST::Break = ast_item { "break" } else { "continue" };
+                return Err(CompileError { message: format!("'{}' outside loop", keyword) });
+            }
+            AST::TryStatement { body, handlers, else_body, finally_body } => {
+                let mut try_instructions = compile_try_statement(body, handlers, else_body, finally_body, offset + all_instructions.len(), &qualified_prefix, results, const_map, context)?;
+                all_instructions.append(&mut try_instructions);
+            }
+            AST::Continue => {
+                let mut exits = compile_block_exits(context.loop_blocks, offset + all_instructions.len(), &qualified_prefix, results, const_map, context)?;
+                all_instructions.append(&mut exits);
+                all_instructions.push(Instruction::UnresolvedContinue);
+            }
             AST::Break => {
                 //In python there's something called a "block stack" and an opcode called POP_BLOCK
                 //that makes this much easier, as well as a BREAK_LOOP instruction that uses block information
@@ -529,19 +963,162 @@ pub fn compile_ast_internal(ast: Vec<AST>, offset: usize, qualified_prefix: Opti
                 //However, when I find a break in the AST, I don't yet know what the program will look like,
                 //and therefore I don't know where to jump. 
                 //Perhaps other features such as generators, for comprehensions, etc really need blocks? I doubt it.
+                //(try statements did need them, so jumping out of one pops its handler first)
+                let mut exits = compile_block_exits(context.loop_blocks, offset + all_instructions.len(), &qualified_prefix, results, const_map, context)?;
+                all_instructions.append(&mut exits);
                 all_instructions.push(Instruction::UnresolvedBreak);
             }
         }
     }
 
-    make_code_object(all_instructions, qualified_prefix.unwrap_or("__main__".to_owned()), const_map, ensure_return)
+    if !context.in_function && all_instructions.contains(&Instruction::YieldValue) {
+        return Err(CompileError { message: String::from("'yield' outside function") });
+    }
+
+    Ok(make_code_object(all_instructions, qualified_prefix.unwrap_or("__main__".to_owned()), const_map, ensure_return))
+}
+
+//leaves the enclosing try blocks past the first keep_blocks ones, innermost first:
+//pops their handlers and runs the finally bodies on the way
+fn compile_block_exits(keep_blocks: usize, offset: usize, qualified_prefix: &Option<String>, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>, context: &CompileContext) -> Result<Vec<Instruction>, CompileError> {
+    let mut instructions = vec![];
+    for (index, block) in context.blocks.iter().enumerate().skip(keep_blocks).rev() {
+        if let EnclosingBlock::Handler = block {
+            instructions.push(Instruction::PopExcept);
+            continue;
+        }
+        instructions.push(Instruction::PopBlock);
+        if let EnclosingBlock::Finally(finally_body) = block {
+            let finally_context = CompileContext { blocks: context.blocks[..index].to_vec(), ..context.clone() };
+            let compiled = compile_ast_internal(finally_body.clone(), offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, &finally_context)?;
+            instructions.extend(compiled.instructions);
+        }
+    }
+    Ok(instructions)
+}
+
+/*
+    SetupExcept(finally_handler)     if there is a finally
+    SetupExcept(first_clause)
+    {body}
+    PopBlock
+    {else_body}
+    JumpUnconditional(after_clauses)
+  first_clause:                      the exception is on the stack
+    {exception type}
+    JumpIfNotExcMatch(next_clause)
+    StoreName(name) or PopTop
+    {handler body}
+    PopExcept
+    JumpUnconditional(after_clauses)
+  next_clause:
+    ...
+    PopExcept
+    Raise                            no clause matched, raise it again
+  after_clauses:
+    PopBlock                         if there is a finally
+    {finally_body}
+    JumpUnconditional(end)
+  finally_handler:
+    {finally_body}
+    PopExcept
+    Raise
+  end:
+*/
+fn compile_try_statement(body: Vec<AST>, handlers: Vec<ExceptHandler>, else_body: Option<Vec<AST>>, finally_body: Option<Vec<AST>>, offset: usize, qualified_prefix: &Option<String>, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>, context: &CompileContext) -> Result<Vec<Instruction>, CompileError> {
+    let mut instructions = vec![];
+    let mut protected_context = context.clone();
+    if let Some(finally_body) = &finally_body {
+        //the handler is only known after the rest is compiled
+        instructions.push(Instruction::SetupExcept(0));
+        protected_context.blocks.push(EnclosingBlock::Finally(finally_body.clone()));
+    }
+
+    if handlers.is_empty() {
+        let compiled_body = compile_ast_internal(body, offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, &protected_context)?;
+        instructions.extend(compiled_body.instructions);
+    } else {
+        let setup_index = instructions.len();
+        instructions.push(Instruction::SetupExcept(0));
+        let mut body_context = protected_context.clone();
+        body_context.blocks.push(EnclosingBlock::Except);
+        let compiled_body = compile_ast_internal(body, offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, &body_context)?;
+        instructions.extend(compiled_body.instructions);
+        instructions.push(Instruction::PopBlock);
+        if let Some(else_body) = else_body {
+            let compiled_else = compile_ast_internal(else_body, offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, &protected_context)?;
+            instructions.extend(compiled_else.instructions);
+        }
+        //jumps to the end of the clauses are patched once it is known
+        let mut jumps_to_end = vec![instructions.len()];
+        instructions.push(Instruction::JumpUnconditional(0));
+        instructions[setup_index] = Instruction::SetupExcept(offset + instructions.len());
+
+        for handler in handlers {
+            let mut match_index = None;
+            if let Some(exception_type) = &handler.exception_type {
+                instructions.append(&mut compile_expr(exception_type, const_map));
+                match_index = Some(instructions.len());
+                instructions.push(Instruction::JumpIfNotExcMatch(0));
+            }
+            match &handler.name {
+                Some(name) => instructions.push(Instruction::UnresolvedStoreName(name.clone())),
+                None => instructions.push(Instruction::PopTop),
+            }
+            let mut handler_context = protected_context.clone();
+            handler_context.blocks.push(EnclosingBlock::Handler);
+            let compiled_handler = compile_ast_internal(handler.body, offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, &handler_context)?;
+            instructions.extend(compiled_handler.instructions);
+            instructions.push(Instruction::PopExcept);
+            jumps_to_end.push(instructions.len());
+            instructions.push(Instruction::JumpUnconditional(0));
+            if let Some(match_index) = match_index {
+                instructions[match_index] = Instruction::JumpIfNotExcMatch(offset + instructions.len());
+            }
+        }
+        instructions.push(Instruction::PopExcept);
+        instructions.push(Instruction::Raise);
+
+        let after_clauses = offset + instructions.len();
+        for index in jumps_to_end {
+            instructions[index] = Instruction::JumpUnconditional(after_clauses);
+        }
+    }
+
+    if let Some(finally_body) = finally_body {
+        instructions.push(Instruction::PopBlock);
+        let compiled_finally = compile_ast_internal(finally_body.clone(), offset + instructions.len(), qualified_prefix.clone(), false, results, const_map, context)?;
+        instructions.extend(compiled_finally.instructions);
+        //+1 for the jump itself
+        let finally_handler = offset + instructions.len() + 1;
+        let compiled_finally = compile_ast_internal(finally_body, finally_handler, qualified_prefix.clone(), false, results, const_map, context)?;
+        //+2 for the pop and the raise after the finally body
+        instructions.push(Instruction::JumpUnconditional(finally_handler + compiled_finally.instructions.len() + 2));
+        instructions.extend(compiled_finally.instructions);
+        instructions.push(Instruction::PopExcept);
+        instructions.push(Instruction::Raise);
+        instructions[0] = Instruction::SetupExcept(finally_handler);
+    }
+    Ok(instructions)
 }
 
-pub fn compile_ast(ast: Vec<AST>, offset: usize, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>) -> CodeObject {
-    compile_ast_internal(ast,offset,None,true,results,const_map)
+pub fn compile_ast(ast: Vec<AST>, offset: usize, results: &mut Vec<CodeObject>, const_map: &mut BTreeMap<Const, usize>, context: &CompileContext) -> Result<CodeObject, CompileError> {
+    compile_ast_internal(ast,offset,None,true,results,const_map,context)
 }
 
-fn make_code_object(instrs: Vec<Instruction>, name: String, const_map: &mut BTreeMap<Const, usize>, ensure_return: bool) -> CodeObject {
+fn make_code_object(mut instrs: Vec<Instruction>, name: String, const_map: &mut BTreeMap<Const, usize>, ensure_return: bool) -> CodeObject {
+
+    if ensure_return {
+        //the body can be empty when all of it was stripped asserts
+        match instrs.last() {
+            Some(Instruction::ReturnValue) => { /*unchanged*/ },
+            _ => {
+                //goes through the const map before the consts are collected, so None is only added once
+                instrs.append(&mut process_constval(Const::None, const_map));
+                instrs.push(Instruction::ReturnValue);
+            }
+        }
+    }
 
     let mut vec_const = vec![];
     for (constval, index) in const_map.iter() {
@@ -552,38 +1129,25 @@ fn make_code_object(instrs: Vec<Instruction>, name: String, const_map: &mut BTre
     }
     vec_const.sort_unstable_by(|a, b| a.index.cmp(&b.index));
 
-    let mut code_obj = CodeObject {
+    return CodeObject {
         instructions: instrs,
         names: vec![],
         params: vec![],
         consts: vec_const.into_iter().map(|x| x.constval).collect(),
         main: false,
+        is_generator: false,
         objname: name
     };
-
-    if ensure_return {
-        match code_obj.instructions.last().unwrap() {
-            Instruction::ReturnValue => { /*unchanged*/ },
-            _ => {
-                if !const_map.contains_key(&Const::None) {
-                    const_map.insert(Const::None, const_map.len());
-                    code_obj.consts.push(Const::None);
-                }
-                //println!("{:#?}", new_const_map);
-                code_obj.instructions.push(Instruction::LoadConst(const_map[&Const::None]));
-                code_obj.instructions.push(Instruction::ReturnValue);
-            }
-        }
-    }
-    return code_obj;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::builtin_types::*;
+    use crate::builtin_types::exception_type::{format_exception, format_uncaught_exception};
     use crate::runtime::interpreter;
-    use crate::runtime::vm::VM;
+    use crate::runtime::vm::{SpecialValue, StepResult, VM};
+    use crate::commons::float::Float;
 
     #[test]
     fn run_pytests() -> std::io::Result<()> {
@@ -596,22 +1160,33 @@ mod tests {
             register_builtins(&mut vm);
             loader::run_loader(&mut vm);
             let tokens = tokenize(&source.unwrap()).unwrap();
-            let expr = parse_ast(tokens);
-            let program = compile(expr);
+            let expr = parse_ast(tokens).unwrap();
+            let program = compile(expr).unwrap();
             interpreter::execute_program(&mut vm, program);
+            if let Some(exception) = vm.get_current_exception() {
+                panic!("{:?} raised an uncaught exception:\n{}", dir.path(), format_uncaught_exception(&vm, exception));
+            }
         }
         
         Ok(())
     }
 
+    //a test that doesn't expect an exception fails on one nobody caught, instead of just stopping the program
+    fn execute_without_exceptions(vm: &mut VM, program: Program) {
+        interpreter::execute_program(vm, program);
+        if let Some(exception) = vm.get_current_exception() {
+            panic!("uncaught exception:\n{}", format_uncaught_exception(vm, exception));
+        }
+    }
+
     #[test]
     fn test_literal_int_1() {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("1").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_int();
         assert_eq!(stack_value, 1);
@@ -622,9 +1197,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("1.0").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, 1.0);
@@ -635,9 +1210,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("True").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_int();
         assert_eq!(stack_value, 1);
@@ -648,9 +1223,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("False").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_int();
         assert_eq!(stack_value, 0);
@@ -661,9 +1236,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("1 + 1").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_int();
         assert_eq!(stack_value, 2);
@@ -674,10 +1249,10 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("1 + 3.5").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
         println!("program: {:?}", program.code_objects);
-        interpreter::execute_program(&mut vm, program);
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, 4.5);
@@ -690,9 +1265,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("-(5.0 / 9.0)").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -705,9 +1280,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("-(5.0 / 9.0) * 32.0").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -720,9 +1295,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("1.0 - (5.0 / 9.0)").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -734,9 +1309,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("(-(5.0 / 9.0) * 32.0) / (1.0 - (5.0 / 9.0))").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -750,9 +1325,9 @@ mod tests {
         register_builtins(&mut vm);
         let tokens =
             tokenize("cos(sin(-(5.0 / 9.0) * 32.0)) / tanh(cos(1.0) - (5.0 / 9.0))").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -764,9 +1339,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("sin(1.0)").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_pop = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_pop).take_float();
         assert_eq!(stack_value, expected_result);
@@ -777,35 +1352,871 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("x = 1 + 2").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
-        let x = vm.get_local(0).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        let x = vm.find_in_module(MAIN_MODULE, "x").unwrap();
         let stack_value = vm.get_raw_data_of_pyobj(x).take_int();
         assert_eq!(stack_value, 3);
     }
 
+    #[test]
+    fn test_semicolon_separated_assignments() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let tokens = tokenize("x = 1; y = x + 2;").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        let x = vm.find_in_module(MAIN_MODULE, "x").unwrap();
+        assert_eq!(vm.get_raw_data_of_pyobj(x).take_int(), 1);
+        let y = vm.find_in_module(MAIN_MODULE, "y").unwrap();
+        assert_eq!(vm.get_raw_data_of_pyobj(y).take_int(), 3);
+    }
+
+    #[test]
+    fn test_print_with_sep_and_end() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let tokens = tokenize("print(1, 2, sep=\"-\", end=\"\")\nprint(\"a\", 3)").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "1-2a 3\n");
+    }
+
+    #[test]
+    fn test_print_and_input_prompts_appear_in_order() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        vm.provide_stdin(&["Ann", "42"]);
+        let source = "
+print(\"a\", end=\"\")
+print(\"b\", flush=True)
+name = input(\"name? \")
+print(\"hi\", name, end=\"!\\n\")
+print(input() + \"?\")
+";
+        let tokens = tokenize(source).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "ab\nname? hi Ann!\n42?\n");
+    }
+
+    #[test]
+    fn test_repr_and_str_of_strings() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let tokens = tokenize("print(repr(\"abc\"), str(\"abc\"), [\"abc\", 1])").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "'abc' abc ['abc', 1]\n");
+    }
+
+    #[test]
+    fn test_str_falls_back_to_repr() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let source = "
+class OnlyRepr:
+    def __repr__(self):
+        return \"repr!\"
+print(str(OnlyRepr()), OnlyRepr())
+";
+        let tokens = tokenize(source).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "repr! repr!\n");
+    }
+
+    #[test]
+    fn test_unary_minus_on_list_raises_type_error() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let source = "
+x = -[1, 2, 3]
+print(\"unreachable\")
+";
+        let tokens = tokenize(source).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        interpreter::execute_program(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "");
+        let exception = vm.get_current_exception().expect("expected a TypeError");
+        assert_eq!(vm.get_pyobj_type_name(exception), "TypeError");
+        assert_eq!(
+            format_exception(&vm, exception),
+            "TypeError: bad operand type for unary -: 'list'"
+        );
+    }
+
+    #[test]
+    fn test_augmented_subscript_evaluates_container_and_key_once() {
+        let tokens = tokenize("d[k] += 1").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        assert_eq!(
+            program.code_objects[0].instructions,
+            vec![
+                Instruction::LoadGlobal(0),
+                Instruction::LoadGlobal(1),
+                Instruction::DupTopTwo,
+                Instruction::IndexAccess,
+                Instruction::LoadConst(0),
+                Instruction::InplaceAdd,
+                Instruction::RotThree,
+                Instruction::StoreSubscript,
+                Instruction::LoadConst(1),
+                Instruction::ReturnValue,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_or_skips_rhs_when_lhs_is_truthy() {
+        let tokens = tokenize("x = a or b + 1").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        assert_eq!(
+            program.code_objects[0].instructions,
+            vec![
+                Instruction::LoadGlobal(1),
+                Instruction::JumpIfTrueOrPop(3),
+                Instruction::LoadGlobal(2),
+                Instruction::LoadConst(0),
+                Instruction::BinaryAdd,
+                Instruction::StoreGlobal(0),
+                Instruction::LoadConst(1),
+                Instruction::ReturnValue,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_assert_raises_assertion_error() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let source = "
+x = 1
+assert x == 1
+assert x == 2, \"x is \" + str(x)
+print(\"unreachable\")
+";
+        let tokens = tokenize(source).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        interpreter::execute_program(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "");
+        let exception = vm.get_current_exception().expect("expected an AssertionError");
+        assert_eq!(vm.get_pyobj_type_name(exception), "AssertionError");
+        assert_eq!(format_exception(&vm, exception), "AssertionError: x is 1");
+    }
+
+    #[test]
+    fn test_assert_message_is_only_evaluated_when_the_assertion_fails() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        let source = "
+def condition(value):
+    print(\"condition\")
+    return value
+def message(text):
+    print(\"message\", text)
+    return text
+assert condition(True), message(\"passed\")
+assert condition(False), message(\"failed\")
+";
+        let tokens = tokenize(source).unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        interpreter::execute_program(&mut vm, program);
+        assert_eq!(vm.take_captured_stdout(), "condition\ncondition\nmessage failed\n");
+        assert_eq!(format_exception(&vm, vm.get_current_exception().unwrap()), "AssertionError: failed");
+    }
+
+    #[test]
+    fn test_strip_asserts_emits_no_assert_instructions() {
+        let source = "
+def f(x):
+    assert x > 0, \"x must be positive\"
+    return x
+assert f(1) == 1
+y = f(2)
+";
+        let without_asserts = "
+def f(x):
+    return x
+y = f(2)
+";
+        let options = CompilerOptions { strip_asserts: true };
+        let stripped = compile_with_options(parse_ast(tokenize(source).unwrap()).unwrap(), &options).unwrap();
+        let expected = compile(parse_ast(tokenize(without_asserts).unwrap()).unwrap()).unwrap();
+        for code_object in stripped.code_objects.iter() {
+            assert!(!code_object.instructions.iter().any(|instr| matches!(instr, Instruction::RaiseAssertionError { .. })));
+        }
+        assert_eq!(stripped.code_objects, expected.code_objects);
+    }
+
+    #[test]
+    fn test_builtin_panic_becomes_an_error() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let tokens = tokenize("x = 1\ny = [1] + x").unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        let result = interpreter::try_execute_program(&mut vm, program);
+        assert_eq!(result, Err(String::from("can only concatenate list (not \"int\") to list")));
+
+        //the VM is still usable and kept its globals
+        vm.capture_stdout();
+        let tokens = tokenize("print(x + 1)").unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        assert_eq!(interpreter::try_execute_program(&mut vm, program), Ok(()));
+        assert_eq!(vm.take_captured_stdout(), "2\n");
+    }
+
+    #[test]
+    fn test_bare_raise_without_active_exception() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        //the except clause that ran before is no longer active
+        let source = "try:\n    x = 'a' + 1\nexcept TypeError:\n    x = 1\nraise";
+        let program = compile(parse_ast(tokenize(source).unwrap()).unwrap()).unwrap();
+        let result = interpreter::try_execute_program(&mut vm, program);
+        assert_eq!(result, Err(String::from("RuntimeError: No active exception to reraise")));
+    }
+
+    #[test]
+    fn test_interrupt_raises_keyboard_interrupt() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        vm.capture_stdout();
+        //KeyboardInterrupt is not an Exception, so only the outer handler catches it
+        let source = "
+i = 0
+caught = 'nothing'
+try:
+    try:
+        while True:
+            i = i + 1
+    except Exception:
+        caught = 'Exception'
+except KeyboardInterrupt as e:
+    caught = repr(e)
+print(caught, i > 0)
+";
+        let program = compile(parse_ast(tokenize(source).unwrap()).unwrap()).unwrap();
+        let interrupt = vm.interrupt.clone();
+        let signaller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            interrupt.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+        assert_eq!(interpreter::try_execute_program(&mut vm, program), Ok(()));
+        signaller.join().unwrap();
+        assert_eq!(vm.take_captured_stdout(), "KeyboardInterrupt() True\n");
+    }
+
+    #[test]
+    fn test_step_and_breakpoints() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let program = compile(parse_ast(tokenize("x = 1\ny = x + 2").unwrap()).unwrap()).unwrap();
+        assert_eq!(program.code_objects[0].instructions[4], Instruction::BinaryAdd);
+        vm.load_program(program);
+        let stack_ints = |vm: &VM| -> Vec<i128> {
+            let frames = vm.stack.borrow();
+            frames.last().unwrap().stack.iter().map(|addr| vm.get_raw_data_of_pyobj(*addr).take_int()).collect()
+        };
+
+        //LoadConst 1, then StoreGlobal x
+        assert_eq!(vm.step(), StepResult::Stepped);
+        assert_eq!(stack_ints(&vm), vec![1]);
+        assert_eq!(vm.step(), StepResult::Stepped);
+        assert_eq!(stack_ints(&vm), Vec::<i128>::new());
+
+        vm.add_breakpoint("__main__", 4);
+        assert_eq!(vm.resume(), StepResult::Breakpoint(4));
+        assert_eq!(vm.get_pc(), 4);
+        assert_eq!(stack_ints(&vm), vec![1, 2]);
+        assert_eq!(vm.step(), StepResult::Stepped);
+        assert_eq!(stack_ints(&vm), vec![3]);
+        assert_eq!(vm.resume(), StepResult::Finished);
+        assert_eq!(vm.step(), StepResult::Finished);
+    }
+
+    #[test]
+    fn test_trace_hook_sees_every_instruction() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let program = compile(parse_ast(tokenize("x = 1\ny = x + 2").unwrap()).unwrap()).unwrap();
+        let instructions = program.code_objects[0].instructions.clone();
+        let traced = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let traced_by_hook = traced.clone();
+        vm.set_trace_hook(Box::new(move |offset, instruction, depth| {
+            traced_by_hook.borrow_mut().push((offset, instruction.clone(), depth));
+        }));
+        execute_without_exceptions(&mut vm, program);
+
+        //no jumps, so every instruction runs once and in order
+        let traced = traced.borrow();
+        assert_eq!(traced.len(), instructions.len());
+        for (index, (offset, instruction, _)) in traced.iter().enumerate() {
+            assert_eq!(*offset, index);
+            assert_eq!(*instruction, instructions[index]);
+        }
+        let depths: Vec<usize> = traced.iter().take(6).map(|(_, _, depth)| *depth).collect();
+        assert_eq!(depths, vec![0, 1, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_profiler_counts_opcodes() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let program = compile(parse_ast(tokenize("i = 0\nwhile i < 10:\n    i = i + 1").unwrap()).unwrap()).unwrap();
+        assert!(vm.profile_report().is_empty());
+        vm.enable_profiler();
+        execute_without_exceptions(&mut vm, program);
+        let report = vm.profile_report();
+        //the condition is checked 11 times, the body runs 10
+        assert_eq!(report["CompareLessThan"].count, 11);
+        assert_eq!(report["BinaryAdd"].count, 10);
+        assert_eq!(report["StoreGlobal"].count, 11);
+        assert!(!report.contains_key("CallFunction"));
+    }
+
+    #[test]
+    fn test_finalizable_instance_freed_by_refcount_still_runs_del() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let source = "log = []\nclass Resource:\n    def __del__(self):\n        log.append(1)\nr = Resource()\n";
+        let program = compile(parse_ast(tokenize(source).unwrap()).unwrap()).unwrap();
+        execute_without_exceptions(&mut vm, program);
+        let instance = vm.find_in_module(MAIN_MODULE, "r").unwrap();
+        vm.remove_from_module(MAIN_MODULE, "r");
+        //drop every remaining reference, as if the last owner let go of it
+        while vm.get_refcount(instance) > 0 {
+            vm.decrease_refcount(instance);
+        }
+        assert_eq!(gc_module::collect(&vm), 1);
+        let log = vm.find_in_module(MAIN_MODULE, "log").unwrap();
+        assert_eq!(vm.get_raw_data_of_pyobj(log).take_list().len(), 1);
+        //nothing refers to it after __del__, so it is freed
+        assert!(!vm.memory.is_valid(instance));
+        assert!(vm.finalizable.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_object_addresses_are_deterministic() {
+        let source = "
+class A:
+    def __init__(self, n):
+        self.n = n
+objects = [A(i) for i in range(5)]
+print([id(o) for o in objects])
+print([o.n for o in set(objects)])
+print(str(objects[0]))
+x = [1, 2]
+x = None
+print(id([3, 4]), id(A(5)))
+";
+        let run = || {
+            let mut vm = VM::new();
+            register_builtins(&mut vm);
+            loader::run_loader(&mut vm);
+            vm.capture_stdout();
+            let program = compile(parse_ast(tokenize(source).unwrap()).unwrap()).unwrap();
+            execute_without_exceptions(&mut vm, program);
+            (vm.take_captured_stdout(), vm)
+        };
+        //both VMs are alive at the same time, so their heap pointers differ
+        let (first, _first_vm) = run();
+        let (second, _second_vm) = run();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_eval_source_returns_the_expression() {
+        let program = compile_eval_source("1 + 2").unwrap();
+        assert_eq!(program.code_objects[0].instructions, vec![
+            Instruction::LoadConst(0),
+            Instruction::LoadConst(1),
+            Instruction::BinaryAdd,
+            Instruction::ReturnValue,
+        ]);
+        assert_eq!(compile_eval_source("x = 1").err(), Some(String::from("invalid syntax")));
+        assert_eq!(compile_eval_source("1\n2").err(), Some(String::from("invalid syntax")));
+    }
+
+    #[test]
+    fn test_function_ending_in_bare_expression_returns_none() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let source = "
+def f(x):
+    x + 1
+def g():
+    return
+a = f(1)
+b = g()
+";
+        let tokens = tokenize(source).unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        let f = program.code_objects[0].consts.iter().find_map(|c| match c {
+            Const::CodeObject(code) if code.objname == "f" => Some(code.clone()),
+            _ => None,
+        }).unwrap();
+        //the value of x + 1 is discarded before the implicit return None
+        assert_eq!(
+            f.instructions[f.instructions.len() - 3..],
+            [
+                Instruction::PopTop,
+                Instruction::LoadConst(f.consts.iter().position(|c| *c == Const::None).unwrap()),
+                Instruction::ReturnValue,
+            ]
+        );
+        execute_without_exceptions(&mut vm, program);
+        let none = vm.special_values[&SpecialValue::NoneValue];
+        assert_eq!(vm.find_in_module(MAIN_MODULE, "a").unwrap(), none);
+        assert_eq!(vm.find_in_module(MAIN_MODULE, "b").unwrap(), none);
+    }
+
+    #[test]
+    fn test_return_outside_function_is_a_syntax_error() {
+        let expected = Err(CompileError { message: String::from("'return' outside function") });
+        for source in ["return 1", "if x:\n    return", "class A:\n    return"] {
+            let tokens = tokenize(source).unwrap();
+            let result = compile(parse_ast(tokens).unwrap()).map(|_| ());
+            assert_eq!(result, expected);
+        }
+        assert_eq!(expected.unwrap_err().to_string(), "SyntaxError: 'return' outside function");
+
+        //methods are functions again
+        let tokens = tokenize("class A:\n    def f(self):\n        return 1").unwrap();
+        assert!(compile(parse_ast(tokens).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_break_and_continue_outside_loop_are_syntax_errors() {
+        let cases = [
+            ("break", "'break' outside loop"),
+            ("continue", "'continue' outside loop"),
+            ("def f():\n    break", "'break' outside loop"),
+            //a function body doesn't inherit the loop around its definition
+            ("while True:\n    def f():\n        if x:\n            continue", "'continue' outside loop"),
+        ];
+        for (source, message) in cases {
+            let tokens = tokenize(source).unwrap();
+            let result = compile(parse_ast(tokens).unwrap()).map(|_| ());
+            assert_eq!(result, Err(CompileError { message: String::from(message) }));
+        }
+
+        let tokens = tokenize("def f():\n    for x in y:\n        if x:\n            break\n        continue").unwrap();
+        assert!(compile(parse_ast(tokens).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_none_is_a_single_const_per_code_object() {
+        //every way of loading None: the literal, bare return, raise and the implicit return
+        let source = "
+def f(x):
+    if x:
+        return
+    if x == 1:
+        raise None
+    y = None
+    if y == None:
+        return None
+x = None
+f(x)
+";
+        fn check(code: &CodeObject) {
+            let nones = code.consts.iter().filter(|c| **c == Const::None).count();
+            assert_eq!(nones, 1, "{} has {} None consts", code.objname, nones);
+            for constval in code.consts.iter() {
+                if let Const::CodeObject(inner) = constval {
+                    check(inner);
+                }
+            }
+        }
+        let tokens = tokenize(source).unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        for code in program.code_objects.iter() {
+            check(code);
+        }
+    }
+
+    #[test]
+    fn test_compiling_nan_constants_does_not_panic() {
+        //there is no NaN literal, so this builds the AST directly: x = 1.5 + nan + nan
+        let nan = || Box::new(Expr::FloatValue(Float(f64::NAN)));
+        let ast = vec![AST::Assign {
+            path: vec![String::from("x")],
+            expression: Expr::BinaryOperation(
+                Box::new(Expr::BinaryOperation(
+                    Box::new(Expr::FloatValue(Float(1.5))),
+                    Operator::Plus,
+                    nan(),
+                )),
+                Operator::Plus,
+                nan(),
+            ),
+        }];
+        let program = compile(ast).unwrap();
+        let consts = &program.code_objects[0].consts;
+        let nans = consts.iter().filter(|c| matches!(c, Const::Float(f) if f.0.is_nan())).count();
+        assert_eq!(nans, 1);
+        assert!(consts.contains(&Const::Float(Float(1.5))));
+    }
+
+    #[test]
+    fn test_divmod_by_zero_raises_zero_division_error() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let tokens = tokenize("x = divmod(1, 0)").unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        interpreter::execute_program(&mut vm, program);
+        let exception = vm.get_current_exception().expect("expected a ZeroDivisionError");
+        assert_eq!(vm.get_pyobj_type_name(exception), "ZeroDivisionError");
+        assert_eq!(
+            format_exception(&vm, exception),
+            "ZeroDivisionError: integer division or modulo by zero"
+        );
+    }
+
+    #[test]
+    fn test_empty_literals() {
+        let tokens = tokenize("x = {}\ny = ()").unwrap();
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile(expr).unwrap();
+        assert_eq!(
+            program.code_objects[0].instructions,
+            vec![
+                Instruction::BuildDict { number_entries: 0 },
+                Instruction::StoreGlobal(0),
+                Instruction::BuildTuple { number_elements: 0 },
+                Instruction::StoreGlobal(1),
+                Instruction::LoadConst(0),
+                Instruction::ReturnValue,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deep_assign_path() {
+        let path: Vec<String> = vec!["a".into(), "b".into(), "c".into(), "d".into()];
+        let instructions = generate_assign_path(path.as_slice(), true);
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction::UnresolvedLoadName("a".into()),
+                Instruction::LoadAttr("b".into()),
+                Instruction::LoadAttr("c".into()),
+                Instruction::UnresolvedStoreAttr("d".into()),
+            ]
+        );
+    }
+
     #[test]
     fn test_string_concat() {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("\"abc\" + 'cde'").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_top = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_top).take_string();
         assert_eq!(stack_value, "abccde");
     }
 
+    #[test]
+    fn test_brace_literals_build_the_right_type() {
+        for (source, type_name) in [("{}", "dict"), ("{1: 2}", "dict"), ("{1, 2}", "set")] {
+            let mut vm = VM::new();
+            register_builtins(&mut vm);
+            let program = compile_repl(parse_ast(tokenize(source).unwrap()).unwrap()).unwrap();
+            execute_without_exceptions(&mut vm, program);
+            let stack_top = vm.get_stack_offset(-1);
+            assert_eq!(vm.get_pyobj_type_name(stack_top), type_name);
+        }
+    }
+
+    fn run_and_get_exception_str(source: &str) -> String {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let tokens = tokenize(source).unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        interpreter::execute_program(&mut vm, program);
+        let exception = vm.get_current_exception().expect("expected an exception");
+        return format_exception(&vm, exception);
+    }
+
+    #[test]
+    fn test_string_concat_non_str_raises_type_error() {
+        assert_eq!(
+            run_and_get_exception_str("x = 'a' + 1"),
+            "TypeError: can only concatenate str (not \"int\") to str"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = 'a' + [1]"),
+            "TypeError: can only concatenate str (not \"list\") to str"
+        );
+    }
+
+    #[test]
+    fn test_fstring_compiles_to_format_value_and_build_string() {
+        let tokens = tokenize("f'pi={x:.2f}!'").unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        let instructions = &program.code_objects[0].instructions;
+        assert_eq!(
+            &instructions[..5],
+            &[
+                Instruction::LoadConst(0),
+                Instruction::LoadGlobal(0),
+                Instruction::LoadConst(1),
+                Instruction::FormatValue { conversion: None, has_spec: true },
+                Instruction::LoadConst(2),
+            ]
+        );
+        assert_eq!(instructions[5], Instruction::BuildString { number_parts: 3 });
+    }
+
+    #[test]
+    fn test_string_index_counts_chars_not_bytes() {
+        assert_eq!(
+            run_and_get_exception_str("x = 'h\u{e9}llo'[5]"),
+            "IndexError: string index out of range"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = 'abc'['a']"),
+            "TypeError: string indices must be integers, not 'str'"
+        );
+    }
+
+    #[test]
+    fn test_unhashable_values_raise_type_error() {
+        assert_eq!(
+            run_and_get_exception_str("x = hash([1])"),
+            "TypeError: unhashable type: 'list'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = hash((1, {}))"),
+            "TypeError: unhashable type: 'dict'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = [1] in frozenset()"),
+            "TypeError: unhashable type: 'list'"
+        );
+    }
+
+    #[test]
+    fn test_json_dumps_rejects_unsupported_values() {
+        assert_eq!(
+            run_and_get_exception_str("import json\nx = json.dumps([1, {1, 2}])"),
+            "TypeError: Object of type set is not JSON serializable"
+        );
+        assert_eq!(
+            run_and_get_exception_str("import json\nx = json.dumps({(1, 2): 3})"),
+            "TypeError: keys must be str, int, float, bool or None, not tuple"
+        );
+        assert_eq!(
+            run_and_get_exception_str("import json\nx = json.loads(1)"),
+            "TypeError: the JSON object must be str, not int"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "JSONDecodeError: Expecting ',' delimiter: line 2 column 4 (char 7)")]
+    fn test_json_loads_reports_position_of_errors() {
+        run_and_get_exception_str("import json\nx = json.loads('[1,\\n 2 3]')");
+    }
+
+    #[test]
+    #[should_panic(expected = "AttributeError: property 'area' of 'Square' object has no setter")]
+    fn test_assigning_to_property_without_setter() {
+        run_and_get_exception_str("
+class Square:
+    def __init__(self):
+        self.side = 2
+    @property
+    def area(self):
+        return self.side * self.side
+s = Square()
+s.area = 5");
+    }
+
+    #[test]
+    fn test_instantiation_checks_init() {
+        assert_eq!(
+            run_and_get_exception_str("class Empty:\n    x = 1\ne = Empty(1)"),
+            "TypeError: Empty() takes no arguments"
+        );
+        assert_eq!(
+            run_and_get_exception_str("class Odd:\n    def __init__(self):\n        return 1\no = Odd()"),
+            "TypeError: __init__() should return None, not 'int'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("class Bad:\n    def __init__(self, x):\n        self.x = 'a' + x\nb = Bad(1)"),
+            "TypeError: can only concatenate str (not \"int\") to str"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "AttributeError: 'Box' object has no attribute 'missing'")]
+    fn test_missing_instance_attribute() {
+        run_and_get_exception_str("class Box:\n    def __init__(self):\n        self.x = 1\nb = Box()\ny = b.missing");
+    }
+
+    #[test]
+    fn test_reduce_of_empty_iterable_without_initial_value() {
+        assert_eq!(
+            run_and_get_exception_str("import functools\nimport itertools\ndef add(a, b):\n    return a + b\nx = functools.reduce(add, itertools.repeat(1, 0))"),
+            "TypeError: reduce() of empty iterable with no initial value"
+        );
+    }
+
+    #[test]
+    fn test_generator_return_value_is_carried_by_stop_iteration() {
+        let mut vm = VM::new();
+        register_builtins(&mut vm);
+        let source = "def gen():\n    yield 1\n    return 42\ng = gen()\nfirst = g.__next__()\ng.__next__()";
+        let tokens = tokenize(source).unwrap();
+        let program = compile(parse_ast(tokens).unwrap()).unwrap();
+        interpreter::execute_program(&mut vm, program);
+
+        let first = vm.find_in_module(MAIN_MODULE, "first").unwrap();
+        assert_eq!(vm.get_raw_data_of_pyobj(first).take_int(), 1);
+        let exception = vm.get_current_exception().expect("expected StopIteration");
+        assert!(vm.is_stop_iteration(exception));
+        let value = vm.get_pyobj_byaddr(exception).properties["value"];
+        assert_eq!(vm.get_raw_data_of_pyobj(value).take_int(), 42);
+    }
+
+    #[test]
+    fn test_send_into_just_started_generator_must_be_none() {
+        assert_eq!(
+            run_and_get_exception_str("def gen():\n    x = yield 1\ng = gen()\ng.send(1)"),
+            "TypeError: can't send non-None value to a just-started generator"
+        );
+    }
+
+    #[test]
+    fn test_yield_outside_function_is_a_syntax_error() {
+        let expected = Err(CompileError { message: String::from("'yield' outside function") });
+        for source in ["yield 1", "x = yield", "class A:\n    yield 1"] {
+            let tokens = tokenize(source).unwrap();
+            let result = compile(parse_ast(tokens).unwrap()).map(|_| ());
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_tuple_holding_a_list_is_not_a_valid_key() {
+        assert_eq!(
+            run_and_get_exception_str("d = {}\nd[([1], 2)] = 3"),
+            "TypeError: unhashable type: 'list'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("d = {([1], 2): 3}"),
+            "TypeError: unhashable type: 'list'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("d = {(1, 2): 3}\nx = d[(1, [2])]"),
+            "TypeError: unhashable type: 'list'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("s = {(1, 2), ([1], 2)}"),
+            "TypeError: unhashable type: 'list'"
+        );
+    }
+
+    #[test]
+    fn test_in_requires_contains() {
+        assert_eq!(
+            run_and_get_exception_str("x = 1 in 2"),
+            "TypeError: argument of type 'int' is not iterable"
+        );
+    }
+
+    #[test]
+    fn test_in_string_requires_string_operand() {
+        assert_eq!(
+            run_and_get_exception_str("x = 1 in 'a1'"),
+            "TypeError: 'in <string>' requires string as left operand, not int"
+        );
+    }
+
+    #[test]
+    fn test_justify_requires_single_fill_char() {
+        assert_eq!(
+            run_and_get_exception_str("x = 'a'.center(5, 'ab')"),
+            "TypeError: The fill character must be exactly one character long"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = 'a'.ljust(5, 1)"),
+            "TypeError: ljust() argument 2 must be str, not int"
+        );
+    }
+
+    #[test]
+    fn test_min_max_argument_errors() {
+        assert_eq!(
+            run_and_get_exception_str("x = max(1, 2, default=0)"),
+            "TypeError: Cannot specify a default for max() with multiple positional arguments"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = min()"),
+            "TypeError: min expected at least 1 argument, got 0"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = min(1, 'a')"),
+            "TypeError: '<' not supported between instances of 'str' and 'int'"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ValueError: max() iterable argument is empty")]
+    fn test_max_of_empty_iterable_without_default() {
+        run_and_get_exception_str("import itertools\nx = max(itertools.repeat(1, 0))");
+    }
+
+    #[test]
+    fn test_tuple_ordering_requires_tuples() {
+        assert_eq!(
+            run_and_get_exception_str("x = (1, 2) < 3"),
+            "TypeError: '<' not supported between instances of 'tuple' and 'int'"
+        );
+    }
+
+    #[test]
+    fn test_string_repeat_non_int_raises_type_error() {
+        assert_eq!(
+            run_and_get_exception_str("x = 'a' * 1.5"),
+            "TypeError: can't multiply sequence by non-int of type 'float'"
+        );
+        assert_eq!(
+            run_and_get_exception_str("x = 'a' * 'b'"),
+            "TypeError: can't multiply sequence by non-int of type 'str'"
+        );
+    }
+
     #[test]
     fn boolean_and() {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("True and False").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_top = vm.get_stack_offset(-1);
         let stack_value = vm.get_raw_data_of_pyobj(stack_top).take_int();
         assert_eq!(stack_value, 0);
@@ -817,9 +2228,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("\"abc\".lower").unwrap();
-        let expr = parse_ast(tokens);
-        let program =  compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program =  compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_top = vm.get_stack_offset(-1);
         let stack_value = vm.get_pyobj_byaddr(stack_top);
         match &stack_value.structure {
@@ -837,9 +2248,9 @@ mod tests {
         let mut vm = VM::new();
         register_builtins(&mut vm);
         let tokens = tokenize("__builtins__.float").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         let stack_top = vm.get_stack_offset(-1);
         let stack_value = vm.get_pyobj_byaddr(stack_top);
         match &stack_value.structure {
@@ -868,9 +2279,9 @@ class SomeClass:
     def __init__(self):        
         self.x = 1
 ").unwrap();
-        let expr = parse_ast(tokens);
-        let program = compile_repl(expr);
-        interpreter::execute_program(&mut vm, program);
+        let expr = parse_ast(tokens).unwrap();
+        let program = compile_repl(expr).unwrap();
+        execute_without_exceptions(&mut vm, program);
         Ok(())
     }
 }
@@ -6,6 +6,7 @@ pub enum Const {
     Float(Float),
     Boolean(bool),
     String(String),
+    Bytes(Vec<u8>),
     CodeObject(CodeObject),
     None
 }
@@ -20,32 +21,181 @@ pub enum Instruction {
     StoreAttr(usize),
     LoadName(usize),
     LoadGlobal(usize),
+    StoreGlobal(usize),
+    //unbinds a local or a global, raising NameError if it wasn't bound
+    DeleteName(usize),
+    DeleteGlobal(usize),
     CallFunction { number_arguments: usize },
+    //the last len(keyword_names) arguments on the stack are passed by keyword
+    CallFunctionKw { number_arguments: usize, keyword_names: Vec<String> },
     JumpIfFalseAndPopStack(usize),
     JumpUnconditional(usize),
+    //and/or: keeps TOS and skips the next N instructions if it is falsy/truthy, otherwise pops it.
+    //the offset is relative because expressions are compiled without knowing where they will be placed
+    JumpIfFalseOrPop(usize),
+    JumpIfTrueOrPop(usize),
+    //relative jumps for conditional expressions and comprehensions, counted from the next instruction.
+    //PopJumpForwardIfFalse always pops the condition
+    PopJumpForwardIfFalse(usize),
+    JumpForward(usize),
+    //jumps back N instructions, counted from itself
+    JumpBackward(usize),
     ReturnValue,
+    //suspends the generator running the frame, handing TOS to whoever resumed it
+    YieldValue,
     PopTop,
+    DupTop,
+    //duplicates the two values on the top of the stack, keeping their order
+    DupTopTwo,
+    //swaps the two values on the top of the stack
+    RotTwo,
+    //moves the top of the stack two positions down
+    RotThree,
+    UnaryPositive,
+    UnaryNegative,
+    UnaryNot,
+    UnaryInvert,
     BinaryAdd,
     BinaryModulus,
     BinarySubtract,
     BinaryMultiply,
     BinaryTrueDivision,
+    BinaryFloorDivision,
+    BinaryPower,
+    //like BinaryAdd, but calls __iadd__ when TOS1 has it, for +=
+    InplaceAdd,
     CompareLessEquals,
     CompareGreaterEquals,
     CompareGreaterThan,
     CompareLessThan,
     CompareEquals,
     CompareNotEquals,
+    //pops the container then the item, pushes container.__contains__(item), negated for not in
+    CompareIn,
+    CompareNotIn,
     BuildList { number_elements: usize },
+    BuildTuple { number_elements: usize },
+    //pops number_entries key/value pairs, keys below their values
+    BuildDict { number_entries: usize },
+    BuildSet { number_elements: usize },
+    //pops the format spec if there is one, then the value: pushes format(value, spec), after the !r/!s/!a conversion
+    FormatValue { conversion: Option<char>, has_spec: bool },
+    //pops number_parts strings and pushes their concatenation, for f-strings
+    BuildString { number_parts: usize },
     IndexAccess,
+    //TOS1[TOS] = TOS2
+    StoreSubscript,
     ForIter(usize),
+    //like ForIter, but skips the next N instructions when the iterator is exhausted
+    ForIterForward(usize),
+    //pops TOS and appends it to the list N positions down the stack, after the pop
+    ListAppend(usize),
     Raise,
+    //raise TOS1 from TOS: pops the cause, then the exception
+    RaiseFrom,
+    //a bare raise: raises the exception being handled again
+    Reraise,
+    //pops the message if there is one, then raises AssertionError
+    RaiseAssertionError { has_message: bool },
+    //until the matching PopBlock, an exception jumps to the handler with the exception on the stack,
+    //and the stack cut back to what it was here
+    SetupExcept(usize),
+    PopBlock,
+    //ends an except clause, the exception the handler got is no longer being handled
+    PopExcept,
+    //pops the exception type and jumps if the exception below it, which stays on the stack, is not of that type
+    JumpIfNotExcMatch(usize),
+    //pushes a registered module
+    ImportName(String),
     UnresolvedBreak,
+    UnresolvedContinue,
     UnresolvedStoreAttr(String),
     UnresolvedStoreName(String),
+    UnresolvedDeleteName(String),
     UnresolvedLoadName(String)
 }
 
+impl Instruction {
+    //the variant name without its arguments, like LoadConst. The profiler calls it for every instruction it runs
+    pub fn opcode_name(&self) -> &'static str {
+        match self {
+            Instruction::LoadConst(..) => "LoadConst",
+            Instruction::LoadAttr(..) => "LoadAttr",
+            Instruction::MakeFunction(..) => "MakeFunction",
+            Instruction::MakeClass => "MakeClass",
+            Instruction::StoreName(..) => "StoreName",
+            Instruction::StoreAttr(..) => "StoreAttr",
+            Instruction::LoadName(..) => "LoadName",
+            Instruction::LoadGlobal(..) => "LoadGlobal",
+            Instruction::StoreGlobal(..) => "StoreGlobal",
+            Instruction::DeleteName(..) => "DeleteName",
+            Instruction::DeleteGlobal(..) => "DeleteGlobal",
+            Instruction::CallFunction { .. } => "CallFunction",
+            Instruction::CallFunctionKw { .. } => "CallFunctionKw",
+            Instruction::JumpIfFalseAndPopStack(..) => "JumpIfFalseAndPopStack",
+            Instruction::JumpUnconditional(..) => "JumpUnconditional",
+            Instruction::JumpIfFalseOrPop(..) => "JumpIfFalseOrPop",
+            Instruction::JumpIfTrueOrPop(..) => "JumpIfTrueOrPop",
+            Instruction::PopJumpForwardIfFalse(..) => "PopJumpForwardIfFalse",
+            Instruction::JumpForward(..) => "JumpForward",
+            Instruction::JumpBackward(..) => "JumpBackward",
+            Instruction::ReturnValue => "ReturnValue",
+            Instruction::YieldValue => "YieldValue",
+            Instruction::PopTop => "PopTop",
+            Instruction::DupTop => "DupTop",
+            Instruction::DupTopTwo => "DupTopTwo",
+            Instruction::RotTwo => "RotTwo",
+            Instruction::RotThree => "RotThree",
+            Instruction::UnaryPositive => "UnaryPositive",
+            Instruction::UnaryNegative => "UnaryNegative",
+            Instruction::UnaryNot => "UnaryNot",
+            Instruction::UnaryInvert => "UnaryInvert",
+            Instruction::BinaryAdd => "BinaryAdd",
+            Instruction::BinaryModulus => "BinaryModulus",
+            Instruction::BinarySubtract => "BinarySubtract",
+            Instruction::BinaryMultiply => "BinaryMultiply",
+            Instruction::BinaryTrueDivision => "BinaryTrueDivision",
+            Instruction::BinaryFloorDivision => "BinaryFloorDivision",
+            Instruction::BinaryPower => "BinaryPower",
+            Instruction::InplaceAdd => "InplaceAdd",
+            Instruction::CompareLessEquals => "CompareLessEquals",
+            Instruction::CompareGreaterEquals => "CompareGreaterEquals",
+            Instruction::CompareGreaterThan => "CompareGreaterThan",
+            Instruction::CompareLessThan => "CompareLessThan",
+            Instruction::CompareEquals => "CompareEquals",
+            Instruction::CompareNotEquals => "CompareNotEquals",
+            Instruction::CompareIn => "CompareIn",
+            Instruction::CompareNotIn => "CompareNotIn",
+            Instruction::BuildList { .. } => "BuildList",
+            Instruction::BuildTuple { .. } => "BuildTuple",
+            Instruction::BuildDict { .. } => "BuildDict",
+            Instruction::BuildSet { .. } => "BuildSet",
+            Instruction::FormatValue { .. } => "FormatValue",
+            Instruction::BuildString { .. } => "BuildString",
+            Instruction::IndexAccess => "IndexAccess",
+            Instruction::StoreSubscript => "StoreSubscript",
+            Instruction::ForIter(..) => "ForIter",
+            Instruction::ForIterForward(..) => "ForIterForward",
+            Instruction::ListAppend(..) => "ListAppend",
+            Instruction::Raise => "Raise",
+            Instruction::RaiseFrom => "RaiseFrom",
+            Instruction::Reraise => "Reraise",
+            Instruction::RaiseAssertionError { .. } => "RaiseAssertionError",
+            Instruction::SetupExcept(..) => "SetupExcept",
+            Instruction::PopBlock => "PopBlock",
+            Instruction::PopExcept => "PopExcept",
+            Instruction::JumpIfNotExcMatch(..) => "JumpIfNotExcMatch",
+            Instruction::ImportName(..) => "ImportName",
+            Instruction::UnresolvedBreak => "UnresolvedBreak",
+            Instruction::UnresolvedContinue => "UnresolvedContinue",
+            Instruction::UnresolvedStoreAttr(..) => "UnresolvedStoreAttr",
+            Instruction::UnresolvedStoreName(..) => "UnresolvedStoreName",
+            Instruction::UnresolvedDeleteName(..) => "UnresolvedDeleteName",
+            Instruction::UnresolvedLoadName(..) => "UnresolvedLoadName",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CodeObject {
     pub instructions: Vec<Instruction>,
@@ -53,7 +203,8 @@ pub struct CodeObject {
     pub params: Vec<String>,
     pub consts: Vec<Const>,
     pub objname: String,
-    pub main: bool
+    pub main: bool,
+    pub is_generator: bool
 }
 
 pub struct Program {
@@ -0,0 +1,129 @@
+use std::ops::{Add, Rem};
+
+//Rust's % truncates towards zero, python's result takes the sign of the divisor: -7 % 3 == 2
+pub fn python_mod<T>(a: T, b: T) -> T
+where
+    T: Rem<Output = T> + Add<Output = T> + PartialOrd + Default + Copy,
+{
+    let remainder = a % b;
+    let zero = T::default();
+    if remainder != zero && ((remainder < zero) != (b < zero)) {
+        return remainder + b;
+    }
+    return remainder;
+}
+
+//python's //, rounded down so that (a // b) * b + a % b == a
+pub trait FloorDiv {
+    fn floor_div(self, other: Self) -> Self;
+}
+
+impl FloorDiv for i128 {
+    fn floor_div(self, other: i128) -> i128 {
+        return (self - python_mod(self, other)) / other;
+    }
+}
+
+impl FloorDiv for f64 {
+    fn floor_div(self, other: f64) -> f64 {
+        //a - a % b is a multiple of b, rounding only removes the error of the division
+        return ((self - python_mod(self, other)) / other).round();
+    }
+}
+
+fn add_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if a >= modulus - b {
+        return a - (modulus - b);
+    }
+    return a + b;
+}
+
+//a * b % modulus without overflowing, falls back to double-and-add when the product doesn't fit
+fn mul_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+    let (mut a, mut b, mut result) = (a % modulus, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    return result;
+}
+
+fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (mut old_r, mut r) = (a, modulus);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    return Some(python_mod(old_s, modulus));
+}
+
+//pow(base, exp, modulus) by square-and-multiply, the result takes the sign of the modulus like python.
+//A negative exponent uses the modular inverse of base, None when there is none. modulus must not be 0.
+pub fn python_mod_pow(base: i128, exp: i128, modulus: i128) -> Option<i128> {
+    let positive_modulus = modulus.abs();
+    let mut base = python_mod(base, positive_modulus);
+    if exp < 0 {
+        base = mod_inverse(base, positive_modulus)?;
+    }
+    let (mut base, mut exp, m) = (base as u128, exp.unsigned_abs(), positive_modulus as u128);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    let result = result as i128;
+    if modulus < 0 && result != 0 {
+        return Some(result + modulus);
+    }
+    return Some(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remainder_takes_the_sign_of_the_divisor() {
+        assert_eq!(python_mod(7, 3), 1);
+        assert_eq!(python_mod(-7, 3), 2);
+        assert_eq!(python_mod(7, -3), -2);
+        assert_eq!(python_mod(-7, -3), -1);
+        assert_eq!(python_mod(-6, 3), 0);
+        assert_eq!(python_mod(-7.5, 2.0), 0.5);
+    }
+
+    #[test]
+    fn floor_division_rounds_down() {
+        assert_eq!(7.floor_div(2), 3);
+        assert_eq!((-7).floor_div(2), -4);
+        assert_eq!(7.floor_div(-2), -4);
+        assert_eq!((-7.5).floor_div(2.0), -4.0);
+        assert_eq!(0.1.floor_div(0.01), 10.0);
+    }
+
+    #[test]
+    fn modular_exponentiation_matches_python() {
+        assert_eq!(python_mod_pow(2, 10, 1000), Some(24));
+        assert_eq!(python_mod_pow(3, 200, 13), Some(9));
+        assert_eq!(python_mod_pow(-2, 3, 5), Some(2));
+        assert_eq!(python_mod_pow(2, 3, -5), Some(-2));
+        assert_eq!(python_mod_pow(5, 0, 1), Some(0));
+        assert_eq!(python_mod_pow(3, -1, 7), Some(5));
+        assert_eq!(python_mod_pow(2, -1, 4), None);
+        assert_eq!(python_mod_pow(i128::MAX - 1, 2, i128::MAX), Some(1));
+    }
+}
@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::mem;
 
-#[derive(PartialOrd, PartialEq, Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub struct Float(pub f64);
 
 impl From<f64> for Float {
@@ -11,21 +11,76 @@ impl From<f64> for Float {
     }
 }
 
-impl Eq for Float {}
-
+//Floats are used as BTreeMap keys (consts), so they need a total order: NaN goes after every number
+//and all NaNs are equal to each other. Python semantics (nan != nan) live in the float type, not here.
 impl Ord for Float {
     fn cmp(&self, other: &Float) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        match self.0.partial_cmp(&other.0) {
+            Some(ordering) => ordering,
+            None => self.0.is_nan().cmp(&other.0.is_nan()),
+        }
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Float) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Float) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
+impl Eq for Float {}
+
 impl Hash for Float {
     fn hash<H>(&self, state: &mut H)
     where
         H: Hasher,
     {
+        //values that compare equal must hash the same: every NaN is one NaN, and -0.0 is 0.0
+        let canonical = if self.0.is_nan() {
+            f64::NAN
+        } else if self.0 == 0.0 {
+            0.0
+        } else {
+            self.0
+        };
         //until I find another way to store floats in a map key, this unsafe is necessary :(
-        let as_u64: u64 = unsafe { mem::transmute(self.0) };
+        let as_u64: u64 = unsafe { mem::transmute(canonical) };
         return as_u64.hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nan_is_ordered_last() {
+        let mut values = [Float(f64::NAN), Float(1.0), Float(f64::INFINITY), Float(-2.5), Float(f64::NAN)];
+        values.sort();
+        assert_eq!(&values[..3], &[Float(-2.5), Float(1.0), Float(f64::INFINITY)]);
+        assert!(values[3].0.is_nan() && values[4].0.is_nan());
+        assert_eq!(Float(f64::NAN), Float(f64::NAN));
+    }
+
+    #[test]
+    fn equal_floats_hash_the_same() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |value: Float| {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        let other_nan = Float(f64::from_bits(f64::NAN.to_bits() | 1));
+        assert_eq!(Float(f64::NAN), other_nan);
+        assert_eq!(hash(Float(f64::NAN)), hash(other_nan));
+        assert_eq!(hash(Float(-f64::NAN)), hash(Float(f64::NAN)));
+        assert_eq!(Float(0.0), Float(-0.0));
+        assert_eq!(hash(Float(0.0)), hash(Float(-0.0)));
+    }
+}
@@ -0,0 +1,360 @@
+use std::convert::TryFrom;
+
+//Python's format specification mini-language, used by __format__ and f-strings:
+//[[fill]align][sign][z][#][0][width][grouping][.precision][type]
+#[derive(Debug, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    pub align: Option<char>,
+    pub sign: char,
+    pub alternate: bool,
+    pub width: usize,
+    pub grouping: Option<char>,
+    pub precision: Option<usize>,
+    pub format_type: Option<char>,
+}
+
+fn take_number(chars: &[char], index: &mut usize) -> Option<usize> {
+    let start = *index;
+    while *index < chars.len() && chars[*index].is_ascii_digit() {
+        *index += 1;
+    }
+    if start == *index {
+        return None;
+    }
+    return chars[start..*index].iter().collect::<String>().parse::<usize>().ok();
+}
+
+pub fn parse_format_spec(spec: &str) -> Result<FormatSpec, String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let is_align = |c: char| matches!(c, '<' | '>' | '=' | '^');
+    let mut result = FormatSpec {
+        fill: ' ',
+        align: None,
+        sign: '-',
+        alternate: false,
+        width: 0,
+        grouping: None,
+        precision: None,
+        format_type: None,
+    };
+    let mut index = 0;
+
+    if chars.len() >= 2 && is_align(chars[1]) {
+        result.fill = chars[0];
+        result.align = Some(chars[1]);
+        index = 2;
+    } else if !chars.is_empty() && is_align(chars[0]) {
+        result.align = Some(chars[0]);
+        index = 1;
+    }
+    if index < chars.len() && matches!(chars[index], '+' | '-' | ' ') {
+        result.sign = chars[index];
+        index += 1;
+    }
+    if index < chars.len() && chars[index] == 'z' {
+        index += 1;
+    }
+    if index < chars.len() && chars[index] == '#' {
+        result.alternate = true;
+        index += 1;
+    }
+    //a leading zero means "pad with zeros after the sign", unless the fill and alignment were given
+    if index < chars.len() && chars[index] == '0' {
+        if result.align.is_none() {
+            result.fill = '0';
+            result.align = Some('=');
+        }
+        index += 1;
+    }
+    if let Some(width) = take_number(&chars, &mut index) {
+        result.width = width;
+    }
+    if index < chars.len() && matches!(chars[index], ',' | '_') {
+        result.grouping = Some(chars[index]);
+        index += 1;
+    }
+    if index < chars.len() && chars[index] == '.' {
+        index += 1;
+        match take_number(&chars, &mut index) {
+            Some(precision) => result.precision = Some(precision),
+            None => return Err(String::from("Format specifier missing precision")),
+        }
+    }
+    if index < chars.len() {
+        result.format_type = Some(chars[index]);
+        index += 1;
+    }
+    if index < chars.len() {
+        return Err(String::from("Invalid format specifier"));
+    }
+    return Ok(result);
+}
+
+fn group_digits(digits: &str, separator: char, group_size: usize) -> String {
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(group_size) {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    return grouped;
+}
+
+fn sign_prefix(is_negative: bool, spec: &FormatSpec) -> &'static str {
+    if is_negative {
+        return "-";
+    }
+    match spec.sign {
+        '+' => "+",
+        ' ' => " ",
+        _ => "",
+    }
+}
+
+//applies fill and alignment, '=' puts the padding between the sign/prefix and the digits
+fn pad(prefix: &str, body: &str, spec: &FormatSpec, default_align: char) -> String {
+    let length = prefix.chars().count() + body.chars().count();
+    if length >= spec.width {
+        return format!("{}{}", prefix, body);
+    }
+    let padding = spec.width - length;
+    let fill = |n: usize| spec.fill.to_string().repeat(n);
+    match spec.align.unwrap_or(default_align) {
+        '<' => format!("{}{}{}", prefix, body, fill(padding)),
+        '^' => format!("{}{}{}{}", fill(padding / 2), prefix, body, fill(padding - padding / 2)),
+        '=' => format!("{}{}{}", prefix, fill(padding), body),
+        _ => format!("{}{}{}", fill(padding), prefix, body),
+    }
+}
+
+pub fn format_int(value: i128, spec: &FormatSpec) -> Result<String, String> {
+    if let Some('e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%') = spec.format_type {
+        return format_float(value as f64, spec);
+    }
+    if spec.precision.is_some() {
+        return Err(String::from("Precision not allowed in integer format specifier"));
+    }
+    let magnitude = value.unsigned_abs();
+    let (digits, prefix, group_size) = match spec.format_type {
+        None | Some('d') | Some('n') => (magnitude.to_string(), "", 3),
+        Some('b') => (format!("{:b}", magnitude), "0b", 4),
+        Some('o') => (format!("{:o}", magnitude), "0o", 4),
+        Some('x') => (format!("{:x}", magnitude), "0x", 4),
+        Some('X') => (format!("{:X}", magnitude), "0X", 4),
+        Some('c') => {
+            if spec.sign != '-' {
+                return Err(String::from("Sign not allowed with integer format specifier 'c'"));
+            }
+            return match u32::try_from(value).ok().and_then(char::from_u32) {
+                Some(c) => Ok(pad("", &c.to_string(), spec, '<')),
+                None => Err(String::from("%c arg not in range(0x110000)")),
+            };
+        }
+        Some(other) => return Err(format!("Unknown format code '{}' for object of type 'int'", other)),
+    };
+    let digits = match spec.grouping {
+        Some(separator) => group_digits(&digits, separator, group_size),
+        None => digits,
+    };
+    let prefix = format!(
+        "{}{}",
+        sign_prefix(value < 0, spec),
+        if spec.alternate { prefix } else { "" }
+    );
+    return Ok(pad(&prefix, &digits, spec, '>'));
+}
+
+//rust writes 1.5e3, python writes 1.5e+03
+fn exponent_notation(value: f64, precision: usize, uppercase: bool) -> String {
+    let formatted = format!("{:.*e}", precision, value);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let e = if uppercase { 'E' } else { 'e' };
+    let sign = if exponent < 0 { '-' } else { '+' };
+    return format!("{}{}{}{:02}", mantissa, e, sign, exponent.abs());
+}
+
+fn strip_trailing_zeros(number: &str) -> String {
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(position) => number.split_at(position),
+        None => (number, ""),
+    };
+    if !mantissa.contains('.') {
+        return number.to_string();
+    }
+    let stripped = mantissa.trim_end_matches('0').trim_end_matches('.');
+    return format!("{}{}", stripped, exponent);
+}
+
+//'g': exponent notation only when the exponent is < -4 or >= precision, trailing zeros removed unless '#'
+fn general_notation(value: f64, precision: usize, spec: &FormatSpec, uppercase: bool) -> String {
+    let precision = precision.max(1);
+    let exponent = if value == 0.0 {
+        0
+    } else {
+        let formatted = format!("{:.*e}", precision - 1, value);
+        formatted.split_once('e').unwrap().1.parse::<i32>().unwrap()
+    };
+    let formatted = if exponent >= -4 && exponent < precision as i32 {
+        format!("{:.*}", (precision as i32 - 1 - exponent) as usize, value)
+    } else {
+        exponent_notation(value, precision - 1, uppercase)
+    };
+    if spec.alternate {
+        return formatted;
+    }
+    return strip_trailing_zeros(&formatted);
+}
+
+pub fn format_float(value: f64, spec: &FormatSpec) -> Result<String, String> {
+    let uppercase = matches!(spec.format_type, Some('E' | 'F' | 'G'));
+    let magnitude = value.abs();
+    let body = if value.is_nan() || value.is_infinite() {
+        let special = if value.is_nan() { "nan" } else { "inf" };
+        let special = if uppercase { special.to_uppercase() } else { special.to_string() };
+        if spec.format_type == Some('%') {
+            special + "%"
+        } else {
+            special
+        }
+    } else {
+        match spec.format_type {
+            Some('f') | Some('F') => {
+                let precision = spec.precision.unwrap_or(6);
+                let fixed = format!("{:.*}", precision, magnitude);
+                if spec.alternate && precision == 0 {
+                    fixed + "."
+                } else {
+                    fixed
+                }
+            }
+            Some('e') | Some('E') => exponent_notation(magnitude, spec.precision.unwrap_or(6), uppercase),
+            Some('g') | Some('G') | Some('n') => general_notation(magnitude, spec.precision.unwrap_or(6), spec, uppercase),
+            Some('%') => format!("{:.*}%", spec.precision.unwrap_or(6), magnitude * 100.0),
+            //no type: like 'g' but always keeps a digit after the point, without precision it is repr()
+            None => match spec.precision {
+                Some(precision) => {
+                    let general = general_notation(magnitude, precision, spec, false);
+                    if general.contains(['.', 'e']) {
+                        general
+                    } else {
+                        general + ".0"
+                    }
+                }
+                None => format!("{:?}", magnitude),
+            },
+            Some(other) => return Err(format!("Unknown format code '{}' for object of type 'float'", other)),
+        }
+    };
+    let body = match spec.grouping {
+        Some(separator) if !value.is_nan() && !value.is_infinite() => {
+            let integer_end = body.find(|c: char| !c.is_ascii_digit()).unwrap_or(body.len());
+            format!("{}{}", group_digits(&body[..integer_end], separator, 3), &body[integer_end..])
+        }
+        _ => body,
+    };
+    let is_negative = value.is_sign_negative() && !value.is_nan();
+    return Ok(pad(sign_prefix(is_negative, spec), &body, spec, '>'));
+}
+
+pub fn format_str(value: &str, spec: &FormatSpec) -> Result<String, String> {
+    match spec.format_type {
+        None | Some('s') => {}
+        Some(other) => return Err(format!("Unknown format code '{}' for object of type 'str'", other)),
+    }
+    if spec.sign != '-' {
+        return Err(String::from("Sign not allowed in string format specifier"));
+    }
+    if spec.align == Some('=') {
+        return Err(String::from("'=' alignment not allowed in string format specifier"));
+    }
+    let truncated: String = match spec.precision {
+        Some(precision) => value.chars().take(precision).collect(),
+        None => value.to_string(),
+    };
+    return Ok(pad("", &truncated, spec, '<'));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i128, spec: &str) -> String {
+        format_int(value, &parse_format_spec(spec).unwrap()).unwrap()
+    }
+
+    fn float(value: f64, spec: &str) -> String {
+        format_float(value, &parse_format_spec(spec).unwrap()).unwrap()
+    }
+
+    fn string(value: &str, spec: &str) -> String {
+        format_str(value, &parse_format_spec(spec).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_every_field() {
+        let spec = parse_format_spec("*^+#012,.3f").unwrap();
+        assert_eq!(
+            spec,
+            FormatSpec {
+                fill: '*',
+                align: Some('^'),
+                sign: '+',
+                alternate: true,
+                width: 12,
+                grouping: Some(','),
+                precision: Some(3),
+                format_type: Some('f'),
+            }
+        );
+        assert!(parse_format_spec(".f").is_err());
+        assert!(parse_format_spec("dd").is_err());
+    }
+
+    #[test]
+    fn formats_ints_like_python() {
+        assert_eq!(int(255, "x"), "ff");
+        assert_eq!(int(255, "#X"), "0XFF");
+        assert_eq!(int(5, "b"), "101");
+        assert_eq!(int(-42, "05"), "-0042");
+        assert_eq!(int(42, "+"), "+42");
+        assert_eq!(int(1234567, ","), "1,234,567");
+        assert_eq!(int(255, "_b"), "1111_1111");
+        assert_eq!(int(42, "<5"), "42   ");
+        assert_eq!(int(42, "^6"), "  42  ");
+        assert_eq!(int(65, "c"), "A");
+        assert_eq!(int(3, ".2f"), "3.00");
+        assert!(format_int(3, &parse_format_spec(".2").unwrap()).is_err());
+    }
+
+    #[test]
+    fn formats_floats_like_python() {
+        assert_eq!(float(1.23456, ".2f"), "1.23");
+        assert_eq!(float(-0.5, ".0f"), "-0");
+        assert_eq!(float(2.5, "08.3f"), "0002.500");
+        assert_eq!(float(1234.5, "e"), "1.234500e+03");
+        assert_eq!(float(0.00001234, ".2E"), "1.23E-05");
+        assert_eq!(float(1234.5, "g"), "1234.5");
+        assert_eq!(float(123456789.0, "g"), "1.23457e+08");
+        assert_eq!(float(0.0001, "g"), "0.0001");
+        assert_eq!(float(0.00001, "g"), "1e-05");
+        assert_eq!(float(0.25, ".1%"), "25.0%");
+        assert_eq!(float(1234567.891, ",.2f"), "1,234,567.89");
+        assert_eq!(float(2.0, ".3"), "2.0");
+        assert_eq!(float(1.5, ""), "1.5");
+        assert_eq!(float(f64::INFINITY, "+f"), "+inf");
+        assert_eq!(float(f64::NAN, "F"), "NAN");
+    }
+
+    #[test]
+    fn formats_strings_like_python() {
+        assert_eq!(string("ab", "5"), "ab   ");
+        assert_eq!(string("ab", ">5"), "   ab");
+        assert_eq!(string("ab", "-^6"), "--ab--");
+        assert_eq!(string("hello", ".2"), "he");
+        assert!(format_str("ab", &parse_format_spec("+").unwrap()).is_err());
+        assert!(format_str("ab", &parse_format_spec("d").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+//Python's numeric hashes: numbers that compare equal hash equal, so hash(1) == hash(1.0).
+//Values are reduced modulo the mersenne prime 2**61 - 1, and -1 is never a hash (it's an error marker in CPython).
+const MODULUS_BITS: u32 = 61;
+const MODULUS: i128 = (1 << MODULUS_BITS) - 1;
+const HASH_INF: i128 = 314159;
+
+fn avoid_minus_one(hash: i128) -> i128 {
+    if hash == -1 {
+        return -2;
+    }
+    return hash;
+}
+
+pub fn hash_int(value: i128) -> i128 {
+    let hash = (value.unsigned_abs() % MODULUS as u128) as i128;
+    if value < 0 {
+        return avoid_minus_one(-hash);
+    }
+    return hash;
+}
+
+//mantissa in [0.5, 1) and exponent such that value == mantissa * 2**exponent, for finite non-zero values
+fn frexp(value: f64) -> (f64, i32) {
+    let mut value = value;
+    let mut exponent_offset = 0;
+    if (value.to_bits() >> 52) & 0x7ff == 0 {
+        //subnormal, scale it up so the exponent bits are meaningful
+        value *= 2f64.powi(54);
+        exponent_offset = -54;
+    }
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32 - 1022;
+    let mantissa = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+    return (mantissa, exponent + exponent_offset);
+}
+
+pub fn hash_float(value: f64) -> i128 {
+    if value.is_nan() {
+        return 0;
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { HASH_INF } else { -HASH_INF };
+    }
+    if value == 0.0 {
+        return 0;
+    }
+    let (mut mantissa, mut exponent) = frexp(value.abs());
+    //consume the mantissa 28 bits at a time, rotating the accumulator so it stays reduced
+    let mut hash: i128 = 0;
+    while mantissa != 0.0 {
+        hash = ((hash << 28) & MODULUS) | (hash >> (MODULUS_BITS - 28));
+        mantissa *= 268435456.0;
+        exponent -= 28;
+        let digit = mantissa as i128;
+        mantissa -= digit as f64;
+        hash += digit;
+        if hash >= MODULUS {
+            hash -= MODULUS;
+        }
+    }
+    let bits = MODULUS_BITS as i32;
+    let exponent = if exponent >= 0 { exponent % bits } else { bits - 1 - ((-1 - exponent) % bits) };
+    hash = ((hash << exponent) & MODULUS) | (hash >> (bits - exponent));
+    if value < 0.0 {
+        return avoid_minus_one(-hash);
+    }
+    return hash;
+}
+
+//str and bytes with the same content hash equal, like in python
+pub fn hash_bytes(data: &[u8]) -> i128 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(data);
+    return avoid_minus_one(hasher.finish() as i64 as i128);
+}
+
+//CPython's xxHash based tuple hash, over the hashes of the elements
+pub fn hash_tuple(element_hashes: &[i128]) -> i128 {
+    const PRIME_1: u64 = 11400714785074694791;
+    const PRIME_2: u64 = 14029467366897019727;
+    const PRIME_5: u64 = 2870177450012600261;
+    let mut acc = PRIME_5;
+    for hash in element_hashes {
+        acc = acc.wrapping_add((*hash as u64).wrapping_mul(PRIME_2));
+        acc = acc.rotate_left(31);
+        acc = acc.wrapping_mul(PRIME_1);
+    }
+    acc = acc.wrapping_add(element_hashes.len() as u64 ^ (PRIME_5 ^ 3527539));
+    if acc == u64::MAX {
+        return 1546275796;
+    }
+    return acc as i64 as i128;
+}
+
+//order independent: the element hashes are shuffled and xor'ed together, so equal sets hash equal
+pub fn hash_set(element_hashes: &[i128]) -> i128 {
+    let shuffle_bits = |hash: u64| ((hash ^ 89869747) ^ (hash << 16)).wrapping_mul(3644798167);
+    let mut hash: u64 = 0;
+    for element_hash in element_hashes {
+        hash ^= shuffle_bits(*element_hash as u64);
+    }
+    hash ^= (element_hashes.len() as u64 + 1).wrapping_mul(1927868237);
+    hash ^= (hash >> 11) ^ (hash >> 25);
+    hash = hash.wrapping_mul(69069).wrapping_add(907133923);
+    let hash = hash as i64 as i128;
+    if hash == -1 {
+        return 590923713;
+    }
+    return hash;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_hashes_match_python() {
+        assert_eq!(hash_int(1), 1);
+        assert_eq!(hash_int(-1), -2);
+        assert_eq!(hash_int(-2), -2);
+        assert_eq!(hash_int(MODULUS), 0);
+        assert_eq!(hash_int(MODULUS + 5), 5);
+        assert_eq!(hash_float(1.0), 1);
+        assert_eq!(hash_float(-1.0), -2);
+        assert_eq!(hash_float(1.5), 1152921504606846977);
+        assert_eq!(hash_float(-0.5), -1152921504606846976);
+        assert_eq!(hash_float(1e300), 1224995262755759164);
+        assert_eq!(hash_float(5e-324), 16777216);
+        assert_eq!(hash_float(f64::INFINITY), 314159);
+    }
+
+    #[test]
+    fn str_and_bytes_hash_equal() {
+        assert_eq!(hash_bytes("abc".as_bytes()), hash_bytes(b"abc"));
+        assert_ne!(hash_bytes(b"abc"), hash_bytes(b"abd"));
+    }
+
+    #[test]
+    fn tuple_hash_matches_python() {
+        assert_eq!(hash_tuple(&[]), 5740354900026072187);
+        assert_eq!(hash_tuple(&[1, 2]), -3550055125485641917);
+    }
+
+    #[test]
+    fn set_hash_is_order_independent() {
+        assert_eq!(hash_set(&[1, 2, 3]), hash_set(&[3, 1, 2]));
+        assert_ne!(hash_set(&[1, 2]), hash_set(&[1, 3]));
+    }
+}
@@ -1 +1,4 @@
-pub mod float;
\ No newline at end of file
+pub mod float;
+pub mod arithmetic;
+pub mod format_spec;
+pub mod hash;
@@ -11,22 +11,90 @@ use std::fs;
 use crate::ast::lexer;
 use crate::ast::parser;
 
+//reports syntax errors instead of crashing
+fn parse_source(source: &str) -> Option<Vec<parser::AST>> {
+    let tokens = match lexer::tokenize(source) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("SyntaxError: {}", e);
+            return None;
+        }
+    };
+    match parser::parse_ast(tokens) {
+        Ok(ast) => Some(ast),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
 fn main() {
     let mut vm = runtime::vm::VM::new();
     builtin_types::register_builtins(&mut vm);
     builtin_types::loader::run_loader(&mut vm);
     let args: Vec<String> = env::args().collect();
+    //-O strips assert statements, like in CPython
+    let options = bytecode::compiler::CompilerOptions {
+        strip_asserts: args.iter().any(|arg| arg == "-O"),
+    };
+    //--tokens, --dump-ast and --dump-bytecode print the tokens, the parse tree or the compiled code of the file instead of running it
+    let dump_tokens = args.iter().any(|arg| arg == "--tokens");
+    let dump_ast = args.iter().any(|arg| arg == "--dump-ast");
+    let dump_bytecode = args.iter().any(|arg| arg == "--dump-bytecode");
+    let flags = ["-O", "--tokens", "--dump-ast", "--dump-bytecode"];
+    let files: Vec<&String> = args.iter().skip(1).filter(|arg| !flags.contains(&arg.as_str())).collect();
 
-    if args.len() == 2 {
+    if files.len() == 1 {
         let input =
-            fs::read_to_string(args[1].clone()).expect(&format!("Could not read file {}", args[1]));
-        let tokens = lexer::tokenize(input.as_str());
-        //println!("Tokens: {:?}", tokens);
-        let ast = parser::parse_ast(tokens.unwrap());
+            fs::read_to_string(files[0]).expect(&format!("Could not read file {}", files[0]));
+        if dump_tokens {
+            match lexer::tokenize(input.as_str()) {
+                Ok(tokens) => {
+                    for token in tokens.iter() {
+                        println!("{:?}", token);
+                    }
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("SyntaxError: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        let ast = match parse_source(input.as_str()) {
+            Some(ast) => ast,
+            None => std::process::exit(1),
+        };
+        if dump_ast {
+            for statement in ast.iter() {
+                print!("{}", statement);
+            }
+            return;
+        }
 
-        let program = bytecode::compiler::compile(ast);
-        runtime::interpreter::execute_program(&mut vm, program);
-       
+        let program = match bytecode::compiler::compile_with_options(ast, &options) {
+            Ok(program) => program,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+        if dump_bytecode {
+            let main_code = program.code_objects.iter().find(|x| x.main).unwrap();
+            print!("{}", runtime::interpreter::disassemble(main_code));
+            return;
+        }
+        runtime::interrupt::install_interrupt_handler(&vm);
+        if let Err(e) = runtime::interpreter::try_execute_program(&mut vm, program) {
+            eprintln!("RuntimeError: {}", e);
+            std::process::exit(1);
+        }
+        //like python, an uncaught exception is reported and the exit status is 1
+        if let Some(exception) = vm.get_current_exception() {
+            eprintln!("{}", builtin_types::exception_type::format_uncaught_exception(&vm, exception));
+            std::process::exit(1);
+        }
         return;
     }
 
@@ -50,17 +118,37 @@ fn main() {
                 if input == "exit\n" {
                     return;
                 }
-                let tokens = lexer::tokenize(input.as_str());
-                let ast = parser::parse_ast(tokens.unwrap());
-                let program = bytecode::compiler::compile_repl(ast);
-                runtime::interpreter::execute_program(&mut vm, program);
-                let result_addr = vm.get_stack_offset(-1);
-                let result_string = vm.call_method(result_addr, "__repr__", runtime::vm::PositionalParameters::empty());
-                match result_string {
-                    None => {}
-                    Some((addr, _)) => {
-                        let pyobj_str = vm.get_raw_data_of_pyobj(addr).take_string();
-                        println!("{}", pyobj_str);
+                let ast = match parse_source(input.as_str()) {
+                    Some(ast) => ast,
+                    None => continue,
+                };
+                let program = match bytecode::compiler::compile_repl(ast) {
+                    Ok(program) => program,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        continue;
+                    }
+                };
+                let depth_before = vm.stack_len();
+                if let Err(e) = runtime::interpreter::try_execute_program(&mut vm, program) {
+                    eprintln!("RuntimeError: {}", e);
+                    continue;
+                }
+                //reported like in file mode, then the next line starts from a clean __main__ frame
+                if let Some(exception) = vm.get_current_exception() {
+                    eprintln!("{}", builtin_types::exception_type::format_uncaught_exception(&vm, exception));
+                    vm.clear_stacks();
+                    continue;
+                }
+                //an expression leaves its value below the None every program ends with, a statement leaves only the None
+                if vm.stack_len() >= depth_before + 2 {
+                    let result_addr = vm.get_stack_offset(-1);
+                    if result_addr != vm.special_values[&runtime::vm::SpecialValue::NoneValue] {
+                        let result_string = vm.call_method(result_addr, "__repr__", runtime::vm::PositionalParameters::empty());
+                        if let Some((addr, _)) = result_string {
+                            let pyobj_str = vm.get_raw_data_of_pyobj(addr).take_string();
+                            println!("{}", pyobj_str);
+                        }
                     }
                 }
 
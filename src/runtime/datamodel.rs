@@ -15,9 +15,16 @@ pub enum BuiltInTypeData {
     Int(i128),
     Float(Float),
     String(String),
+    Bytes(Vec<u8>),
     List(Vec<MemoryAddress>),
+    Tuple(Vec<MemoryAddress>),
+    Dict(Vec<(MemoryAddress, MemoryAddress)>),
+    //unique elements (by __eq__), in insertion order
+    Set(Vec<MemoryAddress>),
+    FrozenSet(Vec<MemoryAddress>),
     ClassInstance,
-    CodeObject(CodeObjectContext)
+    CodeObject(CodeObjectContext),
+    Generator(GeneratorState)
 }
 
 impl ToString for BuiltInTypeData {
@@ -26,11 +33,17 @@ impl ToString for BuiltInTypeData {
             BuiltInTypeData::Int(i) => i.to_string(),
             BuiltInTypeData::Float(i) => i.0.to_string(),
             BuiltInTypeData::String(i) => "String \"".to_owned() + i + "\"",
+            BuiltInTypeData::Bytes(b) => format!("Bytes {:?}", b),
             BuiltInTypeData::List(_i) => {
                 return "a list".into()
             },
+            BuiltInTypeData::Tuple(_i) => "a tuple".to_owned(),
+            BuiltInTypeData::Dict(_i) => "a dict".to_owned(),
+            BuiltInTypeData::Set(_i) => "a set".to_owned(),
+            BuiltInTypeData::FrozenSet(_i) => "a frozenset".to_owned(),
             BuiltInTypeData::ClassInstance => "class instance".to_owned(),
-            BuiltInTypeData::CodeObject(_) => "code object".to_owned()
+            BuiltInTypeData::CodeObject(_) => "code object".to_owned(),
+            BuiltInTypeData::Generator(_) => "a generator".to_owned()
 
         }
     }
@@ -58,6 +71,13 @@ impl BuiltInTypeData {
         }
     }
 
+    pub fn take_bytes(&self) -> &Vec<u8> {
+        match self {
+            BuiltInTypeData::Bytes(b) => b,
+            _ => panic!("Tried to transform something into bytes unexpectedly"),
+        }
+    }
+
     pub fn take_list(&self) -> &Vec<MemoryAddress> {
         match self {
             BuiltInTypeData::List(s) => s,
@@ -72,6 +92,34 @@ impl BuiltInTypeData {
         }
     }
 
+    pub fn take_tuple(&self) -> &Vec<MemoryAddress> {
+        match self {
+            BuiltInTypeData::Tuple(t) => t,
+            _ => panic!("Tried to transform something into tuple unexpectedly"),
+        }
+    }
+
+    pub fn take_set_mut(&mut self) -> &mut Vec<MemoryAddress> {
+        match self {
+            BuiltInTypeData::Set(s) => s,
+            _ => panic!("Tried to transform something into set unexpectedly"),
+        }
+    }
+
+    pub fn take_dict(&self) -> &Vec<(MemoryAddress, MemoryAddress)> {
+        match self {
+            BuiltInTypeData::Dict(d) => d,
+            _ => panic!("Tried to transform something into dict unexpectedly"),
+        }
+    }
+
+    pub fn take_dict_mut(&mut self) -> &mut Vec<(MemoryAddress, MemoryAddress)> {
+        match self {
+            BuiltInTypeData::Dict(d) => d,
+            _ => panic!("Tried to transform something into dict unexpectedly"),
+        }
+    }
+
     pub fn take_code_object(&self) -> &CodeObjectContext {
         match self {
             BuiltInTypeData::CodeObject(cobj) => cobj,
@@ -84,12 +132,18 @@ pub struct CodeObjectContext {
     pub code: CodeObject,
     pub consts: Vec<MemoryAddress>
 }
+//the frame is None once the generator finished
+#[derive(Debug, Eq, PartialEq)]
+pub struct GeneratorState {
+    pub code: CodeObjectContext,
+    pub frame: Option<StackFrame>
+}
 #[derive(Debug, Eq, PartialEq)]
 pub struct ProgramContext {
     pub code_objects: Vec<CodeObjectContext>,
 }
 
-type Namespace = BTreeMap<String, MemoryAddress>;
+pub type Namespace = BTreeMap<String, MemoryAddress>;
 
 #[derive(Debug)]
 pub enum PyObjectStructure {
@@ -1,25 +1,45 @@
 use crate::bytecode::program::*;
 use crate::commons::float::Float;
+use crate::commons::arithmetic::{python_mod, FloorDiv};
 use crate::runtime::vm::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
+use crate::builtin_types::type_error::raise_type_error;
+use crate::builtin_types::assertion_error::raise_assertion_error;
+use crate::builtin_types::name_error::{raise_name_error, raise_unbound_local_error};
+use crate::builtin_types::zero_division_error::{raise_if_modulo_by_zero, raise_if_floor_division_by_zero, raise_if_division_by_zero};
+use crate::builtin_types::keyboard_interrupt::raise_keyboard_interrupt;
+use crate::builtin_types::dict_type::dict_set;
+use crate::builtin_types::set_type::set_add;
 
 use smallvec::{smallvec, SmallVec};
 
 
 pub fn handle_function_call(vm: &VM, number_args: usize) {
+    handle_function_call_kw(vm, number_args, &[])
+}
+
+pub fn handle_function_call_kw(vm: &VM, number_args: usize, keyword_names: &[String]) {
     let mut temp_stack:SmallVec<[MemoryAddress; 4]>= smallvec![];
     for _ in 0..number_args {
         temp_stack.push(vm.pop_stack());
     }
 
+    //keyword values were pushed last, so they are the first ones popped
+    let keyword_params = KeywordParameters {
+        params: keyword_names.iter().rev().zip(temp_stack.iter())
+            .map(|(name, addr)| (name.clone(), *addr)).rev().collect()
+    };
+    let positional_popped = &temp_stack[keyword_names.len()..];
+
     let function_addr = vm.pop_stack();
 
     for addr in temp_stack.iter() {
         vm.increase_refcount(*addr);
     }
 
-    let (returned_value, popped_frame) = vm.run_function(PositionalParameters::from_stack_popped(&temp_stack), function_addr, None);
+    let (returned_value, popped_frame) = vm.run_function_kw(
+        PositionalParameters::from_stack_popped(positional_popped), keyword_params, function_addr, None);
 
     //increase refcount so it survives the pop_stack_frame call.
     let refcount = vm.get_refcount(returned_value);
@@ -56,6 +76,9 @@ fn get_const_memaddr(vm: &VM, const_data: &Const) -> MemoryAddress {
         Const::String(s) => {
             vm.allocate_builtin_type_byname_raw("str", BuiltInTypeData::String(s.clone()))
         }
+        Const::Bytes(b) => {
+            vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.bytes, BuiltInTypeData::Bytes(b.clone()))
+        }
         Const::CodeObject(codeobj) => {
             vm.allocate_builtin_type_byname_raw("code object", BuiltInTypeData::CodeObject(
                 register_codeobj_consts(vm, codeobj)))
@@ -88,6 +111,34 @@ pub fn curry_self(vm: &VM, function: MemoryAddress, self_object: MemoryAddress)
     })
 }
 
+//a function found on a class is bound to the instance it was loaded from, a classmethod is bound
+//to the class, a property loaded from an instance calls its getter,
+//and a staticmethod or any other class attribute is returned as it is
+fn bind_class_attribute(vm: &VM, attr_addr: MemoryAddress, instance: Option<MemoryAddress>, class_addr: MemoryAddress) -> MemoryAddress {
+    let attr_type = vm.get_pyobj_type_addr(attr_addr);
+    if let (Some(instance), true) = (instance, attr_type == vm.builtin_type_addrs.property) {
+        let fget = vm.get_obj_property(attr_addr, "fget").unwrap();
+        let (result, frame) = vm.run_function(PositionalParameters::single(instance), fget, None);
+        if let Some(exception) = frame.exception {
+            vm.raise_exception(exception);
+        }
+        return result;
+    }
+    if attr_type == vm.builtin_type_addrs.classmethod {
+        let function = vm.get_obj_property(attr_addr, "__func__").unwrap();
+        return curry_self(vm, function, class_addr);
+    }
+    if attr_type == vm.builtin_type_addrs.staticmethod {
+        return vm.get_obj_property(attr_addr, "__func__").unwrap();
+    }
+    match (instance, &vm.get_pyobj_byaddr(attr_addr).structure) {
+        (Some(instance), PyObjectStructure::UserDefinedFunction { .. } | PyObjectStructure::NativeCallable { .. }) => {
+            return curry_self(vm, attr_addr, instance);
+        }
+        _ => return attr_addr,
+    }
+}
+
 pub fn handle_load_attr(vm: &VM, attr_name: &str) {
     let stack_top = vm.pop_stack();
 
@@ -111,9 +162,17 @@ pub fn handle_load_attr(vm: &VM, attr_name: &str) {
 
             let method_addr = vm.get_method_addr_byname(type_addr, attr_name);
 
+            //the instance's own attributes shadow everything on the class except properties
+            let is_property = method_addr.is_some_and(|m_addr| vm.get_pyobj_type_addr(m_addr) == vm.builtin_type_addrs.property);
+            if !is_property {
+                if let Some(addr) = vm.get_obj_property(stack_top, attr_name) {
+                    vm.push_onto_stack(addr);
+                    return;
+                }
+            }
+
             if let Some(m_addr) = method_addr {
-                //create bound method
-                let bounded = curry_self(vm, m_addr, stack_top);
+                let bounded = bind_class_attribute(vm, m_addr, Some(stack_top), type_addr);
                 vm.increase_refcount(bounded);
                 vm.push_onto_stack(bounded);
                 return;
@@ -129,6 +188,16 @@ pub fn handle_load_attr(vm: &VM, attr_name: &str) {
         }
         None => {}
     }
+
+    //loading from the class itself: methods come back unbound
+    if let PyObjectStructure::Type { .. } = &pyobj.structure {
+        if let Some(attr_addr) = vm.get_method_addr_byname(stack_top, attr_name) {
+            let bounded = bind_class_attribute(vm, attr_addr, None, stack_top);
+            vm.increase_refcount(bounded);
+            vm.push_onto_stack(bounded);
+            return;
+        }
+    }
     //second: try to load a method name
 
     let type_addr = pyobj.type_addr;
@@ -145,13 +214,19 @@ pub fn handle_load_attr(vm: &VM, attr_name: &str) {
     }
 
     //third: try to load a module function, property, etc
-    let obj = vm.find_in_module_addr(stack_top, attr_name);
-
-    match obj {
-        None => panic!("could not find attribute named {}", attr_name),
-        Some(addr) => {
-            vm.push_onto_stack(addr);
+    match &pyobj.structure {
+        PyObjectStructure::Module { name, .. } => match vm.find_in_module_addr(stack_top, attr_name) {
+            Some(addr) => vm.push_onto_stack(addr),
+            None => panic!("AttributeError: module '{}' has no attribute '{}'", name, attr_name),
+        },
+        PyObjectStructure::Type { name, .. } if attr_name == "__name__" => {
+            vm.push_onto_stack(vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(name.clone())));
+        }
+        PyObjectStructure::Type { name, .. } => {
+            panic!("AttributeError: type object '{}' has no attribute '{}'", name, attr_name)
         }
+        _ if attr_name == "__class__" => vm.push_onto_stack(type_addr),
+        _ => panic!("AttributeError: '{}' object has no attribute '{}'", vm.get_pyobj_type_name(stack_top), attr_name),
     }
 }
 
@@ -166,12 +241,15 @@ pub fn handle_load_global(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
     */
 
     if let Some(name_str) = code_obj.code.names.get(name) {
-        if let Some(addr) = vm.find_in_module(BUILTIN_MODULE, name_str) {
-            vm.push_onto_stack(addr); 
+        if let Some(Some(addr)) = vm.with_frame_namespace(|namespace| namespace.get(name_str).copied()) {
+            vm.push_onto_stack(addr);
             return;
         } else if let Some(addr) = vm.find_in_module(MAIN_MODULE, name_str) {
             vm.push_onto_stack(addr); 
             return;
+        } else if let Some(addr) = vm.find_in_module(BUILTIN_MODULE, name_str) {
+            vm.push_onto_stack(addr); 
+            return;
         }
     }
     
@@ -181,7 +259,7 @@ pub fn handle_load_global(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
             return;
         }
         None => {
-            panic!("could not find global named {}", &code_obj.code.names[name])
+            raise_name_error(vm, &code_obj.code.names[name]);
         }
     };
 }
@@ -216,8 +294,8 @@ macro_rules! create_binary_operator {
                     match raw_data_tos {
                         BuiltInTypeData::Int(j) => match raw_data_tos_1 {
                             BuiltInTypeData::Int(i) => {
-                                let $param_a = i;
-                                let $param_b = j;
+                                let $param_a = *i;
+                                let $param_b = *j;
                                 result = Some(BuiltInTypeData::Int($operation))
                             }
                             BuiltInTypeData::Float(f) => {
@@ -398,9 +476,63 @@ macro_rules! create_compare_operator {
 }
 
 create_binary_operator!(handle_binary_add, a, b, a + b, "__add__");
-create_binary_operator!(handle_binary_mod, a, b, a % b, "__mod__");
+create_binary_operator!(handle_binary_mod_unchecked, a, b, python_mod(a, b), "__mod__");
+
+//the divisions check the operands on the stack before the unchecked fast paths run
+fn raise_if_stack_divisor_is_zero(
+    vm: &VM,
+    check: fn(&VM, MemoryAddress, MemoryAddress) -> Option<MemoryAddress>,
+) -> bool {
+    let (lhs, rhs) = (vm.get_stack_offset(-1), vm.top_stack());
+    if check(vm, lhs, rhs).is_some() {
+        vm.pop_stack();
+        vm.pop_stack();
+        return true;
+    }
+    return false;
+}
+
+fn handle_binary_mod(vm: &VM) {
+    if !raise_if_stack_divisor_is_zero(vm, raise_if_modulo_by_zero) {
+        handle_binary_mod_unchecked(vm);
+    }
+}
 create_binary_operator!(handle_binary_sub, a, b, a - b, "__sub__");
 create_binary_operator!(handle_binary_mul, a, b, a * b, "__mul__");
+create_binary_operator!(handle_binary_floordiv_unchecked, a, b, a.floor_div(b), "__floordiv__");
+
+fn handle_binary_floordiv(vm: &VM) {
+    if !raise_if_stack_divisor_is_zero(vm, raise_if_floor_division_by_zero) {
+        handle_binary_floordiv_unchecked(vm);
+    }
+}
+
+//no fast path, int ** int can be a float or overflow
+fn handle_binary_power(vm: &VM) {
+    let tos = vm.pop_stack();
+    let tos_1 = vm.pop_stack();
+    vm.push_onto_stack(tos_1);
+    handle_load_attr(vm, "__pow__");
+    vm.push_onto_stack(tos);
+    handle_function_call(vm, 1);
+}
+
+//a += b: mutable objects implement __iadd__ and change in place, everything else falls back to __add__
+fn handle_inplace_add(vm: &VM) {
+    let tos = vm.pop_stack();
+    let tos_1 = vm.pop_stack();
+    match vm.call_method(tos_1, "__iadd__", PositionalParameters::single(tos)) {
+        Some((_, popped_frame)) if popped_frame.exception.is_some() => {
+            vm.raise_exception(popped_frame.exception.unwrap());
+        }
+        Some((result, _)) => vm.push_onto_stack(result),
+        None => {
+            vm.push_onto_stack(tos_1);
+            vm.push_onto_stack(tos);
+            handle_binary_add(vm);
+        }
+    }
+}
 
 create_compare_operator!(handle_compare_greater, a, b, a > b, "__gt__");
 create_compare_operator!(handle_compare_greater_eq, a, b, a >= b, "__ge__");
@@ -411,6 +543,9 @@ create_compare_operator!(handle_compare_not_eq, a, b, a != b, "__ne__");
 
 //Division is weird so we do it separately. It always results in a float result
 fn handle_binary_truediv(vm: &VM) {
+    if raise_if_stack_divisor_is_zero(vm, raise_if_division_by_zero) {
+        return;
+    }
     let tos = vm.pop_stack();
     let tos_1 = vm.pop_stack();
 
@@ -496,23 +631,15 @@ fn handle_binary_truediv(vm: &VM) {
     }
 }
 
+//only the names a function assigns or deletes are loaded with LoadName, globals use LoadGlobal,
+//so an unbound slot is a local without a value and must not fall back to the module
 pub fn handle_load_name(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
-    
     match vm.get_local(name) {
         Some(addr) => vm.push_onto_stack(addr),
-        None => match code_obj.code.names.get(name) {
-            //@TODO shouldn't it load from the main module first? Or even better, the current module being executed?
-            Some(name_str) => match vm.find_in_module(BUILTIN_MODULE, name_str) {
-                Some(addr) => vm.push_onto_stack(addr),
-                None => match vm.find_in_module(MAIN_MODULE, name_str) {
-                    Some(addr) => vm.push_onto_stack(addr),
-                    None => panic!("Could not find name {}", name_str),
-                }
-            },
-            None => panic!("Could not find name")
-        },
+        None => {
+            raise_unbound_local_error(vm, &code_obj.code.names[name]);
+        }
     }
-    
 }
 
 pub fn handle_store_name(vm: &VM, name: usize) {
@@ -524,40 +651,130 @@ pub fn handle_store_name(vm: &VM, name: usize) {
     vm.bind_local(name, addr)
 }
 
+pub fn handle_store_global(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
+    let addr = vm.pop_stack();
+    let name_str = &code_obj.code.names[name];
+    match vm.with_frame_namespace(|namespace| namespace.insert(name_str.clone(), addr)) {
+        Some(previous) => {
+            vm.increase_refcount(addr);
+            if let Some(previous) = previous {
+                vm.decrease_refcount(previous);
+            }
+        }
+        None => vm.set_in_module(MAIN_MODULE, name_str, addr),
+    }
+}
+
+pub fn handle_delete_name(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
+    match vm.get_local(name) {
+        Some(addr) => {
+            vm.unbind_local(name);
+            vm.decrease_refcount(addr);
+        }
+        None => {
+            raise_unbound_local_error(vm, &code_obj.code.names[name]);
+        }
+    }
+}
+
+//once a global is gone, loading the name falls back to __builtins__ again
+pub fn handle_delete_global(vm: &VM, code_obj: &CodeObjectContext, name: usize) {
+    let name_str = &code_obj.code.names[name];
+    let removed = match vm.with_frame_namespace(|namespace| namespace.remove(name_str)) {
+        Some(removed) => removed.map(|addr| vm.decrease_refcount(addr)).is_some(),
+        None => vm.remove_from_module(MAIN_MODULE, name_str),
+    };
+    if !removed {
+        raise_name_error(vm, name_str);
+    }
+}
+
+pub fn handle_compare_in(vm: &VM, negate: bool) {
+    let container = vm.pop_stack();
+    let item = vm.pop_stack();
+    match vm.call_method(container, "__contains__", PositionalParameters::single(item)) {
+        Some((_, frame)) if frame.exception.is_some() => {
+            vm.raise_exception(frame.exception.unwrap());
+        }
+        Some((result, _)) => {
+            if vm.is_truthy(result) != negate {
+                vm.push_onto_stack(vm.builtin_type_addrs.true_val);
+            } else {
+                vm.push_onto_stack(vm.builtin_type_addrs.false_val);
+            }
+        }
+        None => {
+            let type_name = vm.get_pyobj_type_name(container);
+            raise_type_error(vm, format!("argument of type '{}' is not iterable", type_name));
+        }
+    }
+}
+
+pub fn handle_unary_operator(vm: &VM, method_name: &str, symbol: &str) {
+    let operand = vm.pop_stack();
+    match vm.call_method(operand, method_name, PositionalParameters::empty()) {
+        Some((result, _)) => vm.push_onto_stack(result),
+        None => {
+            let type_name = vm.get_pyobj_type_name(operand);
+            raise_type_error(vm, format!("bad operand type for unary {}: '{}'", symbol, type_name));
+        }
+    }
+    vm.decrease_refcount(operand);
+}
+
+pub fn handle_unary_not(vm: &VM) {
+    let operand = vm.pop_stack();
+    let result = if vm.is_truthy(operand) {
+        vm.builtin_type_addrs.false_val
+    } else {
+        vm.builtin_type_addrs.true_val
+    };
+    vm.decrease_refcount(operand);
+    vm.push_onto_stack(result);
+}
+
 //returns true if jumped
 pub fn handle_jump_if_false_pop(vm: &VM, destination: usize) -> bool {
     let stack_top = vm.pop_stack();
-    let raw_value = vm.get_raw_data_of_pyobj(stack_top);
-
-    if let BuiltInTypeData::Int(x) = raw_value {
-        let result = if *x == 0 {
-            vm.set_pc(destination);
-            true
-        } else {
-            false
-        };
-        vm.decrease_refcount(stack_top);
-        return result;
+    let result = if vm.is_truthy(stack_top) {
+        false
     } else {
-        let (as_boolean, _) = vm.call_method(stack_top, "__bool__", PositionalParameters::empty()).unwrap();
-        let raw_value = vm.get_raw_data_of_pyobj(as_boolean).take_int();
-        let result = if raw_value == 0 {
-            vm.set_pc(destination);
-            true
-        } else {
-            false
-        };
-        vm.decrease_refcount(stack_top);
-        return result;
+        vm.set_pc(destination);
+        true
+    };
+    release_if_temporary(vm, stack_top);
+    return result;
+}
+
+//loads don't increase the refcount, so only values nobody owns (like the result of an expression) can be released
+//when popped, otherwise a variable used as a condition would be deallocated
+fn release_if_temporary(vm: &VM, addr: MemoryAddress) {
+    if vm.get_refcount(addr) == 0 {
+        vm.decrease_refcount(addr);
     }
 }
 
+//returns true if jumped. The value stays on the stack when jumping, it is the result of the and/or expression
+pub fn handle_jump_or_pop(vm: &VM, skip: usize, jump_if: bool) -> bool {
+    let stack_top = vm.top_stack();
+    if vm.is_truthy(stack_top) == jump_if {
+        vm.set_pc(vm.get_pc() + 1 + skip);
+        return true;
+    }
+    let popped = vm.pop_stack();
+    release_if_temporary(vm, popped);
+    return false;
+}
+
 pub fn handle_build_list(vm: &VM, size: usize) {
     let mut elements: Vec<MemoryAddress> = vec![];
     for _ in 0..size {
         elements.push(vm.pop_stack());
     }
     elements.reverse();
+    for element in elements.iter() {
+        vm.increase_refcount(*element);
+    }
 
     //This bypasses the list.__new__ function
     let built_list = vm.allocate_type_byaddr_raw(
@@ -568,6 +785,206 @@ pub fn handle_build_list(vm: &VM, size: usize) {
     vm.push_onto_stack(built_list);
 }
 
+pub fn handle_build_tuple(vm: &VM, size: usize) {
+    let mut elements: Vec<MemoryAddress> = vec![];
+    for _ in 0..size {
+        elements.push(vm.pop_stack());
+    }
+    elements.reverse();
+    for element in elements.iter() {
+        vm.increase_refcount(*element);
+    }
+
+    let built_tuple = vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.tuple,
+        BuiltInTypeData::Tuple(elements),
+    );
+
+    vm.push_onto_stack(built_tuple);
+}
+
+//ascii() is repr() with every non-ascii char escaped
+fn escape_non_ascii(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        let code = c as u32;
+        if c.is_ascii() {
+            escaped.push(c);
+        } else if code < 0x100 {
+            escaped.push_str(&format!("\\x{:02x}", code));
+        } else if code < 0x10000 {
+            escaped.push_str(&format!("\\u{:04x}", code));
+        } else {
+            escaped.push_str(&format!("\\U{:08x}", code));
+        }
+    }
+    return escaped;
+}
+
+pub fn handle_format_value(vm: &VM, conversion: Option<char>, has_spec: bool) {
+    let spec = if has_spec {
+        vm.pop_stack()
+    } else {
+        vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(String::new()))
+    };
+    let value = vm.pop_stack();
+
+    let converted = match conversion {
+        Some('s') => vm.call_str(value),
+        Some(c) => {
+            let (repr, _) = vm
+                .call_method(value, "__repr__", PositionalParameters::empty())
+                .unwrap();
+            if c == 'a' {
+                let escaped = escape_non_ascii(vm.get_raw_data_of_pyobj(repr).take_string());
+                vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(escaped))
+            } else {
+                repr
+            }
+        }
+        None => value,
+    };
+    vm.push_onto_stack(vm.call_format(converted, spec));
+}
+
+pub fn handle_build_string(vm: &VM, number_parts: usize) {
+    let mut parts: Vec<MemoryAddress> = vec![];
+    for _ in 0..number_parts {
+        parts.push(vm.pop_stack());
+    }
+    let mut result = String::new();
+    for part in parts.iter().rev() {
+        result.push_str(vm.get_raw_data_of_pyobj(*part).take_string());
+    }
+    let built_string = vm.allocate_type_byaddr_raw(vm.builtin_type_addrs.string, BuiltInTypeData::String(result));
+    vm.push_onto_stack(built_string);
+}
+
+pub fn handle_build_set(vm: &VM, size: usize) {
+    let mut elements: Vec<MemoryAddress> = vec![];
+    for _ in 0..size {
+        elements.push(vm.pop_stack());
+    }
+    elements.reverse();
+
+    let built_set = vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.set,
+        BuiltInTypeData::Set(vec![]),
+    );
+    //goes through set_add so that repeated elements are kept once, the first one wins
+    for element in elements {
+        if set_add(vm, built_set, element).is_some() {
+            return;
+        }
+    }
+
+    vm.push_onto_stack(built_set);
+}
+
+pub fn handle_build_dict(vm: &VM, size: usize) {
+    let mut entries: Vec<(MemoryAddress, MemoryAddress)> = vec![];
+    for _ in 0..size {
+        let value = vm.pop_stack();
+        let key = vm.pop_stack();
+        entries.push((key, value));
+    }
+    entries.reverse();
+
+    let built_dict = vm.allocate_type_byaddr_raw(
+        vm.builtin_type_addrs.dict,
+        BuiltInTypeData::Dict(vec![]),
+    );
+    //goes through dict_set so that repeated keys keep the last value, like CPython
+    for (key, value) in entries {
+        if dict_set(vm, built_dict, key, value).is_some() {
+            return;
+        }
+    }
+
+    vm.push_onto_stack(built_dict);
+}
+
+//calls __next__ on the iterator at TOS and pushes the result, returns true when the iterator is exhausted
+pub fn handle_for_iter(vm: &VM) -> bool {
+    let iterator = vm.top_stack();
+    let (next, popped_frame) = vm.call_method(iterator, "__next__", PositionalParameters::empty()).unwrap();
+
+    //This effectivelly catches the exception. This is weird in python: why 
+    //use an ***exception*** to stop iteration? Makes no sense!
+    if let Some(exception_addr) = popped_frame.exception {
+        if vm.is_stop_iteration(exception_addr) {
+            return true;
+        }
+        //anything else propagates out of the loop, to a handler or out of the frame
+        vm.raise_exception(exception_addr);
+        return false;
+    }
+    vm.push_onto_stack(next);
+    return false;
+}
+
+//except clauses: the exception is an instance of the type or one of its subtypes, or the type itself
+//when a class was raised, like raise StopIteration. A tuple matches if any of its types does
+fn exception_matches(vm: &VM, exception: MemoryAddress, expected: MemoryAddress) -> bool {
+    if let Some(BuiltInTypeData::Tuple(types)) = vm.get_pyobj_byaddr(expected).try_get_builtin() {
+        return types.clone().into_iter().any(|t| exception_matches(vm, exception, t));
+    }
+    if exception == expected {
+        return true;
+    }
+    let mut type_addr = Some(vm.get_pyobj_type_addr(exception));
+    while let Some(addr) = type_addr {
+        if addr == expected {
+            return true;
+        }
+        type_addr = match &vm.get_pyobj_byaddr(addr).structure {
+            PyObjectStructure::Type { supertype, .. } => *supertype,
+            _ => None,
+        };
+    }
+    return false;
+}
+
+//an exception raised while another one is being handled keeps it as __context__, like
+//"During handling of the above exception, another exception occurred". Only the first frame
+//the exception goes through links it, and re-raising the handled exception links nothing
+fn link_exception_context(vm: &VM, exception: MemoryAddress) {
+    let handled = match vm.get_handled_exception() {
+        Some(handled) if handled != exception => handled,
+        _ => return,
+    };
+    //raise StopIteration raises the class itself, which must not get the attribute
+    if let PyObjectStructure::Type { .. } = vm.get_pyobj_byaddr(exception).structure {
+        return;
+    }
+    if vm.get_obj_property(exception, "__context__").is_none() {
+        break_context_cycle(vm, handled, exception);
+        vm.set_attribute(exception, "__context__", handled);
+        vm.increase_refcount(handled);
+    }
+}
+
+//like cpython: when the exception is already in the chain of the one being handled,
+//cut the chain there so it doesn't become its own context
+fn break_context_cycle(vm: &VM, handled: MemoryAddress, exception: MemoryAddress) {
+    let none = vm.special_values[&SpecialValue::NoneValue];
+    let mut current = handled;
+    let mut seen = vec![];
+    while let Some(context) = vm.get_obj_property(current, "__context__").filter(|addr| *addr != none) {
+        if context == exception {
+            vm.set_attribute(current, "__context__", none);
+            vm.decrease_refcount(context);
+            return;
+        }
+        //a cycle that doesn't go through the exception was already there
+        if seen.contains(&context) {
+            return;
+        }
+        seen.push(context);
+        current = context;
+    }
+}
+
 pub fn handle_jump_unconditional(vm: &VM, destination: usize) {
     vm.set_pc(destination);
 }
@@ -576,24 +993,72 @@ pub fn handle_store_attr(vm: &VM, code: &CodeObjectContext, attr_name: usize) {
     let obj = vm.pop_stack();
     let value = vm.pop_stack();
     let name = &code.code.names[attr_name];
+    //assigning to a property calls its setter instead of storing on the instance
+    if let Some(BuiltInTypeData::ClassInstance) = vm.get_pyobj_byaddr(obj).try_get_builtin() {
+        let type_addr = vm.get_pyobj_type_addr(obj);
+        if let Some(attr_addr) = vm.get_method_addr_byname(type_addr, name) {
+            if vm.get_pyobj_type_addr(attr_addr) == vm.builtin_type_addrs.property {
+                let fset = vm.get_obj_property(attr_addr, "fset").unwrap();
+                if fset == vm.special_values[&SpecialValue::NoneValue] {
+                    panic!("AttributeError: property '{}' of '{}' object has no setter", name, vm.get_type_name(type_addr));
+                }
+                let (_, frame) = vm.run_function(PositionalParameters { params: vec![obj, value] }, fset, None);
+                if let Some(exception) = frame.exception {
+                    vm.raise_exception(exception);
+                }
+                return;
+            }
+        }
+    }
     vm.set_attribute(obj, name, value);
     vm.increase_refcount(obj);
     vm.increase_refcount(value);
 }
 
+//inside a try block the handler takes over, otherwise finish execution immediately, push None on stack
+fn handle_raised_exception(vm: &VM, code: &CodeObjectContext, exception: MemoryAddress) {
+    link_exception_context(vm, exception);
+    if vm.jump_to_exception_handler() {
+        return;
+    }
+    vm.push_onto_stack(vm.special_values[&SpecialValue::NoneValue]);
+    let instructions_len = code.code.instructions.len();
+    vm.set_pc(instructions_len);
+}
+
 pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
+    if vm.take_interrupt() {
+        let exception = raise_keyboard_interrupt(vm);
+        handle_raised_exception(vm, code, exception);
+        return;
+    }
     let mut advance_pc = true;
     let instruction = code.code.instructions.get(vm.get_pc()).unwrap();
+    if let Some(hook) = vm.trace_hook.borrow().as_ref() {
+        hook(vm.get_pc(), instruction, vm.stack.borrow().last().unwrap().stack.len());
+    }
+    let started = if vm.profile.borrow().is_some() { Some(std::time::Instant::now()) } else { None };
     //println!(">> {:?} {:?} at {:?}", vm.get_pc(), instruction, code.code.objname);
     //vm.print_stack();
     match instruction {
         Instruction::LoadConst(c) => handle_load_const(vm, code, *c),
         Instruction::CallFunction { number_arguments } => handle_function_call(vm, *number_arguments),
+        Instruction::CallFunctionKw { number_arguments, keyword_names } => {
+            handle_function_call_kw(vm, *number_arguments, keyword_names)
+        }
         Instruction::LoadName(name) => handle_load_name(vm, code, *name),
         Instruction::LoadGlobal(name) => handle_load_global(vm, code, *name),
+        Instruction::StoreGlobal(name) => handle_store_global(vm, code, *name),
         Instruction::LoadAttr(name) => handle_load_attr(vm, name),
         Instruction::StoreName(name) => handle_store_name(vm, *name),
+        Instruction::DeleteName(name) => handle_delete_name(vm, code, *name),
+        Instruction::DeleteGlobal(name) => handle_delete_global(vm, code, *name),
+        Instruction::UnaryPositive => handle_unary_operator(vm, "__pos__", "+"),
+        Instruction::UnaryNegative => handle_unary_operator(vm, "__neg__", "-"),
+        Instruction::UnaryNot => handle_unary_not(vm),
+        Instruction::UnaryInvert => handle_unary_operator(vm, "__invert__", "~"),
         Instruction::BinaryAdd => handle_binary_add(vm),
+        Instruction::InplaceAdd => handle_inplace_add(vm),
         Instruction::BinaryModulus => handle_binary_mod(vm),
         Instruction::BinarySubtract => handle_binary_sub(vm),
         Instruction::BinaryMultiply => handle_binary_mul(vm),
@@ -603,13 +1068,38 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
         Instruction::CompareGreaterEquals => handle_compare_greater_eq(vm),
         Instruction::CompareEquals => handle_compare_equals(vm),
         Instruction::CompareNotEquals => handle_compare_not_eq(vm),
+        Instruction::CompareIn => handle_compare_in(vm, false),
+        Instruction::CompareNotIn => handle_compare_in(vm, true),
         Instruction::BinaryTrueDivision => handle_binary_truediv(vm),
+        Instruction::BinaryFloorDivision => handle_binary_floordiv(vm),
+        Instruction::BinaryPower => handle_binary_power(vm),
         Instruction::JumpIfFalseAndPopStack(destination) => {
             advance_pc = !handle_jump_if_false_pop(vm, *destination)
         }
+        Instruction::JumpIfFalseOrPop(skip) => {
+            advance_pc = !handle_jump_or_pop(vm, *skip, false)
+        }
+        Instruction::JumpIfTrueOrPop(skip) => {
+            advance_pc = !handle_jump_or_pop(vm, *skip, true)
+        }
         Instruction::BuildList { number_elements } => {
             handle_build_list(vm, *number_elements)
         }
+        Instruction::BuildTuple { number_elements } => {
+            handle_build_tuple(vm, *number_elements)
+        }
+        Instruction::BuildDict { number_entries } => {
+            handle_build_dict(vm, *number_entries)
+        }
+        Instruction::BuildSet { number_elements } => {
+            handle_build_set(vm, *number_elements)
+        }
+        Instruction::FormatValue { conversion, has_spec } => {
+            handle_format_value(vm, *conversion, *has_spec)
+        }
+        Instruction::BuildString { number_parts } => {
+            handle_build_string(vm, *number_parts)
+        }
         Instruction::JumpUnconditional(destination) => {
             handle_jump_unconditional(vm, *destination);
             advance_pc = false;
@@ -643,7 +1133,6 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
             } else {
                 vm.allocate_user_defined_function(codeobj, qualname.clone(), vec![])
             };
-            vm.add_to_module(MAIN_MODULE, qualname.as_str(), function_addr);
             vm.push_onto_stack(function_addr);
         }
         Instruction::MakeClass => {
@@ -665,12 +1154,14 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
             
             //and observe what changed in the current stack frame namespace 
             let namespace_values = popped_stack_frame.local_namespace;
-            for (index, name) in class_code.code.names.iter().enumerate() {
+            //names past the local slots are globals the class body only reads, like a decorator
+            for (name, value) in class_code.code.names.iter().zip(namespace_values.iter()) {
                 //Insert the value as-is in the namespace
-                namespace.insert(name.clone(), namespace_values[index]);
+                namespace.insert(name.clone(), *value);
             }
 
-            let type_addr = vm.create_type(MAIN_MODULE, &class_name.clone(), None);
+            //the class is bound to its name by the store instruction that follows
+            let type_addr = vm.allocate_type(&class_name, None);
 
             //Registers the regular functions on the type, even those that take the self parameter
             //They will be accessed using `ClassName.function_name`
@@ -683,9 +1174,29 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
                 let instance = method_vm.allocate_type_byaddr_raw(type_addr, BuiltInTypeData::ClassInstance);
                 method_vm.increase_refcount(instance);
                 method_vm.increase_refcount(instance);
+                if method_vm.get_method_addr_byname(type_addr, "__del__").is_some() {
+                    method_vm.finalizable.borrow_mut().insert(instance);
+                }
 
-                method_vm.call_method(instance, "__init__", call_params.params);
-                
+                let has_arguments = call_params.params.len() > 0 || !call_params.keyword_params.params.is_empty();
+                match method_vm.call_method_kw(instance, "__init__", call_params.params, call_params.keyword_params) {
+                    //the instance is the result of the call, whatever __init__ returned
+                    Some((init_result, frame)) => {
+                        if let Some(exception) = frame.exception {
+                            method_vm.raise_exception(exception);
+                            return exception;
+                        }
+                        if init_result != method_vm.special_values[&SpecialValue::NoneValue] {
+                            let type_name = method_vm.get_pyobj_type_name(init_result).to_string();
+                            return raise_type_error(method_vm, format!("__init__() should return None, not '{}'", type_name));
+                        }
+                    }
+                    None if has_arguments => {
+                        let class_name = method_vm.get_type_name(type_addr).to_string();
+                        return raise_type_error(method_vm, format!("{}() takes no arguments", class_name));
+                    }
+                    None => {}
+                }
                 return instance;
             });
 
@@ -694,6 +1205,44 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
         Instruction::PopTop => {
             vm.pop_stack();
         }
+        Instruction::DupTop => {
+            vm.push_onto_stack(vm.top_stack());
+        }
+        Instruction::DupTopTwo => {
+            let top = vm.pop_stack();
+            let second = vm.top_stack();
+            vm.push_onto_stack(top);
+            vm.push_onto_stack(second);
+            vm.push_onto_stack(top);
+        }
+        Instruction::RotTwo => {
+            let top = vm.pop_stack();
+            let second = vm.pop_stack();
+            vm.push_onto_stack(top);
+            vm.push_onto_stack(second);
+        }
+        Instruction::RotThree => {
+            let top = vm.pop_stack();
+            let second = vm.pop_stack();
+            let third = vm.pop_stack();
+            vm.push_onto_stack(top);
+            vm.push_onto_stack(third);
+            vm.push_onto_stack(second);
+        }
+        Instruction::StoreSubscript => {
+            let index_value = vm.pop_stack();
+            let indexed_value = vm.pop_stack();
+            let value = vm.pop_stack();
+            let result = vm.call_method(indexed_value, "__setitem__", PositionalParameters::from_stack_popped(&[value, index_value]));
+            match result {
+                Some((_, popped_frame)) => {
+                    if let Some(exception) = popped_frame.exception {
+                        vm.raise_exception(exception);
+                    }
+                }
+                None => panic!("'{}' object does not support item assignment", vm.get_pyobj_type_name(indexed_value)),
+            }
+        }
         Instruction::ReturnValue => {
             let top = vm.top_stack();
             //increase counter because it is being used by the current function
@@ -701,6 +1250,12 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
             let instructions_len = code.code.instructions.len();
             vm.set_pc(instructions_len);
         }
+        Instruction::YieldValue => {
+            //stops the frame like a return, the generator pops the yielded value and resumes after this instruction
+            vm.suspend_stack_frame(vm.get_pc() + 1);
+            vm.set_pc(code.code.instructions.len());
+            advance_pc = false;
+        }
         Instruction::StoreAttr(attr_name) => {
             handle_store_attr(vm, code, *attr_name);
         }
@@ -708,47 +1263,111 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
             let index_value = vm.pop_stack();
             let indexed_value = vm.pop_stack();
 
-            //for now we only accept integer indexing on lists and strings
-            let index_int = vm.get_raw_data_of_pyobj(index_value).take_int();
-            let list = vm.get_raw_data_of_pyobj(indexed_value).take_list();
-
-            vm.push_onto_stack(list[index_int as usize]);
+            //fast path for integer indexing on lists, __getitem__ raises the IndexError when out of range
+            let fast_path = match (vm.get_raw_data_of_pyobj(indexed_value), vm.get_pyobj_byaddr(index_value).try_get_builtin()) {
+                (BuiltInTypeData::List(list), Some(BuiltInTypeData::Int(index))) => list.get(*index as usize).copied(),
+                _ => None,
+            };
+            if let Some(value) = fast_path {
+                vm.push_onto_stack(value);
+            } else {
+                let (result, popped_frame) = vm
+                    .call_method(indexed_value, "__getitem__", PositionalParameters::single(index_value))
+                    .unwrap_or_else(|| panic!("'{}' object is not subscriptable", vm.get_pyobj_type_name(indexed_value)));
+                if let Some(exception) = popped_frame.exception {
+                    vm.raise_exception(exception);
+                }
+                vm.push_onto_stack(result);
+            }
+        }
+        Instruction::ImportName(name) => {
+            match vm.find_module(name) {
+                Some(module) => vm.push_onto_stack(module),
+                None => panic!("ModuleNotFoundError: No module named '{}'", name),
+            }
         }
         Instruction::Raise => {
             let exception_value = vm.pop_stack();
             vm.raise_exception(exception_value);
         }
+        Instruction::RaiseFrom => {
+            let cause = vm.pop_stack();
+            let exception_value = vm.pop_stack();
+            //like CPython, an explicit cause (even None) hides the context when the exception is reported
+            if !matches!(vm.get_pyobj_byaddr(exception_value).structure, PyObjectStructure::Type { .. }) {
+                vm.set_attribute(exception_value, "__cause__", cause);
+                vm.increase_refcount(cause);
+                let suppress_context = vm.builtin_type_addrs.true_val;
+                vm.set_attribute(exception_value, "__suppress_context__", suppress_context);
+            }
+            vm.raise_exception(exception_value);
+        }
+        Instruction::Reraise => {
+            match vm.get_handled_exception() {
+                Some(exception) => vm.raise_exception(exception),
+                None => panic!("RuntimeError: No active exception to reraise"),
+            }
+        }
+        Instruction::RaiseAssertionError { has_message } => {
+            let message = if *has_message { Some(vm.pop_stack()) } else { None };
+            raise_assertion_error(vm, message);
+        }
+        Instruction::SetupExcept(handler) => {
+            vm.push_handler_block(*handler);
+        }
+        Instruction::PopBlock => {
+            vm.pop_handler_block();
+        }
+        Instruction::PopExcept => {
+            vm.pop_handled_exception();
+        }
+        Instruction::JumpIfNotExcMatch(destination) => {
+            let expected = vm.pop_stack();
+            if !exception_matches(vm, vm.top_stack(), expected) {
+                vm.set_pc(*destination);
+                advance_pc = false;
+            }
+        }
         Instruction::ForIter(end_ptr) => {
-            //TOS is the iterator object
-            let iterator = vm.top_stack();
-
-            //this assumes the iterator is on the top of the call already
-            let (next, popped_frame) = vm.call_method(iterator, "__next__", PositionalParameters::empty()).unwrap();
-            
-            //This effectivelly catches the exception. This is weird in python: why 
-            //use an ***exception*** to stop iteration? Makes no sense!
-            if let Some(exception_addr) = popped_frame.exception {
-                if exception_addr == vm.special_values[&SpecialValue::StopIterationType] {
-                    vm.set_pc(*end_ptr);
-                    advance_pc = false;
-                }
-            } else {
-                vm.push_onto_stack(next);   
+            if handle_for_iter(vm) {
+                vm.set_pc(*end_ptr);
+                advance_pc = false;
             }
-
-            //unimplemented!();
+        }
+        Instruction::ForIterForward(skip) => {
+            if handle_for_iter(vm) {
+                vm.set_pc(vm.get_pc() + 1 + skip);
+                advance_pc = false;
+            }
+        }
+        Instruction::ListAppend(depth) => {
+            let value = vm.pop_stack();
+            let list = vm.get_stack_offset(1 - *depth as isize);
+            vm.get_raw_data_of_pyobj_mut(list).take_list_mut().push(value);
+        }
+        Instruction::PopJumpForwardIfFalse(skip) => {
+            advance_pc = !handle_jump_if_false_pop(vm, vm.get_pc() + 1 + skip)
+        }
+        Instruction::JumpForward(skip) => {
+            vm.set_pc(vm.get_pc() + 1 + skip);
+            advance_pc = false;
+        }
+        Instruction::JumpBackward(distance) => {
+            vm.set_pc(vm.get_pc() - distance);
+            advance_pc = false;
         }
         _ => {
             panic!("Unsupported instruction: {:?}", instruction);
         }
     }
     
-    if let Some(_) = vm.get_current_exception() {
-        //if an exception happened, then finish execution immediately, push None on stack
-        vm.push_onto_stack(vm.special_values[&SpecialValue::NoneValue]);
-        let instructions_len = code.code.instructions.len();
-        vm.set_pc(instructions_len);
-        advance_pc = false;
+    if let Some(started) = started {
+        vm.record_profile(instruction, started.elapsed());
+    }
+
+    if let Some(exception) = vm.get_current_exception() {
+        handle_raised_exception(vm, code, exception);
+        return;
     }
 
 
@@ -759,6 +1378,7 @@ pub fn execute_next_instruction(vm: &VM, code: &CodeObjectContext) {
 }
 
 pub fn execute_code_object(vm: &VM, code: &CodeObjectContext) {
+    vm.set_local_names(&code.code.names);
     loop {
         if vm.get_pc() >= code.code.instructions.len() {
             return;
@@ -769,7 +1389,7 @@ pub fn execute_code_object(vm: &VM, code: &CodeObjectContext) {
 }
 
 
-fn register_codeobj_consts(vm: &VM, codeobj: &CodeObject) -> CodeObjectContext {
+pub fn register_codeobj_consts(vm: &VM, codeobj: &CodeObject) -> CodeObjectContext {
     let mut consts = vec![];
     for c in codeobj.consts.iter() {
         let memaddr = get_const_memaddr(vm, c);
@@ -781,48 +1401,86 @@ fn register_codeobj_consts(vm: &VM, codeobj: &CodeObject) -> CodeObjectContext {
     }
 }
 
-#[allow(dead_code)]
-//#[cfg(test)]
-fn print_codeobj(codeobj: &CodeObject, codeobj_name: Option<String>) {
-    for inst in codeobj.consts.iter() {
-        if let Const::CodeObject(obj) = inst {
-            print_codeobj(obj, Some(obj.objname.clone()));
-        }
-    }
-
-    println!("\nInstructions of code object {:?}", codeobj_name);
+//one line per instruction, with the const or name it refers to, followed by the code objects in its consts
+pub fn disassemble(codeobj: &CodeObject) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    writeln!(out, "Instructions of code object {}", codeobj.objname).unwrap();
     for (index, inst) in codeobj.instructions.iter().enumerate() {
         if let Instruction::LoadConst(n) = inst {
             let constval =  &codeobj.consts[*n];
             if let Const::CodeObject(obj) = constval {
-                println!("{} - {:?} => code object {}", index, inst, obj.objname);
+                writeln!(out, "{} - {:?} => code object {}", index, inst, obj.objname).unwrap();
             } else {
-                println!("{} - {:?} => constval = {:?}", index, inst, constval);
+                writeln!(out, "{} - {:?} => constval = {:?}", index, inst, constval).unwrap();
             }
         } 
-        else if let Instruction::LoadGlobal(n) = inst {
-            println!("{} - {:?} => global name = {:?}", index, inst, &codeobj.names[*n]);
-        }
-        else if let Instruction::LoadName(n) = inst {
-            println!("{} - {:?} => name = {:?}", index, inst, &codeobj.names[*n]);
+        else if let Instruction::LoadGlobal(n) | Instruction::StoreGlobal(n) | Instruction::DeleteGlobal(n) = inst {
+            writeln!(out, "{} - {:?} => global name = {:?}", index, inst, &codeobj.names[*n]).unwrap();
         }
-        else if let Instruction::StoreName(n) = inst {
-            println!("{} - {:?} => name = {:?}", index, inst, &codeobj.names[*n]);
-        }
-        else if let Instruction::StoreAttr(n) = inst {
-            println!("{} - {:?} => name = {:?}", index, inst, &codeobj.names[*n]);
+        else if let Instruction::LoadName(n) | Instruction::StoreName(n) | Instruction::DeleteName(n) | Instruction::StoreAttr(n) = inst {
+            writeln!(out, "{} - {:?} => name = {:?}", index, inst, &codeobj.names[*n]).unwrap();
         }
         else {
-            println!("{} - {:?}", index, inst);
+            writeln!(out, "{} - {:?}", index, inst).unwrap();
         }
     }
+
+    for inst in codeobj.consts.iter() {
+        if let Const::CodeObject(obj) = inst {
+            out.push('\n');
+            out.push_str(&disassemble(obj));
+        }
+    }
+    return out;
 }
 
 pub fn execute_program(vm: &mut VM, program: Program) {
-    //print_codeobj(&program.code_objects[0], None);
-
     let main_code = program.code_objects.iter().find(|x| x.main).unwrap();
     let main_codeobj_ctx = register_codeobj_consts(vm, main_code);
      
     execute_code_object(vm, &main_codeobj_ctx);
-}
\ No newline at end of file
+}
+
+thread_local! {
+    static CATCHING_PANICS: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+//The default hook prints Rust's "thread 'main' panicked at" report before catch_unwind gets the payload.
+//Panics caught by try_execute_program are reported by the caller, so the hook stays quiet for those.
+fn install_quiet_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !CATCHING_PANICS.with(|catching| catching.get()) {
+                previous(info);
+            }
+        }));
+    });
+}
+
+//Builtins still panic on a lot of bad input. This keeps those panics from unwinding into the host:
+//the message comes back as an Err and the VM is reset to an empty __main__ frame, keeping globals.
+pub fn try_execute_program(vm: &mut VM, program: Program) -> Result<(), String> {
+    install_quiet_panic_hook();
+    let was_catching = CATCHING_PANICS.with(|catching| catching.replace(true));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        execute_program(vm, program);
+    }));
+    CATCHING_PANICS.with(|catching| catching.set(was_catching));
+    match result {
+        Ok(()) => Ok(()),
+        Err(payload) => {
+            vm.clear_stacks();
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                String::from("interpreter panicked")
+            };
+            Err(message)
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::runtime::vm::VM;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+static INTERRUPT_FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+extern "C" fn handle_sigint(_signal: libc::c_int) {
+    //only an atomic store here, the interpreter raises KeyboardInterrupt on its next instruction
+    if let Some(flag) = INTERRUPT_FLAG.get() {
+        flag.store(true, Ordering::Relaxed);
+    }
+}
+
+//makes ctrl-c raise KeyboardInterrupt in the given VM instead of killing the process
+pub fn install_interrupt_handler(vm: &VM) {
+    if INTERRUPT_FLAG.set(vm.interrupt.clone()).is_err() {
+        return;
+    }
+    unsafe {
+        libc::signal(libc::SIGINT, handle_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t);
+    }
+}
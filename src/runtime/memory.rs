@@ -1,5 +1,5 @@
 use crate::runtime::datamodel::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
 
 
@@ -190,15 +190,33 @@ impl Memory for VecRefCellMemory {
 pub type MemoryAddress = *mut PyObject;
 pub struct UnsafeMemory {
     pub recently_deallocated_addr: RefCell<Vec<*mut PyObject>>,
+    //addresses are heap pointers that change between runs, so id(), identity hashes and default reprs
+    //use the order in which the address was first allocated instead. Reused addresses keep their number.
+    pub object_ids: RefCell<BTreeMap<MemoryAddress, usize>>,
+    pub next_object_id: Cell<usize>,
 }
 
 impl UnsafeMemory {
     pub fn new() -> Self {
         Self {
-            recently_deallocated_addr: RefCell::new(vec![])
+            recently_deallocated_addr: RefCell::new(vec![]),
+            object_ids: RefCell::new(BTreeMap::new()),
+            next_object_id: Cell::new(1),
         }
     }
 
+    pub fn object_id(&self, address: MemoryAddress) -> usize {
+        match self.object_ids.borrow().get(&address) {
+            Some(id) => *id,
+            None => panic!("Attempt to get the id of unallocated memory {:p}", address),
+        }
+    }
+
+    //whether the address can still be read, instead of panicking like check_mem
+    pub fn is_valid(&self, address: MemoryAddress) -> bool {
+        !address.is_null() && !self.recently_deallocated_addr.borrow().contains(&address)
+    }
+
     pub fn check_mem(&self, address: MemoryAddress) {
         if self.recently_deallocated_addr.borrow().contains(&address) {
             panic!("Trying to get recently deallocated memory {:p} {:?}", address, unsafe{ &*address})
@@ -258,7 +276,11 @@ impl Memory for UnsafeMemory {
             None => {
                 let boxed = Box::new(data);
                 let mutref = Box::leak(boxed); //hehe
-                return mutref as *mut PyObject;
+                let address = mutref as *mut PyObject;
+                let id = self.next_object_id.get();
+                self.next_object_id.set(id + 1);
+                self.object_ids.borrow_mut().insert(address, id);
+                return address;
             }
         }
     }
@@ -2,4 +2,5 @@ pub mod datamodel;
 pub mod memory;
 #[macro_use]
 pub mod vm;
-pub mod interpreter;
\ No newline at end of file
+pub mod interpreter;
+pub mod interrupt;
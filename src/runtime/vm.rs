@@ -2,7 +2,13 @@ use crate::bytecode::program::*;
 use crate::runtime::datamodel::*;
 use crate::runtime::memory::*;
 use std::cell::Cell;
-use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+pub const SMALL_INT_MIN: i128 = -5;
+pub const SMALL_INT_MAX: i128 = 256;
 
 /* this is done by somewhat following the python data model in https://docs.python.org/3/reference/datamodel.html */
 
@@ -54,10 +60,28 @@ impl PositionalParameters {
     }
 }
 
+#[derive(Clone)]
+pub struct KeywordParameters {
+    pub params: Vec<(String, MemoryAddress)>,
+}
+
+impl KeywordParameters {
+    pub fn empty() -> KeywordParameters {
+        KeywordParameters {
+            params: vec![]
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<MemoryAddress> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, addr)| *addr)
+    }
+}
+
 pub struct CallParams<'a> {
     pub func_address: MemoryAddress,
     pub func_name: Option<&'a str>,
     pub params: PositionalParameters,
+    pub keyword_params: KeywordParameters,
 }
 
 pub struct FunctionCallParams {
@@ -109,28 +133,113 @@ pub struct BuiltinTypeAddresses {
     pub float: MemoryAddress,
     pub boolean: MemoryAddress,
     pub string: MemoryAddress,
+    pub string_iterator: MemoryAddress,
+    pub bytes: MemoryAddress,
     pub list: MemoryAddress,
+    pub tuple: MemoryAddress,
+    pub dict: MemoryAddress,
+    pub set: MemoryAddress,
+    pub frozenset: MemoryAddress,
+    pub classmethod: MemoryAddress,
+    pub staticmethod: MemoryAddress,
+    pub property: MemoryAddress,
+    pub base_exception: MemoryAddress,
+    pub exception: MemoryAddress,
     pub index_err: MemoryAddress,
+    pub key_err: MemoryAddress,
+    pub type_err: MemoryAddress,
+    pub assertion_err: MemoryAddress,
+    pub zero_division_err: MemoryAddress,
+    pub value_err: MemoryAddress,
+    pub name_err: MemoryAddress,
+    pub unbound_local_err: MemoryAddress,
+    pub syntax_err: MemoryAddress,
+    pub keyboard_interrupt: MemoryAddress,
     pub code_object: MemoryAddress,
+    pub generator: MemoryAddress,
     pub true_val: MemoryAddress,
     pub false_val: MemoryAddress,
 }
 
-#[derive(Debug)]
+//a try block the frame is running, set up by SetupExcept
+#[derive(Debug, PartialEq, Eq)]
+pub struct HandlerBlock {
+    pub handler: usize,
+    pub stack_depth: usize,
+    pub handled_depth: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct StackFrame {
     pub function_name: String,
     pub local_namespace: Vec<MemoryAddress>, //the compiler knows which index will be loaded at compile time, so no need for a HashMap here.
     pub stack: Vec<MemoryAddress>,
     pub exception: Option<MemoryAddress>,
     pub prog_counter: Cell<usize>,
+    pub local_names: Vec<String>, //names of the code object running in this frame, indexed the same way as local_namespace
+    pub resume_pc: Option<usize>, //set when a generator yields, where it continues when resumed
+    pub handler_blocks: Vec<HandlerBlock>, //innermost last
+    pub handled_exceptions: Vec<MemoryAddress>, //caught by the handlers running in this frame, innermost last
+    pub namespace: Option<Namespace>, //eval and exec called from a function: the caller's locals, used before the globals
+}
+
+//What VM::step and VM::resume stopped at
+#[derive(Debug, PartialEq, Eq)]
+pub enum StepResult {
+    Stepped,
+    //the next instruction of the loaded code object is at this offset and has a breakpoint
+    Breakpoint(usize),
+    Finished,
+}
+
+//Receives the offset of the instruction about to run, the instruction and the depth of the value stack
+pub type TraceHook = Box<dyn Fn(usize, &Instruction, usize)>;
+
+//How many times an opcode ran and for how long. The time of calls includes the code they ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpcodeProfile {
+    pub count: u64,
+    pub total_time: Duration,
+}
+
+//Where print writes to. Tests capture the output instead of writing to the process stdout.
+pub enum OutputSink {
+    Stdout,
+    Captured(String),
+}
+
+//Where input reads lines from. Tests provide the lines instead of reading the process stdin.
+pub enum InputSource {
+    Stdin,
+    Provided(VecDeque<String>),
 }
 
 pub struct VM {
     pub stack: RefCell<Vec<StackFrame>>,
+    pub stdout: RefCell<OutputSink>,
+    pub stdin: RefCell<InputSource>,
     pub memory: UnsafeMemory,
     pub builtin_type_addrs: BuiltinTypeAddresses,
     pub special_values: BTreeMap<SpecialValue, MemoryAddress>,
     pub modules: BTreeMap<String, MemoryAddress>,
+    //preallocated ints from SMALL_INT_MIN to SMALL_INT_MAX, shared by every allocation of those values
+    pub small_ints: Vec<MemoryAddress>,
+    //class instances whose type defines __del__ and that weren't finalized yet, see gc.collect()
+    pub finalizable: RefCell<HashSet<MemoryAddress>>,
+    //containers whose repr is being computed, a container found again inside itself is shown as [...]
+    pub repr_in_progress: RefCell<BTreeSet<MemoryAddress>>,
+    //same for pairs of containers being compared with __eq__
+    pub eq_in_progress: RefCell<BTreeSet<(MemoryAddress, MemoryAddress)>>,
+    //set from outside the interpreter (the SIGINT handler), checked before every instruction
+    pub interrupt: Arc<AtomicBool>,
+    //main code object of the program loaded for stepping, see load_program
+    pub loaded_code: RefCell<Option<CodeObjectContext>>,
+    //(code object name, instruction offset) pairs where resume stops
+    pub breakpoints: RefCell<BTreeSet<(String, usize)>>,
+    //called before every instruction, in any code object, see set_trace_hook
+    pub trace_hook: RefCell<Option<TraceHook>>,
+    //per opcode counts and times, only kept after enable_profiler
+    pub profile: RefCell<Option<BTreeMap<&'static str, OpcodeProfile>>>,
     //pub builtin_names: Vec<MemoryAddress>,
 }
 
@@ -145,21 +254,58 @@ impl VM {
                 stack: vec![],
                 exception: None,
                 prog_counter: Cell::new(0),
+                local_names: vec![],
+                resume_pc: None,
+                handler_blocks: vec![],
+                handled_exceptions: vec![],
+                namespace: None,
             }]),
+            stdout: RefCell::new(OutputSink::Stdout),
+            stdin: RefCell::new(InputSource::Stdin),
             memory: memory,
             special_values: BTreeMap::new(),
             modules: BTreeMap::new(),
+            small_ints: vec![],
+            finalizable: RefCell::new(HashSet::new()),
+            repr_in_progress: RefCell::new(BTreeSet::new()),
+            eq_in_progress: RefCell::new(BTreeSet::new()),
+            interrupt: Arc::new(AtomicBool::new(false)),
+            loaded_code: RefCell::new(None),
+            breakpoints: RefCell::new(BTreeSet::new()),
+            trace_hook: RefCell::new(None),
+            profile: RefCell::new(None),
             //builtin_names: vec![],
             builtin_type_addrs: BuiltinTypeAddresses {
                 int: nullptr,
                 float: nullptr,
                 boolean: nullptr,
                 string: nullptr,
+                string_iterator: nullptr,
+                bytes: nullptr,
                 list: nullptr,
+                tuple: nullptr,
+                dict: nullptr,
+                set: nullptr,
+                frozenset: nullptr,
+                classmethod: nullptr,
+                staticmethod: nullptr,
+                property: nullptr,
                 true_val: nullptr,
                 false_val: nullptr,
+                base_exception: nullptr,
+                exception: nullptr,
                 index_err: nullptr,
+                key_err: nullptr,
+                type_err: nullptr,
+                assertion_err: nullptr,
+                zero_division_err: nullptr,
+                value_err: nullptr,
+                name_err: nullptr,
+                unbound_local_err: nullptr,
+                syntax_err: nullptr,
+                keyboard_interrupt: nullptr,
                 code_object: nullptr,
+                generator: nullptr,
             },
         };
         let type_type = interpreter.allocate_and_write(PyObject {
@@ -314,6 +460,13 @@ impl VM {
         name: &str,
         supertype: Option<MemoryAddress>,
     ) -> MemoryAddress {
+        let type_address = self.allocate_type(name, supertype);
+        self.add_to_module(module, name, type_address);
+        return type_address;
+    }
+
+    //creates a type without registering it in any module
+    pub fn allocate_type(&self, name: &str, supertype: Option<MemoryAddress>) -> MemoryAddress {
         let created_type = PyObject {
             properties: BTreeMap::new(),
             type_addr: self.special_values[&SpecialValue::Type],
@@ -324,7 +477,38 @@ impl VM {
             },
             is_const: false,
         };
-        let type_address = self.allocate_and_write(created_type);
+        return self.allocate_and_write(created_type);
+    }
+
+    //an empty module that scripts can bring in with import
+    pub fn create_module(&mut self, name: &str) -> MemoryAddress {
+        let module_type = self.get_pyobj_byaddr(self.modules[BUILTIN_MODULE]).type_addr;
+        let module = self.allocate_and_write(PyObject {
+            type_addr: module_type,
+            properties: BTreeMap::new(),
+            structure: PyObjectStructure::Module {
+                name: name.to_string(),
+                global_namespace: BTreeMap::new(),
+            },
+            is_const: false,
+        });
+        self.modules.insert(name.to_owned(), module);
+        return module;
+    }
+
+    //sets __name__ in every module, which needs str to be registered: __main__ for the entry file,
+    //the module's own name for the ones scripts import
+    pub fn set_module_names(&self) {
+        for (name, module) in self.modules.iter() {
+            let name_addr = self.allocate_type_byaddr_raw(self.builtin_type_addrs.string, BuiltInTypeData::String(name.clone()));
+            self.increase_refcount(name_addr);
+            if let PyObjectStructure::Module { global_namespace, .. } = &mut self.get_pyobj_byaddr_mut(*module).structure {
+                global_namespace.insert(String::from("__name__"), name_addr);
+            }
+        }
+    }
+
+    pub fn add_to_module(&self, module: &str, name: &str, pyobject_addr: MemoryAddress) {
         let module_addr = *self.modules.get(module).unwrap();
         let pyobj = self.get_pyobj_byaddr_mut(module_addr);
         match &mut pyobj.structure {
@@ -335,8 +519,7 @@ impl VM {
                     panic!("Name already exists in module {}: {}", module, name);
                 }
                 None => {
-                    global_namespace.insert(name.to_string(), type_address);
-                    return type_address;
+                    global_namespace.insert(name.to_string(), pyobject_addr);
                 }
             },
             _ => {
@@ -348,20 +531,19 @@ impl VM {
         }
     }
 
-    pub fn add_to_module(&self, module: &str, name: &str, pyobject_addr: MemoryAddress) {
+    //adds or replaces a name in the module namespace
+    pub fn set_in_module(&self, module: &str, name: &str, pyobject_addr: MemoryAddress) {
         let module_addr = *self.modules.get(module).unwrap();
         let pyobj = self.get_pyobj_byaddr_mut(module_addr);
         match &mut pyobj.structure {
             PyObjectStructure::Module {
                 global_namespace, ..
-            } => match global_namespace.get(name) {
-                Some(_) => {
-                    panic!("Name already exists in module {}: {}", module, name);
-                }
-                None => {
-                    global_namespace.insert(name.to_string(), pyobject_addr);
+            } => {
+                self.increase_refcount(pyobject_addr);
+                if let Some(previous) = global_namespace.insert(name.to_string(), pyobject_addr) {
+                    self.decrease_refcount(previous);
                 }
-            },
+            }
             _ => {
                 panic!(
                     "Module name {} was found but it's not actually a module",
@@ -371,6 +553,33 @@ impl VM {
         }
     }
 
+    pub fn remove_from_module(&self, module: &str, name: &str) -> bool {
+        let module_addr = *self.modules.get(module).unwrap();
+        let pyobj = self.get_pyobj_byaddr_mut(module_addr);
+        match &mut pyobj.structure {
+            PyObjectStructure::Module {
+                global_namespace, ..
+            } => match global_namespace.remove(name) {
+                Some(previous) => {
+                    self.decrease_refcount(previous);
+                    true
+                }
+                None => false,
+            },
+            _ => panic!("Module name {} was found but it's not actually a module", module),
+        }
+    }
+
+    pub fn module_namespace(&self, module: &str) -> Vec<(String, MemoryAddress)> {
+        let module_addr = self.find_module(module).unwrap();
+        match &self.get_pyobj_byaddr(module_addr).structure {
+            PyObjectStructure::Module {
+                global_namespace, ..
+            } => global_namespace.iter().map(|(name, addr)| (name.clone(), *addr)).collect(),
+            _ => panic!("Object is not a module"),
+        }
+    }
+
     pub fn find_module(&self, module: &str) -> Option<MemoryAddress> {
         return self.modules.get(module).map(|addr: &MemoryAddress| *addr);
     }
@@ -456,7 +665,8 @@ impl VM {
                 *refcount = *refcount - 1;
             }
 
-            if *refcount <= 0 {
+            //objects with __del__ stay allocated: gc.collect runs it once they are unreachable
+            if *refcount <= 0 && !self.finalizable.borrow().contains(&addr) {
                 self.memory.deallocate(addr);
             }
         }
@@ -556,9 +766,24 @@ impl VM {
         type_addr: MemoryAddress,
         raw_data: BuiltInTypeData,
     ) -> MemoryAddress {
+        if let BuiltInTypeData::Int(value) = raw_data {
+            if type_addr == self.builtin_type_addrs.int && !self.small_ints.is_empty()
+                && (SMALL_INT_MIN..=SMALL_INT_MAX).contains(&value) {
+                return self.small_ints[(value - SMALL_INT_MIN) as usize];
+            }
+        }
         return self.memory.allocate_and_write_builtin(type_addr, raw_data);
     }
 
+    pub fn intern_small_ints(&mut self) {
+        let small_ints = (SMALL_INT_MIN..=SMALL_INT_MAX).map(|value| {
+            let addr = self.memory.allocate_and_write_builtin(self.builtin_type_addrs.int, BuiltInTypeData::Int(value));
+            self.make_const(addr);
+            addr
+        }).collect();
+        self.small_ints = small_ints;
+    }
+
     pub fn create_unbounded_callable_pyobj(
         &self,
         callable: PyCallable,
@@ -707,7 +932,12 @@ impl VM {
     }
     
 
-    pub fn run_function(&self, mut positional_params: PositionalParameters, 
+    pub fn run_function(&self, positional_params: PositionalParameters, 
+        function_addr: MemoryAddress, bound_addr: Option<MemoryAddress>) -> (MemoryAddress, StackFrame) {
+        self.run_function_kw(positional_params, KeywordParameters::empty(), function_addr, bound_addr)
+    }
+
+    pub fn run_function_kw(&self, mut positional_params: PositionalParameters, keyword_params: KeywordParameters,
         function_addr: MemoryAddress, bound_addr: Option<MemoryAddress>) -> (MemoryAddress, StackFrame) {
         let func_name = self.get_function_name(function_addr);
         let pyobj_func = self.try_load_function(function_addr);
@@ -728,6 +958,7 @@ impl VM {
                     func_address: function_addr,
                     func_name: name.as_deref(),//.map(|x| x.as_str()),
                     params: positional_params,
+                    keyword_params,
                 };
                 
                 let result = (code.code)(self, call_params);
@@ -736,39 +967,61 @@ impl VM {
                 (result, popped_stacked_frame)
             }
             PyObjectStructure::UserDefinedFunction {code, qualname, defaults} => {
-                let mut expected_number_args = code.code.params.len();
-                if let Some(_) = bound_addr {
-                    expected_number_args -= 1; //because self is already being passed
+                let param_names = &code.code.params;
+                //self is already being passed when the function is bound
+                let first_param = if bound_addr.is_some() { 1 } else { 0 };
+                let expected_number_args = param_names.len() - first_param;
+
+                if positional_params.len() > expected_number_args {
+                    panic!("Function {} expects at most {} parameters, but {} were provided", qualname, expected_number_args, positional_params.len());
                 }
-                
-                if defaults.len() == 0 {
-                    if expected_number_args != positional_params.params.len() {
-                        panic!("Function {} expects {} parameters, but {} were provided", qualname, code.code.params.len(), positional_params.params.len());
-                    }
-                } else {
-                    let non_default_params = expected_number_args - defaults.len();
-                    if non_default_params > positional_params.params.len() {
-                        panic!("Function {} expects {} non-default parameters, but {} were provided", qualname, code.code.params.len(), positional_params.params.len());
-                    }
 
-                    //pass the last N parameters that are necessary
-                    let to_pass = defaults.iter().skip(positional_params.params.len());
+                let mut slots: Vec<Option<MemoryAddress>> = vec![None; param_names.len()];
+                if let Some(a) = bound_addr {
+                    slots[0] = Some(a);
+                }
+                for (number, addr) in positional_params.params.iter().enumerate() {
+                    slots[first_param + number] = Some(*addr);
+                }
+                for (name, addr) in keyword_params.params.iter() {
+                    match param_names.iter().position(|p| p == name) {
+                        Some(index) if slots[index].is_some() => {
+                            panic!("Function {} got multiple values for argument {}", qualname, name)
+                        }
+                        Some(index) => slots[index] = Some(*addr),
+                        None => panic!("Function {} got an unexpected keyword argument {}", qualname, name),
+                    }
+                }
 
-                    for mem_addr in to_pass {
-                        positional_params.params.insert(0, *mem_addr);
+                //defaults belong to the last N parameters
+                let first_default = param_names.len() - defaults.len();
+                for (index, slot) in slots.iter_mut().enumerate() {
+                    if slot.is_none() {
+                        if index < first_default {
+                            panic!("Function {} missing required argument {}", qualname, param_names[index]);
+                        }
+                        *slot = Some(defaults[index - first_default]);
                     }
                 }
     
                 self.new_stack_frame(func_name);
-                if let Some(a) = bound_addr {
-                    self.bind_local(0, a); 
-                    for (number, addr) in positional_params.params.iter().enumerate() {
-                        self.bind_local(number + 1, *addr);
-                    }
-                } else {
-                    for (number, addr) in positional_params.params.iter().enumerate() {
-                        self.bind_local(number, *addr);
+                for (number, addr) in slots.iter().enumerate() {
+                    self.bind_local(number, addr.unwrap());
+                }
+
+                //calling a generator function only binds the arguments, the body runs on __next__
+                if code.code.is_generator {
+                    let frame = self.take_stack_frame();
+                    for addr in frame.local_namespace.iter() {
+                        self.increase_refcount(*addr);
                     }
+                    let generator = self.allocate_type_byaddr_raw(
+                        self.builtin_type_addrs.generator,
+                        BuiltInTypeData::Generator(GeneratorState { code: code.clone(), frame: Some(frame) }),
+                    );
+                    self.increase_refcount(generator);
+                    self.new_stack_frame(func_name);
+                    return (generator, self.pop_stack_frame());
                 }
                 
                 //what a mess
@@ -780,7 +1033,7 @@ impl VM {
                 (result_addr, popped_stacked_frame)
             }
             PyObjectStructure::BoundMethod {function_address, bound_address} => {
-                self.run_function(positional_params, *function_address, Some(*bound_address))
+                self.run_function_kw(positional_params, keyword_params, *function_address, Some(*bound_address))
             }
             _ => {
                 panic!("Not a function at addr: {:?}", function_addr);
@@ -793,20 +1046,237 @@ impl VM {
         bound_addr: MemoryAddress,
         method_name: &str,
         params: PositionalParameters,
+    ) -> Option<(MemoryAddress, StackFrame)> {
+        self.call_method_kw(bound_addr, method_name, params, KeywordParameters::empty())
+    }
+
+    pub fn call_method_kw(
+        &self,
+        bound_addr: MemoryAddress,
+        method_name: &str,
+        params: PositionalParameters,
+        keyword_params: KeywordParameters,
     ) -> Option<(MemoryAddress, StackFrame)> {
         let pyobj = self.get_pyobj_byaddr(bound_addr);
         self.get_method_addr_byname(pyobj.type_addr, method_name)
             .map(move |method_addr| {
-                self.run_function(params, method_addr, Some(bound_addr))
+                self.run_function_kw(params, keyword_params, method_addr, Some(bound_addr))
             })
     }
 
+    pub fn write_stdout(&self, text: &str) {
+        match &mut *self.stdout.borrow_mut() {
+            OutputSink::Stdout => {
+                use std::io::Write;
+                print!("{}", text);
+                std::io::stdout().flush().unwrap();
+            }
+            OutputSink::Captured(buffer) => buffer.push_str(text),
+        }
+    }
+
+    pub fn capture_stdout(&self) {
+        *self.stdout.borrow_mut() = OutputSink::Captured(String::new());
+    }
+
+    pub fn take_captured_stdout(&self) -> String {
+        match &mut *self.stdout.borrow_mut() {
+            OutputSink::Captured(buffer) => std::mem::take(buffer),
+            OutputSink::Stdout => panic!("stdout is not being captured"),
+        }
+    }
+
+    pub fn provide_stdin(&self, lines: &[&str]) {
+        *self.stdin.borrow_mut() = InputSource::Provided(lines.iter().map(|line| line.to_string()).collect());
+    }
+
+    //a line without its line ending, None at the end of the input
+    pub fn read_stdin_line(&self) -> Option<String> {
+        match &mut *self.stdin.borrow_mut() {
+            InputSource::Stdin => {
+                let mut line = String::new();
+                if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+                    return None;
+                }
+                let without_ending = line.strip_suffix('\n').unwrap_or(&line);
+                Some(without_ending.strip_suffix('\r').unwrap_or(without_ending).to_string())
+            }
+            InputSource::Provided(lines) => lines.pop_front(),
+        }
+    }
+
+    //like Py_ReprEnter: false when the repr of addr is already being computed further up
+    pub fn repr_enter(&self, addr: MemoryAddress) -> bool {
+        return self.repr_in_progress.borrow_mut().insert(addr);
+    }
+
+    pub fn repr_leave(&self, addr: MemoryAddress) {
+        self.repr_in_progress.borrow_mut().remove(&addr);
+    }
+
+    //a pair of containers compared again inside its own comparison is assumed equal,
+    //any difference is found by the comparison that is already running
+    pub fn eq_enter(&self, lhs: MemoryAddress, rhs: MemoryAddress) -> bool {
+        return self.eq_in_progress.borrow_mut().insert((lhs, rhs));
+    }
+
+    pub fn eq_leave(&self, lhs: MemoryAddress, rhs: MemoryAddress) {
+        self.eq_in_progress.borrow_mut().remove(&(lhs, rhs));
+    }
+
+    //str(obj) semantics: use __str__ if defined, else __repr__, else a default representation
+    pub fn call_str(&self, addr: MemoryAddress) -> MemoryAddress {
+        let result = self.call_method(addr, "__str__", PositionalParameters::empty())
+            .or_else(|| self.call_method(addr, "__repr__", PositionalParameters::empty()));
+        let str_addr = match result {
+            Some((str_addr, _)) => str_addr,
+            None => {
+                let default_repr = format!("<{} object at {:#x}>", self.get_pyobj_type_name(addr), self.memory.object_id(addr));
+                return self.allocate_type_byaddr_raw(self.builtin_type_addrs.string, BuiltInTypeData::String(default_repr));
+            }
+        };
+        match self.get_raw_data_of_pyobj(str_addr) {
+            BuiltInTypeData::String(_) => str_addr,
+            _ => panic!("__str__ returned non-string (type {})", self.get_pyobj_type_name(str_addr)),
+        }
+    }
+
+    //format(obj, spec) semantics: use __format__ if defined, else objects only accept an empty spec and format as str(obj)
+    pub fn call_format(&self, addr: MemoryAddress, spec_addr: MemoryAddress) -> MemoryAddress {
+        let formatted = match self.call_method(addr, "__format__", PositionalParameters::single(spec_addr)) {
+            Some((formatted, _)) => formatted,
+            None => {
+                if !self.get_raw_data_of_pyobj(spec_addr).take_string().is_empty() {
+                    panic!("TypeError: unsupported format string passed to {}.__format__", self.get_pyobj_type_name(addr));
+                }
+                return self.call_str(addr);
+            }
+        };
+        match self.get_raw_data_of_pyobj(formatted) {
+            BuiltInTypeData::String(_) => formatted,
+            _ => panic!("TypeError: __format__ must return a str, not {}", self.get_pyobj_type_name(formatted)),
+        }
+    }
+
+    //hash(obj) semantics: use __hash__ if defined, mutable containers are unhashable (None), other objects hash by identity.
+    //On None, raise_unhashable_type_error reports it.
+    pub fn call_hash(&self, addr: MemoryAddress) -> Option<i128> {
+        if let Some((hash, frame)) = self.call_method(addr, "__hash__", PositionalParameters::empty()) {
+            return match self.get_raw_data_of_pyobj(hash) {
+                BuiltInTypeData::Int(h) => Some(*h),
+                //__hash__ raised, like a tuple holding a list: reraise it in the caller's frame
+                _ if frame.exception.is_some() => {
+                    self.raise_exception(frame.exception.unwrap());
+                    None
+                }
+                _ => panic!("TypeError: __hash__ method should return an integer"),
+            };
+        }
+        match self.get_pyobj_byaddr(addr).try_get_builtin() {
+            Some(BuiltInTypeData::List(_)) | Some(BuiltInTypeData::Dict(_)) | Some(BuiltInTypeData::Set(_)) => None,
+            _ => Some(self.memory.object_id(addr) as i128),
+        }
+    }
+
+    //bool(obj) semantics: use __bool__ if defined, else __len__ != 0, else the object is truthy
+    pub fn is_truthy(&self, addr: MemoryAddress) -> bool {
+        if let PyObjectStructure::Object { raw_data: BuiltInTypeData::Int(x), .. } = &self.get_pyobj_byaddr(addr).structure {
+            return *x != 0;
+        }
+        if let Some((as_bool, _)) = self.call_method(addr, "__bool__", PositionalParameters::empty()) {
+            return self.get_raw_data_of_pyobj(as_bool).take_int() != 0;
+        }
+        if let Some((len, _)) = self.call_method(addr, "__len__", PositionalParameters::empty()) {
+            return self.get_raw_data_of_pyobj(len).take_int() != 0;
+        }
+        return true;
+    }
+
+    pub fn set_local_names(&self, names: &[String]) {
+        let mut stack = self.stack.borrow_mut();
+        let current_frame = stack.last_mut().unwrap();
+        if current_frame.local_names.as_slice() != names {
+            current_frame.local_names = names.to_vec();
+        }
+    }
+
+    //Locals bound in a frame, as (name, value) pairs. frame_index 0 is the bottom (main) frame.
+    pub fn frame_locals(&self, frame_index: usize) -> Vec<(String, MemoryAddress)> {
+        let stack = self.stack.borrow();
+        let frame = &stack[frame_index];
+        frame.local_namespace
+            .iter()
+            .enumerate()
+            .filter(|(_, addr)| !addr.is_null())
+            .filter_map(|(index, addr)| frame.local_names.get(index).map(|name| (name.clone(), *addr)))
+            .collect()
+    }
+
+    //the global name instructions of the current frame look here before the module, the values are kept alive
+    pub fn set_frame_namespace(&self, namespace: Namespace) {
+        for addr in namespace.values() {
+            self.increase_refcount(*addr);
+        }
+        self.stack.borrow_mut().last_mut().unwrap().namespace = Some(namespace);
+    }
+
+    //None when the current frame has no namespace of its own
+    pub fn with_frame_namespace<T>(&self, f: impl FnOnce(&mut Namespace) -> T) -> Option<T> {
+        self.stack.borrow_mut().last_mut().unwrap().namespace.as_mut().map(f)
+    }
+
+    pub fn current_frame_locals(&self) -> Vec<(String, MemoryAddress)> {
+        let top = self.stack.borrow().len() - 1;
+        self.frame_locals(top)
+    }
+
     pub fn raise_exception(&self, exception_value_addr: MemoryAddress) {
         let mut stack = self.stack.borrow_mut();
         let top_stack_frame = stack.last_mut().unwrap();
         top_stack_frame.exception = Some(exception_value_addr)
     }
     
+    pub fn push_handler_block(&self, handler: usize) {
+        let mut stack = self.stack.borrow_mut();
+        let top_stack_frame = stack.last_mut().unwrap();
+        let stack_depth = top_stack_frame.stack.len();
+        let handled_depth = top_stack_frame.handled_exceptions.len();
+        top_stack_frame.handler_blocks.push(HandlerBlock { handler, stack_depth, handled_depth });
+    }
+
+    pub fn pop_handler_block(&self) {
+        self.stack.borrow_mut().last_mut().unwrap().handler_blocks.pop();
+    }
+
+    //catches the current exception in the innermost try block of the frame: the exception
+    //is pushed for the handler and the pc moved to it. Returns false when there is no try block.
+    pub fn jump_to_exception_handler(&self) -> bool {
+        let mut stack = self.stack.borrow_mut();
+        let top_stack_frame = stack.last_mut().unwrap();
+        let block = match top_stack_frame.handler_blocks.pop() {
+            Some(block) => block,
+            None => return false,
+        };
+        let exception = top_stack_frame.exception.take().unwrap();
+        top_stack_frame.stack.truncate(block.stack_depth);
+        top_stack_frame.stack.push(exception);
+        //handlers the exception escaped from are done
+        top_stack_frame.handled_exceptions.truncate(block.handled_depth);
+        top_stack_frame.handled_exceptions.push(exception);
+        top_stack_frame.prog_counter.set(block.handler);
+        return true;
+    }
+
+    pub fn pop_handled_exception(&self) {
+        self.stack.borrow_mut().last_mut().unwrap().handled_exceptions.pop();
+    }
+
+    //the innermost exception being handled, a function called from an except clause sees the caller's
+    pub fn get_handled_exception(&self) -> Option<MemoryAddress> {
+        let stack = self.stack.borrow();
+        return stack.iter().rev().find_map(|frame| frame.handled_exceptions.last().copied());
+    }
+
     pub fn get_current_exception(&self) -> Option<MemoryAddress> {
         let stack = self.stack.borrow();
         let top_stack_frame = stack.last().unwrap();
@@ -820,9 +1290,38 @@ impl VM {
             stack: vec![],
             exception: None,
             prog_counter: Cell::new(0),
+            local_names: vec![],
+            resume_pc: None,
+            handler_blocks: vec![],
+            handled_exceptions: vec![],
+            namespace: None,
         })
     }
 
+    //clears the flag, so each ctrl-c raises KeyboardInterrupt only once
+    pub fn take_interrupt(&self) -> bool {
+        self.interrupt.load(Ordering::Relaxed) && self.interrupt.swap(false, Ordering::Relaxed)
+    }
+
+    //generators keep their frame between calls, so the values on its stack are not released
+    pub fn push_stack_frame(&self, frame: StackFrame) {
+        self.stack.borrow_mut().push(frame);
+    }
+
+    pub fn take_stack_frame(&self) -> StackFrame {
+        return self.stack.borrow_mut().pop().unwrap();
+    }
+
+    pub fn suspend_stack_frame(&self, resume_pc: usize) {
+        self.stack.borrow_mut().last_mut().unwrap().resume_pc = Some(resume_pc);
+    }
+
+    //raise StopIteration raises the type itself, generators raise instances carrying the returned value
+    pub fn is_stop_iteration(&self, addr: MemoryAddress) -> bool {
+        let stop_iteration = self.special_values[&SpecialValue::StopIterationType];
+        return addr == stop_iteration || self.get_pyobj_byaddr(addr).type_addr == stop_iteration;
+    }
+
     pub fn pop_stack(&self) -> MemoryAddress {
         match self.stack.borrow_mut().last_mut().unwrap().stack.pop() {
             Some(addr) => addr,
@@ -858,6 +1357,11 @@ impl VM {
         return self.stack.borrow_mut().last().unwrap().stack[index as usize];
     }
 
+    //how many values are on the current frame's stack
+    pub fn stack_len(&self) -> usize {
+        self.stack.borrow().last().unwrap().stack.len()
+    }
+
     pub fn top_stack(&self) -> MemoryAddress {
         return self.get_stack_offset(0);
     }
@@ -906,13 +1410,98 @@ impl VM {
     pub fn get_local(&self, name: usize) -> Option<MemoryAddress> {
         let stack = self.stack.borrow();
         let current_frame = stack.last().unwrap();
-        current_frame.local_namespace.get(name).map(|a| *a)
+        current_frame.local_namespace.get(name).map(|a| *a).filter(|a| !a.is_null())
+    }
+
+    //leaves the slot unbound, like the padding bind_local adds
+    pub fn unbind_local(&self, name: usize) {
+        let mut stack = self.stack.borrow_mut();
+        let current_frame = stack.last_mut().unwrap();
+        if let Some(slot) = current_frame.local_namespace.get_mut(name) {
+            *slot = self.memory.null_ptr();
+        }
     }
 
     pub fn allocate_and_write(&self, data: PyObject) -> MemoryAddress {
         self.memory.allocate_and_write(data)
     }
 
+    //prepares the program to be run instruction by instruction with step and resume, instead of execute_program
+    pub fn load_program(&self, program: Program) {
+        let main_code = program.code_objects.iter().find(|x| x.main).unwrap();
+        let code = crate::runtime::interpreter::register_codeobj_consts(self, main_code);
+        self.set_local_names(&code.code.names);
+        self.set_pc(0);
+        *self.loaded_code.borrow_mut() = Some(code);
+    }
+
+    //runs the next instruction of the loaded program. Function calls run to completion inside a single step.
+    pub fn step(&self) -> StepResult {
+        let code = match self.loaded_code.borrow().as_ref() {
+            Some(code) => code.clone(),
+            None => return StepResult::Finished,
+        };
+        if self.get_pc() >= code.code.instructions.len() {
+            return StepResult::Finished;
+        }
+        crate::runtime::interpreter::execute_next_instruction(self, &code);
+        let pc = self.get_pc();
+        if pc >= code.code.instructions.len() {
+            StepResult::Finished
+        } else if self.breakpoints.borrow().contains(&(code.code.objname.clone(), pc)) {
+            StepResult::Breakpoint(pc)
+        } else {
+            StepResult::Stepped
+        }
+    }
+
+    //steps until the program finishes or reaches a breakpoint. Only breakpoints in the loaded code object stop it.
+    pub fn resume(&self) -> StepResult {
+        loop {
+            match self.step() {
+                StepResult::Stepped => continue,
+                result => return result,
+            }
+        }
+    }
+
+    //like sys.settrace, but called for each instruction instead of each line
+    pub fn set_trace_hook(&self, hook: TraceHook) {
+        *self.trace_hook.borrow_mut() = Some(hook);
+    }
+
+    pub fn clear_trace_hook(&self) {
+        *self.trace_hook.borrow_mut() = None;
+    }
+
+    pub fn enable_profiler(&self) {
+        let mut profile = self.profile.borrow_mut();
+        if profile.is_none() {
+            *profile = Some(BTreeMap::new());
+        }
+    }
+
+    pub fn record_profile(&self, instruction: &Instruction, elapsed: Duration) {
+        if let Some(profile) = self.profile.borrow_mut().as_mut() {
+            let entry = profile.entry(instruction.opcode_name()).or_default();
+            entry.count += 1;
+            entry.total_time += elapsed;
+        }
+    }
+
+    //opcodes by name, empty when the profiler wasn't enabled
+    pub fn profile_report(&self) -> BTreeMap<&'static str, OpcodeProfile> {
+        self.profile.borrow().clone().unwrap_or_default()
+    }
+
+    pub fn add_breakpoint(&self, code_object: &str, offset: usize) {
+        self.breakpoints.borrow_mut().insert((code_object.to_owned(), offset));
+    }
+
+    pub fn remove_breakpoint(&self, code_object: &str, offset: usize) {
+        self.breakpoints.borrow_mut().remove(&(code_object.to_owned(), offset));
+    }
+
     pub fn get_pc(&self) -> usize {
         self.stack.borrow().last().unwrap().prog_counter.get()
     }
@@ -1085,4 +1674,32 @@ mod tests {
 
         assert_eq!(result_value, 17);
     }
+
+    #[test]
+    fn current_frame_locals_skips_unbound_slots() {
+        let mut interpreter = VM::new();
+        register_builtins(&mut interpreter);
+        interpreter.set_local_names(&["x".to_string(), "unused".to_string(), "y".to_string()]);
+        let number1 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(1));
+        let number2 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(2));
+        interpreter.bind_local(0, number1);
+        interpreter.bind_local(2, number2);
+
+        let locals = interpreter.current_frame_locals();
+
+        assert_eq!(locals, vec![("x".to_string(), number1), ("y".to_string(), number2)]);
+    }
+
+    #[test]
+    fn small_ints_are_interned() {
+        let mut interpreter = VM::new();
+        register_builtins(&mut interpreter);
+        let small1 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(5));
+        let small2 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(5));
+        let big1 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(1000));
+        let big2 = interpreter.allocate_builtin_type_byname_raw("int", BuiltInTypeData::Int(1000));
+
+        assert_eq!(small1, small2);
+        assert_ne!(big1, big2);
+    }
 }
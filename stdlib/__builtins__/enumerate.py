@@ -0,0 +1,13 @@
+class enumerate:
+    def __init__(self, iterable, start=0):
+        self.iterator = iterable.__iter__()
+        self.count = start
+
+    def __next__(self):
+        next = self.iterator.__next__()
+        index = self.count
+        self.count = self.count + 1
+        return (index, next)
+
+    def __iter__(self):
+        return self
@@ -9,4 +9,7 @@ class list_iterator:
         else:
             result = self.list[self.index_cur]
             self.index_cur = self.index_cur + 1
-            return result
\ No newline at end of file
+            return result
+
+    def __iter__(self):
+        return self
\ No newline at end of file
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+//writes the source to a file of its own, so tests running in parallel don't overwrite each other's
+fn write_script(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("horse_cli_{}_{}.py", std::process::id(), name));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+fn run_horse(args: &[&str], script: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_horse"))
+        .args(args)
+        .arg(script)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn dump_bytecode_prints_every_code_object_without_running() {
+    let script = write_script("dump_bytecode", "def f(a):\n    return a + 1\nprint(f(2))\n");
+    let output = run_horse(&["--dump-bytecode"], &script);
+    std::fs::remove_file(&script).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(stdout.contains("Instructions of code object __main__"));
+    assert!(stdout.contains("Instructions of code object f"));
+    assert!(stdout.contains("MakeFunction"));
+    assert!(stdout.contains("BinaryAdd"));
+    assert!(stdout.contains("CallFunction { number_arguments: 1 }"));
+    //print wasn't called
+    assert!(!stdout.contains("\n3\n"));
+}
+
+#[test]
+fn tokens_prints_one_token_per_line() {
+    let script = write_script("tokens", "x = f(1, 'a')\n");
+    let output = run_horse(&["--tokens"], &script);
+    std::fs::remove_file(&script).unwrap();
+    assert!(output.status.success());
+    let expected = "\
+Identifier(\"x\")
+Assign
+Identifier(\"f\")
+OpenParen
+LiteralInteger(1)
+Comma
+LiteralString(\"a\")
+CloseParen
+NewLine
+";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn uncaught_exception_exits_with_status_1() {
+    let script = write_script("uncaught", "print('before')\ntry:\n    raise ValueError('inner')\nexcept ValueError:\n    raise TypeError('outer')\nprint('after')\n");
+    let output = run_horse(&[], &script);
+    std::fs::remove_file(&script).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "before\n");
+    let expected = "\
+ValueError: inner
+
+During handling of the above exception, another exception occurred:
+
+TypeError: outer
+";
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), expected);
+}
+
+#[test]
+fn script_without_exceptions_exits_with_status_0() {
+    let script = write_script("no_exception", "try:\n    raise ValueError('caught')\nexcept ValueError:\n    print('handled')\n");
+    let output = run_horse(&[], &script);
+    std::fs::remove_file(&script).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "handled\n");
+}
+
+#[test]
+fn cyclic_exception_context_is_printed_once() {
+    let script = write_script("cyclic_context", "try:\n    raise ValueError('a')\nexcept ValueError as a:\n    try:\n        raise TypeError('b')\n    except TypeError:\n        raise a\n");
+    let output = run_horse(&[], &script);
+    std::fs::remove_file(&script).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let expected = "\
+TypeError: b
+
+During handling of the above exception, another exception occurred:
+
+ValueError: a
+";
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), expected);
+}
+
+#[test]
+fn interpreter_panics_print_only_the_runtime_error() {
+    let script = write_script("interpreter_panic", "print('before')\nimport nosuchmodule\n");
+    let output = run_horse(&[], &script);
+    std::fs::remove_file(&script).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "before\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "RuntimeError: ModuleNotFoundError: No module named 'nosuchmodule'\n"
+    );
+}
+
+#[test]
+fn repl_reports_uncaught_exceptions_and_keeps_going() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_horse"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"[1][5]\n1 + 1\nraise ValueError(\"x\")\nx = 3\nx\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert!(stderr.contains("IndexError: list index out of range"));
+    assert!(stderr.contains("ValueError: x"));
+    assert!(!stderr.contains("panicked"));
+    assert!(stdout.contains("\n2\n"));
+    assert!(stdout.contains("\n3\n"));
+}